@@ -1,3 +1,4 @@
+use std::str::FromStr;
 use structopt::StructOpt;
 use sc_cli::RunCmd;
 
@@ -8,6 +9,42 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Replace Aura and GRANDPA with manual sealing, for development chains.
+	///
+	/// Either `instant`, `interval=<ms>` or `manual`. Blocks are sealed with the Aura key of the
+	/// node and can always be requested with the `engine_createBlock` RPC.
+	#[structopt(long)]
+	pub sealing: Option<Sealing>,
+}
+
+/// How blocks are authored when manual sealing is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal and finalize a block as soon as a transaction enters the pool.
+	Instant,
+	/// Seal and finalize a block, possibly an empty one, every given number of milliseconds.
+	Interval(u64),
+	/// Only seal blocks through the `engine_createBlock` RPC.
+	Manual,
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"instant" => Ok(Sealing::Instant),
+			"manual" => Ok(Sealing::Manual),
+			s => s.strip_prefix("interval=")
+				.and_then(|millis| millis.parse().ok())
+				.filter(|millis| *millis > 0)
+				.map(Sealing::Interval)
+				.ok_or_else(|| format!(
+					"invalid sealing `{}`, expected `instant`, `interval=<ms>` or `manual`", s,
+				)),
+		}
+	}
 }
 
 #[derive(Debug, StructOpt)]
@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 use std::time::Duration;
+use futures::{channel::mpsc, prelude::*};
 use sc_client_api::{ExecutorProvider, RemoteBackend};
 use node_template_runtime::{self, opaque::Block, RuntimeApi};
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
@@ -10,9 +11,13 @@ use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 use sc_consensus_aura::{ImportQueueParams, StartAuraParams, SlotProportion};
+use sc_consensus_manual_seal::{
+	consensus::aura::AuraConsensusDataProvider, EngineCommand, ManualSealParams,
+};
 use sc_finality_grandpa::SharedVoterState;
 use sc_keystore::LocalKeystore;
 use sc_telemetry::TelemetrySpan;
+use crate::cli::Sealing;
 
 // Our native executor instance.
 native_executor_instance!(
@@ -26,7 +31,10 @@ type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 
-pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponents<
+pub fn new_partial(
+	config: &Configuration,
+	sealing: Option<Sealing>,
+) -> Result<sc_service::PartialComponents<
 	FullClient, FullBackend, FullSelectChain,
 	sp_consensus::DefaultImportQueue<Block, FullClient>,
 	sc_transaction_pool::FullPool<Block, FullClient>,
@@ -68,19 +76,31 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		grandpa_block_import.clone(), client.clone(),
 	);
 
-	let import_queue = sc_consensus_aura::import_queue::<AuraPair, _, _, _, _, _>(
-		ImportQueueParams {
-			block_import: aura_block_import.clone(),
-			justification_import: Some(Box::new(grandpa_block_import.clone())),
-			client: client.clone(),
-			inherent_data_providers: inherent_data_providers.clone(),
-			spawner: &task_manager.spawn_essential_handle(),
-			can_author_with: sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone()),
-			slot_duration: sc_consensus_aura::slot_duration(&*client)?,
-			registry: config.prometheus_registry(),
-			check_for_equivocation: Default::default(),
-		},
-	)?;
+	let import_queue = if sealing.is_some() {
+		// manually sealed blocks are final as soon as they are sealed, there's no need to go
+		// through Aura and GRANDPA.
+		sc_consensus_manual_seal::import_queue(
+			Box::new(client.clone()),
+			&task_manager.spawn_essential_handle(),
+			config.prometheus_registry(),
+		)
+	} else {
+		sc_consensus_aura::import_queue::<AuraPair, _, _, _, _, _>(
+			ImportQueueParams {
+				block_import: aura_block_import.clone(),
+				justification_import: Some(Box::new(grandpa_block_import.clone())),
+				client: client.clone(),
+				inherent_data_providers: inherent_data_providers.clone(),
+				spawner: &task_manager.spawn_essential_handle(),
+				can_author_with: sp_consensus::CanAuthorWithNativeVersion::new(
+					client.executor().clone(),
+				),
+				slot_duration: sc_consensus_aura::slot_duration(&*client)?,
+				registry: config.prometheus_registry(),
+				check_for_equivocation: Default::default(),
+			},
+		)?
+	};
 
 	Ok(sc_service::PartialComponents {
 		client,
@@ -103,7 +123,13 @@ fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+///
+/// If `sealing` is set, blocks are authored by manual seal instead of Aura and finalized as
+/// soon as they are sealed, instead of by GRANDPA.
+pub fn new_full(
+	mut config: Configuration,
+	sealing: Option<Sealing>,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -114,7 +140,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		transaction_pool,
 		inherent_data_providers,
 		other: (block_import, grandpa_link),
-	} = new_partial(&config)?;
+	} = new_partial(&config, sealing)?;
 
 	if let Some(url) = &config.keystore_remote {
 		match remote_keystore(url) {
@@ -152,15 +178,20 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	// Channel for the rpc handler to communicate with the manual seal authorship task.
+	let (command_sink, commands_stream) = mpsc::channel(1024);
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let command_sink = sealing.map(|_| command_sink);
 
 		Box::new(move |deny_unsafe, _| {
 			let deps = crate::rpc::FullDeps {
 				client: client.clone(),
 				pool: pool.clone(),
 				deny_unsafe,
+				command_sink: command_sink.clone(),
 			};
 
 			crate::rpc::create_full(deps)
@@ -188,6 +219,63 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		},
 	)?;
 
+	if let Some(sealing) = sealing {
+		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+			task_manager.spawn_handle(),
+			client.clone(),
+			transaction_pool.clone(),
+			prometheus_registry.as_ref(),
+		);
+
+		let consensus_data_provider = AuraConsensusDataProvider::<_, _, AuraPair>::new(
+			client.clone(),
+			keystore_container.sync_keystore(),
+			&inherent_data_providers,
+		).map_err(|e| ServiceError::Other(e.to_string()))?;
+		let clock = consensus_data_provider.clock();
+
+		let seal_new_block = |create_empty| EngineCommand::SealNewBlock {
+			create_empty,
+			finalize: true,
+			parent_hash: None,
+			sender: None,
+		};
+		// blocks can be requested through rpc in any mode, `instant` and `interval` sealing
+		// only add their own triggers.
+		let triggers = match sealing {
+			Sealing::Instant => transaction_pool.pool().validated_pool()
+				.import_notification_stream()
+				.map(move |_| seal_new_block(false))
+				.boxed(),
+			Sealing::Interval(millis) => stream::repeat(())
+				.then(move |_| futures_timer::Delay::new(Duration::from_millis(millis)))
+				.map(move |_| seal_new_block(true))
+				.boxed(),
+			Sealing::Manual => stream::empty().boxed(),
+		};
+
+		let authorship_future = sc_consensus_manual_seal::run_manual_seal(
+			ManualSealParams {
+				block_import: client.clone(),
+				env: proposer_factory,
+				client: client.clone(),
+				pool: transaction_pool.pool().clone(),
+				commands_stream: stream::select(commands_stream, triggers),
+				select_chain,
+				consensus_data_provider: Some(Box::new(consensus_data_provider)),
+				inherent_data_providers,
+				clock: Some(clock),
+			},
+		);
+
+		// the manual seal authoring task is considered essential, i.e. if it
+		// fails we take down the service with it.
+		task_manager.spawn_essential_handle().spawn_blocking("manual-seal", authorship_future);
+
+		network_starter.start_network();
+		return Ok(task_manager);
+	}
+
 	if role.is_authority() {
 		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
@@ -213,6 +301,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 				can_author_with,
 				sync_oracle: network.clone(),
 				block_proposal_slot_portion: SlotProportion::new(2f32 / 3f32),
+				max_block_proposal_slot_portion: None,
 			},
 		)?;
 
@@ -431,7 +431,9 @@ impl_runtime_apis! {
 		}
 	}
 
-	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
+	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance, u32>
+		for Runtime
+	{
 		fn query_info(
 			uxt: <Block as BlockT>::Extrinsic,
 			len: u32,
@@ -444,6 +446,14 @@ impl_runtime_apis! {
 		) -> pallet_transaction_payment::FeeDetails<Balance> {
 			TransactionPayment::query_fee_details(uxt, len)
 		}
+		fn query_fee_details_in_asset(
+			_uxt: <Block as BlockT>::Extrinsic,
+			_len: u32,
+			_asset_id: u32,
+		) -> Option<pallet_transaction_payment::FeeDetails<Balance>> {
+			// Fees can only be paid in the native currency.
+			None
+		}
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
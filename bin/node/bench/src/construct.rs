@@ -76,6 +76,7 @@ impl core::BenchmarkDescription for ConstructionBenchmarkDescription {
 
 		match self.profile {
 			Profile::Wasm => path.push("wasm"),
+			Profile::WasmPooled => path.push("wasm_pooled"),
 			Profile::Native => path.push("native"),
 		}
 
@@ -65,6 +65,7 @@ impl core::BenchmarkDescription for ImportBenchmarkDescription {
 
 		match self.profile {
 			Profile::Wasm => path.push("wasm"),
+			Profile::WasmPooled => path.push("wasm_pooled"),
 			Profile::Native => path.push("native"),
 		}
 
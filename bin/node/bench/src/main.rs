@@ -84,7 +84,7 @@ fn main() {
 
 	let mut import_benchmarks = Vec::new();
 
-	for profile in [Profile::Wasm, Profile::Native].iter() {
+	for profile in [Profile::Wasm, Profile::WasmPooled, Profile::Native].iter() {
 		for size in [
 			SizeType::Empty,
 			SizeType::Small,
@@ -150,7 +150,21 @@ fn main() {
 			size: SizeType::Large,
 			database_type: BenchDataBaseType::RocksDb,
 		},
-		PoolBenchmarkDescription { database_type: BenchDataBaseType::RocksDb },
+		ConstructionBenchmarkDescription {
+			profile: Profile::WasmPooled,
+			key_types: KeyTypes::Sr25519,
+			block_type: BlockType::RandomTransfersKeepAlive,
+			size: SizeType::Large,
+			database_type: BenchDataBaseType::RocksDb,
+		},
+		PoolBenchmarkDescription {
+			database_type: BenchDataBaseType::RocksDb,
+			profile: Profile::Wasm,
+		},
+		PoolBenchmarkDescription {
+			database_type: BenchDataBaseType::RocksDb,
+			profile: Profile::WasmPooled,
+		},
 	);
 
 	if opt.list {
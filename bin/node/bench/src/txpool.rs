@@ -33,15 +33,25 @@ use crate::core::{self, Path, Mode};
 
 pub struct PoolBenchmarkDescription {
 	pub database_type: DatabaseType,
+	pub profile: Profile,
 }
 
 pub struct PoolBenchmark {
 	database: BenchDb,
+	profile: Profile,
 }
 
 impl core::BenchmarkDescription for PoolBenchmarkDescription {
 	fn path(&self) -> Path {
-		Path::new(&["node", "txpool"])
+		let mut path = Path::new(&["node", "txpool"]);
+
+		match self.profile {
+			Profile::Wasm => path.push("wasm"),
+			Profile::WasmPooled => path.push("wasm_pooled"),
+			Profile::Native => path.push("native"),
+		}
+
+		path
 	}
 
 	fn setup(self: Box<Self>) -> Box<dyn core::Benchmark> {
@@ -51,6 +61,7 @@ impl core::BenchmarkDescription for PoolBenchmarkDescription {
 				50_000,
 				KeyTypes::Sr25519,
 			),
+			profile: self.profile,
 		})
 	}
 
@@ -61,7 +72,7 @@ impl core::BenchmarkDescription for PoolBenchmarkDescription {
 
 impl core::Benchmark for PoolBenchmark {
 	fn run(&mut self, mode: Mode) -> std::time::Duration {
-		let context = self.database.create_context(Profile::Wasm);
+		let context = self.database.create_context(self.profile);
 
 		let _ = context.client.runtime_version_at(&BlockId::Number(0))
 			.expect("Failed to get runtime version")
@@ -105,6 +105,7 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		let (_, grandpa_link, babe_link) = &import_setup;
 
 		let justification_stream = grandpa_link.justification_stream();
+		let equivocation_stream = grandpa_link.equivocation_stream();
 		let shared_authority_set = grandpa_link.shared_authority_set().clone();
 		let shared_voter_state = grandpa::SharedVoterState::empty();
 		let rpc_setup = shared_voter_state.clone();
@@ -118,6 +119,8 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		let shared_epoch_changes = babe_link.epoch_changes().clone();
 
 		let client = client.clone();
+		let rpc_backend = backend.clone();
+		let offchain_indexing_enabled = config.offchain_worker.indexing_enabled;
 		let pool = transaction_pool.clone();
 		let select_chain = select_chain.clone();
 		let keystore = keystore_container.sync_keystore();
@@ -126,6 +129,8 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		let rpc_extensions_builder = move |deny_unsafe, subscription_executor| {
 			let deps = node_rpc::FullDeps {
 				client: client.clone(),
+				backend: rpc_backend.clone(),
+				offchain_indexing_enabled,
 				pool: pool.clone(),
 				select_chain: select_chain.clone(),
 				chain_spec: chain_spec.cloned_box(),
@@ -139,6 +144,7 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 					shared_voter_state: shared_voter_state.clone(),
 					shared_authority_set: shared_authority_set.clone(),
 					justification_stream: justification_stream.clone(),
+					equivocation_stream: equivocation_stream.clone(),
 					subscription_executor,
 					finality_provider: finality_proof_provider.clone(),
 				},
@@ -280,6 +286,7 @@ pub fn new_full_base(
 			babe_link,
 			can_author_with,
 			block_proposal_slot_portion: SlotProportion::new(0.5),
+			max_block_proposal_slot_portion: None,
 		};
 
 		let babe = sc_consensus_babe::start_babe(babe_config)?;
@@ -667,7 +674,7 @@ mod tests {
 				let check_era = frame_system::CheckEra::from(Era::Immortal);
 				let check_nonce = frame_system::CheckNonce::from(index);
 				let check_weight = frame_system::CheckWeight::new();
-				let payment = pallet_transaction_payment::ChargeTransactionPayment::from(0);
+				let payment = pallet_asset_tx_payment::ChargeAssetTxPayment::from(0, None);
 				let extra = (
 					check_spec_version,
 					check_tx_version,
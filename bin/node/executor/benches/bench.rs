@@ -214,6 +214,8 @@ fn bench_execute_block(c: &mut Criterion) {
 			ExecutionMethod::Wasm(WasmExecutionMethod::Interpreted),
 			#[cfg(feature = "wasmtime")]
 			ExecutionMethod::Wasm(WasmExecutionMethod::Compiled),
+			#[cfg(feature = "wasmtime")]
+			ExecutionMethod::Wasm(WasmExecutionMethod::CompiledPooled),
 		],
 	);
 }
@@ -624,6 +624,7 @@ fn deploying_wasm_contract_should_work() {
 					pallet_contracts::Call::instantiate_with_code::<Runtime>(
 						1000 * DOLLARS + subsistence,
 						500_000_000,
+						None,
 						transfer_code,
 						Vec::new(),
 						Vec::new(),
@@ -637,6 +638,7 @@ fn deploying_wasm_contract_should_work() {
 						sp_runtime::MultiAddress::Id(addr.clone()),
 						10,
 						500_000_000,
+						None,
 						vec![0x00, 0x01, 0x02, 0x03]
 					)
 				),
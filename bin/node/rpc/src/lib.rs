@@ -33,12 +33,13 @@
 use std::sync::Arc;
 
 use sp_keystore::SyncCryptoStorePtr;
-use node_primitives::{Block, BlockNumber, AccountId, Index, Balance, Hash};
+use node_primitives::{Block, BlockNumber, AccountId, AssetId, Index, Balance, Hash};
 use sc_consensus_babe::{Config, Epoch};
 use sc_consensus_babe_rpc::BabeRpcHandler;
 use sc_consensus_epochs::SharedEpochChanges;
 use sc_finality_grandpa::{
-	SharedVoterState, SharedAuthoritySet, FinalityProofProvider, GrandpaJustificationStream
+	SharedVoterState, SharedAuthoritySet, FinalityProofProvider, GrandpaJustificationStream,
+	GrandpaEquivocationStream,
 };
 use sc_finality_grandpa_rpc::GrandpaRpcHandler;
 pub use sc_rpc_api::DenyUnsafe;
@@ -81,6 +82,8 @@ pub struct GrandpaDeps<B> {
 	pub shared_authority_set: SharedAuthoritySet<Hash, BlockNumber>,
 	/// Receives notifications about justification events from Grandpa.
 	pub justification_stream: GrandpaJustificationStream<Block>,
+	/// Receives notifications about equivocations detected by Grandpa.
+	pub equivocation_stream: GrandpaEquivocationStream<Block>,
 	/// Executor to drive the subscription manager in the Grandpa RPC handler.
 	pub subscription_executor: SubscriptionTaskExecutor,
 	/// Finality proof provider.
@@ -91,6 +94,10 @@ pub struct GrandpaDeps<B> {
 pub struct FullDeps<C, P, SC, B> {
 	/// The client instance to use.
 	pub client: Arc<C>,
+	/// The backend instance, used to access the Off-chain DB.
+	pub backend: Arc<B>,
+	/// Whether offchain indexing is enabled, i.e. whether MMR nodes end up in the Off-chain DB.
+	pub offchain_indexing_enabled: bool,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
 	/// The SelectChain Strategy
@@ -115,8 +122,9 @@ pub fn create_full<C, P, SC, B>(
 	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore +
 		HeaderMetadata<Block, Error=BlockChainError> + Sync + Send + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
-	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
-	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber, Hash>,
+	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance, AssetId>,
+	C::Api: pallet_mmr_rpc::MmrRuntimeApi<Block, (BlockNumber, Hash), Hash>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
@@ -127,10 +135,13 @@ pub fn create_full<C, P, SC, B>(
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 	use pallet_contracts_rpc::{Contracts, ContractsApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+	use pallet_mmr_rpc::{Mmr, MmrApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
 		client,
+		backend,
+		offchain_indexing_enabled,
 		pool,
 		select_chain,
 		chain_spec,
@@ -148,6 +159,7 @@ pub fn create_full<C, P, SC, B>(
 		shared_voter_state,
 		shared_authority_set,
 		justification_stream,
+		equivocation_stream,
 		subscription_executor,
 		finality_provider,
 	} = grandpa;
@@ -164,6 +176,16 @@ pub fn create_full<C, P, SC, B>(
 	io.extend_with(
 		TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
 	);
+	// MMR proofs are built from the nodes in the Off-chain DB, which the runtime only writes
+	// when the node runs with offchain indexing enabled.
+	let offchain_storage = backend.offchain_storage().filter(|_| offchain_indexing_enabled);
+	if let Some(offchain_storage) = offchain_storage {
+		io.extend_with(
+			MmrApi::to_delegate(
+				Mmr::<_, Block, _, (BlockNumber, Hash), Hash>::new(client.clone(), offchain_storage)
+			)
+		);
+	}
 	io.extend_with(
 		sc_consensus_babe_rpc::BabeApi::to_delegate(
 			BabeRpcHandler::new(
@@ -182,6 +204,7 @@ pub fn create_full<C, P, SC, B>(
 				shared_authority_set.clone(),
 				shared_voter_state,
 				justification_stream,
+				equivocation_stream,
 				subscription_executor,
 				finality_provider,
 			)
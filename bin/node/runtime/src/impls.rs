@@ -17,8 +17,11 @@
 
 //! Some configurable implementations as associated type for the substrate runtime.
 
-use frame_support::traits::{OnUnbalanced, Currency};
-use crate::{Balances, Authorship, NegativeImbalance};
+use frame_support::traits::{OnUnbalanced, Currency, tokens::fungibles::Unbalanced};
+use sp_runtime::traits::{Convert, SaturatedConversion};
+use pallet_asset_tx_payment::HandleCredit;
+use node_primitives::{AccountId, AssetId, Balance};
+use crate::{Assets, Balances, Authorship, NegativeImbalance};
 
 pub struct Author;
 impl OnUnbalanced<NegativeImbalance> for Author {
@@ -27,6 +30,25 @@ impl OnUnbalanced<NegativeImbalance> for Author {
 	}
 }
 
+/// Gives the asset fees to the block author. Whatever the author can't receive is burned.
+pub struct CreditToBlockAuthor;
+impl HandleCredit<AccountId, Assets> for CreditToBlockAuthor {
+	fn handle_credit(asset: AssetId, amount: u64) {
+		let deposited = Assets::increase_balance_at_most(asset, &Authorship::author(), amount);
+		let remainder = amount.saturating_sub(deposited);
+		<() as HandleCredit<AccountId, Assets>>::handle_credit(asset, remainder);
+	}
+}
+
+/// Converts a native balance into the balance type of the assets pallet, saturating at its
+/// maximum value.
+pub struct SaturatingToAssetBalance;
+impl Convert<Balance, u64> for SaturatingToAssetBalance {
+	fn convert(balance: Balance) -> u64 {
+		balance.saturated_into()
+	}
+}
+
 #[cfg(test)]
 mod multiplier_tests {
 	use sp_runtime::{assert_eq_error_rate, FixedPointNumber, traits::Convert};
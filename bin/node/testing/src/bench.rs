@@ -399,7 +399,7 @@ impl BenchDb {
 	// This should return client that is doing everything that full node
 	// is doing.
 	//
-	// - This client should use best wasm execution method.
+	// - This client should use best wasm execution method of the profile.
 	// - This client should work with real database only.
 	fn bench_client(
 		database_type: DatabaseType,
@@ -419,7 +419,7 @@ impl BenchDb {
 
 		let (client, backend) = sc_service::new_client(
 			db_config,
-			NativeExecutor::new(WasmExecutionMethod::Compiled, None, 8),
+			NativeExecutor::new(profile.wasm_method(), None, 8),
 			&keyring.generate_genesis(),
 			None,
 			None,
@@ -621,12 +621,14 @@ pub enum Profile {
 	Native,
 	/// As wasm as possible.
 	Wasm,
+	/// As wasm as possible, using a pool of pre-created instances.
+	WasmPooled,
 }
 
 impl Profile {
 	fn into_execution_strategies(self) -> ExecutionStrategies {
 		match self {
-			Profile::Wasm => ExecutionStrategies {
+			Profile::Wasm | Profile::WasmPooled => ExecutionStrategies {
 				syncing: ExecutionStrategy::AlwaysWasm,
 				importing: ExecutionStrategy::AlwaysWasm,
 				block_construction: ExecutionStrategy::AlwaysWasm,
@@ -642,6 +644,13 @@ impl Profile {
 			}
 		}
 	}
+
+	fn wasm_method(self) -> WasmExecutionMethod {
+		match self {
+			Profile::WasmPooled => WasmExecutionMethod::CompiledPooled,
+			Profile::Wasm | Profile::Native => WasmExecutionMethod::Compiled,
+		}
+	}
 }
 
 struct Guard(tempfile::TempDir);
@@ -0,0 +1,233 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use codec::{Decode, Encode};
+use log::{debug, trace};
+use parking_lot::RwLock;
+
+use sc_network::PeerId;
+use sc_network_gossip::{MessageIntent, ValidationResult, Validator, ValidatorContext};
+use sp_runtime::traits::{Block, Hash, Header, NumberFor};
+
+use sp_beefy::{
+	crypto::{AuthorityId, AuthoritySignature},
+	MmrRootHash, VoteMessage,
+};
+
+use crate::keystore::BeefyKeystore;
+
+/// A BEEFY vote as sent over the wire.
+pub(crate) type Vote<B> =
+	VoteMessage<MmrRootHash, NumberFor<B>, AuthorityId, AuthoritySignature>;
+
+/// Gossip engine messages topic
+pub(crate) fn topic<B>() -> B::Hash
+where
+	B: Block,
+{
+	<<B::Header as Header>::Hashing as Hash>::hash(b"beefy")
+}
+
+/// BEEFY gossip validator
+///
+/// Validate BEEFY gossip messages and limit the number of live BEEFY voting rounds.
+///
+/// Allows messages for blocks after the last concluded round, messages for earlier blocks
+/// are considered expired. Every message has to carry a valid signature of the voter
+/// over the encoded commitment.
+///
+/// All messages are gossiped on the same topic, see [`topic`].
+pub(crate) struct GossipValidator<B>
+where
+	B: Block,
+{
+	topic: B::Hash,
+	concluded: RwLock<Option<NumberFor<B>>>,
+}
+
+impl<B> GossipValidator<B>
+where
+	B: Block,
+{
+	pub fn new() -> GossipValidator<B> {
+		GossipValidator { topic: topic::<B>(), concluded: RwLock::new(None) }
+	}
+
+	/// Note a concluded voting round.
+	///
+	/// Messages for this round and any earlier round are considered expired from now on.
+	pub fn note_concluded(&self, round: NumberFor<B>) {
+		trace!(target: "beefy", "🥩 About to note round #{}", round);
+
+		let mut concluded = self.concluded.write();
+		if concluded.map_or(true, |c| round > c) {
+			*concluded = Some(round);
+		}
+	}
+
+	fn is_live(&self, round: NumberFor<B>) -> bool {
+		self.concluded.read().map_or(true, |concluded| round > concluded)
+	}
+}
+
+impl<B> Validator<B> for GossipValidator<B>
+where
+	B: Block,
+{
+	fn validate(
+		&self,
+		_context: &mut dyn ValidatorContext<B>,
+		sender: &PeerId,
+		mut data: &[u8],
+	) -> ValidationResult<B::Hash> {
+		if let Ok(msg) = Vote::<B>::decode(&mut data) {
+			if !self.is_live(msg.commitment.block_number) {
+				return ValidationResult::Discard;
+			}
+
+			let encoded = msg.commitment.encode();
+			if BeefyKeystore::verify(&msg.id, &msg.signature, &encoded) {
+				return ValidationResult::ProcessAndKeep(self.topic);
+			}
+
+			debug!(target: "beefy", "🥩 Bad signature on message: {:?}, from: {:?}", msg, sender);
+		}
+
+		ValidationResult::Discard
+	}
+
+	fn message_expired<'a>(&'a self) -> Box<dyn FnMut(B::Hash, &[u8]) -> bool + 'a> {
+		let concluded = *self.concluded.read();
+		Box::new(move |_topic, mut data| {
+			let msg = match Vote::<B>::decode(&mut data) {
+				Ok(vote) => vote,
+				Err(_) => return true,
+			};
+
+			let round = msg.commitment.block_number;
+			let expired = concluded.map_or(false, |c| round <= c);
+			trace!(target: "beefy", "🥩 Message for round #{} expired: {}", round, expired);
+
+			expired
+		})
+	}
+
+	fn message_allowed<'a>(
+		&'a self,
+	) -> Box<dyn FnMut(&PeerId, MessageIntent, &B::Hash, &[u8]) -> bool + 'a> {
+		let concluded = *self.concluded.read();
+		Box::new(move |_who, _intent, _topic, mut data| {
+			let msg = match Vote::<B>::decode(&mut data) {
+				Ok(vote) => vote,
+				Err(_) => return false,
+			};
+
+			concluded.map_or(true, |c| msg.commitment.block_number > c)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use sp_core::{ecdsa, Pair};
+	use sp_runtime::testing::{Block as RawBlock, ExtrinsicWrapper};
+
+	use sp_beefy::{Commitment, VoteMessage};
+
+	use super::*;
+
+	type Block = RawBlock<ExtrinsicWrapper<u64>>;
+
+	struct TestContext;
+	impl<B: sp_runtime::traits::Block> ValidatorContext<B> for TestContext {
+		fn broadcast_topic(&mut self, _topic: B::Hash, _force: bool) {}
+
+		fn broadcast_message(&mut self, _topic: B::Hash, _message: Vec<u8>, _force: bool) {}
+
+		fn send_message(&mut self, _who: &PeerId, _message: Vec<u8>) {}
+
+		fn send_topic(&mut self, _who: &PeerId, _topic: B::Hash, _force: bool) {}
+	}
+
+	fn vote(block_number: u64, signed_block_number: u64) -> Vec<u8> {
+		let pair = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let commitment = |block_number| Commitment {
+			payload: MmrRootHash::repeat_byte(1),
+			block_number,
+			validator_set_id: 0,
+		};
+		let signature = pair.sign(&commitment(signed_block_number).encode());
+
+		VoteMessage {
+			commitment: commitment(block_number),
+			id: AuthorityId::from(pair.public()),
+			signature: AuthoritySignature::from(signature),
+		}
+		.encode()
+	}
+
+	#[test]
+	fn validate_checks_signature_and_round() {
+		let validator = GossipValidator::<Block>::new();
+		let sender = PeerId::random();
+		let mut context = TestContext;
+
+		let want = topic::<Block>();
+		let res = validator.validate(&mut context, &sender, &vote(10, 10));
+		assert!(matches!(res, ValidationResult::ProcessAndKeep(topic) if topic == want));
+
+		// signature over a different commitment
+		let res = validator.validate(&mut context, &sender, &vote(10, 11));
+		assert!(matches!(res, ValidationResult::Discard));
+
+		// garbage
+		let res = validator.validate(&mut context, &sender, &[1, 2, 3]);
+		assert!(matches!(res, ValidationResult::Discard));
+
+		validator.note_concluded(10);
+
+		let res = validator.validate(&mut context, &sender, &vote(10, 10));
+		assert!(matches!(res, ValidationResult::Discard));
+
+		let res = validator.validate(&mut context, &sender, &vote(11, 11));
+		assert!(matches!(res, ValidationResult::ProcessAndKeep(_)));
+	}
+
+	#[test]
+	fn messages_of_concluded_rounds_expire() {
+		let validator = GossipValidator::<Block>::new();
+		let topic = topic::<Block>();
+		let sender = PeerId::random();
+		let allowed = |data: &[u8]| {
+			validator.message_allowed()(&sender, MessageIntent::Broadcast, &topic, data)
+		};
+
+		assert!(!validator.message_expired()(topic, &vote(5, 5)));
+		assert!(allowed(&vote(5, 5)));
+
+		validator.note_concluded(5);
+		// noting an older round does not move the window back
+		validator.note_concluded(3);
+
+		assert!(validator.message_expired()(topic, &vote(5, 5)));
+		assert!(!validator.message_expired()(topic, &vote(6, 6)));
+		assert!(!allowed(&vote(4, 4)));
+		assert!(allowed(&vote(6, 6)));
+		assert!(validator.message_expired()(topic, &[1, 2, 3]));
+	}
+}
@@ -0,0 +1,169 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::convert::TryInto;
+
+use sp_application_crypto::{Public, RuntimeAppPublic};
+use sp_core::ecdsa;
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+
+use sp_beefy::{
+	crypto::{AuthorityId, AuthoritySignature},
+	KEY_TYPE,
+};
+
+use crate::error;
+
+/// A BEEFY specific keystore implemented as a `Newtype`. This is basically a
+/// wrapper around [`sp_keystore::SyncCryptoStore`] and allows to customize
+/// common cryptographic functionality.
+pub(crate) struct BeefyKeystore(Option<SyncCryptoStorePtr>);
+
+impl BeefyKeystore {
+	/// Check if the keystore contains a private key for one of the public keys
+	/// contained in `keys`. A public key with a matching private key is known
+	/// as a local authority id.
+	///
+	/// Return the public key for which we also do have a private key. If no
+	/// matching private key is found, `None` will be returned.
+	pub fn authority_id(&self, keys: &[AuthorityId]) -> Option<AuthorityId> {
+		let store = self.0.clone()?;
+
+		// we do check for multiple private keys as a key store sanity check.
+		let public: Vec<AuthorityId> = keys
+			.iter()
+			.filter(|k| SyncCryptoStore::has_keys(&*store, &[(Public::to_raw_vec(*k), KEY_TYPE)]))
+			.cloned()
+			.collect();
+
+		if public.len() > 1 {
+			log::warn!(
+				target: "beefy",
+				"🥩 Multiple private keys found for: {:?} ({})",
+				public,
+				public.len(),
+			);
+		}
+
+		public.get(0).cloned()
+	}
+
+	/// Sign `message` with the `public` key.
+	///
+	/// Note that the keystore hashes `message` with `blake2_256` before signing it.
+	///
+	/// Return the message signature or an error in case of failure.
+	pub fn sign(
+		&self,
+		public: &AuthorityId,
+		message: &[u8],
+	) -> Result<AuthoritySignature, error::Error> {
+		let store = self.0.clone().ok_or_else(|| error::Error::Keystore("no Keystore".into()))?;
+
+		let sig = SyncCryptoStore::sign_with(
+			&*store,
+			KEY_TYPE,
+			&Public::to_public_crypto_pair(public),
+			message,
+		)
+		.map_err(|e| error::Error::Keystore(e.to_string()))?
+		.ok_or_else(|| error::Error::Signature("sign_with() failed".to_string()))?;
+
+		// check that `sig` has the expected result type
+		let sig: [u8; 65] = sig.clone().try_into().map_err(|_| {
+			error::Error::Signature(format!("invalid signature {:?} for key {:?}", sig, public))
+		})?;
+
+		Ok(AuthoritySignature::from(ecdsa::Signature::from_raw(sig)))
+	}
+
+	/// Use the `public` key to verify that `sig` is a valid signature for `message`.
+	///
+	/// Return `true` if the signature is authentic, `false` otherwise.
+	pub fn verify(public: &AuthorityId, sig: &AuthoritySignature, message: &[u8]) -> bool {
+		RuntimeAppPublic::verify(public, &message, sig)
+	}
+}
+
+impl From<Option<SyncCryptoStorePtr>> for BeefyKeystore {
+	fn from(store: Option<SyncCryptoStorePtr>) -> BeefyKeystore {
+		BeefyKeystore(store)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use sc_keystore::LocalKeystore;
+	use sp_core::{ecdsa, Pair};
+
+	use super::*;
+
+	fn keystore(seeds: &[&str]) -> (BeefyKeystore, Vec<AuthorityId>) {
+		let store: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+		let keys = seeds
+			.iter()
+			.map(|seed| {
+				SyncCryptoStore::ecdsa_generate_new(&*store, KEY_TYPE, Some(*seed))
+					.map(AuthorityId::from)
+					.unwrap()
+			})
+			.collect();
+
+		(Some(store).into(), keys)
+	}
+
+	#[test]
+	fn authority_id_works() {
+		let (store, keys) = keystore(&["//Alice"]);
+		let bob = ecdsa::Pair::from_string("//Bob", None).unwrap().public();
+		let bob = AuthorityId::from(bob);
+
+		assert_eq!(store.authority_id(&[bob.clone(), keys[0].clone()]), Some(keys[0].clone()));
+		assert_eq!(store.authority_id(&[bob]), None);
+		assert_eq!(BeefyKeystore::from(None).authority_id(&keys), None);
+	}
+
+	#[test]
+	fn sign_and_verify_work() {
+		let (store, keys) = keystore(&["//Alice", "//Bob"]);
+		let msg = b"are you involved or committed?";
+
+		let sig = store.sign(&keys[0], msg).unwrap();
+
+		assert!(BeefyKeystore::verify(&keys[0], &sig, msg));
+		assert!(!BeefyKeystore::verify(&keys[1], &sig, msg));
+		assert!(!BeefyKeystore::verify(&keys[0], &sig, b"another message"));
+	}
+
+	#[test]
+	fn sign_error() {
+		let (store, _) = keystore(&["//Alice"]);
+		let bob = AuthorityId::from(ecdsa::Pair::from_string("//Bob", None).unwrap().public());
+
+		assert_eq!(
+			store.sign(&bob, b"message"),
+			Err(error::Error::Signature("sign_with() failed".to_string())),
+		);
+		assert_eq!(
+			BeefyKeystore::from(None).sign(&bob, b"message"),
+			Err(error::Error::Keystore("no Keystore".to_string())),
+		);
+	}
+}
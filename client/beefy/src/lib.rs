@@ -0,0 +1,146 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! BEEFY (Bridge Efficiency Enabling Finality Yielder) client gadget.
+//!
+//! BEEFY is a secondary finality gadget running on top of GRANDPA. Once a block is finalized
+//! by GRANDPA, BEEFY validators sign a [`sp_beefy::Commitment`] to the MMR root that the
+//! runtime deposited in the block header and gossip their votes to each other. A round
+//! concludes as soon as more than two thirds of the validator set signed the same commitment,
+//! and the resulting [`notification::SignedCommitment`] is published through
+//! [`notification::BeefySignedCommitmentStream`].
+//!
+//! Signatures are `secp256k1` ECDSA signatures, which makes BEEFY justifications cheap to
+//! verify for light clients living on other chains, e.g. in an Ethereum bridge contract.
+//!
+//! The gadget needs a runtime with the BEEFY pallet, which implements [`sp_beefy::BeefyApi`]
+//! and deposits MMR roots through `pallet_beefy::DepositMmrRoot`. The Substrate node in
+//! `bin/node` is not set up for BEEFY.
+
+use std::sync::Arc;
+
+use log::debug;
+use prometheus_endpoint::Registry;
+
+use sc_client_api::BlockchainEvents;
+use sc_network_gossip::{GossipEngine, Network as GossipNetwork};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_keystore::SyncCryptoStorePtr;
+use sp_runtime::traits::Block;
+
+use sp_beefy::BeefyApi;
+
+mod error;
+mod gossip;
+mod keystore;
+mod metrics;
+mod round;
+mod worker;
+
+pub mod notification;
+
+/// Name of the notifications protocol used by BEEFY.
+///
+/// Must be registered towards the networking in order for BEEFY to properly function.
+pub const BEEFY_PROTOCOL_NAME: &str = "/paritytech/beefy/1";
+
+/// Returns the configuration value to put in
+/// [`sc_network::config::NetworkConfiguration::extra_sets`].
+pub fn beefy_peers_set_config() -> sc_network::config::NonDefaultSetConfig {
+	sc_network::config::NonDefaultSetConfig {
+		notifications_protocol: BEEFY_PROTOCOL_NAME.into(),
+		max_notification_size: 1024 * 1024,
+		set_config: sc_network::config::SetConfig {
+			in_peers: 0,
+			out_peers: 0,
+			reserved_nodes: Vec::new(),
+			non_reserved_mode: sc_network::config::NonReservedPeerMode::Deny,
+		},
+	}
+}
+
+/// BEEFY gadget initialization parameters.
+pub struct BeefyParams<B, C, N>
+where
+	B: Block,
+{
+	/// BEEFY client
+	pub client: Arc<C>,
+	/// Local key store
+	pub key_store: Option<SyncCryptoStorePtr>,
+	/// Gossip network
+	pub network: N,
+	/// BEEFY signed commitment sender
+	pub signed_commitment_sender: notification::BeefySignedCommitmentSender<B>,
+	/// Minimal delta between blocks, BEEFY should vote for
+	pub min_block_delta: u32,
+	/// Prometheus metric registry
+	pub prometheus_registry: Option<Registry>,
+}
+
+/// Start the BEEFY gadget.
+///
+/// This is a thin shim around running and awaiting a BEEFY worker.
+pub async fn start_beefy_gadget<B, C, N>(beefy_params: BeefyParams<B, C, N>)
+where
+	B: Block,
+	C: BlockchainEvents<B> + HeaderBackend<B> + ProvideRuntimeApi<B>,
+	C::Api: BeefyApi<B>,
+	N: GossipNetwork<B> + Clone + Send + 'static,
+{
+	let BeefyParams {
+		client,
+		key_store,
+		network,
+		signed_commitment_sender,
+		min_block_delta,
+		prometheus_registry,
+	} = beefy_params;
+
+	let gossip_validator = Arc::new(gossip::GossipValidator::new());
+	let gossip_engine =
+		GossipEngine::new(network, BEEFY_PROTOCOL_NAME, gossip_validator.clone(), None);
+
+	let metrics = prometheus_registry.as_ref().map(metrics::Metrics::register).and_then(
+		|result| match result {
+			Ok(metrics) => {
+				debug!(target: "beefy", "🥩 Registered metrics");
+				Some(metrics)
+			}
+			Err(err) => {
+				debug!(target: "beefy", "🥩 Failed to register metrics: {:?}", err);
+				None
+			}
+		},
+	);
+
+	let worker_params = worker::WorkerParams {
+		client,
+		key_store: key_store.into(),
+		signed_commitment_sender,
+		gossip_engine,
+		gossip_validator,
+		min_block_delta,
+		metrics,
+	};
+
+	let worker = worker::BeefyWorker::new(worker_params);
+
+	worker.run().await
+}
@@ -0,0 +1,238 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use log::{debug, trace};
+
+use sp_beefy::{
+	crypto::{AuthorityId, AuthoritySignature},
+	ValidatorSet, ValidatorSetId,
+};
+use sp_runtime::traits::MaybeDisplay;
+
+/// The votes collected for a single round, i.e. for a single commitment.
+#[derive(Default)]
+struct RoundTracker {
+	votes: Vec<(AuthorityId, AuthoritySignature)>,
+}
+
+impl RoundTracker {
+	fn add_vote(&mut self, vote: (AuthorityId, AuthoritySignature)) -> bool {
+		// this needs to handle equivocations in the future
+		if self.votes.iter().any(|(id, _)| id == &vote.0) {
+			return false;
+		}
+
+		self.votes.push(vote);
+		true
+	}
+
+	fn is_done(&self, threshold: usize) -> bool {
+		self.votes.len() >= threshold
+	}
+}
+
+/// Minimum number of votes needed to conclude a round with `authorities` voters.
+///
+/// This is `n - f`, where `f` is the maximum number of faulty voters tolerated, i.e.
+/// strictly more than two thirds of the voters.
+pub(crate) fn threshold(authorities: usize) -> usize {
+	let faulty = authorities.saturating_sub(1) / 3;
+	authorities - faulty
+}
+
+/// The rounds in progress for the active validator set.
+///
+/// A round is identified by the commitment payload and the number of the block the payload was
+/// taken from.
+pub(crate) struct Rounds<Payload, Number> {
+	rounds: BTreeMap<(Payload, Number), RoundTracker>,
+	validator_set: ValidatorSet<AuthorityId>,
+}
+
+impl<Payload, Number> Rounds<Payload, Number>
+where
+	Payload: Ord,
+	Number: Ord + Copy + MaybeDisplay,
+{
+	pub(crate) fn new(validator_set: ValidatorSet<AuthorityId>) -> Self {
+		Rounds { rounds: BTreeMap::new(), validator_set }
+	}
+
+	pub(crate) fn validator_set_id(&self) -> ValidatorSetId {
+		self.validator_set.id
+	}
+
+	pub(crate) fn validators(&self) -> &[AuthorityId] {
+		&self.validator_set.validators
+	}
+
+	/// Add a vote for the given round.
+	///
+	/// Returns `false` if the vote was ignored, either because the voter is not part of the
+	/// validator set or because the voter already voted in this round.
+	pub(crate) fn add_vote(
+		&mut self,
+		round: (Payload, Number),
+		vote: (AuthorityId, AuthoritySignature),
+	) -> bool {
+		if !self.validator_set.validators.contains(&vote.0) {
+			debug!(target: "beefy", "🥩 ignoring vote from non-authority {:?}", vote.0);
+			return false;
+		}
+
+		self.rounds.entry(round).or_default().add_vote(vote)
+	}
+
+	pub(crate) fn is_done(&self, round: &(Payload, Number)) -> bool {
+		let done = self
+			.rounds
+			.get(round)
+			.map(|tracker| tracker.is_done(threshold(self.validator_set.validators.len())))
+			.unwrap_or(false);
+
+		trace!(target: "beefy", "🥩 Round #{} done: {}", round.1, done);
+
+		done
+	}
+
+	/// Conclude the given round.
+	///
+	/// Returns the collected signatures ordered like the validator set, with `None` for every
+	/// validator that did not vote. Rounds for older blocks are dropped as well, since the
+	/// concluded commitment supersedes them.
+	pub(crate) fn conclude(
+		&mut self,
+		round: &(Payload, Number),
+	) -> Option<Vec<Option<AuthoritySignature>>> {
+		trace!(target: "beefy", "🥩 Rounds: {:?}", self.rounds.len());
+
+		let signatures = self.rounds.remove(round)?;
+		let block_number = round.1;
+		self.rounds.retain(|(_, number), _| *number > block_number);
+
+		Some(
+			self.validator_set
+				.validators
+				.iter()
+				.map(|authority_id| {
+					signatures
+						.votes
+						.iter()
+						.find(|(id, _)| id == authority_id)
+						.map(|(_, sig)| sig.clone())
+				})
+				.collect(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use sp_core::{ecdsa, Pair};
+
+	use super::*;
+
+	fn authority(seed: &str) -> (ecdsa::Pair, AuthorityId) {
+		let pair = ecdsa::Pair::from_string(seed, None).unwrap();
+		let id = AuthorityId::from(pair.public());
+		(pair, id)
+	}
+
+	fn vote(pair: &ecdsa::Pair, id: &AuthorityId, msg: &[u8]) -> (AuthorityId, AuthoritySignature) {
+		(id.clone(), AuthoritySignature::from(pair.sign(msg)))
+	}
+
+	#[test]
+	fn threshold_works() {
+		assert_eq!(threshold(1), 1);
+		assert_eq!(threshold(2), 2);
+		assert_eq!(threshold(3), 3);
+		assert_eq!(threshold(4), 3);
+		assert_eq!(threshold(7), 5);
+		assert_eq!(threshold(100), 67);
+	}
+
+	#[test]
+	fn add_vote_works() {
+		let alice = authority("//Alice");
+		let bob = authority("//Bob");
+		let charlie = authority("//Charlie");
+		let dave = authority("//Dave");
+
+		let validators = vec![alice.1.clone(), bob.1.clone(), charlie.1.clone()];
+		let mut rounds = Rounds::<u64, u64>::new(ValidatorSet { validators, id: 42 });
+		assert_eq!(rounds.validator_set_id(), 42);
+
+		let round = (7, 10);
+
+		assert!(rounds.add_vote(round, vote(&alice.0, &alice.1, b"I am committed")));
+		assert!(!rounds.is_done(&round));
+
+		// votes are counted once per authority
+		assert!(!rounds.add_vote(round, vote(&alice.0, &alice.1, b"I am committed")));
+		// votes from outside the validator set are ignored
+		assert!(!rounds.add_vote(round, vote(&dave.0, &dave.1, b"I am committed")));
+		assert!(!rounds.is_done(&round));
+
+		assert!(rounds.add_vote(round, vote(&bob.0, &bob.1, b"I am committed")));
+		assert!(!rounds.is_done(&round));
+
+		assert!(rounds.add_vote(round, vote(&charlie.0, &charlie.1, b"I am committed")));
+		assert!(rounds.is_done(&round));
+	}
+
+	#[test]
+	fn conclude_works() {
+		let alice = authority("//Alice");
+		let bob = authority("//Bob");
+		let charlie = authority("//Charlie");
+		let dave = authority("//Dave");
+
+		let validators = vec![alice.1.clone(), bob.1.clone(), charlie.1.clone(), dave.1.clone()];
+		let mut rounds = Rounds::<u64, u64>::new(ValidatorSet { validators, id: 0 });
+
+		let old_round = (1, 5);
+		let round = (2, 10);
+		let new_round = (3, 15);
+
+		assert!(rounds.add_vote(old_round, vote(&alice.0, &alice.1, b"old")));
+		assert!(rounds.add_vote(new_round, vote(&alice.0, &alice.1, b"new")));
+		assert!(rounds.add_vote(round, vote(&charlie.0, &charlie.1, b"current")));
+		assert!(rounds.add_vote(round, vote(&alice.0, &alice.1, b"current")));
+		assert!(rounds.add_vote(round, vote(&dave.0, &dave.1, b"current")));
+		assert!(rounds.is_done(&round));
+
+		let signatures = rounds.conclude(&round).unwrap();
+		assert_eq!(
+			signatures,
+			vec![
+				Some(vote(&alice.0, &alice.1, b"current").1),
+				None,
+				Some(vote(&charlie.0, &charlie.1, b"current").1),
+				Some(vote(&dave.0, &dave.1, b"current").1),
+			],
+		);
+
+		// concluded and older rounds are gone, newer ones are kept
+		assert!(rounds.conclude(&round).is_none());
+		assert!(rounds.conclude(&old_round).is_none());
+		assert!(rounds.conclude(&new_round).is_some());
+	}
+}
@@ -0,0 +1,387 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use futures::{future, FutureExt, StreamExt};
+use log::{debug, error, info, trace, warn};
+use parking_lot::Mutex;
+
+use sc_client_api::{BlockchainEvents, FinalityNotification};
+use sc_network_gossip::GossipEngine;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::{BlockId, OpaqueDigestItemId},
+	traits::{AtLeast32BitUnsigned, Block, Header, NumberFor, SaturatedConversion},
+};
+
+use sp_beefy::{
+	crypto::{AuthorityId, AuthoritySignature},
+	BeefyApi, Commitment, ConsensusLog, MmrRootHash, SignedCommitment, ValidatorSet,
+	VoteMessage, BEEFY_ENGINE_ID,
+};
+
+use crate::{
+	gossip::{topic, GossipValidator, Vote},
+	keystore::BeefyKeystore,
+	metrics::Metrics,
+	notification::BeefySignedCommitmentSender,
+	round::Rounds,
+};
+
+pub(crate) struct WorkerParams<B, C>
+where
+	B: Block,
+{
+	pub client: Arc<C>,
+	pub key_store: BeefyKeystore,
+	pub signed_commitment_sender: BeefySignedCommitmentSender<B>,
+	pub gossip_engine: GossipEngine<B>,
+	pub gossip_validator: Arc<GossipValidator<B>>,
+	pub min_block_delta: u32,
+	pub metrics: Option<Metrics>,
+}
+
+/// A BEEFY worker plays the BEEFY protocol
+pub(crate) struct BeefyWorker<B, C>
+where
+	B: Block,
+{
+	client: Arc<C>,
+	key_store: BeefyKeystore,
+	signed_commitment_sender: BeefySignedCommitmentSender<B>,
+	gossip_engine: Arc<Mutex<GossipEngine<B>>>,
+	gossip_validator: Arc<GossipValidator<B>>,
+	/// Blocks BEEFY votes on are multiples of this value
+	min_block_delta: u32,
+	metrics: Option<Metrics>,
+	/// Voting rounds of the active validator set
+	rounds: Option<Rounds<MmrRootHash, NumberFor<B>>>,
+	/// Best block this node voted on
+	best_voted: Option<NumberFor<B>>,
+	/// Best block a BEEFY voting round has been concluded for
+	best_beefy_block: Option<NumberFor<B>>,
+}
+
+impl<B, C> BeefyWorker<B, C>
+where
+	B: Block,
+	C: BlockchainEvents<B> + HeaderBackend<B> + ProvideRuntimeApi<B>,
+	C::Api: BeefyApi<B>,
+{
+	/// Return a new BEEFY worker instance.
+	///
+	/// Note that a BEEFY worker is only fully functional if a corresponding
+	/// BEEFY pallet has been deployed on-chain.
+	///
+	/// The BEEFY pallet is needed in order to keep track of the BEEFY authority set.
+	pub(crate) fn new(worker_params: WorkerParams<B, C>) -> Self {
+		let WorkerParams {
+			client,
+			key_store,
+			signed_commitment_sender,
+			gossip_engine,
+			gossip_validator,
+			min_block_delta,
+			metrics,
+		} = worker_params;
+
+		BeefyWorker {
+			client,
+			key_store,
+			signed_commitment_sender,
+			gossip_engine: Arc::new(Mutex::new(gossip_engine)),
+			gossip_validator,
+			min_block_delta: min_block_delta.max(1),
+			metrics,
+			rounds: None,
+			best_voted: None,
+			best_beefy_block: None,
+		}
+	}
+
+	/// Return the block BEEFY should vote on after `number` has been finalized, if any.
+	fn vote_target(&self, number: NumberFor<B>) -> Option<NumberFor<B>> {
+		vote_target(number, self.min_block_delta, self.best_voted, self.best_beefy_block)
+	}
+
+	/// Switch to `validator_set` unless it is the active one already.
+	fn update_validator_set(&mut self, validator_set: ValidatorSet<AuthorityId>) {
+		let id = validator_set.id;
+		if self.rounds.as_ref().map_or(false, |rounds| rounds.validator_set_id() == id) {
+			return;
+		}
+
+		debug!(target: "beefy", "🥩 New active validator set id: {:?}", validator_set);
+		if let Some(metrics) = self.metrics.as_ref() {
+			metrics.beefy_validator_set_id.set(id);
+		}
+
+		self.rounds = Some(Rounds::new(validator_set));
+	}
+
+	fn handle_finality_notification(&mut self, notification: FinalityNotification<B>) {
+		trace!(target: "beefy", "🥩 Finality notification: {:?}", notification);
+
+		let number = *notification.header.number();
+		let target = match self.vote_target(number) {
+			Some(target) => target,
+			None => return,
+		};
+
+		let header = if target == number {
+			notification.header
+		} else {
+			match self.client.header(BlockId::Number(target)) {
+				Ok(Some(header)) => header,
+				Ok(None) => {
+					warn!(target: "beefy", "🥩 Missing header of finalized block #{}", target);
+					return;
+				}
+				Err(err) => {
+					warn!(target: "beefy", "🥩 Failed to fetch header #{}: {:?}", target, err);
+					return;
+				}
+			}
+		};
+
+		let at = BlockId::hash(header.hash());
+		let validator_set = match self.client.runtime_api().validator_set(&at) {
+			Ok(validator_set) => validator_set,
+			Err(err) => {
+				warn!(target: "beefy", "🥩 Failed to fetch validator set: {:?}", err);
+				return;
+			}
+		};
+
+		if validator_set.validators.is_empty() {
+			trace!(target: "beefy", "🥩 No BEEFY validators at #{}", target);
+			return;
+		}
+
+		self.update_validator_set(validator_set);
+		self.best_voted = Some(target);
+
+		if let Some(metrics) = self.metrics.as_ref() {
+			metrics.beefy_should_vote_on.set(target.saturated_into());
+		}
+
+		let mmr_root = match find_mmr_root_digest::<B>(&header) {
+			Some(root) => root,
+			None => {
+				warn!(target: "beefy", "🥩 No MMR root digest found for: {:?}", at);
+				return;
+			}
+		};
+
+		let rounds = self.rounds.as_ref().expect("validator set has been updated above; qed");
+		let authority_id = match self.key_store.authority_id(rounds.validators()) {
+			Some(id) => id,
+			None => {
+				trace!(target: "beefy", "🥩 Missing validator id - can't vote for: {:?}", at);
+				return;
+			}
+		};
+
+		let commitment = Commitment {
+			payload: mmr_root,
+			block_number: target,
+			validator_set_id: rounds.validator_set_id(),
+		};
+
+		let signature = match self.key_store.sign(&authority_id, &commitment.encode()) {
+			Ok(sig) => sig,
+			Err(err) => {
+				warn!(target: "beefy", "🥩 Error signing commitment: {:?}", err);
+				return;
+			}
+		};
+
+		let message = VoteMessage { commitment, id: authority_id, signature };
+		let encoded_message = message.encode();
+
+		if let Some(metrics) = self.metrics.as_ref() {
+			metrics.beefy_votes_sent.inc();
+		}
+
+		debug!(target: "beefy", "🥩 Sent vote message: {:?}", message);
+
+		self.gossip_engine.lock().gossip_message(topic::<B>(), encoded_message, false);
+		self.handle_vote(message);
+	}
+
+	fn handle_vote(&mut self, vote: Vote<B>) {
+		let VoteMessage { commitment, id, signature } = vote;
+		let round = (commitment.payload, commitment.block_number);
+
+		if self.best_beefy_block.map_or(false, |best| round.1 <= best) {
+			return;
+		}
+
+		let rounds = match self.rounds.as_mut() {
+			Some(rounds) if rounds.validator_set_id() == commitment.validator_set_id => rounds,
+			_ => {
+				debug!(
+					target: "beefy",
+					"🥩 Ignoring vote for inactive validator set id {}",
+					commitment.validator_set_id,
+				);
+				return;
+			}
+		};
+
+		if !rounds.add_vote(round, (id, signature)) || !rounds.is_done(&round) {
+			return;
+		}
+
+		let signatures: Vec<Option<AuthoritySignature>> = match rounds.conclude(&round) {
+			Some(signatures) => signatures,
+			None => return,
+		};
+
+		let signed_commitment = SignedCommitment { commitment, signatures };
+
+		info!(
+			target: "beefy",
+			"🥩 Round #{} concluded, committed: {:?}.",
+			round.1,
+			signed_commitment,
+		);
+
+		self.gossip_validator.note_concluded(round.1);
+		self.best_beefy_block = Some(round.1);
+
+		if let Some(metrics) = self.metrics.as_ref() {
+			metrics.beefy_round_concluded.set(round.1.saturated_into());
+			metrics.beefy_best_block.set(round.1.saturated_into());
+		}
+
+		self.signed_commitment_sender.notify(signed_commitment);
+	}
+
+	pub(crate) async fn run(mut self) {
+		let mut finality_notifications = self.client.finality_notification_stream().fuse();
+		let mut votes = Box::pin(
+			self.gossip_engine
+				.lock()
+				.messages_for(topic::<B>())
+				.filter_map(|notification| async move {
+					trace!(target: "beefy", "🥩 Got vote message: {:?}", notification);
+
+					Vote::<B>::decode(&mut &notification.message[..]).ok()
+				})
+				.fuse(),
+		);
+
+		loop {
+			let engine = self.gossip_engine.clone();
+			let gossip_engine = future::poll_fn(|cx| engine.lock().poll_unpin(cx));
+
+			futures::select! {
+				notification = finality_notifications.next() => {
+					if let Some(notification) = notification {
+						self.handle_finality_notification(notification);
+					} else {
+						return;
+					}
+				},
+				vote = votes.next() => {
+					if let Some(vote) = vote {
+						self.handle_vote(vote);
+					} else {
+						return;
+					}
+				},
+				_ = gossip_engine.fuse() => {
+					error!(target: "beefy", "🥩 Gossip engine has terminated.");
+					return;
+				}
+			}
+		}
+	}
+}
+
+/// Return the block BEEFY should vote on after `number` has been finalized, if any.
+///
+/// Voters pick the latest finalized multiple of `min_block_delta`, so that all of them
+/// vote on the same blocks even if they missed some finality notifications. Nothing is
+/// returned if that block has been voted on or concluded already.
+fn vote_target<N>(
+	number: N,
+	min_block_delta: u32,
+	best_voted: Option<N>,
+	best_beefy_block: Option<N>,
+) -> Option<N>
+where
+	N: AtLeast32BitUnsigned + Copy,
+{
+	let target = number - number % N::from(min_block_delta.max(1));
+
+	let already_done = |best: Option<N>| best.map_or(false, |b| target <= b);
+	if already_done(best_voted) || already_done(best_beefy_block) {
+		return None;
+	}
+
+	Some(target)
+}
+
+/// Extract the MMR root hash from a digest in the given header, if it exists.
+fn find_mmr_root_digest<B>(header: &B::Header) -> Option<MmrRootHash>
+where
+	B: Block,
+{
+	let id = OpaqueDigestItemId::Consensus(&BEEFY_ENGINE_ID);
+
+	let filter = |log: ConsensusLog<AuthorityId>| match log {
+		ConsensusLog::MmrRoot(root) => Some(root),
+		_ => None,
+	};
+
+	header.digest().convert_first(|l| l.try_to(id).and_then(filter))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn vote_target_picks_latest_multiple_of_min_block_delta() {
+		assert_eq!(vote_target(0u64, 1, None, None), Some(0));
+		assert_eq!(vote_target(7u64, 1, None, None), Some(7));
+		assert_eq!(vote_target(7u64, 4, None, None), Some(4));
+		assert_eq!(vote_target(8u64, 4, None, None), Some(8));
+		assert_eq!(vote_target(3u64, 4, None, None), Some(0));
+		// A delta of zero is treated as one.
+		assert_eq!(vote_target(7u64, 0, None, None), Some(7));
+	}
+
+	#[test]
+	fn vote_target_skips_blocks_voted_on_or_concluded() {
+		// Already voted on the target, or on a later block.
+		assert_eq!(vote_target(9u64, 4, Some(8), None), None);
+		assert_eq!(vote_target(9u64, 4, Some(12), None), None);
+		assert_eq!(vote_target(13u64, 4, Some(8), None), Some(12));
+
+		// A round concluded for the target, or for a later block.
+		assert_eq!(vote_target(9u64, 4, None, Some(8)), None);
+		assert_eq!(vote_target(9u64, 4, Some(4), Some(8)), None);
+		assert_eq!(vote_target(13u64, 4, Some(4), Some(8)), Some(12));
+	}
+}
@@ -29,6 +29,8 @@ arg_enum! {
 		Interpreted,
 		// Uses a compiled runtime.
 		Compiled,
+		// Uses a compiled runtime with a pool of pre-created instances.
+		CompiledPooled,
 	}
 }
 
@@ -38,7 +40,7 @@ impl WasmExecutionMethod {
 		Self::variants()
 			.iter()
 			.cloned()
-			.filter(|&name| cfg!(feature = "wasmtime") || name != "Compiled")
+			.filter(|&name| cfg!(feature = "wasmtime") || name == "Interpreted")
 			.collect()
 	}
 }
@@ -51,8 +53,11 @@ impl Into<sc_service::config::WasmExecutionMethod> for WasmExecutionMethod {
 			}
 			#[cfg(feature = "wasmtime")]
 			WasmExecutionMethod::Compiled => sc_service::config::WasmExecutionMethod::Compiled,
+			#[cfg(feature = "wasmtime")]
+			WasmExecutionMethod::CompiledPooled =>
+				sc_service::config::WasmExecutionMethod::CompiledPooled,
 			#[cfg(not(feature = "wasmtime"))]
-			WasmExecutionMethod::Compiled => panic!(
+			WasmExecutionMethod::Compiled | WasmExecutionMethod::CompiledPooled => panic!(
 				"Substrate must be compiled with \"wasmtime\" feature for compiled Wasm execution"
 			),
 		}
@@ -132,7 +132,9 @@ pub struct AuraVerifier<C, P, CAW> {
 }
 
 impl<C, P, CAW> AuraVerifier<C, P, CAW> {
-	pub(crate) fn new(
+	/// Create a new verifier. The inherent data providers must provide the timestamp and the
+	/// Aura slot of the current time.
+	pub fn new(
 		client: Arc<C>,
 		inherent_data_providers: InherentDataProviders,
 		can_author_with: CAW,
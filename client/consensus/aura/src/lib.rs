@@ -55,7 +55,9 @@ use sp_core::crypto::Pair;
 use sp_keystore::{SyncCryptoStorePtr, SyncCryptoStore};
 use sp_inherents::{InherentDataProviders, InherentData};
 use sp_timestamp::{TimestampInherentData, InherentType as TimestampInherent};
-use sc_consensus_slots::{SlotInfo, SlotCompatible, StorageChanges, BackoffAuthoringBlocksStrategy};
+use sc_consensus_slots::{
+	SlotInfo, SlotCompatible, StorageChanges, BackoffAuthoringBlocksStrategy, BlockImportDuration,
+};
 use sp_consensus_slots::Slot;
 
 mod import_queue;
@@ -68,7 +70,9 @@ pub use sp_consensus_aura::{
 	},
 };
 pub use sp_consensus::SyncOracle;
-pub use import_queue::{ImportQueueParams, import_queue, AuraBlockImport, CheckForEquivocation};
+pub use import_queue::{
+	ImportQueueParams, import_queue, AuraBlockImport, AuraVerifier, CheckForEquivocation,
+};
 pub use sc_consensus_slots::SlotProportion;
 
 type AuthorityId<P> = <P as Pair>::Public;
@@ -149,6 +153,9 @@ pub struct StartAuraParams<C, SC, I, PF, SO, BS, CAW> {
 	/// slot. However, the proposing can still take longer when there is some lenience factor applied,
 	/// because there were no blocks produced for some slots.
 	pub block_proposal_slot_portion: SlotProportion,
+	/// The maximum proportion of the slot dedicated to proposing with any lenience factor applied
+	/// due to no blocks being produced.
+	pub max_block_proposal_slot_portion: Option<SlotProportion>,
 }
 
 /// Start the aura worker. The returned future should be run in a futures executor.
@@ -166,6 +173,7 @@ pub fn start_aura<P, B, C, SC, PF, I, SO, CAW, BS, Error>(
 		keystore,
 		can_author_with,
 		block_proposal_slot_portion,
+		max_block_proposal_slot_portion,
 	}: StartAuraParams<C, SC, I, PF, SO, BS, CAW>,
 ) -> Result<impl Future<Output = ()>, sp_consensus::Error> where
 	B: BlockT,
@@ -193,6 +201,8 @@ pub fn start_aura<P, B, C, SC, PF, I, SO, CAW, BS, Error>(
 		backoff_authoring_blocks,
 		_key_type: PhantomData::<P>,
 		block_proposal_slot_portion,
+		max_block_proposal_slot_portion,
+		block_import_duration: Default::default(),
 	};
 	register_aura_inherent_data_provider(
 		&inherent_data_providers,
@@ -218,6 +228,8 @@ struct AuraWorker<C, E, I, P, SO, BS> {
 	force_authoring: bool,
 	backoff_authoring_blocks: Option<BS>,
 	block_proposal_slot_portion: SlotProportion,
+	max_block_proposal_slot_portion: Option<SlotProportion>,
+	block_import_duration: BlockImportDuration,
 	_key_type: PhantomData<P>,
 }
 
@@ -376,38 +388,25 @@ where
 		head: &B::Header,
 		slot_info: &SlotInfo,
 	) -> std::time::Duration {
-		let max_proposing = slot_info.duration.mul_f32(self.block_proposal_slot_portion.get());
-
-		let slot_remaining = slot_info.ends_at
-			.checked_duration_since(std::time::Instant::now())
-			.unwrap_or_default();
-
-		let slot_remaining = std::cmp::min(slot_remaining, max_proposing);
-
 		// If parent is genesis block, we don't require any lenience factor.
-		if head.number().is_zero() {
-			return slot_remaining
-		}
-
-		let parent_slot = match find_pre_digest::<B, P::Signature>(head) {
-			Err(_) => return slot_remaining,
-			Ok(d) => d,
+		let parent_slot = if head.number().is_zero() {
+			None
+		} else {
+			find_pre_digest::<B, P::Signature>(head).ok()
 		};
 
-		if let Some(slot_lenience) =
-			sc_consensus_slots::slot_lenience_exponential(parent_slot, slot_info)
-		{
-			debug!(
-				target: "aura",
-				"No block for {} slots. Applying linear lenience of {}s",
-				slot_info.slot.saturating_sub(parent_slot + 1),
-				slot_lenience.as_secs(),
-			);
+		sc_consensus_slots::proposing_remaining_duration(
+			parent_slot,
+			slot_info,
+			&self.block_proposal_slot_portion,
+			self.max_block_proposal_slot_portion.as_ref(),
+			self.block_import_duration.last(),
+			self.logging_target(),
+		)
+	}
 
-			slot_remaining + slot_lenience
-		} else {
-			slot_remaining
-		}
+	fn block_import_duration(&self) -> Option<BlockImportDuration> {
+		Some(self.block_import_duration.clone())
 	}
 }
 
@@ -670,6 +669,7 @@ mod tests {
 				keystore,
 				can_author_with: sp_consensus::AlwaysCanAuthor,
 				block_proposal_slot_portion: SlotProportion::new(0.5),
+				max_block_proposal_slot_portion: None,
 			}).expect("Starts aura"));
 		}
 
@@ -731,6 +731,8 @@ mod tests {
 			backoff_authoring_blocks: Some(BackoffAuthoringOnFinalizedHeadLagging::default()),
 			_key_type: PhantomData::<AuthorityPair>,
 			block_proposal_slot_portion: SlotProportion::new(0.5),
+			max_block_proposal_slot_portion: None,
+			block_import_duration: Default::default(),
 		};
 
 		let head = Header::new(
@@ -779,6 +781,8 @@ mod tests {
 			backoff_authoring_blocks: Option::<()>::None,
 			_key_type: PhantomData::<AuthorityPair>,
 			block_proposal_slot_portion: SlotProportion::new(0.5),
+			max_block_proposal_slot_portion: None,
+			block_import_duration: Default::default(),
 		};
 
 		let head = client.header(&BlockId::Number(0)).unwrap().unwrap();
@@ -796,5 +800,7 @@ mod tests {
 
 		// The returned block should be imported and we should be able to get its header by now.
 		assert!(client.header(&BlockId::Hash(res.block.hash())).unwrap().is_some());
+		// and the time it took to import it is set aside when proposing the next one.
+		assert!(worker.block_import_duration.last().is_some());
 	}
 }
@@ -29,10 +29,12 @@ use sc_consensus_epochs::{descendent_query, Epoch as EpochT, SharedEpochChanges}
 use sp_consensus_babe::{
 	AuthorityId,
 	BabeApi as BabeRuntimeApi,
+	BabeEpochConfiguration,
 	digests::PreDigest,
 };
 use serde::{Deserialize, Serialize};
 use sp_core::{
+	Bytes,
 	crypto::Public,
 };
 use sp_application_crypto::AppKey;
@@ -42,7 +44,10 @@ use sp_api::{ProvideRuntimeApi, BlockId};
 use sp_runtime::traits::{Block as BlockT, Header as _};
 use sp_consensus::{SelectChain, Error as ConsensusError};
 use sp_blockchain::{HeaderBackend, HeaderMetadata, Error as BlockChainError};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::{BTreeMap, HashMap}, sync::Arc};
+
+/// The maximum number of epochs that can be requested in a single `babe_epochs` call.
+const MAX_EPOCHS_PER_REQUEST: u64 = 256;
 
 type FutureResult<T> = Box<dyn rpc_future::Future<Item = T, Error = RpcError> + Send>;
 
@@ -53,6 +58,18 @@ pub trait BabeApi {
 	/// with the keys in the keystore.
 	#[rpc(name = "babe_epochAuthorship")]
 	fn epoch_authorship(&self) -> FutureResult<HashMap<AuthorityId, EpochAuthorship>>;
+
+	/// Returns the epochs with an index in `from..=to` that are known on the best chain, along
+	/// with the configuration changes that took effect at any of them.
+	///
+	/// Epochs which were already pruned from the epoch changes tree, or which were not
+	/// announced yet, are omitted.
+	#[rpc(name = "babe_epochs")]
+	fn epochs(&self, from: u64, to: u64) -> FutureResult<EpochHistory>;
+
+	/// Returns the secondary slot assignments of every authority of the current epoch.
+	#[rpc(name = "babe_authoringSchedule")]
+	fn authoring_schedule(&self) -> FutureResult<AuthoringSchedule>;
 }
 
 /// Implements the BabeRpc trait for interacting with Babe.
@@ -118,19 +135,7 @@ impl<B, C, SC> BabeApi for BabeRpcHandler<B, C, SC>
 			self.select_chain.clone(),
 		);
 		let future = async move {
-			let header = select_chain.best_chain().map_err(Error::Consensus)?;
-			let epoch_start = client.runtime_api()
-				.current_epoch_start(&BlockId::Hash(header.hash()))
-				.map_err(|err| {
-					Error::StringError(format!("{:?}", err))
-				})?;
-			let epoch = epoch_data(
-				&shared_epoch,
-				&client,
-				&babe_config,
-				*epoch_start,
-				&select_chain,
-			)?;
+			let (_, epoch) = current_epoch(&shared_epoch, &client, &babe_config, &select_chain)?;
 			let (epoch_start, epoch_end) = (epoch.start_slot(), epoch.end_slot());
 
 			let mut claims: HashMap<AuthorityId, EpochAuthorship> = HashMap::new();
@@ -171,6 +176,119 @@ impl<B, C, SC> BabeApi for BabeRpcHandler<B, C, SC>
 
 		Box::new(future.compat())
 	}
+
+	fn epochs(&self, from: u64, to: u64) -> FutureResult<EpochHistory> {
+		let (babe_config, shared_epoch, client, select_chain) = (
+			self.babe_config.clone(),
+			self.shared_epoch_changes.clone(),
+			self.client.clone(),
+			self.select_chain.clone(),
+		);
+		let future = async move {
+			if from > to {
+				return Err(Error::StringError(
+					format!("Invalid epoch range: {} is greater than {}", from, to),
+				));
+			}
+			if to - from >= MAX_EPOCHS_PER_REQUEST {
+				return Err(Error::StringError(format!(
+					"Too many epochs requested, at most {} can be queried at once",
+					MAX_EPOCHS_PER_REQUEST,
+				)));
+			}
+
+			let (header, current) =
+				current_epoch(&shared_epoch, &client, &babe_config, &select_chain)?;
+
+			// we also look up the epoch preceding `from` in order to tell whether the
+			// configuration changed at `from`.
+			let mut previous = match from.checked_sub(1) {
+				Some(index) => epoch_by_index(
+					&shared_epoch,
+					&client,
+					&babe_config,
+					&header,
+					&current,
+					index,
+				)?,
+				None => None,
+			};
+
+			let mut history = EpochHistory::default();
+			for index in from..=to {
+				let epoch = epoch_by_index(
+					&shared_epoch,
+					&client,
+					&babe_config,
+					&header,
+					&current,
+					index,
+				)?;
+
+				if let Some(epoch) = &epoch {
+					let changed = previous.as_ref()
+						.map_or(false, |previous| previous.config != epoch.config);
+					if changed {
+						history.config_changes.push(EpochConfigChange {
+							epoch_index: epoch.epoch_index,
+							start_slot: *epoch.start_slot,
+							config: epoch.config.clone().into(),
+						});
+					}
+					history.epochs.push(epoch.into());
+				}
+
+				previous = epoch;
+			}
+
+			Ok(history)
+		}.boxed();
+
+		Box::new(future.compat())
+	}
+
+	fn authoring_schedule(&self) -> FutureResult<AuthoringSchedule> {
+		let (babe_config, shared_epoch, client, select_chain) = (
+			self.babe_config.clone(),
+			self.shared_epoch_changes.clone(),
+			self.client.clone(),
+			self.select_chain.clone(),
+		);
+		let future = async move {
+			let (_, epoch) = current_epoch(&shared_epoch, &client, &babe_config, &select_chain)?;
+
+			let mut secondary: BTreeMap<AuthorityId, Vec<u64>> = epoch.authorities.iter()
+				.map(|(authority, _)| (authority.clone(), Vec::new()))
+				.collect();
+
+			let allowed_slots = epoch.config.allowed_slots;
+			if allowed_slots.is_secondary_plain_slots_allowed() ||
+				allowed_slots.is_secondary_vrf_slots_allowed()
+			{
+				for slot in *epoch.start_slot()..*epoch.end_slot() {
+					let author = authorship::secondary_slot_author(
+						slot.into(),
+						&epoch.authorities,
+						epoch.randomness,
+					);
+
+					if let Some(author) = author {
+						secondary.entry(author.clone()).or_default().push(slot);
+					}
+				}
+			}
+
+			Ok(AuthoringSchedule {
+				epoch_index: epoch.epoch_index,
+				start_slot: *epoch.start_slot(),
+				end_slot: *epoch.end_slot(),
+				allowed_slots: allowed_slots.into(),
+				secondary,
+			})
+		}.boxed();
+
+		Box::new(future.compat())
+	}
 }
 
 /// Holds information about the `slot`'s that can be claimed by a given key.
@@ -184,6 +302,115 @@ pub struct EpochAuthorship {
 	secondary_vrf: Vec<u64>,
 }
 
+/// The epochs known on the best chain within a requested range.
+#[derive(Default, Debug, Deserialize, Serialize)]
+pub struct EpochHistory {
+	/// The known epochs, in ascending order of their index.
+	epochs: Vec<EpochDescriptor>,
+	/// The configuration changes that took effect at any of the returned epochs.
+	config_changes: Vec<EpochConfigChange>,
+}
+
+/// Describes a single BABE epoch.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EpochDescriptor {
+	/// The epoch index.
+	epoch_index: u64,
+	/// The starting slot of the epoch.
+	start_slot: u64,
+	/// The number of slots in the epoch.
+	duration: u64,
+	/// The authorities and their weights.
+	authorities: Vec<(AuthorityId, u64)>,
+	/// The randomness of the epoch.
+	randomness: Bytes,
+	/// The configuration of the epoch.
+	config: EpochConfiguration,
+}
+
+impl<'a> From<&'a Epoch> for EpochDescriptor {
+	fn from(epoch: &'a Epoch) -> Self {
+		EpochDescriptor {
+			epoch_index: epoch.epoch_index,
+			start_slot: *epoch.start_slot,
+			duration: epoch.duration,
+			authorities: epoch.authorities.clone(),
+			randomness: epoch.randomness.to_vec().into(),
+			config: epoch.config.clone().into(),
+		}
+	}
+}
+
+/// A configuration change, as announced by a `NextConfigDescriptor`, that took effect at the
+/// start of an epoch.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EpochConfigChange {
+	/// The index of the first epoch using the new configuration.
+	epoch_index: u64,
+	/// The starting slot of that epoch.
+	start_slot: u64,
+	/// The new configuration.
+	config: EpochConfiguration,
+}
+
+/// The configuration of a BABE epoch.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EpochConfiguration {
+	/// The constant `c` used in the primary slot threshold calculation, as a rational.
+	c: (u64, u64),
+	/// The types of slots that are allowed to be claimed.
+	allowed_slots: AllowedSlots,
+}
+
+impl From<BabeEpochConfiguration> for EpochConfiguration {
+	fn from(config: BabeEpochConfiguration) -> Self {
+		EpochConfiguration {
+			c: config.c,
+			allowed_slots: config.allowed_slots.into(),
+		}
+	}
+}
+
+/// Types of slots that can be claimed within an epoch.
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AllowedSlots {
+	/// Only primary slots.
+	PrimarySlots,
+	/// Primary and secondary plain slots.
+	PrimaryAndSecondaryPlainSlots,
+	/// Primary and secondary VRF slots.
+	PrimaryAndSecondaryVRFSlots,
+}
+
+impl From<sp_consensus_babe::AllowedSlots> for AllowedSlots {
+	fn from(allowed_slots: sp_consensus_babe::AllowedSlots) -> Self {
+		match allowed_slots {
+			sp_consensus_babe::AllowedSlots::PrimarySlots =>
+				AllowedSlots::PrimarySlots,
+			sp_consensus_babe::AllowedSlots::PrimaryAndSecondaryPlainSlots =>
+				AllowedSlots::PrimaryAndSecondaryPlainSlots,
+			sp_consensus_babe::AllowedSlots::PrimaryAndSecondaryVRFSlots =>
+				AllowedSlots::PrimaryAndSecondaryVRFSlots,
+		}
+	}
+}
+
+/// The secondary slot assignments of all authorities in the current epoch.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AuthoringSchedule {
+	/// The index of the current epoch.
+	epoch_index: u64,
+	/// The first slot of the epoch.
+	start_slot: u64,
+	/// The end slot of the epoch (exclusive).
+	end_slot: u64,
+	/// The types of slots that are allowed to be claimed in the epoch.
+	allowed_slots: AllowedSlots,
+	/// The secondary slots assigned to each authority. Empty if the epoch doesn't allow
+	/// secondary slots.
+	secondary: BTreeMap<AuthorityId, Vec<u64>>,
+}
+
 /// Errors encountered by the RPC
 #[derive(Debug, derive_more::Display, derive_more::From)]
 pub enum Error {
@@ -203,20 +430,82 @@ impl From<Error> for jsonrpc_core::Error {
 	}
 }
 
-/// fetches the epoch data for a given slot.
-fn epoch_data<B, C, SC>(
+/// fetches the best block and the current epoch on top of it.
+fn current_epoch<B, C, SC>(
 	epoch_changes: &SharedEpochChanges<B, Epoch>,
 	client: &Arc<C>,
 	babe_config: &Config,
-	slot: u64,
 	select_chain: &SC,
-) -> Result<Epoch, Error>
+) -> Result<(B::Header, Epoch), Error>
 	where
 		B: BlockT,
-		C: HeaderBackend<B> + HeaderMetadata<B, Error=BlockChainError> + 'static,
+		C: ProvideRuntimeApi<B> + HeaderBackend<B> + HeaderMetadata<B, Error=BlockChainError>,
+		C: 'static,
+		C::Api: BabeRuntimeApi<B>,
 		SC: SelectChain<B>,
 {
-	let parent = select_chain.best_chain()?;
+	let header = select_chain.best_chain().map_err(Error::Consensus)?;
+	let epoch_start = client.runtime_api()
+		.current_epoch_start(&BlockId::Hash(header.hash()))
+		.map_err(|err| {
+			Error::StringError(format!("{:?}", err))
+		})?;
+	let epoch = epoch_data(epoch_changes, client, babe_config, &header, *epoch_start)?
+		.ok_or(Error::Consensus(ConsensusError::InvalidAuthoritiesSet))?;
+
+	Ok((header, epoch))
+}
+
+/// fetches the epoch with the given index on the chain of `parent`, if it is still known.
+///
+/// Epochs are contiguous, so the epoch is looked up by the slot it is expected to start at,
+/// relative to the `current` one.
+fn epoch_by_index<B, C>(
+	epoch_changes: &SharedEpochChanges<B, Epoch>,
+	client: &Arc<C>,
+	babe_config: &Config,
+	parent: &B::Header,
+	current: &Epoch,
+	index: u64,
+) -> Result<Option<Epoch>, Error>
+	where
+		B: BlockT,
+		C: HeaderBackend<B> + HeaderMetadata<B, Error=BlockChainError> + 'static,
+{
+	if index == current.epoch_index {
+		return Ok(Some(current.clone()));
+	}
+
+	let start_slot = if index > current.epoch_index {
+		(index - current.epoch_index).checked_mul(current.duration)
+			.and_then(|offset| current.start_slot.checked_add(offset))
+	} else {
+		(current.epoch_index - index).checked_mul(current.duration)
+			.and_then(|offset| current.start_slot.checked_sub(offset))
+	};
+	let start_slot = match start_slot {
+		Some(start_slot) => start_slot,
+		None => return Ok(None),
+	};
+
+	let epoch = epoch_data(epoch_changes, client, babe_config, parent, start_slot)?;
+
+	// the lookup yields the closest known epoch, which is not necessarily the one requested.
+	Ok(epoch.filter(|epoch| epoch.epoch_index == index && *epoch.start_slot == start_slot))
+}
+
+/// fetches the epoch data for a given slot on top of `parent`.
+fn epoch_data<B, C>(
+	epoch_changes: &SharedEpochChanges<B, Epoch>,
+	client: &Arc<C>,
+	babe_config: &Config,
+	parent: &B::Header,
+	slot: u64,
+) -> Result<Option<Epoch>, Error>
+	where
+		B: BlockT,
+		C: HeaderBackend<B> + HeaderMetadata<B, Error=BlockChainError> + 'static,
+{
 	epoch_changes.lock().epoch_data_for_child_of(
 		descendent_query(&**client),
 		&parent.hash(),
@@ -224,8 +513,7 @@ fn epoch_data<B, C, SC>(
 		slot.into(),
 		|slot| Epoch::genesis(&babe_config, slot),
 	)
-		.map_err(|e| Error::Consensus(ConsensusError::ChainLookup(format!("{:?}", e))))?
-		.ok_or(Error::Consensus(ConsensusError::InvalidAuthoritiesSet))
+		.map_err(|e| Error::Consensus(ConsensusError::ChainLookup(format!("{:?}", e))))
 }
 
 #[cfg(test)]
@@ -314,4 +602,74 @@ mod tests {
 
 		assert_eq!(error, RpcError::method_not_found())
 	}
+
+	#[test]
+	fn epochs_returns_known_epochs_in_range() {
+		let handler = test_babe_rpc_handler(DenyUnsafe::Yes);
+		let mut io = IoHandler::new();
+
+		io.extend_with(BabeApi::to_delegate(handler));
+		let request = r#"{"jsonrpc":"2.0","method":"babe_epochs","params":[0,5],"id":1}"#;
+
+		let response = io.handle_request_sync(request).unwrap();
+		let mut response: serde_json::Value = serde_json::from_str(&response).unwrap();
+		let history: EpochHistory = serde_json::from_value(response["result"].take()).unwrap();
+
+		// only the genesis epoch is known before any block is imported.
+		assert_eq!(history.epochs.len(), 1);
+		assert!(history.config_changes.is_empty());
+
+		let epoch = &history.epochs[0];
+		assert_eq!(epoch.epoch_index, 0);
+		assert_eq!(epoch.start_slot, 0);
+		assert_eq!(epoch.config.c, (3, 10));
+		assert_eq!(epoch.config.allowed_slots, AllowedSlots::PrimaryAndSecondaryPlainSlots);
+		let alice: AuthorityId = Sr25519Keyring::Alice.public().into();
+		assert!(epoch.authorities.iter().any(|(id, _)| *id == alice));
+	}
+
+	#[test]
+	fn epochs_rejects_invalid_ranges() {
+		let handler = test_babe_rpc_handler(DenyUnsafe::No);
+		let mut io = IoHandler::new();
+
+		io.extend_with(BabeApi::to_delegate(handler));
+
+		for params in &["[5,0]", "[0,256]"] {
+			let request = format!(
+				r#"{{"jsonrpc":"2.0","method":"babe_epochs","params":{},"id":1}}"#,
+				params,
+			);
+
+			let response = io.handle_request_sync(&request).unwrap();
+			let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+			assert!(response["error"].is_object());
+		}
+	}
+
+	#[test]
+	fn authoring_schedule_assigns_every_slot() {
+		let handler = test_babe_rpc_handler(DenyUnsafe::Yes);
+		let mut io = IoHandler::new();
+
+		io.extend_with(BabeApi::to_delegate(handler));
+		let request = r#"{"jsonrpc":"2.0","method":"babe_authoringSchedule","params":[],"id":1}"#;
+
+		let response = io.handle_request_sync(request).unwrap();
+		let mut response: serde_json::Value = serde_json::from_str(&response).unwrap();
+		let schedule: AuthoringSchedule =
+			serde_json::from_value(response["result"].take()).unwrap();
+
+		assert_eq!(schedule.epoch_index, 0);
+		assert_eq!(schedule.allowed_slots, AllowedSlots::PrimaryAndSecondaryPlainSlots);
+
+		let mut slots = schedule.secondary.values().flatten().cloned().collect::<Vec<_>>();
+		slots.sort();
+		assert_eq!(slots, (schedule.start_slot..schedule.end_slot).collect::<Vec<_>>());
+
+		// matches the secondary claims reported by `babe_epochAuthorship`.
+		let alice: AuthorityId = Sr25519Keyring::Alice.public().into();
+		let alice = schedule.secondary.get(&alice).unwrap();
+		assert!([1, 2, 4].iter().all(|slot| alice.contains(slot)));
+	}
 }
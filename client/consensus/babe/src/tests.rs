@@ -432,6 +432,7 @@ fn run_one_test(
 			keystore,
 			can_author_with: sp_consensus::AlwaysCanAuthor,
 			block_proposal_slot_portion: SlotProportion::new(0.5),
+			max_block_proposal_slot_portion: None,
 		}).expect("Starts babe"));
 	}
 	futures::executor::block_on(future::select(
@@ -0,0 +1,223 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Pluggable fork-choice rules.
+//!
+//! A [`ForkChoiceRule`] decides which of the current leaves is the best chain to build upon,
+//! and which leaves should not be built upon at all. [`ForkChoice`] turns any such rule into
+//! a [`SelectChain`] implementation that can be handed to consensus engines.
+
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use sc_client_api::backend;
+use sp_consensus::{SelectChain, Error as ConsensusError};
+use sp_blockchain::{Backend as _, HeaderBackend, tree_route};
+use sp_runtime::{
+	traits::{NumberFor, Block as BlockT, Header as HeaderT},
+	generic::BlockId,
+};
+
+/// A rule used to choose the best chain among the leaves of the block tree.
+///
+/// Rules are handed the backend, which gives them access to both the block tree and to the
+/// auxiliary data stored by consensus engines on import (e.g. the total difficulty of a chain).
+pub trait ForkChoiceRule<B, Block: BlockT>: Send + Sync {
+	/// Whether the chain ending at `head` may be built upon at all.
+	///
+	/// Leaves that are not viable are never returned as the best chain or as a finality target.
+	fn is_viable(&self, _backend: &B, _head: &Block::Header) -> Result<bool, ConsensusError> {
+		Ok(true)
+	}
+
+	/// Compare the chains ending at `a` and `b`, both of which are viable.
+	///
+	/// `Ordering::Greater` means that the chain ending at `a` is preferred over the one ending
+	/// at `b`. Ties are broken in favour of the leaf that comes first in the backend.
+	fn compare(
+		&self,
+		backend: &B,
+		a: &Block::Header,
+		b: &Block::Header,
+	) -> Result<Ordering, ConsensusError>;
+}
+
+impl<B, Block: BlockT, R: ForkChoiceRule<B, Block> + ?Sized> ForkChoiceRule<B, Block> for Arc<R> {
+	fn is_viable(&self, backend: &B, head: &Block::Header) -> Result<bool, ConsensusError> {
+		(**self).is_viable(backend, head)
+	}
+
+	fn compare(
+		&self,
+		backend: &B,
+		a: &Block::Header,
+		b: &Block::Header,
+	) -> Result<Ordering, ConsensusError> {
+		(**self).compare(backend, a, b)
+	}
+}
+
+/// Fork-choice rule where the best chain is the one with the highest number of blocks.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LongestChainRule;
+
+impl<B, Block: BlockT> ForkChoiceRule<B, Block> for LongestChainRule {
+	fn compare(
+		&self,
+		_backend: &B,
+		a: &Block::Header,
+		b: &Block::Header,
+	) -> Result<Ordering, ConsensusError> {
+		Ok(a.number().cmp(b.number()))
+	}
+}
+
+/// Implements `SelectChain` on top of a given fork-choice rule.
+pub struct ForkChoice<B, Block, R> {
+	backend: Arc<B>,
+	rule: Arc<R>,
+	_phantom: PhantomData<Block>
+}
+
+impl<B, Block, R> Clone for ForkChoice<B, Block, R> {
+	fn clone(&self) -> Self {
+		ForkChoice {
+			backend: self.backend.clone(),
+			rule: self.rule.clone(),
+			_phantom: Default::default()
+		}
+	}
+}
+
+impl<B, Block, R> ForkChoice<B, Block, R>
+	where
+		B: backend::Backend<Block>,
+		Block: BlockT,
+		R: ForkChoiceRule<B, Block>,
+{
+	/// Instantiate a new ForkChoice for Backend B, using the given rule.
+	pub fn new(backend: Arc<B>, rule: R) -> Self {
+		ForkChoice {
+			backend,
+			rule: Arc::new(rule),
+			_phantom: Default::default()
+		}
+	}
+
+	/// Get a reference to the fork-choice rule.
+	pub fn rule(&self) -> &R {
+		&self.rule
+	}
+
+	/// Returns the headers of all viable leaves, best chain first.
+	fn viable_leaves(&self) -> Result<Vec<Block::Header>, ConsensusError> {
+		let leaves = {
+			// an import could add or displace leaves while we're looking them up.
+			let _import_guard = self.backend.get_import_lock().read();
+			self.backend.blockchain().leaves().map_err(chain_lookup)?
+		};
+
+		let mut viable = Vec::with_capacity(leaves.len());
+		for hash in leaves {
+			let header = self.header(hash)?;
+			if self.rule.is_viable(&self.backend, &header)? {
+				viable.push(header);
+			}
+		}
+
+		let mut error = None;
+		viable.sort_by(|a, b| match self.rule.compare(&self.backend, b, a) {
+			Ok(ordering) => ordering,
+			Err(e) => {
+				error.get_or_insert(e);
+				Ordering::Equal
+			},
+		});
+
+		match error {
+			Some(e) => Err(e),
+			None => Ok(viable),
+		}
+	}
+
+	fn header(&self, hash: Block::Hash) -> Result<Block::Header, ConsensusError> {
+		self.backend.blockchain().header(BlockId::Hash(hash))
+			.map_err(chain_lookup)?
+			.ok_or_else(|| ConsensusError::ChainLookup(format!("Missing header {}", hash)))
+	}
+}
+
+impl<B, Block, R> SelectChain<Block> for ForkChoice<B, Block, R>
+	where
+		B: backend::Backend<Block>,
+		Block: BlockT,
+		R: ForkChoiceRule<B, Block>,
+{
+	fn leaves(&self) -> Result<Vec<<Block as BlockT>::Hash>, ConsensusError> {
+		Ok(self.viable_leaves()?.iter().map(|header| header.hash()).collect())
+	}
+
+	fn best_chain(&self) -> Result<<Block as BlockT>::Header, ConsensusError> {
+		match self.viable_leaves()?.into_iter().next() {
+			Some(header) => Ok(header),
+			// the finalized chain is always safe to build upon.
+			None => self.header(self.backend.blockchain().info().finalized_hash),
+		}
+	}
+
+	fn finality_target(
+		&self,
+		target_hash: Block::Hash,
+		maybe_max_number: Option<NumberFor<Block>>
+	) -> Result<Option<Block::Hash>, ConsensusError> {
+		let target_header = match self.backend.blockchain().header(BlockId::Hash(target_hash)) {
+			Ok(Some(header)) => header,
+			Ok(None) => return Ok(None),
+			Err(e) => return Err(chain_lookup(e)),
+		};
+
+		if maybe_max_number.map_or(false, |max_number| *target_header.number() > max_number) {
+			return Ok(None);
+		}
+
+		// for each viable chain, best first, find the first one containing the target.
+		for leaf in self.viable_leaves()? {
+			let mut best = leaf;
+			if let Some(max_number) = maybe_max_number {
+				while *best.number() > max_number {
+					best = self.header(*best.parent_hash())?;
+				}
+			}
+
+			let contains_target = best.hash() == target_hash ||
+				tree_route::<Block, _>(self.backend.blockchain(), target_hash, best.hash())
+					.map_err(chain_lookup)?
+					.common_block().hash == target_hash;
+
+			if contains_target {
+				return Ok(Some(best.hash()));
+			}
+		}
+
+		Ok(None)
+	}
+}
+
+fn chain_lookup(e: sp_blockchain::Error) -> ConsensusError {
+	ConsensusError::ChainLookup(e.to_string())
+}
@@ -17,6 +17,8 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 //! Collection of common consensus specific implementations
+mod fork_choice;
 mod longest_chain;
 
+pub use fork_choice::{ForkChoice, ForkChoiceRule, LongestChainRule};
 pub use longest_chain::LongestChain;
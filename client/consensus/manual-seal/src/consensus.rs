@@ -23,7 +23,9 @@ use sp_runtime::traits::{Block as BlockT, DigestFor};
 use sp_inherents::InherentData;
 use sp_consensus::BlockImportParams;
 
+pub mod aura;
 pub mod babe;
+pub mod timestamp;
 
 /// Consensus data provider, manual seal uses this trait object for authoring blocks valid 
 /// for any runtime.
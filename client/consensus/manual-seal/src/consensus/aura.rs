@@ -0,0 +1,358 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Aura consensus data provider
+
+use super::{ConsensusDataProvider, timestamp::{ManualClock, ManualTimestampProvider}};
+use crate::Error;
+use codec::{Codec, Decode, Encode};
+use std::{convert::TryFrom, hash::Hash, marker::PhantomData, sync::Arc};
+use sc_client_api::AuxStore;
+use sc_consensus_aura::{
+	slot_duration, AuraApi, AuraInherentData, CompatibleDigestItem, InherentDataProvider,
+	INHERENT_IDENTIFIER,
+};
+use sp_api::{ProvideRuntimeApi, TransactionFor};
+use sp_application_crypto::{AppKey, AppPublic};
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockImportParams;
+use sp_consensus_slots::Slot;
+use sp_core::crypto::{Pair, Public};
+use sp_inherents::{InherentData, InherentDataProviders};
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+use sp_runtime::{
+	traits::{Block as BlockT, DigestFor, DigestItemFor, Header, Member, Zero},
+	generic::{BlockId, Digest},
+};
+
+type AuthorityId<P> = <P as Pair>::Public;
+
+/// Provides Aura-compatible predigests and seals, so that the authored blocks go through the
+/// same checks as the ones authored by the Aura worker.
+/// Intended for use with Aura runtimes.
+pub struct AuraConsensusDataProvider<B, C, P> {
+	/// Shared reference to the client.
+	client: Arc<C>,
+
+	/// Shared reference to keystore, holding the key of the slot author.
+	keystore: SyncCryptoStorePtr,
+
+	/// Clock backing the timestamp inherent.
+	clock: ManualClock,
+
+	_phantom: PhantomData<(B, P)>,
+}
+
+impl<B, C, P> AuraConsensusDataProvider<B, C, P>
+	where
+		B: BlockT,
+		C: AuxStore + HeaderBackend<B> + ProvideRuntimeApi<B>,
+		C::Api: AuraApi<B, AuthorityId<P>>,
+		P: Pair + Send + Sync,
+		P::Public: AppPublic + Hash + Member + Encode + Decode,
+		P::Signature: TryFrom<Vec<u8>> + Hash + Member + Encode + Decode,
+{
+	/// Create a new Aura consensus data provider, registering the timestamp and Aura inherent
+	/// data providers it relies on.
+	pub fn new(
+		client: Arc<C>,
+		keystore: SyncCryptoStorePtr,
+		provider: &InherentDataProviders,
+	) -> Result<Self, Error> {
+		let slot_duration = slot_duration::<AuthorityId<P>, _, _>(&*client)?.get();
+		let clock = slot_clock::<B, _, P::Signature>(&*client, slot_duration)?;
+
+		let timestamp_provider = ManualTimestampProvider::new(clock.clone(), slot_duration);
+
+		provider.register_provider(timestamp_provider)?;
+		if !provider.has_provider(&INHERENT_IDENTIFIER) {
+			provider.register_provider(InherentDataProvider::new(slot_duration))?;
+		}
+
+		Ok(Self {
+			client,
+			keystore,
+			clock,
+			_phantom: PhantomData,
+		})
+	}
+
+	/// The clock driving the timestamp inherent, and hence the slots, of the authored blocks.
+	///
+	/// Pass it to the authorship task to control it through the `engine_setTimestamp` and
+	/// `engine_advanceTime` RPCs.
+	pub fn clock(&self) -> ManualClock {
+		self.clock.clone()
+	}
+
+	/// The author of the given slot, as long as its key is in our keystore.
+	fn slot_author(&self, parent: &B::Header, slot: Slot) -> Result<AuthorityId<P>, Error> {
+		let authorities = self.client.runtime_api()
+			.authorities(&BlockId::Hash(parent.hash()))
+			.map_err(|e| Error::StringError(format!("failed to fetch authorities: {:?}", e)))?;
+
+		if authorities.is_empty() {
+			return Err(sp_consensus::Error::InvalidAuthoritiesSet.into())
+		}
+
+		let author = authorities[(*slot % authorities.len() as u64) as usize].clone();
+		let has_key = SyncCryptoStore::has_keys(
+			&*self.keystore,
+			&[(author.to_raw_vec(), <AuthorityId<P> as AppKey>::ID)],
+		);
+
+		if has_key {
+			Ok(author)
+		} else {
+			Err(Error::StringError(format!(
+				"the key of {:?}, the author of slot {}, is not in the keystore",
+				author,
+				*slot,
+			)))
+		}
+	}
+}
+
+impl<B, C, P> ConsensusDataProvider<B> for AuraConsensusDataProvider<B, C, P>
+	where
+		B: BlockT,
+		C: AuxStore + HeaderBackend<B> + ProvideRuntimeApi<B> + Send + Sync,
+		C::Api: AuraApi<B, AuthorityId<P>>,
+		P: Pair + Send + Sync,
+		P::Public: AppPublic + Hash + Member + Encode + Decode,
+		P::Signature: TryFrom<Vec<u8>> + Hash + Member + Encode + Decode,
+{
+	type Transaction = TransactionFor<C, B>;
+
+	fn create_digest(
+		&self,
+		parent: &B::Header,
+		inherents: &InherentData,
+	) -> Result<DigestFor<B>, Error> {
+		let slot = inherents.aura_inherent_data()?;
+		// make sure we'll be able to seal the block before building it.
+		self.slot_author(parent, slot)?;
+
+		let logs = vec![
+			<DigestItemFor<B> as CompatibleDigestItem<P::Signature>>::aura_pre_digest(slot),
+		];
+
+		Ok(Digest { logs })
+	}
+
+	fn append_block_import(
+		&self,
+		parent: &B::Header,
+		params: &mut BlockImportParams<B, Self::Transaction>,
+		inherents: &InherentData
+	) -> Result<(), Error> {
+		let slot = inherents.aura_inherent_data()?;
+		let author = self.slot_author(parent, slot)?;
+
+		// sign the pre-sealed hash of the block, just like the aura worker does.
+		let header_hash = params.header.hash();
+		let signature = SyncCryptoStore::sign_with(
+			&*self.keystore,
+			<AuthorityId<P> as AppKey>::ID,
+			&author.to_public_crypto_pair(),
+			header_hash.as_ref(),
+		)
+			.map_err(|e| sp_consensus::Error::CannotSign(author.to_raw_vec(), e.to_string()))?
+			.ok_or_else(|| sp_consensus::Error::CannotSign(
+				author.to_raw_vec(), "Could not find key in keystore.".into(),
+			))?;
+		let signature = P::Signature::try_from(signature.clone())
+			.map_err(|_| sp_consensus::Error::InvalidSignature(signature, author.to_raw_vec()))?;
+
+		params.post_digests.push(
+			<DigestItemFor<B> as CompatibleDigestItem<P::Signature>>::aura_seal(signature),
+		);
+
+		Ok(())
+	}
+}
+
+/// Create a clock for the timestamp inherent that produces timestamps for the next aura slot.
+fn slot_clock<B, C, S>(client: &C, slot_duration: u64) -> Result<ManualClock, Error>
+	where
+		B: BlockT,
+		C: HeaderBackend<B>,
+		S: Codec,
+{
+	let info = client.info();
+
+	// looks like this isn't the first block, rehydrate the fake time.
+	// otherwise we'd be producing blocks for older slots.
+	if info.best_number != Zero::zero() {
+		let header = client.header(BlockId::Hash(info.best_hash))?
+			.ok_or_else(|| Error::BlockNotFound(format!("{}", info.best_hash)))?;
+		let slot = header.digest().logs().iter()
+			.find_map(|log| CompatibleDigestItem::<S>::as_aura_pre_digest(log))
+			.ok_or_else(|| Error::StringError("best block has no aura pre-digest".into()))?;
+		// add the slot duration so there's no collision of slots
+		Ok(ManualClock::new((*slot * slot_duration) + slot_duration))
+	} else {
+		// this is the first block, use the correct time.
+		ManualClock::from_system_time()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{run_manual_seal, CreatedBlock, EngineCommand, ManualSealParams};
+	use futures::{channel::{mpsc, oneshot}, SinkExt};
+	use sc_basic_authorship::ProposerFactory;
+	use sc_client_api::BlockBackend;
+	use sc_consensus_aura::{AuraVerifier, CheckForEquivocation};
+	use sc_keystore::LocalKeystore;
+	use sc_transaction_pool::{BasicPool, RevalidationType, txpool::Options};
+	use sp_application_crypto::key_types::AURA;
+	use sp_consensus::{AlwaysCanAuthor, BlockOrigin, import_queue::Verifier};
+	use sp_consensus_aura::sr25519::AuthorityPair;
+	use sp_keyring::sr25519::Keyring;
+	use substrate_test_runtime_client::{
+		DefaultTestClientBuilderExt, TestClient, TestClientBuilder, TestClientBuilderExt,
+		runtime::{Block, Hash},
+	};
+	use substrate_test_runtime_transaction_pool::TestApi;
+
+	/// Slot duration of the test runtime.
+	const SLOT_DURATION: u64 = 1000;
+
+	/// Start the authorship task of a chain whose only key in the keystore is Alice's, the
+	/// first of the three authorities of the test runtime.
+	fn start_aura_engine() -> (Arc<TestClient>, ManualClock, mpsc::Sender<EngineCommand<Hash>>) {
+		let (client, select_chain) = TestClientBuilder::new().build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			Arc::new(TestApi::empty()),
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None);
+
+		let keystore = LocalKeystore::in_memory();
+		SyncCryptoStore::sr25519_generate_new(&keystore, AURA, Some(&Keyring::Alice.to_seed()))
+			.expect("creates the authority key");
+
+		let inherent_data_providers = InherentDataProviders::new();
+		let consensus_data_provider = AuraConsensusDataProvider::<_, _, AuthorityPair>::new(
+			client.clone(),
+			Arc::new(keystore),
+			&inherent_data_providers,
+		).unwrap();
+		let clock = consensus_data_provider.clock();
+
+		let (sink, commands_stream) = mpsc::channel(1024);
+		let future = run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env,
+			client: client.clone(),
+			pool: pool.pool().clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: Some(Box::new(consensus_data_provider)),
+			inherent_data_providers,
+			clock: Some(clock.clone()),
+		});
+		std::thread::spawn(|| {
+			let mut rt = tokio::runtime::Runtime::new().unwrap();
+			// spawn the background authorship task
+			rt.block_on(future);
+		});
+
+		(client, clock, sink)
+	}
+
+	async fn seal_new_block(
+		sink: &mut mpsc::Sender<EngineCommand<Hash>>,
+	) -> Result<CreatedBlock<Hash>, Error> {
+		let (tx, rx) = oneshot::channel();
+		sink.send(EngineCommand::SealNewBlock {
+			create_empty: true,
+			finalize: false,
+			parent_hash: None,
+			sender: Some(tx),
+		}).await.unwrap();
+
+		rx.await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn sealed_blocks_pass_the_aura_verifier() {
+		let (client, clock, mut sink) = start_aura_engine();
+
+		// slot 3 belongs to Alice.
+		clock.set(3 * SLOT_DURATION).unwrap();
+		let created = seal_new_block(&mut sink).await.unwrap();
+
+		let block = client.block(&BlockId::Hash(created.hash)).unwrap().unwrap().block;
+		let (header, body) = block.deconstruct();
+		assert_eq!(
+			header.digest().logs().iter()
+				.find_map(|log| CompatibleDigestItem::<
+					<AuthorityPair as Pair>::Signature
+				>::as_aura_pre_digest(log)),
+			Some(Slot::from(3)),
+		);
+
+		let inherent_data_providers = InherentDataProviders::new();
+		inherent_data_providers.register_provider(sp_timestamp::InherentDataProvider).unwrap();
+		inherent_data_providers.register_provider(InherentDataProvider::new(SLOT_DURATION))
+			.unwrap();
+		let mut verifier = AuraVerifier::<_, AuthorityPair, _>::new(
+			client.clone(),
+			inherent_data_providers,
+			AlwaysCanAuthor,
+			CheckForEquivocation::Yes,
+		);
+
+		let (params, _) = Verifier::<Block>::verify(
+			&mut verifier,
+			BlockOrigin::NetworkBroadcast,
+			header,
+			None,
+			Some(body),
+		).unwrap();
+		assert_eq!(params.post_hash, Some(created.hash));
+		assert_eq!(params.post_digests.len(), 1);
+	}
+
+	#[tokio::test]
+	async fn sealing_requires_the_key_of_the_slot_author() {
+		let (client, clock, mut sink) = start_aura_engine();
+
+		// slot 4 belongs to Bob, whose key isn't in the keystore.
+		clock.set(4 * SLOT_DURATION).unwrap();
+		match seal_new_block(&mut sink).await {
+			Err(Error::StringError(e)) => assert!(e.contains("is not in the keystore")),
+			other => panic!("unexpected result: {:?}", other),
+		}
+		assert_eq!(client.info().best_number, 0);
+
+		// slot 6 is Alice's again.
+		clock.set(6 * SLOT_DURATION).unwrap();
+		seal_new_block(&mut sink).await.unwrap();
+		assert_eq!(client.info().best_number, 1);
+	}
+}
@@ -18,15 +18,10 @@
 
 //! BABE consensus data provider
 
-use super::ConsensusDataProvider;
+use super::{ConsensusDataProvider, timestamp::{ManualClock, ManualTimestampProvider}};
 use crate::Error;
 use codec::Encode;
-use std::{
-	any::Any,
-	borrow::Cow,
-	sync::{Arc, atomic},
-	time::SystemTime,
-};
+use std::{any::Any, borrow::Cow, sync::Arc};
 use sc_client_api::AuxStore;
 use sc_consensus_babe::{
 	Config, Epoch, authorship, CompatibleDigestItem, BabeIntermediate,
@@ -43,12 +38,12 @@ use sp_consensus_babe::{
 	BabeApi, inherents::BabeInherentData, ConsensusLog, BABE_ENGINE_ID, AuthorityId,
 	digests::{PreDigest, SecondaryPlainPreDigest, NextEpochDescriptor}, BabeAuthorityWeight,
 };
-use sp_inherents::{InherentDataProviders, InherentData, ProvideInherentData, InherentIdentifier};
+use sp_inherents::{InherentDataProviders, InherentData};
 use sp_runtime::{
 	traits::{DigestItemFor, DigestFor, Block as BlockT, Zero, Header},
 	generic::{Digest, BlockId},
 };
-use sp_timestamp::{InherentType, InherentError, INHERENT_IDENTIFIER, TimestampInherentData};
+use sp_timestamp::TimestampInherentData;
 
 /// Provides BABE-compatible predigests and BlockImportParams.
 /// Intended for use with BABE runtimes.
@@ -67,6 +62,9 @@ pub struct BabeConsensusDataProvider<B: BlockT, C> {
 
 	/// Authorities to be used for this babe chain.
 	authorities: Vec<(AuthorityId, BabeAuthorityWeight)>,
+
+	/// Clock backing the timestamp inherent.
+	clock: ManualClock,
 }
 
 impl<B, C> BabeConsensusDataProvider<B, C>
@@ -87,7 +85,12 @@ impl<B, C> BabeConsensusDataProvider<B, C>
 		}
 
 		let config = Config::get_or_compute(&*client)?;
-		let timestamp_provider = SlotTimestampProvider::new(client.clone())?;
+		let clock = slot_clock(&*client, config.slot_duration)?;
+
+		// babe can't skip epochs, make sure there's a block in every epoch when the clock is moved
+		// far ahead.
+		let timestamp_provider = ManualTimestampProvider::new(clock.clone(), config.slot_duration)
+			.with_max_step(config.epoch_length * config.slot_duration);
 
 		provider.register_provider(timestamp_provider)?;
 		register_babe_inherent_data_provider(provider, config.slot_duration)?;
@@ -98,9 +101,18 @@ impl<B, C> BabeConsensusDataProvider<B, C>
 			keystore,
 			epoch_changes,
 			authorities,
+			clock,
 		})
 	}
 
+	/// The clock driving the timestamp inherent, and hence the slots, of the authored blocks.
+	///
+	/// Pass it to the authorship task to control it through the `engine_setTimestamp` and
+	/// `engine_advanceTime` RPCs.
+	pub fn clock(&self) -> ManualClock {
+		self.clock.clone()
+	}
+
 	fn epoch(&self, parent: &B::Header, slot: Slot) -> Result<Epoch, Error> {
 		let epoch_changes = self.epoch_changes.lock();
 		let epoch_descriptor = epoch_changes
@@ -246,59 +258,23 @@ impl<B, C> ConsensusDataProvider<B> for BabeConsensusDataProvider<B, C>
 	}
 }
 
-/// Provide duration since unix epoch in millisecond for timestamp inherent.
-/// Mocks the timestamp inherent to always produce the timestamp for the next babe slot.
-struct SlotTimestampProvider {
-	time: atomic::AtomicU64,
-	slot_duration: u64
-}
-
-impl SlotTimestampProvider {
-	/// create a new mocked time stamp provider.
-	fn new<B, C>(client: Arc<C>) -> Result<Self, Error>
-		where
-			B: BlockT,
-			C: AuxStore + HeaderBackend<B> + ProvideRuntimeApi<B>,
-			C::Api: BabeApi<B>,
-	{
-		let slot_duration = Config::get_or_compute(&*client)?.slot_duration;
-		let info = client.info();
-
-		// looks like this isn't the first block, rehydrate the fake time.
-		// otherwise we'd be producing blocks for older slots.
-		let duration = if info.best_number != Zero::zero() {
-			let header = client.header(BlockId::Hash(info.best_hash))?.unwrap();
-			let slot = find_pre_digest::<B>(&header).unwrap().slot();
-			// add the slot duration so there's no collision of slots
-			(*slot * slot_duration) + slot_duration
-		} else {
-			// this is the first block, use the correct time.
-			let now = SystemTime::now();
-			now.duration_since(SystemTime::UNIX_EPOCH)
-				.map_err(|err| Error::StringError(format!("{}", err)))?
-				.as_millis() as u64
-		};
-
-		Ok(Self {
-			time: atomic::AtomicU64::new(duration),
-			slot_duration,
-		})
-	}
-}
-
-impl ProvideInherentData for SlotTimestampProvider {
-	fn inherent_identifier(&self) -> &'static InherentIdentifier {
-		&INHERENT_IDENTIFIER
-	}
-
-	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), sp_inherents::Error> {
-		// we update the time here.
-		let duration: InherentType = self.time.fetch_add(self.slot_duration, atomic::Ordering::SeqCst);
-		inherent_data.put_data(INHERENT_IDENTIFIER, &duration)?;
-		Ok(())
-	}
-
-	fn error_to_string(&self, error: &[u8]) -> Option<String> {
-		InherentError::try_from(&INHERENT_IDENTIFIER, error).map(|e| format!("{:?}", e))
+/// Create a clock for the timestamp inherent that produces timestamps for the next babe slot.
+fn slot_clock<B, C>(client: &C, slot_duration: u64) -> Result<ManualClock, Error>
+	where
+		B: BlockT,
+		C: HeaderBackend<B>,
+{
+	let info = client.info();
+
+	// looks like this isn't the first block, rehydrate the fake time.
+	// otherwise we'd be producing blocks for older slots.
+	if info.best_number != Zero::zero() {
+		let header = client.header(BlockId::Hash(info.best_hash))?.unwrap();
+		let slot = find_pre_digest::<B>(&header).unwrap().slot();
+		// add the slot duration so there's no collision of slots
+		Ok(ManualClock::new((*slot * slot_duration) + slot_duration))
+	} else {
+		// this is the first block, use the correct time.
+		ManualClock::from_system_time()
 	}
 }
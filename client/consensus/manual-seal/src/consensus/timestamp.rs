@@ -0,0 +1,257 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Timestamp inherent driven by a clock that is controlled by the manual seal engine.
+//!
+//! The default `sp_timestamp::InherentDataProvider` reads the wall clock, which ties block
+//! timestamps, and therefore slot numbers of slot based engines, to real time. The
+//! [`ManualClock`] only moves when a block is authored or when the engine is told to move it
+//! through `engine_setTimestamp` / `engine_advanceTime`, which lets tests go through many
+//! sessions or eras without waiting for them.
+
+use crate::Error;
+use parking_lot::Mutex;
+use std::{
+	sync::{Arc, atomic::{AtomicU64, Ordering}},
+	time::SystemTime,
+};
+use sp_inherents::{InherentData, InherentIdentifier, ProvideInherentData};
+use sp_timestamp::{InherentError, InherentType, INHERENT_IDENTIFIER};
+
+/// Shared handle to the time used for the timestamp inherent, in milliseconds since the unix
+/// epoch.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+	time: Arc<AtomicU64>,
+	last_authored: Arc<Mutex<Option<u64>>>,
+}
+
+impl ManualClock {
+	/// Create a new clock starting at the given time.
+	pub fn new(now: u64) -> Self {
+		Self { time: Arc::new(AtomicU64::new(now)), last_authored: Arc::new(Mutex::new(None)) }
+	}
+
+	/// Create a new clock starting at the current system time.
+	pub fn from_system_time() -> Result<Self, Error> {
+		let now = SystemTime::now()
+			.duration_since(SystemTime::UNIX_EPOCH)
+			.map_err(|err| Error::StringError(format!("{}", err)))?
+			.as_millis() as u64;
+
+		Ok(Self::new(now))
+	}
+
+	/// The timestamp the next block will be authored with.
+	pub fn now(&self) -> u64 {
+		self.time.load(Ordering::SeqCst)
+	}
+
+	/// The timestamp of the last block authored with this clock, if any.
+	pub fn last_authored(&self) -> Option<u64> {
+		*self.last_authored.lock()
+	}
+
+	/// Set the timestamp the next block will be authored with.
+	///
+	/// Fails if `timestamp` is earlier than the timestamp of the last authored block, since the
+	/// runtime rejects blocks that go back in time.
+	pub fn set(&self, timestamp: u64) -> Result<(), Error> {
+		let last_authored = self.last_authored.lock();
+		match *last_authored {
+			Some(last) if timestamp < last => Err(Error::TimestampInPast { timestamp, last }),
+			_ => {
+				self.time.store(timestamp, Ordering::SeqCst);
+				Ok(())
+			},
+		}
+	}
+
+	/// Move the clock forward by `millis`, returning the new time.
+	pub fn advance(&self, millis: u64) -> u64 {
+		self.update(|time| time.saturating_add(millis)).saturating_add(millis)
+	}
+
+	/// Return the current time and move the clock forward by `increment`.
+	fn tick(&self, increment: u64) -> u64 {
+		self.update(|time| time.saturating_add(increment))
+	}
+
+	/// Apply `f` to the time, returning the previous time.
+	fn update(&self, f: impl Fn(u64) -> u64) -> u64 {
+		// `fetch_update` only fails if the closure returns `None`.
+		self.time.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |time| Some(f(time)))
+			.unwrap_or_else(|time| time)
+	}
+}
+
+/// Provides the timestamp inherent from a [`ManualClock`].
+///
+/// Every block is authored with the current time of the clock, after which the clock moves
+/// forward by `increment` milliseconds. For slot based engines `increment` should be the slot
+/// duration, so that consecutive blocks are authored in consecutive slots.
+///
+/// When a maximum step is set, a block is never authored more than `max_step` milliseconds
+/// after the previous one. If the clock was moved further ahead, the following blocks walk up
+/// to it in steps of `max_step`, leaving the clock untouched until they catch up with it.
+///
+/// This must be registered instead of `sp_timestamp::InherentDataProvider`, before any provider
+/// which derives the slot from the timestamp.
+pub struct ManualTimestampProvider {
+	clock: ManualClock,
+	increment: u64,
+	max_step: Option<u64>,
+}
+
+impl ManualTimestampProvider {
+	/// Create a new timestamp provider reading from `clock`.
+	pub fn new(clock: ManualClock, increment: u64) -> Self {
+		Self { clock, increment, max_step: None }
+	}
+
+	/// Never author a block more than `max_step` milliseconds after the previous one.
+	pub fn with_max_step(mut self, max_step: u64) -> Self {
+		self.max_step = Some(max_step);
+		self
+	}
+
+	/// The timestamp of the block being authored.
+	fn next_timestamp(&self) -> u64 {
+		let mut last = self.clock.last_authored.lock();
+		let timestamp = match (*last, self.max_step) {
+			(Some(last), Some(max_step)) if self.clock.now() > last.saturating_add(max_step) =>
+				last + max_step,
+			_ => self.clock.tick(self.increment),
+		};
+		*last = Some(timestamp);
+
+		timestamp
+	}
+}
+
+impl ProvideInherentData for ManualTimestampProvider {
+	fn inherent_identifier(&self) -> &'static InherentIdentifier {
+		&INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> Result<(), sp_inherents::Error> {
+		let timestamp: InherentType = self.next_timestamp();
+		inherent_data.put_data(INHERENT_IDENTIFIER, &timestamp)
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		InherentError::try_from(&INHERENT_IDENTIFIER, error).map(|e| format!("{:?}", e))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_timestamp::TimestampInherentData;
+
+	#[test]
+	fn clock_only_moves_when_told_to() {
+		let clock = ManualClock::new(1_000);
+		assert_eq!(clock.now(), 1_000);
+
+		assert_eq!(clock.advance(500), 1_500);
+		assert_eq!(clock.now(), 1_500);
+
+		clock.set(42).unwrap();
+		assert_eq!(clock.clone().now(), 42);
+
+		// the clock saturates instead of overflowing.
+		assert_eq!(clock.advance(u64::max_value()), u64::max_value());
+		assert_eq!(clock.now(), u64::max_value());
+	}
+
+	#[test]
+	fn clock_cannot_be_set_before_the_last_authored_block() {
+		let clock = ManualClock::new(6_000);
+		let provider = ManualTimestampProvider::new(clock.clone(), 6_000);
+		assert_eq!(clock.last_authored(), None);
+
+		let mut inherent_data = InherentData::new();
+		provider.provide_inherent_data(&mut inherent_data).unwrap();
+		assert_eq!(clock.last_authored(), Some(6_000));
+
+		assert_matches::assert_matches!(
+			clock.set(5_999),
+			Err(Error::TimestampInPast { timestamp: 5_999, last: 6_000 })
+		);
+		assert_eq!(clock.now(), 12_000);
+
+		clock.set(6_000).unwrap();
+		assert_eq!(clock.now(), 6_000);
+	}
+
+	#[test]
+	fn provider_ticks_the_shared_clock() {
+		let clock = ManualClock::new(6_000);
+		let provider = ManualTimestampProvider::new(clock.clone(), 6_000);
+
+		let mut inherent_data = InherentData::new();
+		provider.provide_inherent_data(&mut inherent_data).unwrap();
+		assert_eq!(inherent_data.timestamp_inherent_data().unwrap(), 6_000);
+		assert_eq!(clock.now(), 12_000);
+
+		// a week later.
+		clock.advance(7 * 24 * 60 * 60 * 1_000);
+		let mut inherent_data = InherentData::new();
+		provider.provide_inherent_data(&mut inherent_data).unwrap();
+		assert_eq!(inherent_data.timestamp_inherent_data().unwrap(), 604_812_000);
+	}
+
+	#[test]
+	fn provider_walks_up_to_the_clock_in_steps_of_max_step() {
+		let slot_duration = 1_000;
+		let epoch_length = 10;
+		let clock = ManualClock::new(5 * slot_duration);
+		let provider = ManualTimestampProvider::new(clock.clone(), slot_duration)
+			.with_max_step(epoch_length * slot_duration);
+
+		let author = || {
+			let mut inherent_data = InherentData::new();
+			provider.provide_inherent_data(&mut inherent_data).unwrap();
+			inherent_data.timestamp_inherent_data().unwrap() / slot_duration
+		};
+
+		// the first block after startup is never capped.
+		assert_eq!(author(), 5);
+		assert_eq!(author(), 6);
+
+		// skip past 35 epochs.
+		let target = clock.advance(350 * slot_duration);
+		let mut slots = vec![6];
+		while slots.last() != Some(&(target / slot_duration)) {
+			slots.push(author());
+		}
+
+		// the clock is only moved once the blocks caught up with it.
+		assert_eq!(clock.now(), target + slot_duration);
+		assert_eq!(author(), target / slot_duration + 1);
+
+		// every epoch on the way got a block.
+		let mut epochs: Vec<_> = slots.iter().map(|slot| slot / epoch_length).collect();
+		epochs.dedup();
+		assert_eq!(epochs, (0..=35).collect::<Vec<_>>());
+	}
+}
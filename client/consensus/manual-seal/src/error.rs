@@ -33,6 +33,8 @@ mod codes {
 	pub const CONSENSUS_ERROR: i64 = 14_000;
 	pub const INHERENTS_ERROR: i64 = 15_000;
 	pub const BLOCKCHAIN_ERROR: i64 = 16_000;
+	pub const CLOCK_UNAVAILABLE: i64 = 17_000;
+	pub const TIMESTAMP_IN_PAST: i64 = 18_000;
 	pub const UNKNOWN_ERROR: i64 = 20_000;
 }
 
@@ -59,6 +61,22 @@ pub enum Error {
 	#[display(fmt = "Supplied parent_hash: {} doesn't exist in chain", _0)]
 	#[from(ignore)]
 	BlockNotFound(String),
+	/// The engine was started without a manually controlled clock
+	#[display(fmt = "Time can't be changed: manual seal was started without a clock")]
+	ClockUnavailable,
+	/// The requested timestamp is earlier than the timestamp of the last authored block
+	#[display(
+		fmt = "Timestamp {} is before the last authored block's timestamp {}",
+		timestamp,
+		last
+	)]
+	#[from(ignore)]
+	TimestampInPast {
+		/// The requested timestamp
+		timestamp: u64,
+		/// The timestamp of the last authored block
+		last: u64,
+	},
 	/// Some string error
 	#[display(fmt = "{}", _0)]
 	#[from(ignore)]
@@ -84,6 +102,8 @@ impl Error {
 			ConsensusError(_) => codes::CONSENSUS_ERROR,
 			InherentError(_) => codes::INHERENTS_ERROR,
 			BlockchainError(_) => codes::BLOCKCHAIN_ERROR,
+			ClockUnavailable => codes::CLOCK_UNAVAILABLE,
+			TimestampInPast { .. } => codes::TIMESTAMP_IN_PAST,
 			SendError(_) | Canceled(_) => codes::SERVER_SHUTTING_DOWN,
 			_ => codes::UNKNOWN_ERROR
 		}
@@ -42,7 +42,7 @@ pub mod rpc;
 
 pub use self::{
 	error::Error,
-	consensus::ConsensusDataProvider,
+	consensus::{ConsensusDataProvider, timestamp::{ManualClock, ManualTimestampProvider}},
 	finalize_block::{finalize_block, FinalizeBlockParams},
 	seal_block::{SealBlockParams, seal_block, MAX_PROPOSAL_DURATION},
 	rpc::{EngineCommand, CreatedBlock},
@@ -115,6 +115,12 @@ pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, A: txpool
 
 	/// Provider for inherents to include in blocks.
 	pub inherent_data_providers: InherentDataProviders,
+
+	/// Clock moved by the `engine_setTimestamp` and `engine_advanceTime` commands.
+	///
+	/// It only affects the authored blocks if it also backs the timestamp inherent, see
+	/// [`ManualTimestampProvider`].
+	pub clock: Option<ManualClock>,
 }
 
 /// Params required to start the manual sealing authorship task.
@@ -152,6 +158,7 @@ pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS>(
 		select_chain,
 		inherent_data_providers,
 		consensus_data_provider,
+		clock,
 		..
 	}: ManualSealParams<B, BI, E, C, A, SC, CS>
 )
@@ -203,6 +210,21 @@ pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS>(
 					}
 				).await
 			}
+			EngineCommand::SetTimestamp { timestamp, mut sender } => {
+				let result = clock.as_ref()
+					.ok_or(Error::ClockUnavailable)
+					.and_then(|clock| {
+						clock.set(timestamp)?;
+						Ok(clock.now())
+					});
+				rpc::send_result(&mut sender, result)
+			}
+			EngineCommand::AdvanceTime { millis, mut sender } => {
+				let result = clock.as_ref()
+					.map(|clock| clock.advance(millis))
+					.ok_or(Error::ClockUnavailable);
+				rpc::send_result(&mut sender, result)
+			}
 		}
 	}
 }
@@ -257,6 +279,8 @@ pub async fn run_instant_seal<B, BI, CB, E, C, A, SC>(
 			select_chain,
 			consensus_data_provider,
 			inherent_data_providers,
+			// instant seal isn't driven by rpc, so there's nobody to move the clock.
+			clock: None,
 		}
 	).await
 }
@@ -326,6 +350,7 @@ mod tests {
 				select_chain,
 				inherent_data_providers,
 				consensus_data_provider: None,
+				clock: None,
 			}
 		);
 		std::thread::spawn(|| {
@@ -384,6 +409,7 @@ mod tests {
 				select_chain,
 				consensus_data_provider: None,
 				inherent_data_providers,
+				clock: None,
 			}
 		);
 		std::thread::spawn(|| {
@@ -459,6 +485,7 @@ mod tests {
 				select_chain,
 				consensus_data_provider: None,
 				inherent_data_providers,
+				clock: None,
 			}
 		);
 		std::thread::spawn(|| {
@@ -532,4 +559,102 @@ mod tests {
 		// assert that fork block is in the db
 		assert!(client.header(&BlockId::Hash(imported.hash)).unwrap().is_some())
 	}
+
+	#[tokio::test]
+	async fn manual_seal_time_travel() {
+		let builder = TestClientBuilder::new();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let inherent_data_providers = InherentDataProviders::new();
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(), true.into(), api(), None, RevalidationType::Full, spawner.clone(),
+		));
+		let env = ProposerFactory::new(
+			spawner.clone(),
+			client.clone(),
+			pool.clone(),
+			None,
+		);
+		let clock = ManualClock::new(1_000);
+		// this test checks that the engine's clock is moved by the time travel commands.
+		let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let future = run_manual_seal(
+			ManualSealParams {
+				block_import: client.clone(),
+				env,
+				client: client.clone(),
+				pool: pool.pool().clone(),
+				commands_stream,
+				select_chain,
+				consensus_data_provider: None,
+				inherent_data_providers,
+				clock: Some(clock.clone()),
+			}
+		);
+		std::thread::spawn(|| {
+			let mut rt = tokio::runtime::Runtime::new().unwrap();
+			// spawn the background authorship task
+			rt.block_on(future);
+		});
+
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SetTimestamp {
+			timestamp: 60_000,
+			sender: Some(tx),
+		}).await.unwrap();
+		assert_eq!(rx.await.unwrap().unwrap(), 60_000);
+		assert_eq!(clock.now(), 60_000);
+
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::AdvanceTime {
+			millis: 7 * 24 * 60 * 60 * 1_000,
+			sender: Some(tx),
+		}).await.unwrap();
+		assert_eq!(rx.await.unwrap().unwrap(), 604_860_000);
+		assert_eq!(clock.now(), 604_860_000);
+	}
+
+	#[tokio::test]
+	async fn time_travel_requires_a_clock() {
+		let builder = TestClientBuilder::new();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(), true.into(), api(), None, RevalidationType::Full, spawner.clone(),
+		));
+		let env = ProposerFactory::new(
+			spawner.clone(),
+			client.clone(),
+			pool.clone(),
+			None,
+		);
+		let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let future = run_manual_seal(
+			ManualSealParams {
+				block_import: client.clone(),
+				env,
+				client: client.clone(),
+				pool: pool.pool().clone(),
+				commands_stream,
+				select_chain,
+				consensus_data_provider: None,
+				inherent_data_providers: InherentDataProviders::new(),
+				clock: None,
+			}
+		);
+		std::thread::spawn(|| {
+			let mut rt = tokio::runtime::Runtime::new().unwrap();
+			// spawn the background authorship task
+			rt.block_on(future);
+		});
+
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::AdvanceTime {
+			millis: 6_000,
+			sender: Some(tx),
+		}).await.unwrap();
+		assert_matches::assert_matches!(rx.await.unwrap(), Err(Error::ClockUnavailable));
+	}
 }
@@ -63,7 +63,21 @@ pub enum EngineCommand<Hash> {
 		sender: Sender<()>,
 		/// finalization justification
 		justification: Option<Justification>,
-	}
+	},
+	/// Tells the engine to author the next block with the supplied timestamp
+	SetTimestamp {
+		/// milliseconds since the unix epoch.
+		timestamp: u64,
+		/// sender to report the new time or errors to the rpc.
+		sender: Sender<u64>,
+	},
+	/// Tells the engine to move its clock forward
+	AdvanceTime {
+		/// milliseconds to move the clock by.
+		millis: u64,
+		/// sender to report the new time or errors to the rpc.
+		sender: Sender<u64>,
+	},
 }
 
 /// RPC trait that provides methods for interacting with the manual-seal authorship task over rpc.
@@ -85,6 +99,22 @@ pub trait ManualSealApi<Hash> {
 		hash: Hash,
 		justification: Option<Justification>
 	) -> FutureResult<bool>;
+
+	/// Sets the timestamp, in milliseconds since the unix epoch, the next block will be
+	/// authored with. Slot numbers of slot based engines follow the timestamp. Timestamps
+	/// earlier than the timestamp of the last authored block are rejected.
+	///
+	/// Returns the new time of the engine's clock.
+	#[rpc(name = "engine_setTimestamp")]
+	fn set_timestamp(&self, timestamp: u64) -> FutureResult<u64>;
+
+	/// Moves the engine's clock forward by the supplied number of milliseconds, skipping the
+	/// slots in between for slot based engines. BABE can't skip whole epochs, so on BABE chains
+	/// the blocks following the advance move at most one epoch each until they reach the clock.
+	///
+	/// Returns the new time of the engine's clock.
+	#[rpc(name = "engine_advanceTime")]
+	fn advance_time(&self, millis: u64) -> FutureResult<u64>;
 }
 
 /// A struct that implements the [`ManualSealApi`].
@@ -144,6 +174,30 @@ impl<Hash: Send + 'static> ManualSealApi<Hash> for ManualSeal<Hash> {
 
 		Box::new(future.boxed().map_err(Error::from).compat())
 	}
+
+	fn set_timestamp(&self, timestamp: u64) -> FutureResult<u64> {
+		let mut sink = self.import_block_channel.clone();
+		let future = async move {
+			let (sender, receiver) = oneshot::channel();
+			sink.send(EngineCommand::SetTimestamp { timestamp, sender: Some(sender) }).await?;
+
+			receiver.await?
+		};
+
+		Box::new(future.boxed().map_err(Error::from).compat())
+	}
+
+	fn advance_time(&self, millis: u64) -> FutureResult<u64> {
+		let mut sink = self.import_block_channel.clone();
+		let future = async move {
+			let (sender, receiver) = oneshot::channel();
+			sink.send(EngineCommand::AdvanceTime { millis, sender: Some(sender) }).await?;
+
+			receiver.await?
+		};
+
+		Box::new(future.boxed().map_err(Error::from).compat())
+	}
 }
 
 /// report any errors or successes encountered by the authorship task back
@@ -20,10 +20,7 @@
 
 use crate::{Error, rpc, CreatedBlock, ConsensusDataProvider};
 use std::sync::Arc;
-use sp_runtime::{
-	traits::{Block as BlockT, Header as HeaderT},
-	generic::BlockId,
-};
+use sp_runtime::{traits::Block as BlockT, generic::BlockId};
 use futures::prelude::*;
 use sc_transaction_pool::txpool;
 use sp_consensus::{
@@ -134,7 +131,7 @@ pub async fn seal_block<B, BI, SC, C, E, P>(
 		}
 
 		let (header, body) = proposal.block.deconstruct();
-		let mut params = BlockImportParams::new(BlockOrigin::Own, header.clone());
+		let mut params = BlockImportParams::new(BlockOrigin::Own, header);
 		params.body = Some(body);
 		params.finalized = finalize;
 		params.fork_choice = Some(ForkChoiceStrategy::LongestChain);
@@ -144,9 +141,12 @@ pub async fn seal_block<B, BI, SC, C, E, P>(
 			digest_provider.append_block_import(&parent, &mut params, &id)?;
 		}
 
+		// consensus data providers may seal the block, which changes its hash.
+		let post_hash = params.post_hash();
+
 		match block_import.import_block(params, HashMap::new())? {
 			ImportResult::Imported(aux) => {
-				Ok(CreatedBlock { hash: <B as BlockT>::Header::hash(&header), aux })
+				Ok(CreatedBlock { hash: post_hash, aux })
 			},
 			other => Err(other.into()),
 		}
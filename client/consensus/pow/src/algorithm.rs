@@ -0,0 +1,359 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! PoW algorithm adapters: difficulty read from the runtime, and mining with several algorithms.
+
+use std::{sync::Arc, collections::BTreeMap};
+use codec::Decode;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_consensus_pow::{Seal, AlgorithmId, DifficultyApi, MultiAlgorithmPreDigest, TotalDifficulty};
+
+use crate::{Error, PowAlgorithm};
+
+/// PoW algorithm taking its difficulty from the runtime through [`DifficultyApi`], as provided
+/// by `pallet-difficulty` for instance. Everything else is delegated to the wrapped algorithm.
+///
+/// The difficulty is the one of the algorithm the wrapped algorithm selects for the block, see
+/// [`PowAlgorithm::algorithm`]. To mine with several algorithms, wrap a [`MultiAlgorithm`].
+pub struct RuntimeDifficulty<C, Algorithm> {
+	client: Arc<C>,
+	inner: Algorithm,
+}
+
+impl<C, Algorithm> RuntimeDifficulty<C, Algorithm> {
+	/// Wrap `inner`, reading difficulties from the runtime of `client`.
+	pub fn new(client: Arc<C>, inner: Algorithm) -> Self {
+		Self { client, inner }
+	}
+}
+
+impl<C, Algorithm: Clone> Clone for RuntimeDifficulty<C, Algorithm> {
+	fn clone(&self) -> Self {
+		Self { client: self.client.clone(), inner: self.inner.clone() }
+	}
+}
+
+impl<B, C, Algorithm> PowAlgorithm<B> for RuntimeDifficulty<C, Algorithm> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: DifficultyApi<B, Algorithm::Difficulty>,
+	Algorithm: PowAlgorithm<B>,
+{
+	type Difficulty = Algorithm::Difficulty;
+
+	fn difficulty(&self, parent: B::Hash) -> Result<Self::Difficulty, Error<B>> {
+		self.difficulty_for(parent, None)
+	}
+
+	fn difficulty_for(
+		&self,
+		parent: B::Hash,
+		pre_digest: Option<&[u8]>,
+	) -> Result<Self::Difficulty, Error<B>> {
+		let algorithm = self.inner.algorithm(pre_digest)?;
+		let api = self.client.runtime_api();
+		let at = BlockId::Hash(parent);
+		let has_v2 = api
+			.has_api_with::<dyn DifficultyApi<B, Algorithm::Difficulty>, _>(&at, |v| v >= 2)
+			.map_err(|e| Error::Client(e.into()))?;
+		let difficulty = if has_v2 {
+			api.difficulty(&at, algorithm)
+		} else {
+			#[allow(deprecated)] // a single difficulty for all the algorithms
+			api.difficulty_before_version_2(&at)
+		};
+
+		difficulty.map_err(|e| Error::Client(e.into()))
+	}
+
+	fn algorithm(&self, pre_digest: Option<&[u8]>) -> Result<AlgorithmId, Error<B>> {
+		self.inner.algorithm(pre_digest)
+	}
+
+	fn preliminary_verify(
+		&self,
+		pre_hash: &B::Hash,
+		seal: &Seal,
+	) -> Result<Option<bool>, Error<B>> {
+		self.inner.preliminary_verify(pre_hash, seal)
+	}
+
+	fn break_tie(&self, own_seal: &Seal, new_seal: &Seal) -> bool {
+		self.inner.break_tie(own_seal, new_seal)
+	}
+
+	fn verify(
+		&self,
+		parent: &BlockId<B>,
+		pre_hash: &B::Hash,
+		pre_digest: Option<&[u8]>,
+		seal: &Seal,
+		difficulty: Self::Difficulty,
+	) -> Result<bool, Error<B>> {
+		self.inner.verify(parent, pre_hash, pre_digest, seal, difficulty)
+	}
+
+	fn name(&self, pre_digest: Option<&[u8]>) -> String {
+		self.inner.name(pre_digest)
+	}
+}
+
+/// A boxed PoW algorithm, as combined by [`MultiAlgorithm`].
+pub type BoxPowAlgorithm<B, Difficulty> =
+	Box<dyn PowAlgorithm<B, Difficulty = Difficulty> + Send + Sync>;
+
+/// PoW algorithm dispatching to one of several algorithms, selected by the
+/// [`MultiAlgorithmPreDigest`] of every block.
+///
+/// The difficulty of a block is the one of its algorithm, so each algorithm can retarget on its
+/// own. To read the difficulties from the runtime, wrap the whole `MultiAlgorithm` in a
+/// [`RuntimeDifficulty`], which queries the difficulty of the selected algorithm.
+///
+/// To mine with all of the algorithms, pass one encoded [`MultiAlgorithmPreDigest`] per algorithm
+/// to [`start_mining_worker`](crate::start_mining_worker).
+/// Mining metrics are labelled with the algorithm identifier.
+pub struct MultiAlgorithm<B: BlockT, Difficulty> {
+	algorithms: Arc<BTreeMap<AlgorithmId, BoxPowAlgorithm<B, Difficulty>>>,
+	default: Option<AlgorithmId>,
+}
+
+impl<B: BlockT, Difficulty> MultiAlgorithm<B, Difficulty> {
+	/// Combine the given algorithms, each selected by its identifier.
+	pub fn new(algorithms: Vec<(AlgorithmId, BoxPowAlgorithm<B, Difficulty>)>) -> Self {
+		Self { algorithms: Arc::new(algorithms.into_iter().collect()), default: None }
+	}
+
+	/// Use `algorithm` for the blocks without pre-runtime digest, and for
+	/// [`difficulty`](PowAlgorithm::difficulty).
+	///
+	/// Without a default algorithm, blocks without pre-runtime digest are rejected.
+	pub fn with_default(mut self, algorithm: AlgorithmId) -> Self {
+		self.default = Some(algorithm);
+		self
+	}
+
+	/// Select the algorithm of a block out of its pre-runtime digest, returning its identifier
+	/// and itself along with the pre-runtime digest meant for it.
+	fn select(
+		&self,
+		pre_digest: Option<&[u8]>,
+	) -> Result<(AlgorithmId, &BoxPowAlgorithm<B, Difficulty>, Option<Vec<u8>>), Error<B>> {
+		let (algorithm, inner) = match pre_digest {
+			Some(mut pre_digest) => {
+				let MultiAlgorithmPreDigest { algorithm, inner } =
+					Decode::decode(&mut pre_digest).map_err(Error::Codec)?;
+				(algorithm, inner)
+			},
+			None => (self.default.ok_or(Error::NoAlgorithmSelected)?, None),
+		};
+		let selected = self.algorithms.get(&algorithm)
+			.ok_or(Error::UnknownAlgorithm(algorithm))?;
+
+		Ok((algorithm, selected, inner))
+	}
+}
+
+impl<B: BlockT, Difficulty> Clone for MultiAlgorithm<B, Difficulty> {
+	fn clone(&self) -> Self {
+		Self { algorithms: self.algorithms.clone(), default: self.default }
+	}
+}
+
+impl<B, Difficulty> PowAlgorithm<B> for MultiAlgorithm<B, Difficulty> where
+	B: BlockT,
+	Difficulty: TotalDifficulty + Default + codec::Encode + Decode + Ord + Clone + Copy,
+{
+	type Difficulty = Difficulty;
+
+	/// Difficulty of the default algorithm, see [`MultiAlgorithm::with_default`].
+	fn difficulty(&self, parent: B::Hash) -> Result<Self::Difficulty, Error<B>> {
+		self.difficulty_for(parent, None)
+	}
+
+	fn difficulty_for(
+		&self,
+		parent: B::Hash,
+		pre_digest: Option<&[u8]>,
+	) -> Result<Self::Difficulty, Error<B>> {
+		let (_, algorithm, inner) = self.select(pre_digest)?;
+		algorithm.difficulty_for(parent, inner.as_deref())
+	}
+
+	fn algorithm(&self, pre_digest: Option<&[u8]>) -> Result<AlgorithmId, Error<B>> {
+		self.select(pre_digest).map(|(algorithm, ..)| algorithm)
+	}
+
+	fn verify(
+		&self,
+		parent: &BlockId<B>,
+		pre_hash: &B::Hash,
+		pre_digest: Option<&[u8]>,
+		seal: &Seal,
+		difficulty: Self::Difficulty,
+	) -> Result<bool, Error<B>> {
+		let (_, algorithm, inner) = self.select(pre_digest)?;
+		algorithm.verify(parent, pre_hash, inner.as_deref(), seal, difficulty)
+	}
+
+	fn name(&self, pre_digest: Option<&[u8]>) -> String {
+		let algorithm = match pre_digest {
+			Some(mut pre_digest) => MultiAlgorithmPreDigest::decode(&mut pre_digest)
+				.ok()
+				.map(|pre_digest| pre_digest.algorithm),
+			None => self.default,
+		};
+
+		algorithm
+			.map(|algorithm| String::from_utf8_lossy(&algorithm).into_owned())
+			.unwrap_or_else(|| "unknown".into())
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use codec::Encode;
+	use substrate_test_runtime_client::runtime::{Block, Hash};
+
+	pub(crate) const SHA3: AlgorithmId = *b"sha3";
+	pub(crate) const BLAKE: AlgorithmId = *b"blak";
+
+	/// Algorithm with a fixed difficulty, accepting the encoded difficulty as the only valid
+	/// seal, as long as the block isn't handed a pre-runtime digest.
+	pub(crate) struct Fixed(pub(crate) u128);
+
+	impl PowAlgorithm<Block> for Fixed {
+		type Difficulty = u128;
+
+		fn difficulty(&self, _parent: Hash) -> Result<u128, Error<Block>> {
+			Ok(self.0)
+		}
+
+		fn verify(
+			&self,
+			_parent: &BlockId<Block>,
+			_pre_hash: &Hash,
+			pre_digest: Option<&[u8]>,
+			seal: &Seal,
+			difficulty: u128,
+		) -> Result<bool, Error<Block>> {
+			Ok(pre_digest.is_none() && difficulty == self.0 && seal == &self.0.encode())
+		}
+	}
+
+	/// Mine with SHA3 at difficulty 10 or BLAKE at difficulty 20.
+	pub(crate) fn sha3_and_blake() -> MultiAlgorithm<Block, u128> {
+		MultiAlgorithm::new(vec![
+			(SHA3, Box::new(Fixed(10)) as BoxPowAlgorithm<_, _>),
+			(BLAKE, Box::new(Fixed(20))),
+		])
+	}
+
+	pub(crate) fn pre_digest(algorithm: AlgorithmId) -> Vec<u8> {
+		MultiAlgorithmPreDigest { algorithm, inner: None }.encode()
+	}
+
+	#[test]
+	fn algorithm_is_selected_by_pre_digest() {
+		let hash = Hash::default();
+		let algorithm = sha3_and_blake();
+		let parent = BlockId::Hash(hash);
+		let (sha3, blake) = (pre_digest(SHA3), pre_digest(BLAKE));
+
+		assert_eq!(algorithm.difficulty_for(hash, Some(&sha3[..])).unwrap(), 10);
+		assert_eq!(algorithm.difficulty_for(hash, Some(&blake[..])).unwrap(), 20);
+		assert_eq!(algorithm.algorithm(Some(&blake[..])).unwrap(), BLAKE);
+		assert_eq!(algorithm.name(Some(&blake[..])), "blak");
+
+		let seal = 20u128.encode();
+		assert!(algorithm.verify(&parent, &hash, Some(&blake[..]), &seal, 20).unwrap());
+		assert!(!algorithm.verify(&parent, &hash, Some(&sha3[..]), &seal, 20).unwrap());
+	}
+
+	#[test]
+	fn inner_pre_digest_is_handed_to_the_selected_algorithm() {
+		let hash = Hash::default();
+		let algorithm = sha3_and_blake();
+		let parent = BlockId::Hash(hash);
+		let blake = MultiAlgorithmPreDigest { algorithm: BLAKE, inner: Some(vec![42]) }.encode();
+
+		// `Fixed` rejects any pre-runtime digest.
+		let seal = 20u128.encode();
+		assert!(!algorithm.verify(&parent, &hash, Some(&blake[..]), &seal, 20).unwrap());
+	}
+
+	#[test]
+	fn missing_or_unknown_algorithm_is_an_error() {
+		let hash = Hash::default();
+		let algorithm = sha3_and_blake();
+
+		assert!(matches!(
+			algorithm.difficulty_for(hash, None),
+			Err(Error::NoAlgorithmSelected),
+		));
+		assert!(matches!(
+			algorithm.difficulty(hash),
+			Err(Error::NoAlgorithmSelected),
+		));
+		assert!(matches!(
+			algorithm.algorithm(None),
+			Err(Error::NoAlgorithmSelected),
+		));
+		assert!(matches!(
+			algorithm.difficulty_for(hash, Some(&pre_digest(*b"etha")[..])),
+			Err(Error::UnknownAlgorithm(id)) if id == *b"etha",
+		));
+		assert!(matches!(
+			algorithm.difficulty_for(hash, Some(&[1u8, 2][..])),
+			Err(Error::Codec(_)),
+		));
+		assert_eq!(algorithm.name(None), "unknown");
+	}
+
+	#[test]
+	fn single_algorithm_is_the_default_algorithm() {
+		assert_eq!(
+			PowAlgorithm::<Block>::algorithm(&Fixed(10), None).unwrap(),
+			sp_consensus_pow::DEFAULT_ALGORITHM,
+		);
+	}
+
+	#[test]
+	fn default_algorithm_is_used_without_pre_digest() {
+		let hash = Hash::default();
+		let algorithm = sha3_and_blake().with_default(BLAKE);
+		let parent = BlockId::Hash(hash);
+
+		assert_eq!(algorithm.difficulty(hash).unwrap(), 20);
+		assert_eq!(algorithm.difficulty_for(hash, None).unwrap(), 20);
+		assert_eq!(algorithm.algorithm(None).unwrap(), BLAKE);
+		assert_eq!(algorithm.name(None), "blak");
+		assert!(algorithm.verify(&parent, &hash, None, &20u128.encode(), 20).unwrap());
+
+		// the pre-runtime digest still wins.
+		let sha3 = pre_digest(SHA3);
+		assert_eq!(algorithm.difficulty_for(hash, Some(&sha3[..])).unwrap(), 10);
+
+		let unknown = sha3_and_blake().with_default(*b"etha");
+		assert!(matches!(
+			unknown.difficulty(hash),
+			Err(Error::UnknownAlgorithm(id)) if id == *b"etha",
+		));
+	}
+}
@@ -30,9 +30,20 @@
 //! for the auxiliary storage. It is also possible to just use the runtime
 //! as the storage, but it is not recommended as it won't work well with light
 //! clients.
+//!
+//! Difficulty adjustment can also live in the runtime, for example with `pallet-difficulty`:
+//! wrap the algorithm in `RuntimeDifficulty` to read the difficulty through `DifficultyApi`.
+//! Chains mined with several algorithms combine them with `MultiAlgorithm`, which selects one
+//! per block out of a `MultiAlgorithmPreDigest`. Wrapping the `MultiAlgorithm` in
+//! `RuntimeDifficulty` reads the difficulty of the selected algorithm.
+//!
+//! The total difficulty is also exposed as a fork-choice rule, `TotalDifficultyRule`, which can
+//! be used with `sc_consensus::ForkChoice` or wrapped by other rules.
 
+mod algorithm;
 mod worker;
 
+pub use crate::algorithm::{RuntimeDifficulty, MultiAlgorithm, BoxPowAlgorithm};
 pub use crate::worker::{MiningWorker, MiningMetadata, MiningBuild};
 
 use std::{
@@ -42,13 +53,14 @@ use std::{
 use futures::{prelude::*, future::Either};
 use parking_lot::Mutex;
 use sc_client_api::{BlockOf, backend::AuxStore, BlockchainEvents};
+use sc_consensus::ForkChoiceRule;
 use sp_blockchain::{HeaderBackend, ProvideCache, well_known_cache_keys::Id as CacheKeyId};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_runtime::{Justification, RuntimeString};
 use sp_runtime::generic::{BlockId, Digest, DigestItem};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 use sp_api::ProvideRuntimeApi;
-use sp_consensus_pow::{Seal, TotalDifficulty, POW_ENGINE_ID};
+use sp_consensus_pow::{Seal, TotalDifficulty, AlgorithmId, DEFAULT_ALGORITHM, POW_ENGINE_ID};
 use sp_inherents::{InherentDataProviders, InherentData};
 use sp_consensus::{
 	BlockImportParams, BlockOrigin, ForkChoiceStrategy, SyncOracle, Environment, Proposer,
@@ -63,7 +75,7 @@ use sc_client_api;
 use log::*;
 use sp_timestamp::{InherentError as TIError, TimestampInherentData};
 
-use crate::worker::UntilImportedOrTimeout;
+use crate::worker::{UntilImportedOrTimeout, MiningMetrics};
 
 #[derive(derive_more::Display, Debug)]
 pub enum Error<B: BlockT> {
@@ -95,6 +107,10 @@ pub enum Error<B: BlockT> {
 	CheckInherents(String),
 	#[display(fmt = "Multiple pre-runtime digests")]
 	MultiplePreRuntimeDigests,
+	#[display(fmt = "Pre-runtime digest does not select a mining algorithm")]
+	NoAlgorithmSelected,
+	#[display(fmt = "Unknown mining algorithm {:?}", _0)]
+	UnknownAlgorithm(AlgorithmId),
 	Client(sp_blockchain::Error),
 	Codec(codec::Error),
 	Environment(String),
@@ -155,6 +171,46 @@ impl<Difficulty> PowAux<Difficulty> where
 	}
 }
 
+/// Fork-choice rule preferring the chain with the highest total difficulty, as recorded in
+/// the auxiliary storage by `PowBlockImport`.
+///
+/// Use it with `sc_consensus::ForkChoice` to select the chain to mine upon.
+pub struct TotalDifficultyRule<Difficulty>(PhantomData<fn() -> Difficulty>);
+
+impl<Difficulty> TotalDifficultyRule<Difficulty> {
+	/// Create a new total difficulty fork-choice rule.
+	pub fn new() -> Self {
+		TotalDifficultyRule(PhantomData)
+	}
+}
+
+impl<Difficulty> Default for TotalDifficultyRule<Difficulty> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<C, B, Difficulty> ForkChoiceRule<C, B> for TotalDifficultyRule<Difficulty> where
+	C: AuxStore,
+	B: BlockT,
+	Difficulty: Decode + Default + Ord,
+{
+	fn compare(
+		&self,
+		backend: &C,
+		a: &B::Header,
+		b: &B::Header,
+	) -> Result<Ordering, ConsensusError> {
+		let total_difficulty = |header: &B::Header| {
+			PowAux::<Difficulty>::read::<_, B>(backend, &header.hash())
+				.map(|aux| aux.total_difficulty)
+				.map_err(|e| ConsensusError::ChainLookup(e.to_string()))
+		};
+
+		Ok(total_difficulty(a)?.cmp(&total_difficulty(b)?))
+	}
+}
+
 /// Algorithm used for proof of work.
 pub trait PowAlgorithm<B: BlockT> {
 	/// Difficulty for the algorithm.
@@ -165,6 +221,24 @@ pub trait PowAlgorithm<B: BlockT> {
 	/// This function will be called twice during the import process, so the implementation
 	/// should be properly cached.
 	fn difficulty(&self, parent: B::Hash) -> Result<Self::Difficulty, Error<B>>;
+	/// Get the next block's difficulty, for a block with the given pre-runtime digest.
+	///
+	/// Chains mined with several algorithms select one through the pre-runtime digest, each with
+	/// its own difficulty. By default, this is the same as `difficulty`.
+	fn difficulty_for(
+		&self,
+		parent: B::Hash,
+		_pre_digest: Option<&[u8]>,
+	) -> Result<Self::Difficulty, Error<B>> {
+		self.difficulty(parent)
+	}
+	/// Identifier of the algorithm mining blocks with the given pre-runtime digest.
+	///
+	/// Chains mined with several algorithms select one through the pre-runtime digest. By
+	/// default, this is [`DEFAULT_ALGORITHM`].
+	fn algorithm(&self, _pre_digest: Option<&[u8]>) -> Result<AlgorithmId, Error<B>> {
+		Ok(DEFAULT_ALGORITHM)
+	}
 	/// Verify that the seal is valid against given pre hash when parent block is not yet imported.
 	///
 	/// None means that preliminary verify is not available for this algorithm.
@@ -197,6 +271,11 @@ pub trait PowAlgorithm<B: BlockT> {
 		seal: &Seal,
 		difficulty: Self::Difficulty,
 	) -> Result<bool, Error<B>>;
+	/// Name of the algorithm mining blocks with the given pre-runtime digest, labelling the
+	/// mining metrics.
+	fn name(&self, _pre_digest: Option<&[u8]>) -> String {
+		"pow".into()
+	}
 }
 
 /// A block importer for PoW.
@@ -364,13 +443,13 @@ impl<B, I, C, S, Algorithm, CAW> BlockImport<B> for PowBlockImport<B, I, C, S, A
 			INTERMEDIATE_KEY
 		)?;
 
+		let pre_hash = block.header.hash();
+		let pre_digest = find_pre_digest::<B>(&block.header)?;
+
 		let difficulty = match intermediate.difficulty {
 			Some(difficulty) => difficulty,
-			None => self.algorithm.difficulty(parent_hash)?,
+			None => self.algorithm.difficulty_for(parent_hash, pre_digest.as_deref())?,
 		};
-
-		let pre_hash = block.header.hash();
-		let pre_digest = find_pre_digest::<B>(&block.header)?;
 		if !self.algorithm.verify(
 			&BlockId::hash(parent_hash),
 			&pre_hash,
@@ -534,8 +613,10 @@ pub fn import_queue<B, Transaction, Algorithm>(
 /// mining metadata and submitting mined blocks, and a future, which must be polled to fill in
 /// information in the worker.
 ///
-/// `pre_runtime` is a parameter that allows a custom additional pre-runtime digest to be inserted
-/// for blocks being built. This can encode authorship information, or just be a graffiti.
+/// `pre_runtimes` are custom additional pre-runtime digests to be inserted for blocks being built.
+/// They can encode authorship information, or just be a graffiti. One build is kept per
+/// pre-runtime digest, so that chains mined with several algorithms (see [`MultiAlgorithm`]) can
+/// be mined with all of them at once. If empty, a single build without pre-runtime digest is kept.
 pub fn start_mining_worker<Block, C, S, Algorithm, E, SO, CAW>(
 	block_import: BoxBlockImport<Block, sp_api::TransactionFor<C, Block>>,
 	client: Arc<C>,
@@ -543,11 +624,12 @@ pub fn start_mining_worker<Block, C, S, Algorithm, E, SO, CAW>(
 	algorithm: Algorithm,
 	mut env: E,
 	mut sync_oracle: SO,
-	pre_runtime: Option<Vec<u8>>,
+	pre_runtimes: Vec<Vec<u8>>,
 	inherent_data_providers: sp_inherents::InherentDataProviders,
 	timeout: Duration,
 	build_time: Duration,
 	can_author_with: CAW,
+	registry: Option<&Registry>,
 ) -> (
 	Arc<Mutex<MiningWorker<Block, Algorithm, C, <E::Proposer as Proposer<Block>>::Proof>>>,
 	impl Future<Output = ()>,
@@ -567,11 +649,24 @@ pub fn start_mining_worker<Block, C, S, Algorithm, E, SO, CAW>(
 		warn!("Registering inherent data provider for timestamp failed");
 	}
 
+	let metrics = registry.and_then(|registry| {
+		MiningMetrics::register(registry)
+			.map_err(|err| warn!("Failed to register PoW mining prometheus metrics: {}", err))
+			.ok()
+	});
+
+	let pre_runtimes = if pre_runtimes.is_empty() {
+		vec![None]
+	} else {
+		pre_runtimes.into_iter().map(Some).collect::<Vec<_>>()
+	};
+
 	let timer = UntilImportedOrTimeout::new(client.import_notification_stream(), timeout);
 	let worker = Arc::new(Mutex::new(MiningWorker::<Block, Algorithm, C, _> {
-		build: None,
+		builds: Vec::new(),
 		algorithm: algorithm.clone(),
 		block_import,
+		metrics,
 	}));
 	let worker_ret = worker.clone();
 
@@ -615,20 +710,6 @@ pub fn start_mining_worker<Block, C, S, Algorithm, E, SO, CAW>(
 		// The worker is locked for the duration of the whole proposing period. Within this period,
 		// the mining target is outdated and useless anyway.
 
-		let difficulty = match algorithm.difficulty(best_hash) {
-			Ok(x) => x,
-			Err(err) => {
-				warn!(
-					target: "pow",
-					"Unable to propose new block for authoring. \
-					 Fetch difficulty failed: {:?}",
-					err,
-				);
-				return Either::Left(future::ready(()))
-			},
-		};
-
-		let awaiting_proposer = env.init(&best_header);
 		let inherent_data = match inherent_data_providers.create_inherent_data() {
 			Ok(x) => x,
 			Err(err) => {
@@ -641,55 +722,87 @@ pub fn start_mining_worker<Block, C, S, Algorithm, E, SO, CAW>(
 				return Either::Left(future::ready(()))
 			},
 		};
-		let mut inherent_digest = Digest::<Block::Hash>::default();
-		if let Some(pre_runtime) = &pre_runtime {
-			inherent_digest.push(DigestItem::PreRuntime(POW_ENGINE_ID, pre_runtime.to_vec()));
-		}
-
-		let pre_runtime = pre_runtime.clone();
 
-		Either::Right(async move {
-			let proposer = match awaiting_proposer.await {
+		let mut proposals = Vec::with_capacity(pre_runtimes.len());
+		for pre_runtime in &pre_runtimes {
+			let difficulty = match algorithm.difficulty_for(best_hash, pre_runtime.as_deref()) {
 				Ok(x) => x,
 				Err(err) => {
 					warn!(
 						target: "pow",
 						"Unable to propose new block for authoring. \
-						 Creating proposer failed: {:?}",
+						 Fetch difficulty failed: {:?}",
 						err,
 					);
-					return
+					continue
 				},
 			};
 
-			let proposal = match proposer.propose(
-				inherent_data,
-				inherent_digest,
-				build_time.clone(),
-			).await {
-				Ok(x) => x,
-				Err(err) => {
-					warn!(
-						target: "pow",
-						"Unable to propose new block for authoring. \
-						 Creating proposal failed: {:?}",
-						err,
-					);
-					return
-				},
-			};
+			let mut inherent_digest = Digest::<Block::Hash>::default();
+			if let Some(pre_runtime) = pre_runtime {
+				inherent_digest.push(DigestItem::PreRuntime(POW_ENGINE_ID, pre_runtime.to_vec()));
+			}
 
-			let build = MiningBuild::<Block, Algorithm, C, _> {
-				metadata: MiningMetadata {
-					best_hash,
-					pre_hash: proposal.block.header().hash(),
-					pre_runtime: pre_runtime.clone(),
-					difficulty,
-				},
-				proposal,
-			};
+			let awaiting_proposer = env.init(&best_header);
+			let inherent_data = inherent_data.clone();
+			let pre_runtime = pre_runtime.clone();
+
+			proposals.push(async move {
+				let proposer = match awaiting_proposer.await {
+					Ok(x) => x,
+					Err(err) => {
+						warn!(
+							target: "pow",
+							"Unable to propose new block for authoring. \
+							 Creating proposer failed: {:?}",
+							err,
+						);
+						return None
+					},
+				};
+
+				let proposal = match proposer.propose(
+					inherent_data,
+					inherent_digest,
+					build_time.clone(),
+				).await {
+					Ok(x) => x,
+					Err(err) => {
+						warn!(
+							target: "pow",
+							"Unable to propose new block for authoring. \
+							 Creating proposal failed: {:?}",
+							err,
+						);
+						return None
+					},
+				};
+
+				Some(MiningBuild::<Block, Algorithm, C, _> {
+					metadata: MiningMetadata {
+						best_hash,
+						pre_hash: proposal.block.header().hash(),
+						pre_runtime,
+						difficulty,
+					},
+					proposal,
+				})
+			});
+		}
 
-			worker.lock().on_build(build);
+		if proposals.is_empty() {
+			return Either::Left(future::ready(()))
+		}
+
+		Either::Right(async move {
+			let builds = future::join_all(proposals).await
+				.into_iter()
+				.flatten()
+				.collect::<Vec<_>>();
+
+			if !builds.is_empty() {
+				worker.lock().on_build(builds);
+			}
 		})
 	});
 
@@ -23,6 +23,7 @@ use sp_consensus::{Proposal, BlockOrigin, BlockImportParams, import_queue::BoxBl
 use futures::{prelude::*, task::{Context, Poll}};
 use futures_timer::Delay;
 use log::*;
+use prometheus_endpoint::{register, PrometheusError, Registry, CounterVec, GaugeVec, Opts, U64};
 
 use crate::{INTERMEDIATE_KEY, POW_ENGINE_ID, Seal, PowAlgorithm, PowIntermediate};
 
@@ -52,6 +53,37 @@ pub struct MiningBuild<
 	pub proposal: Proposal<Block, sp_api::TransactionFor<C, Block>, Proof>,
 }
 
+/// Prometheus metrics of the mining worker, labelled by algorithm.
+#[derive(Clone)]
+pub(crate) struct MiningMetrics {
+	hashrate: GaugeVec<U64>,
+	found_seals: CounterVec<U64>,
+}
+
+impl MiningMetrics {
+	pub(crate) fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			hashrate: register(
+				GaugeVec::new(
+					Opts::new("pow_hashrate", "Hashes per second last reported by the miner"),
+					&["algorithm"],
+				)?,
+				registry,
+			)?,
+			found_seals: register(
+				CounterVec::new(
+					Opts::new(
+						"pow_found_seals_total",
+						"Number of mined seals successfully imported",
+					),
+					&["algorithm"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
 /// Mining worker that exposes structs to query the current mining build and submit mined blocks.
 pub struct MiningWorker<
 	Block: BlockT,
@@ -59,9 +91,10 @@ pub struct MiningWorker<
 	C: sp_api::ProvideRuntimeApi<Block>,
 	Proof
 > {
-	pub(crate) build: Option<MiningBuild<Block, Algorithm, C, Proof>>,
+	pub(crate) builds: Vec<MiningBuild<Block, Algorithm, C, Proof>>,
 	pub(crate) algorithm: Algorithm,
 	pub(crate) block_import: BoxBlockImport<Block, sp_api::TransactionFor<C, Block>>,
+	pub(crate) metrics: Option<MiningMetrics>,
 }
 
 impl<Block, Algorithm, C, Proof> MiningWorker<Block, Algorithm, C, Proof> where
@@ -73,29 +106,73 @@ impl<Block, Algorithm, C, Proof> MiningWorker<Block, Algorithm, C, Proof> where
 	/// Get the current best hash. `None` if the worker has just started or the client is doing
 	/// major syncing.
 	pub fn best_hash(&self) -> Option<Block::Hash> {
-		self.build.as_ref().map(|b| b.metadata.best_hash)
+		self.builds.first().map(|b| b.metadata.best_hash)
 	}
 
 	pub(crate) fn on_major_syncing(&mut self) {
-		self.build = None;
+		self.builds.clear();
 	}
 
 	pub(crate) fn on_build(
 		&mut self,
-		build: MiningBuild<Block, Algorithm, C, Proof>,
+		builds: Vec<MiningBuild<Block, Algorithm, C, Proof>>,
 	) {
-		self.build = Some(build);
+		self.builds = builds;
 	}
 
 	/// Get a copy of the current mining metadata, if available.
+	///
+	/// When mining with several pre-runtime digests, this is the metadata of the first one.
 	pub fn metadata(&self) -> Option<MiningMetadata<Block::Hash, Algorithm::Difficulty>> {
-		self.build.as_ref().map(|b| b.metadata.clone())
+		self.builds.first().map(|b| b.metadata.clone())
+	}
+
+	/// Get a copy of the current mining metadata of every pre-runtime digest.
+	pub fn all_metadata(&self) -> Vec<MiningMetadata<Block::Hash, Algorithm::Difficulty>> {
+		self.builds.iter().map(|b| b.metadata.clone()).collect()
+	}
+
+	/// Report `hashes` computed during `elapsed` while mining the build of the given pre-hash,
+	/// updating the hashrate metric.
+	pub fn note_hashes(&self, pre_hash: &Block::Hash, hashes: u64, elapsed: Duration) {
+		let metrics = match &self.metrics {
+			Some(metrics) => metrics,
+			None => return,
+		};
+
+		if let Some(build) = self.builds.iter().find(|b| &b.metadata.pre_hash == pre_hash) {
+			let name = self.algorithm.name(build.metadata.pre_runtime.as_deref());
+			let millis = elapsed.as_millis().max(1);
+			let hashrate = (hashes as u128).saturating_mul(1000) / millis;
+			metrics.hashrate.with_label_values(&[name.as_str()]).set(hashrate as u64);
+		}
 	}
 
 	/// Submit a mined seal. The seal will be validated again. Returns true if the submission is
 	/// successful.
+	///
+	/// When mining with several pre-runtime digests, the seal is submitted for the first one. Use
+	/// `submit_for` to pick another one.
 	pub fn submit(&mut self, seal: Seal) -> bool {
-		if let Some(build) = self.build.take() {
+		match self.builds.first().map(|b| b.metadata.pre_hash) {
+			Some(pre_hash) => self.submit_for(&pre_hash, seal),
+			None => {
+				warn!(
+					target: "pow",
+					"Unable to import mined block: build does not exist",
+				);
+				false
+			},
+		}
+	}
+
+	/// Submit a seal mined for the build of the given pre-hash. The seal will be validated again.
+	/// Returns true if the submission is successful.
+	pub fn submit_for(&mut self, pre_hash: &Block::Hash, seal: Seal) -> bool {
+		let index = self.builds.iter().position(|b| &b.metadata.pre_hash == pre_hash);
+		if let Some(build) = index.map(|index| self.builds.remove(index)) {
+			let name = self.algorithm.name(build.metadata.pre_runtime.as_deref());
+
 			match self.algorithm.verify(
 				&BlockId::Hash(build.metadata.best_hash),
 				&build.metadata.pre_hash,
@@ -145,6 +222,11 @@ impl<Block, Algorithm, C, Proof> MiningWorker<Block, Algorithm, C, Proof> where
 						"✅ Successfully mined block on top of: {}",
 						build.metadata.best_hash
 					);
+					if let Some(metrics) = &self.metrics {
+						metrics.found_seals.with_label_values(&[name.as_str()]).inc();
+					}
+					// The other builds are now mining on top of a stale block.
+					self.builds.clear();
 					true
 				},
 				Err(err) => {
@@ -221,3 +303,98 @@ impl<Block: BlockT> Stream for UntilImportedOrTimeout<Block> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Arc;
+	use codec::Encode;
+	use parking_lot::Mutex;
+	use sc_block_builder::BlockBuilderProvider;
+	use sp_consensus::{BlockCheckParams, BlockImport, Error as ConsensusError, ImportResult};
+	use sp_runtime::{generic::Digest, traits::Header as _};
+	use substrate_test_runtime_client::{
+		DefaultTestClientBuilderExt, TestClient, TestClientBuilder, TestClientBuilderExt,
+		runtime::{Block, Hash},
+	};
+	use sp_blockchain::well_known_cache_keys::Id as CacheKeyId;
+	use crate::algorithm::{MultiAlgorithm, tests::{SHA3, BLAKE, sha3_and_blake, pre_digest}};
+
+	type Transaction = sp_api::TransactionFor<TestClient, Block>;
+
+	/// Block import recording the pre-hashes of the imported blocks.
+	#[derive(Clone, Default)]
+	struct RecordingImport(Arc<Mutex<Vec<Hash>>>);
+
+	impl BlockImport<Block> for RecordingImport {
+		type Error = ConsensusError;
+		type Transaction = Transaction;
+
+		fn check_block(
+			&mut self,
+			_block: BlockCheckParams<Block>,
+		) -> Result<ImportResult, Self::Error> {
+			Ok(ImportResult::imported(false))
+		}
+
+		fn import_block(
+			&mut self,
+			block: BlockImportParams<Block, Self::Transaction>,
+			_cache: HashMap<CacheKeyId, Vec<u8>>,
+		) -> Result<ImportResult, Self::Error> {
+			self.0.lock().push(block.header.hash());
+			Ok(ImportResult::imported(true))
+		}
+	}
+
+	/// Build a block on top of genesis, mined with the algorithm of `pre_runtime`.
+	fn build(
+		client: &TestClient,
+		pre_runtime: Vec<u8>,
+		difficulty: u128,
+	) -> MiningBuild<Block, MultiAlgorithm<Block, u128>, TestClient, ()> {
+		let digest = Digest {
+			logs: vec![DigestItem::PreRuntime(POW_ENGINE_ID, pre_runtime.clone())],
+		};
+		let built = client.new_block(digest).unwrap().build().unwrap();
+
+		MiningBuild {
+			metadata: MiningMetadata {
+				best_hash: client.chain_info().genesis_hash,
+				pre_hash: built.block.header().hash(),
+				pre_runtime: Some(pre_runtime),
+				difficulty,
+			},
+			proposal: Proposal {
+				block: built.block,
+				proof: (),
+				storage_changes: built.storage_changes,
+			},
+		}
+	}
+
+	#[test]
+	fn submit_for_clears_the_stale_builds() {
+		let client = TestClientBuilder::new().build();
+		let sha3 = build(&client, pre_digest(SHA3), 10);
+		let blake = build(&client, pre_digest(BLAKE), 20);
+		let (sha3_hash, blake_hash) = (sha3.metadata.pre_hash, blake.metadata.pre_hash);
+
+		let imported = RecordingImport::default();
+		let mut worker = MiningWorker {
+			builds: vec![sha3, blake],
+			algorithm: sha3_and_blake(),
+			block_import: Box::new(imported.clone()),
+			metrics: None,
+		};
+		assert_eq!(worker.all_metadata().len(), 2);
+
+		assert!(worker.submit_for(&blake_hash, 20u128.encode()));
+		assert_eq!(*imported.0.lock(), vec![blake_hash]);
+
+		// the SHA3 build was mining on top of the same parent, and is gone as well.
+		assert!(worker.all_metadata().is_empty());
+		assert!(!worker.submit_for(&sha3_hash, 10u128.encode()));
+		assert_eq!(*imported.0.lock(), vec![blake_hash]);
+	}
+}
@@ -32,7 +32,7 @@ pub use slots::SlotInfo;
 use slots::Slots;
 pub use aux_schema::{check_equivocation, MAX_SLOT_CAPACITY, PRUNING_BOUND};
 
-use std::{fmt::Debug, ops::Deref, pin::Pin, sync::Arc, time::Duration};
+use std::{fmt::Debug, ops::Deref, pin::Pin, sync::Arc, time::{Duration, Instant}};
 use codec::{Decode, Encode};
 use futures::{prelude::*, future::{self, Either}};
 use futures_timer::Delay;
@@ -187,6 +187,15 @@ pub trait SimpleSlotWorker<B: BlockT> {
 		slot_info: &SlotInfo,
 	) -> Duration;
 
+	/// Returns the tracker of how long importing the authored blocks takes.
+	///
+	/// By default the import time isn't tracked. Workers which track it should set the
+	/// tracked time aside in [`Self::proposing_remaining_duration`], see
+	/// [`proposing_remaining_duration`].
+	fn block_import_duration(&self) -> Option<BlockImportDuration> {
+		None
+	}
+
 	/// Implements [`SlotWorker::on_slot`].
 	fn on_slot(
 		&mut self,
@@ -318,6 +327,7 @@ pub trait SimpleSlotWorker<B: BlockT> {
 
 		let block_import_params_maker = self.block_import_params();
 		let block_import = self.block_import();
+		let block_import_duration = self.block_import_duration();
 		let logging_target = self.logging_target();
 
 		proposal_work.and_then(move |(proposal, claim)| async move {
@@ -350,7 +360,15 @@ pub trait SimpleSlotWorker<B: BlockT> {
 			);
 
 			let header = block_import_params.post_header();
-			if let Err(err) = block_import.lock().import_block(block_import_params, Default::default()) {
+			let import_started = Instant::now();
+			let import_result = block_import.lock()
+				.import_block(block_import_params, Default::default());
+
+			if let Some(block_import_duration) = block_import_duration {
+				block_import_duration.note(import_started.elapsed());
+			}
+
+			if let Err(err) = import_result {
 				warn!(
 					target: logging_target,
 					"Error with block built on {:?}: {:?}",
@@ -572,6 +590,85 @@ impl SlotProportion {
 	}
 }
 
+/// The time it took to import the last block authored by a slot worker.
+///
+/// Cloning it gives another handle to the same value, so it can be shared with the futures
+/// importing the authored blocks.
+#[derive(Debug, Clone, Default)]
+pub struct BlockImportDuration(Arc<Mutex<Option<Duration>>>);
+
+impl BlockImportDuration {
+	/// Record the time the last import took.
+	pub fn note(&self, duration: Duration) {
+		*self.0.lock() = Some(duration);
+	}
+
+	/// The time the last import took, if any block was imported yet.
+	pub fn last(&self) -> Option<Duration> {
+		*self.0.lock()
+	}
+}
+
+/// Calculate the time left for proposing in the given slot.
+///
+/// The proposer gets at most `block_proposal_slot_portion` of the slot, minus the time the last
+/// block import took, so that the block is likely to be imported before the slot ends. At most
+/// half of the proposing time is set aside for the import, so that a single slow import can't
+/// prevent authoring altogether.
+///
+/// If blocks were missed since `parent_slot` an exponential lenience is added, which is capped
+/// to `max_block_proposal_slot_portion` of the slot, if given. `parent_slot` should be `None`
+/// when building on genesis.
+pub fn proposing_remaining_duration(
+	parent_slot: Option<Slot>,
+	slot_info: &SlotInfo,
+	block_proposal_slot_portion: &SlotProportion,
+	max_block_proposal_slot_portion: Option<&SlotProportion>,
+	last_import_duration: Option<Duration>,
+	log_target: &str,
+) -> Duration {
+	let proposing_duration = slot_info.duration.mul_f32(block_proposal_slot_portion.get());
+
+	let slot_remaining = slot_info.ends_at
+		.checked_duration_since(Instant::now())
+		.unwrap_or_default();
+
+	let import_duration = std::cmp::min(
+		last_import_duration.unwrap_or_default(),
+		proposing_duration / 2,
+	);
+	let slot_remaining = slot_remaining.checked_sub(import_duration).unwrap_or_default();
+
+	let slot_remaining = std::cmp::min(slot_remaining, proposing_duration);
+
+	// If parent is genesis block, we don't require any lenience factor.
+	let parent_slot = match parent_slot {
+		Some(parent_slot) => parent_slot,
+		None => return slot_remaining,
+	};
+
+	if let Some(slot_lenience) = slot_lenience_exponential(parent_slot, slot_info) {
+		debug!(
+			target: log_target,
+			"No block for {} slots. Applying exponential lenience of {}s",
+			slot_info.slot.saturating_sub(parent_slot + 1),
+			slot_lenience.as_secs(),
+		);
+
+		let proposing_remaining = slot_remaining + slot_lenience;
+
+		match max_block_proposal_slot_portion {
+			Some(max) => {
+				let max_proposing = slot_info.duration.mul_f32(max.get());
+				std::cmp::max(slot_remaining, std::cmp::min(proposing_remaining, max_proposing))
+			},
+			None => proposing_remaining,
+		}
+	} else {
+		slot_remaining
+	}
+}
+
 /// Calculate a slot duration lenience based on the number of missed slots from current
 /// to parent. If the number of skipped slots is greated than 0 this method will apply
 /// an exponential backoff of at most `2^7 * slot_duration`, if no slots were skipped
@@ -789,6 +886,76 @@ mod test {
 		);
 	}
 
+	fn slot_ending_in(slot: u64, ends_in: Duration) -> super::slots::SlotInfo {
+		super::slots::SlotInfo {
+			ends_at: Instant::now() + ends_in,
+			..self::slot(slot)
+		}
+	}
+
+	#[test]
+	fn proposing_time_sets_the_last_import_aside() {
+		let slot_info = slot_ending_in(2, Duration::from_millis(4000));
+		let remaining = super::proposing_remaining_duration(
+			Some(1.into()),
+			&slot_info,
+			&super::SlotProportion::new(1.0),
+			None,
+			Some(Duration::from_millis(1000)),
+			"test",
+		);
+
+		assert!(remaining <= Duration::from_millis(3000));
+		assert!(remaining > Duration::from_millis(2500));
+	}
+
+	#[test]
+	fn slow_import_cannot_take_more_than_half_of_the_proposing_time() {
+		let slot_info = slot_ending_in(2, SLOT_DURATION);
+		let remaining = super::proposing_remaining_duration(
+			Some(1.into()),
+			&slot_info,
+			&super::SlotProportion::new(1.0),
+			None,
+			Some(SLOT_DURATION * 2),
+			"test",
+		);
+
+		assert!(remaining <= Duration::from_millis(3000));
+		assert!(remaining > Duration::from_millis(2500));
+	}
+
+	#[test]
+	fn proposing_time_lenience_is_capped() {
+		// 3 slots were skipped, which gives a lenience of 2 slots.
+		let slot_info = slot_ending_in(5, SLOT_DURATION);
+		let remaining = |max: Option<&super::SlotProportion>| super::proposing_remaining_duration(
+			Some(1.into()),
+			&slot_info,
+			&super::SlotProportion::new(0.5),
+			max,
+			None,
+			"test",
+		);
+
+		assert_eq!(remaining(None), Duration::from_millis(15000));
+		assert_eq!(remaining(Some(&super::SlotProportion::new(1.0))), SLOT_DURATION);
+		// the cap never takes away from the regular proposing time.
+		assert_eq!(
+			remaining(Some(&super::SlotProportion::new(0.1))),
+			Duration::from_millis(3000),
+		);
+	}
+
+	#[test]
+	fn block_import_duration_is_shared() {
+		let duration = super::BlockImportDuration::default();
+		assert_eq!(duration.last(), None);
+
+		duration.clone().note(Duration::from_millis(42));
+		assert_eq!(duration.last(), Some(Duration::from_millis(42)));
+	}
+
 	#[derive(PartialEq, Debug)]
 	struct HeadState {
 		head_number: NumberFor<Block>,
@@ -0,0 +1,38 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Deterministic limiting of the stack height of a runtime.
+//!
+//! How deep native recursion can go depends on the execution engine and its configuration.
+//! To make all engines fail at the same point the runtime is instrumented to keep track of
+//! a logical stack height and to trap with `unreachable` when the height exceeds the limit.
+//!
+//! The logical stack height of a function is the number of its locals and arguments plus the
+//! maximum height of its operand stack.
+
+use crate::error::WasmError;
+use parity_wasm::elements::{deserialize_buffer, serialize};
+
+/// Instrument `code` so that it traps once the logical stack height exceeds `max_stack_height`.
+pub fn inject_limiter(code: &[u8], max_stack_height: u32) -> Result<Vec<u8>, WasmError> {
+	let module = deserialize_buffer(code).map_err(|_| WasmError::CantDeserializeWasm)?;
+	let module = pwasm_utils::stack_height::inject_limiter(module, max_stack_height)
+		.map_err(|e| WasmError::Other(format!("cannot inject the stack limiter: {:?}", e)))?;
+	serialize(module)
+		.map_err(|e| WasmError::Other(format!("cannot serialize the instrumented module: {}", e)))
+}
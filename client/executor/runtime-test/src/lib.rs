@@ -307,6 +307,11 @@ sp_core::wasm_export_functions! {
 		data.to_vec()
 	}
 
+	// Recurse `depth` times and return the sum of all depths.
+	fn test_recursion(depth: u32) -> u32 {
+		recurse(depth)
+	}
+
 	// Check that the heap at `heap_base + offset` don't contains the test message.
 	// After the check succeeds the test message is written into the heap.
 	//
@@ -359,6 +364,18 @@ sp_core::wasm_export_functions! {
 	}
  }
 
+#[cfg(not(feature = "std"))]
+#[inline(never)]
+fn recurse(depth: u32) -> u32 {
+	if depth == 0 {
+		return 0;
+	}
+	// The volatile read after the call prevents the recursion from being turned into a loop.
+	let this = depth;
+	let rest = recurse(depth - 1);
+	unsafe { sp_std::ptr::read_volatile(&this) }.wrapping_add(rest)
+}
+
 #[cfg(not(feature = "std"))]
 fn execute_sandboxed(
 	code: &[u8],
@@ -51,6 +51,12 @@ macro_rules! test_wasm_execution {
 			fn [<$method_name _compiled>]() {
 				$method_name(WasmExecutionMethod::Compiled);
 			}
+
+			#[test]
+			#[cfg(feature = "wasmtime")]
+			fn [<$method_name _compiled_pooled>]() {
+				$method_name(WasmExecutionMethod::CompiledPooled);
+			}
 		}
 	};
 
@@ -120,7 +126,7 @@ fn call_not_existing_function(wasm_method: WasmExecutionMethod) {
 					"\"Trap: Trap { kind: Host(Other(\\\"Function `missing_external` is only a stub. Calling a stub is not allowed.\\\")) }\""
 				),
 				#[cfg(feature = "wasmtime")]
-				WasmExecutionMethod::Compiled => assert!(
+				WasmExecutionMethod::Compiled | WasmExecutionMethod::CompiledPooled => assert!(
 					format!("{:?}", e).contains("Wasm execution trapped: call to a missing function env:missing_external")
 				),
 			}
@@ -147,7 +153,7 @@ fn call_yet_another_not_existing_function(wasm_method: WasmExecutionMethod) {
 					"\"Trap: Trap { kind: Host(Other(\\\"Function `yet_another_missing_external` is only a stub. Calling a stub is not allowed.\\\")) }\""
 				),
 				#[cfg(feature = "wasmtime")]
-				WasmExecutionMethod::Compiled => assert!(
+				WasmExecutionMethod::Compiled | WasmExecutionMethod::CompiledPooled => assert!(
 					format!("{:?}", e).contains("Wasm execution trapped: call to a missing function env:yet_another_missing_external")
 				),
 			}
@@ -557,10 +563,12 @@ fn returns_mutable_static(wasm_method: WasmExecutionMethod) {
 	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
 		wasm_method,
 		1024,
+		1,
 		&wasm_binary_unwrap()[..],
 		HostFunctions::host_functions(),
 		true,
 		None,
+		None,
 	).expect("Creates runtime");
 
 	let instance = runtime.new_instance().unwrap();
@@ -591,10 +599,12 @@ fn restoration_of_globals(wasm_method: WasmExecutionMethod) {
 	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
 		wasm_method,
 		REQUIRED_MEMORY_PAGES,
+		1,
 		&wasm_binary_unwrap()[..],
 		HostFunctions::host_functions(),
 		true,
 		None,
+		None,
 	).expect("Creates runtime");
 	let instance = runtime.new_instance().unwrap();
 
@@ -612,10 +622,12 @@ fn heap_is_reset_between_calls(wasm_method: WasmExecutionMethod) {
 	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
 		wasm_method,
 		1024,
+		1,
 		&wasm_binary_unwrap()[..],
 		HostFunctions::host_functions(),
 		true,
 		None,
+		None,
 	).expect("Creates runtime");
 	let instance = runtime.new_instance().unwrap();
 
@@ -632,6 +644,38 @@ fn heap_is_reset_between_calls(wasm_method: WasmExecutionMethod) {
 	instance.call_export("check_and_set_in_heap", &params).unwrap();
 }
 
+#[test]
+#[cfg(all(feature = "wasmtime", target_os = "linux"))]
+fn heap_is_reset_between_calls_compiled_pooled() {
+	heap_is_reset_between_calls(WasmExecutionMethod::CompiledPooled);
+}
+
+test_wasm_execution!(stack_height_is_limited_deterministically);
+fn stack_height_is_limited_deterministically(wasm_method: WasmExecutionMethod) {
+	let executor = crate::WasmExecutor::new(
+		wasm_method,
+		Some(1024),
+		HostFunctions::host_functions(),
+		8,
+		None,
+	).with_max_stack_height(1024);
+	let mut ext = TestExternalities::default();
+	let mut call = |depth: u32| executor.call_in_wasm(
+		&wasm_binary_unwrap()[..],
+		None,
+		"test_recursion",
+		&depth.encode(),
+		&mut ext.ext(),
+		sp_core::traits::MissingHostFunctions::Allow,
+	);
+
+	assert_eq!(call(10).unwrap(), 55u32.encode());
+	// Deep enough to exceed the logical limit while staying far below the native stack limit
+	// of every execution method.
+	let err = call(1000).unwrap_err();
+	assert!(err.to_lowercase().contains("unreachable"), "unexpected error: {}", err);
+}
+
 test_wasm_execution!(parallel_execution);
 fn parallel_execution(wasm_method: WasmExecutionMethod) {
 	let executor = std::sync::Arc::new(crate::WasmExecutor::new(
@@ -106,6 +106,8 @@ pub struct WasmExecutor {
 	/// The path to a directory which the executor can leverage for a file cache, e.g. put there
 	/// compiled artifacts.
 	cache_path: Option<PathBuf>,
+	/// The maximum logical stack height of the runtime, if any.
+	max_stack_height: Option<u32>,
 }
 
 impl WasmExecutor {
@@ -140,9 +142,20 @@ impl WasmExecutor {
 			cache: Arc::new(RuntimeCache::new(max_runtime_instances, cache_path.clone())),
 			max_runtime_instances,
 			cache_path,
+			max_stack_height: None,
 		}
 	}
 
+	/// Limit the logical stack height of the runtime to `max_stack_height`.
+	///
+	/// The runtime is instrumented to trap once the limit is exceeded. Unlike the native stack
+	/// limit this is the same for all execution methods. A runtime that exhausts the stack on
+	/// one method therefore fails on all of them.
+	pub fn with_max_stack_height(mut self, max_stack_height: u32) -> Self {
+		self.max_stack_height = Some(max_stack_height);
+		self
+	}
+
 	/// Execute the given closure `f` with the latest runtime (based on `runtime_code`).
 	///
 	/// The closure `f` is expected to return `Err(_)` when there happened a `panic!` in native code
@@ -177,6 +190,7 @@ impl WasmExecutor {
 			self.default_heap_pages,
 			&*self.host_functions,
 			allow_missing_host_functions,
+			self.max_stack_height,
 			|module, instance, version, ext| {
 				let module = AssertUnwindSafe(module);
 				let instance = AssertUnwindSafe(instance);
@@ -222,9 +236,11 @@ impl sp_core::traits::CallInWasm for WasmExecutor {
 			let module = crate::wasm_runtime::create_wasm_runtime_with_code(
 				self.method,
 				self.default_heap_pages,
+				1,
 				&wasm_code,
 				self.host_functions.to_vec(),
 				allow_missing_host_functions,
+				self.max_stack_height,
 				self.cache_path.as_deref(),
 			)
 				.map_err(|e| format!("Failed to create module: {:?}", e))?;
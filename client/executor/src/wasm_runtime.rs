@@ -41,6 +41,12 @@ pub enum WasmExecutionMethod {
 	/// Uses the Wasmtime compiled runtime.
 	#[cfg(feature = "wasmtime")]
 	Compiled,
+	/// Uses the Wasmtime compiled runtime with a pool of pre-created instances.
+	///
+	/// On Linux the linear memory of an instance is restored with copy-on-write mappings
+	/// of its initial contents before each call instead of writing the data segments again.
+	#[cfg(feature = "wasmtime")]
+	CompiledPooled,
 }
 
 impl Default for WasmExecutionMethod {
@@ -189,6 +195,8 @@ impl RuntimeCache {
 	///
 	/// `allow_missing_func_imports` - Ignore missing function imports.
 	///
+	/// `max_stack_height` - The maximum logical stack height of the runtime, if any.
+	///
 	/// `max_runtime_instances` - The size of the instances cache.
 	///
 	/// `f` - Function to execute.
@@ -208,6 +216,7 @@ impl RuntimeCache {
 		default_heap_pages: u64,
 		host_functions: &[&'static dyn Function],
 		allow_missing_func_imports: bool,
+		max_stack_height: Option<u32>,
 		f: F,
 	) -> Result<Result<R, Error>, Error>
 		where F: FnOnce(
@@ -243,6 +252,7 @@ impl RuntimeCache {
 					heap_pages,
 					host_functions.into(),
 					allow_missing_func_imports,
+					max_stack_height,
 					self.max_runtime_instances,
 					self.cache_path.as_deref(),
 				);
@@ -275,12 +285,22 @@ impl RuntimeCache {
 }
 
 /// Create a wasm runtime with the given `code`.
+///
+/// `max_instances` is the number of instances that are created up front for execution methods
+/// that keep a pool of instances. It also bounds the number of instances such a pool keeps
+/// alive. The instances cached by `RuntimeCache` are taken from the pool and count against
+/// that bound, so the pool and the cache together keep at most `max_instances` instances.
+///
+/// `max_stack_height` limits the logical stack height of the runtime in the same way for all
+/// execution methods. No limit is enforced by the instrumentation if it is `None`.
 pub fn create_wasm_runtime_with_code(
 	wasm_method: WasmExecutionMethod,
 	heap_pages: u64,
+	max_instances: usize,
 	code: &[u8],
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	max_stack_height: Option<u32>,
 	cache_path: Option<&Path>,
 ) -> Result<Arc<dyn WasmModule>, WasmError> {
 	match wasm_method {
@@ -288,14 +308,16 @@ pub fn create_wasm_runtime_with_code(
 			// Wasmi doesn't have any need in a cache directory.
 			//
 			// We drop the cache_path here to silence warnings that cache_path is not used if compiling
-			// without the `wasmtime` flag.
+			// without the `wasmtime` flag. Wasmi does not keep a pool of instances either.
 			drop(cache_path);
+			let _ = max_instances;
 
 			sc_executor_wasmi::create_runtime(
 				code,
 				heap_pages,
 				host_functions,
 				allow_missing_func_imports,
+				max_stack_height,
 			)
 			.map(|runtime| -> Arc<dyn WasmModule> { Arc::new(runtime) })
 		}
@@ -307,6 +329,19 @@ pub fn create_wasm_runtime_with_code(
 				host_functions,
 				allow_missing_func_imports,
 				cache_path,
+				sc_executor_wasmtime::InstantiationStrategy::LegacyInstanceReuse,
+				max_stack_height,
+			).map(|runtime| -> Arc<dyn WasmModule> { Arc::new(runtime) }),
+		#[cfg(feature = "wasmtime")]
+		WasmExecutionMethod::CompiledPooled =>
+			sc_executor_wasmtime::create_runtime(
+				code,
+				heap_pages,
+				host_functions,
+				allow_missing_func_imports,
+				cache_path,
+				sc_executor_wasmtime::InstantiationStrategy::Pooling { instances: max_instances },
+				max_stack_height,
 			).map(|runtime| -> Arc<dyn WasmModule> { Arc::new(runtime) }),
 	}
 }
@@ -338,6 +373,7 @@ fn create_versioned_wasm_runtime(
 	heap_pages: u64,
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	max_stack_height: Option<u32>,
 	max_instances: usize,
 	cache_path: Option<&Path>,
 ) -> Result<VersionedRuntime, WasmError> {
@@ -346,9 +382,11 @@ fn create_versioned_wasm_runtime(
 	let runtime = create_wasm_runtime_with_code(
 		wasm_method,
 		heap_pages,
+		max_instances,
 		&code,
 		host_functions,
 		allow_missing_func_imports,
+		max_stack_height,
 		cache_path,
 	)?;
 
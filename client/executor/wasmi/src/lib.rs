@@ -35,6 +35,7 @@ use sc_executor_common::wasm_runtime::{WasmModule, WasmInstance, InvokeMethod};
 use sc_executor_common::{
 	error::{Error, WasmError},
 	sandbox,
+	stack_height,
 };
 use sc_executor_common::util::{DataSegmentsSnapshot, WasmModuleInfo};
 
@@ -640,12 +641,24 @@ impl WasmModule for WasmiRuntime {
 
 /// Create a new `WasmiRuntime` given the code. This function loads the module and
 /// stores it in the instance.
+///
+/// When `max_stack_height` is given the code is instrumented to trap deterministically once
+/// the logical stack height exceeds it. See [`sc_executor_common::stack_height`].
 pub fn create_runtime(
 	code: &[u8],
 	heap_pages: u64,
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	max_stack_height: Option<u32>,
 ) -> Result<WasmiRuntime, WasmError> {
+	let instrumented_code;
+	let code = match max_stack_height {
+		Some(max_stack_height) => {
+			instrumented_code = stack_height::inject_limiter(code, max_stack_height)?;
+			&instrumented_code[..]
+		},
+		None => code,
+	};
 	let module = Module::from_buffer(&code).map_err(|_| WasmError::InvalidModule)?;
 
 	// Extract the data segments from the wasm code.
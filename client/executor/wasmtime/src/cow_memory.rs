@@ -0,0 +1,352 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Linear memories that are restored to their initial contents with copy-on-write mappings.
+//!
+//! The initial contents of the linear memory, i.e. the data segments of the module, are written
+//! once into an in-memory file (`memfd`). Every linear memory maps this file privately, so that
+//! writes of the instance only touch its own copies of the pages. Discarding these copies with
+//! `madvise(MADV_DONTNEED)` restores the initial image without copying any data.
+
+use sc_executor_common::util::DataSegmentsSnapshot;
+use std::{
+	collections::HashMap,
+	fs::File,
+	os::unix::{fs::FileExt, io::{AsRawFd, FromRawFd}},
+	ptr,
+	sync::{Arc, Mutex, Weak, atomic::{AtomicUsize, Ordering}},
+};
+use wasmtime::{LinearMemory, MemoryCreator, MemoryType};
+
+/// The size of a wasm page in bytes.
+const WASM_PAGE_SIZE: usize = 65536;
+
+/// The number of wasm pages that can be addressed with 32 bit pointers.
+const WASM_MAX_PAGES: usize = 65536;
+
+/// The initial contents of a linear memory backed by an in-memory file.
+pub struct MemoryImage {
+	file: File,
+	len: usize,
+}
+
+impl MemoryImage {
+	/// Write the given data segments to a new in-memory file.
+	pub fn new(data_segments: &DataSegmentsSnapshot) -> Result<Self, String> {
+		let mut len = 0;
+		data_segments.apply(|offset, contents| -> Result<(), String> {
+			len = len.max(offset as usize + contents.len());
+			Ok(())
+		})?;
+		let len = round_up_to_host_page(len);
+
+		// Safe because the name is a valid nul-terminated string and the returned file
+		// descriptor is owned by `file` afterwards. libc only exposes a `memfd_create` wrapper
+		// for glibc targets since 0.2.112, so the syscall is invoked directly.
+		let file = unsafe {
+			let fd = libc::syscall(
+				libc::SYS_memfd_create,
+				b"wasm-memory-image\0".as_ptr(),
+				libc::MFD_CLOEXEC,
+			) as libc::c_int;
+			if fd < 0 {
+				return Err(format!("memfd_create failed: {}", std::io::Error::last_os_error()));
+			}
+			File::from_raw_fd(fd)
+		};
+		file.set_len(len as u64)
+			.map_err(|e| format!("cannot resize the memory image: {}", e))?;
+		data_segments.apply(|offset, contents| {
+			file.write_all_at(contents, offset as u64)
+				.map_err(|e| format!("cannot write the memory image: {}", e))
+		})?;
+
+		Ok(Self { file, len })
+	}
+}
+
+/// A `MemoryCreator` that maps a [`MemoryImage`] into every memory it creates.
+///
+/// The memories are handed to wasmtime. Use [`CowMemoryCreator::take`] to get a handle that
+/// allows resetting a memory once the instance was created.
+pub struct CowMemoryCreator {
+	image: Arc<MemoryImage>,
+	created: Mutex<HashMap<usize, Weak<CowMemory>>>,
+}
+
+impl CowMemoryCreator {
+	/// Create a new memory creator for the given image.
+	pub fn new(image: MemoryImage) -> Self {
+		Self {
+			image: Arc::new(image),
+			created: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Returns the handle of the memory that starts at `base`.
+	///
+	/// Returns `None` if the memory was not created by this creator.
+	pub fn take(&self, base: *mut u8) -> Option<Arc<CowMemory>> {
+		self.created
+			.lock()
+			.expect("the lock is never held across a panic; qed")
+			.remove(&(base as usize))
+			.and_then(|memory| memory.upgrade())
+	}
+}
+
+unsafe impl MemoryCreator for CowMemoryCreator {
+	fn new_memory(
+		&self,
+		ty: MemoryType,
+		reserved_size_in_bytes: Option<u64>,
+		guard_size_in_bytes: u64,
+	) -> Result<Box<dyn LinearMemory>, String> {
+		let memory = Arc::new(CowMemory::new(
+			self.image.clone(),
+			ty.limits().min() as usize,
+			ty.limits().max().map(|max| max as usize),
+			reserved_size_in_bytes.map(|size| size as usize),
+			guard_size_in_bytes as usize,
+		)?);
+
+		let mut created = self.created
+			.lock()
+			.expect("the lock is never held across a panic; qed");
+		// Drop the entries of memories which were never taken, e.g. because the
+		// instantiation failed.
+		created.retain(|_, memory| memory.strong_count() > 0);
+		created.insert(memory.base as usize, Arc::downgrade(&memory));
+
+		Ok(Box::new(SharedCowMemory(memory)))
+	}
+}
+
+/// A linear memory whose initial contents are mapped copy-on-write from a [`MemoryImage`].
+///
+/// The memory never moves. Its whole address range is reserved up front and pages are made
+/// accessible as the memory grows.
+pub struct CowMemory {
+	base: *mut u8,
+	/// The size of the reserved address range including the guard pages.
+	mapped: usize,
+	/// The maximum size the memory can grow to without moving.
+	reserved: usize,
+	maximum: Option<u32>,
+	/// The number of bytes that are currently accessible.
+	accessible: AtomicUsize,
+	/// The number of bytes that are accessible right after a reset.
+	baseline: AtomicUsize,
+	// Keep the image alive as long as it is mapped.
+	_image: Arc<MemoryImage>,
+}
+
+// The memory is only accessed by the instance that owns it. The atomics merely make
+// the bookkeeping sound when the instance is moved to another thread.
+unsafe impl Send for CowMemory {}
+unsafe impl Sync for CowMemory {}
+
+impl CowMemory {
+	fn new(
+		image: Arc<MemoryImage>,
+		minimum: usize,
+		maximum: Option<usize>,
+		reserved: Option<usize>,
+		guard: usize,
+	) -> Result<Self, String> {
+		let reserved = reserved
+			.unwrap_or_else(|| maximum.unwrap_or(WASM_MAX_PAGES) * WASM_PAGE_SIZE);
+		let accessible = minimum * WASM_PAGE_SIZE;
+		if accessible > reserved || image.len > accessible {
+			return Err("the initial memory does not fit into the reservation".into());
+		}
+		let mapped = reserved + guard;
+
+		unsafe {
+			let base = libc::mmap(
+				ptr::null_mut(),
+				mapped,
+				libc::PROT_NONE,
+				libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_NORESERVE,
+				-1,
+				0,
+			);
+			if base == libc::MAP_FAILED {
+				return Err(format!("mmap failed: {}", std::io::Error::last_os_error()));
+			}
+			let memory = Self {
+				base: base as *mut u8,
+				mapped,
+				reserved,
+				maximum: maximum.map(|max| max as u32),
+				accessible: AtomicUsize::new(0),
+				baseline: AtomicUsize::new(0),
+				_image: image.clone(),
+			};
+
+			if image.len > 0 {
+				let mapped_image = libc::mmap(
+					base,
+					image.len,
+					libc::PROT_READ | libc::PROT_WRITE,
+					libc::MAP_PRIVATE | libc::MAP_FIXED,
+					image.file.as_raw_fd(),
+					0,
+				);
+				if mapped_image == libc::MAP_FAILED {
+					return Err(format!(
+						"mmap of the memory image failed: {}",
+						std::io::Error::last_os_error(),
+					));
+				}
+			}
+			memory.protect(image.len, accessible, libc::PROT_READ | libc::PROT_WRITE)?;
+			memory.accessible.store(accessible, Ordering::SeqCst);
+			memory.baseline.store(accessible, Ordering::SeqCst);
+
+			Ok(memory)
+		}
+	}
+
+	/// Remember the current size of the memory as the size to restore on [`Self::reset`].
+	///
+	/// Called after the memory was grown by the heap pages of the runtime.
+	pub fn set_baseline(&self) {
+		self.baseline.store(self.accessible.load(Ordering::SeqCst), Ordering::SeqCst);
+	}
+
+	/// Restore the initial image and shrink the memory back to its baseline size.
+	pub fn reset(&self) -> Result<(), String> {
+		let accessible = self.accessible.load(Ordering::SeqCst);
+		let baseline = self.baseline.load(Ordering::SeqCst);
+
+		// Discarding the pages of a private file mapping reverts them to the contents of the
+		// file, all other pages are zero filled on the next access.
+		let result = unsafe { libc::madvise(self.base as _, accessible, libc::MADV_DONTNEED) };
+		if result != 0 {
+			return Err(format!("madvise failed: {}", std::io::Error::last_os_error()));
+		}
+		if accessible > baseline {
+			self.protect(baseline, accessible, libc::PROT_NONE)?;
+			self.accessible.store(baseline, Ordering::SeqCst);
+		}
+
+		Ok(())
+	}
+
+	/// Change the protection of the pages in `start..end`.
+	fn protect(&self, start: usize, end: usize, prot: libc::c_int) -> Result<(), String> {
+		if start >= end {
+			return Ok(());
+		}
+		let result = unsafe { libc::mprotect(self.base.add(start) as _, end - start, prot) };
+		if result != 0 {
+			return Err(format!("mprotect failed: {}", std::io::Error::last_os_error()));
+		}
+		Ok(())
+	}
+}
+
+impl Drop for CowMemory {
+	fn drop(&mut self) {
+		unsafe {
+			libc::munmap(self.base as _, self.mapped);
+		}
+	}
+}
+
+/// The part of a [`CowMemory`] that is handed to wasmtime.
+struct SharedCowMemory(Arc<CowMemory>);
+
+unsafe impl LinearMemory for SharedCowMemory {
+	fn size(&self) -> u32 {
+		(self.0.accessible.load(Ordering::SeqCst) / WASM_PAGE_SIZE) as u32
+	}
+
+	fn maximum(&self) -> Option<u32> {
+		self.0.maximum
+	}
+
+	fn grow(&self, delta: u32) -> Option<u32> {
+		let old_pages = self.size();
+		let new_pages = old_pages.checked_add(delta)?;
+		if self.0.maximum.map_or(false, |max| new_pages > max) {
+			return None;
+		}
+		let old_len = old_pages as usize * WASM_PAGE_SIZE;
+		let new_len = new_pages as usize * WASM_PAGE_SIZE;
+		if new_len > self.0.reserved {
+			return None;
+		}
+		self.0.protect(old_len, new_len, libc::PROT_READ | libc::PROT_WRITE).ok()?;
+		self.0.accessible.store(new_len, Ordering::SeqCst);
+		Some(old_pages)
+	}
+
+	fn as_ptr(&self) -> *mut u8 {
+		self.0.base
+	}
+}
+
+fn round_up_to_host_page(len: usize) -> usize {
+	let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+	(len + page_size - 1) / page_size * page_size
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn memory_with_image() -> CowMemory {
+		let module = parity_wasm::builder::module()
+			.memory().with_min(1).build()
+			.data().offset(parity_wasm::elements::Instruction::I32Const(16)).value(vec![1, 2, 3]).build()
+			.build();
+		let code = parity_wasm::serialize(module).unwrap();
+		let module_info = sc_executor_common::util::WasmModuleInfo::new(&code).unwrap();
+		let snapshot = DataSegmentsSnapshot::take(&module_info).unwrap();
+		let image = MemoryImage::new(&snapshot).unwrap();
+		CowMemory::new(Arc::new(image), 1, Some(4), None, 0).unwrap()
+	}
+
+	fn contents(memory: &CowMemory, range: std::ops::Range<usize>) -> Vec<u8> {
+		unsafe { std::slice::from_raw_parts(memory.base.add(range.start), range.len()) }.to_vec()
+	}
+
+	#[test]
+	fn reset_restores_image_and_size() {
+		let memory = Arc::new(memory_with_image());
+		let linear = SharedCowMemory(memory.clone());
+		assert_eq!(contents(&memory, 15..20), vec![0, 1, 2, 3, 0]);
+
+		assert_eq!(linear.grow(1), Some(1));
+		memory.set_baseline();
+		assert_eq!(linear.grow(2), Some(2));
+		assert_eq!(linear.grow(1), None);
+		unsafe {
+			*memory.base.add(17) = 42;
+			*memory.base.add(WASM_PAGE_SIZE * 2) = 42;
+		}
+
+		memory.reset().unwrap();
+		assert_eq!(linear.size(), 2);
+		assert_eq!(contents(&memory, 15..20), vec![0, 1, 2, 3, 0]);
+		assert_eq!(linear.grow(1), Some(2));
+		assert_eq!(contents(&memory, WASM_PAGE_SIZE * 2..WASM_PAGE_SIZE * 2 + 1), vec![0]);
+	}
+}
@@ -291,6 +291,11 @@ impl InstanceWrapper {
 		self.table.as_ref()
 	}
 
+	/// Returns the base address of the linear memory instance attached to this instance.
+	pub fn memory_base_ptr(&self) -> *mut u8 {
+		self.memory.data_ptr()
+	}
+
 	/// Returns the byte size of the linear memory instance attached to this instance.
 	pub fn memory_size(&self) -> u32 {
 		self.memory.data_size() as u32
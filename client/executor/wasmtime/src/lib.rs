@@ -18,6 +18,8 @@
 
 ///! Defines a `WasmRuntime` that uses the Wasmtime JIT to execute.
 
+#[cfg(target_os = "linux")]
+mod cow_memory;
 mod host;
 mod runtime;
 mod state_holder;
@@ -25,4 +27,4 @@ mod imports;
 mod instance_wrapper;
 mod util;
 
-pub use runtime::create_runtime;
+pub use runtime::{create_runtime, InstantiationStrategy};
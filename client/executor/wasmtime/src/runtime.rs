@@ -22,12 +22,19 @@ use crate::host::HostState;
 use crate::imports::{Imports, resolve_imports};
 use crate::instance_wrapper::{ModuleWrapper, InstanceWrapper, GlobalsSnapshot, EntryPoint};
 use crate::state_holder;
+#[cfg(target_os = "linux")]
+use crate::cow_memory::{CowMemory, CowMemoryCreator, MemoryImage};
+#[cfg(target_os = "linux")]
+use sc_executor_common::util::{WasmModuleInfo, DataSegmentsSnapshot};
 
+use std::cell::Cell;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::path::Path;
+use parking_lot::Mutex;
 use sc_executor_common::{
 	error::{Result, WasmError},
+	stack_height,
 	wasm_runtime::{WasmModule, WasmInstance, InvokeMethod},
 };
 use sp_allocator::FreeingBumpHeapAllocator;
@@ -35,6 +42,30 @@ use sp_runtime_interface::unpack_ptr_and_len;
 use sp_wasm_interface::{Function, Pointer, WordSize, Value};
 use wasmtime::{Config, Engine, Store};
 
+/// Defines how instances of a runtime are created and reset between calls.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum InstantiationStrategy {
+	/// Every call to `new_instance` creates a new instance. Before each call the data segments
+	/// are written to the linear memory again.
+	LegacyInstanceReuse,
+	/// The given number of instances is created up front and instances are put back into the
+	/// pool when they are dropped.
+	///
+	/// The number of instances also bounds how many instances are kept alive in total, whether
+	/// they are in the pool or handed out. Instances held by a caller, like the instances kept
+	/// by the runtime cache of `sc-executor`, count against it, so the pool doesn't keep a
+	/// second set of instances next to them. Instances created on demand beyond that number are
+	/// dropped after use.
+	///
+	/// On Linux the linear memory is restored with copy-on-write mappings of its initial image
+	/// before each call. Other platforms restore the data segments like
+	/// [`InstantiationStrategy::LegacyInstanceReuse`].
+	Pooling {
+		/// The maximum number of instances kept alive.
+		instances: usize,
+	},
+}
+
 /// A `WasmModule` implementation using wasmtime to compile the runtime module to machine code
 /// and execute the compiled code.
 pub struct WasmtimeRuntime {
@@ -43,10 +74,16 @@ pub struct WasmtimeRuntime {
 	allow_missing_func_imports: bool,
 	host_functions: Vec<&'static dyn Function>,
 	engine: Engine,
+	instantiation_strategy: InstantiationStrategy,
+	/// Instances that are ready to be handed out by `new_instance`.
+	pool: Arc<InstancePool>,
+	#[cfg(target_os = "linux")]
+	memory_creator: Option<Arc<CowMemoryCreator>>,
 }
 
-impl WasmModule for WasmtimeRuntime {
-	fn new_instance(&self) -> Result<Box<dyn WasmInstance>> {
+impl WasmtimeRuntime {
+	/// Create a new instance of the module.
+	fn instantiate(&self) -> Result<WasmtimeInstance> {
 		let store = Store::new(&self.engine);
 
 		// Scan all imports, find the matching host functions, and create stubs that adapt arguments
@@ -64,7 +101,20 @@ impl WasmModule for WasmtimeRuntime {
 		let heap_base = instance_wrapper.extract_heap_base()?;
 		let globals_snapshot = GlobalsSnapshot::take(&instance_wrapper)?;
 
-		Ok(Box::new(WasmtimeInstance {
+		#[cfg(target_os = "linux")]
+		let cow_memory = match &self.memory_creator {
+			Some(creator) => {
+				let memory = creator
+					.take(instance_wrapper.memory_base_ptr())
+					.ok_or("linear memory was not created by the copy-on-write allocator")?;
+				// The memory was already grown by the heap pages. Keep them on reset.
+				memory.set_baseline();
+				Some(memory)
+			},
+			None => None,
+		};
+
+		Ok(WasmtimeInstance {
 			store,
 			instance_wrapper: Rc::new(instance_wrapper),
 			module_wrapper: Arc::clone(&self.module_wrapper),
@@ -72,7 +122,34 @@ impl WasmModule for WasmtimeRuntime {
 			globals_snapshot,
 			heap_pages: self.heap_pages,
 			heap_base,
-		}))
+			#[cfg(target_os = "linux")]
+			cow_memory,
+		})
+	}
+}
+
+impl WasmModule for WasmtimeRuntime {
+	fn new_instance(&self) -> Result<Box<dyn WasmInstance>> {
+		match self.instantiation_strategy {
+			InstantiationStrategy::LegacyInstanceReuse => Ok(Box::new(self.instantiate()?)),
+			InstantiationStrategy::Pooling { .. } => {
+				let instance = match self.pool.take() {
+					Some(instance) => instance,
+					None => match self.instantiate() {
+						Ok(instance) => instance,
+						Err(e) => {
+							self.pool.put_back(None);
+							return Err(e);
+						},
+					},
+				};
+				Ok(Box::new(PooledInstance {
+					instance: Some(instance),
+					pool: Arc::clone(&self.pool),
+					failed: Cell::new(false),
+				}))
+			},
+		}
 	}
 }
 
@@ -86,24 +163,40 @@ pub struct WasmtimeInstance {
 	imports: Imports,
 	heap_pages: u32,
 	heap_base: u32,
+	/// Set when the linear memory is restored with copy-on-write mappings.
+	#[cfg(target_os = "linux")]
+	cow_memory: Option<Arc<CowMemory>>,
 }
 
 // This is safe because `WasmtimeInstance` does not leak reference to `self.imports`
 // and all imports don't reference any anything, other than host functions and memory
 unsafe impl Send for WasmtimeInstance {}
 
-impl WasmInstance for WasmtimeInstance {
-	fn call(&self, method: InvokeMethod, data: &[u8]) -> Result<Vec<u8>> {
-		let entrypoint = self.instance_wrapper.resolve_entrypoint(method)?;
-		let allocator = FreeingBumpHeapAllocator::new(self.heap_base);
+impl WasmtimeInstance {
+	/// Restore the initial contents of the linear memory.
+	fn reset_memory(&self) -> Result<()> {
+		#[cfg(target_os = "linux")]
+		{
+			if let Some(memory) = &self.cow_memory {
+				return memory.reset().map_err(Into::into);
+			}
+		}
 
 		self.module_wrapper
 			.data_segments_snapshot()
 			.apply(|offset, contents| {
 				self.instance_wrapper
 					.write_memory_from(Pointer::new(offset), contents)
-			})?;
+			})
+	}
+}
 
+impl WasmInstance for WasmtimeInstance {
+	fn call(&self, method: InvokeMethod, data: &[u8]) -> Result<Vec<u8>> {
+		let entrypoint = self.instance_wrapper.resolve_entrypoint(method)?;
+		let allocator = FreeingBumpHeapAllocator::new(self.heap_base);
+
+		self.reset_memory()?;
 		self.globals_snapshot.apply(&*self.instance_wrapper)?;
 
 		perform_call(
@@ -120,6 +213,83 @@ impl WasmInstance for WasmtimeInstance {
 	}
 }
 
+/// The instances of a `WasmtimeRuntime` using [`InstantiationStrategy::Pooling`].
+struct InstancePool<I = WasmtimeInstance> {
+	/// The maximum number of instances that are alive, idle or handed out.
+	capacity: usize,
+	inner: Mutex<InstancePoolInner<I>>,
+}
+
+struct InstancePoolInner<I> {
+	/// Instances that are ready to be handed out.
+	idle: Vec<I>,
+	/// The number of instances that are currently handed out.
+	in_use: usize,
+}
+
+impl<I> InstancePool<I> {
+	fn new(capacity: usize) -> Self {
+		InstancePool {
+			capacity,
+			inner: Mutex::new(InstancePoolInner { idle: Vec::new(), in_use: 0 }),
+		}
+	}
+
+	/// Take an idle instance, if any. Either way, the caller must call `put_back` once the
+	/// instance it uses is dropped.
+	fn take(&self) -> Option<I> {
+		let mut inner = self.inner.lock();
+		inner.in_use += 1;
+		inner.idle.pop()
+	}
+
+	/// Return an instance that was handed out. It is only kept if the number of alive
+	/// instances stays within the capacity.
+	fn put_back(&self, instance: Option<I>) {
+		let mut inner = self.inner.lock();
+		inner.in_use -= 1;
+		if inner.idle.len() + inner.in_use < self.capacity {
+			inner.idle.extend(instance);
+		}
+	}
+}
+
+/// An instance that is taken from the pool of a `WasmtimeRuntime` and is put back on drop.
+struct PooledInstance {
+	/// Always `Some` until the instance is dropped.
+	instance: Option<WasmtimeInstance>,
+	pool: Arc<InstancePool>,
+	/// Instances that failed a call are not put back into the pool.
+	failed: Cell<bool>,
+}
+
+impl PooledInstance {
+	fn instance(&self) -> &WasmtimeInstance {
+		self.instance.as_ref().expect("only taken in `drop`; qed")
+	}
+}
+
+impl WasmInstance for PooledInstance {
+	fn call(&self, method: InvokeMethod, data: &[u8]) -> Result<Vec<u8>> {
+		let result = self.instance().call(method, data);
+		if result.is_err() {
+			self.failed.set(true);
+		}
+		result
+	}
+
+	fn get_global_const(&self, name: &str) -> Result<Option<Value>> {
+		self.instance().get_global_const(name)
+	}
+}
+
+impl Drop for PooledInstance {
+	fn drop(&mut self) {
+		let failed = self.failed.get();
+		self.pool.put_back(self.instance.take().filter(|_| !failed));
+	}
+}
+
 /// Prepare a directory structure and a config file to enable wasmtime caching.
 ///
 /// In case of an error the caching will not be enabled.
@@ -162,16 +332,44 @@ directory = \"{cache_dir}\"
 /// machine code, which can be computationally heavy.
 ///
 /// The `cache_path` designates where this executor implementation can put compiled artifacts.
+///
+/// With [`InstantiationStrategy::Pooling`] the pool is filled before this function returns.
+///
+/// When `max_stack_height` is given the code is instrumented before compilation to trap
+/// deterministically once the logical stack height exceeds it. See
+/// [`sc_executor_common::stack_height`].
 pub fn create_runtime(
 	code: &[u8],
 	heap_pages: u64,
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
 	cache_path: Option<&Path>,
+	instantiation_strategy: InstantiationStrategy,
+	max_stack_height: Option<u32>,
 ) -> std::result::Result<WasmtimeRuntime, WasmError> {
+	let instrumented_code;
+	let code = match max_stack_height {
+		Some(max_stack_height) => {
+			instrumented_code = stack_height::inject_limiter(code, max_stack_height)?;
+			&instrumented_code[..]
+		},
+		None => code,
+	};
+
 	// Create the engine, store and finally the module from the given code.
 	let mut config = Config::new();
 	config.cranelift_opt_level(wasmtime::OptLevel::SpeedAndSize);
+	#[cfg(target_os = "linux")]
+	let memory_creator = match instantiation_strategy {
+		InstantiationStrategy::Pooling { .. } => create_memory_creator(code),
+		InstantiationStrategy::LegacyInstanceReuse => None,
+	};
+	#[cfg(target_os = "linux")]
+	{
+		if let Some(creator) = &memory_creator {
+			config.with_host_memory(creator.clone());
+		}
+	}
 	if let Some(cache_path) = cache_path {
 		if let Err(reason) = setup_wasmtime_caching(cache_path, &mut config) {
 			log::warn!(
@@ -185,13 +383,55 @@ pub fn create_runtime(
 	let module_wrapper = ModuleWrapper::new(&engine, code)
 		.map_err(|e| WasmError::Other(format!("cannot create module: {}", e)))?;
 
-	Ok(WasmtimeRuntime {
+	let runtime = WasmtimeRuntime {
 		module_wrapper: Arc::new(module_wrapper),
 		heap_pages: heap_pages as u32,
 		allow_missing_func_imports,
 		host_functions,
 		engine,
-	})
+		instantiation_strategy,
+		pool: Arc::new(InstancePool::new(match instantiation_strategy {
+			InstantiationStrategy::Pooling { instances } => instances,
+			InstantiationStrategy::LegacyInstanceReuse => 0,
+		})),
+		#[cfg(target_os = "linux")]
+		memory_creator,
+	};
+
+	if let InstantiationStrategy::Pooling { instances } = instantiation_strategy {
+		let pool = (0..instances)
+			.map(|_| runtime.instantiate())
+			.collect::<Result<Vec<_>>>()
+			.map_err(|e| WasmError::Instantiation(format!("cannot fill the instance pool: {}", e)))?;
+		runtime.pool.inner.lock().idle = pool;
+	}
+
+	Ok(runtime)
+}
+
+/// Create a memory creator that maps the initial memory of the module copy-on-write.
+///
+/// Returns `None` if the image cannot be created. Memories are then restored by writing
+/// the data segments.
+#[cfg(target_os = "linux")]
+fn create_memory_creator(code: &[u8]) -> Option<Arc<CowMemoryCreator>> {
+	let image = WasmModuleInfo::new(code)
+		.ok_or_else(|| "cannot deserialize module".to_string())
+		.and_then(|module_info| {
+			DataSegmentsSnapshot::take(&module_info).map_err(|e| e.to_string())
+		})
+		.and_then(|data_segments| MemoryImage::new(&data_segments));
+
+	match image {
+		Ok(image) => Some(Arc::new(CowMemoryCreator::new(image))),
+		Err(reason) => {
+			log::warn!(
+				"failed to create the memory image. Falling back to restoring data segments: {}.",
+				reason,
+			);
+			None
+		},
+	}
 }
 
 fn perform_call(
@@ -232,3 +472,44 @@ fn extract_output_data(
 	instance.read_memory_into(Pointer::new(output_ptr), &mut output)?;
 	Ok(output)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::InstancePool;
+
+	#[test]
+	fn instance_pool_bounds_alive_instances() {
+		let pool = InstancePool::new(2);
+		pool.inner.lock().idle = vec![1, 2];
+
+		// instances held by the caller, e.g. the runtime cache, count against the capacity.
+		let first = pool.take();
+		let second = pool.take();
+		assert_eq!((first, second), (Some(2), Some(1)));
+
+		// the pool is exhausted, so the caller creates an instance on demand.
+		assert_eq!(pool.take(), None);
+		pool.put_back(Some(3));
+		assert!(pool.inner.lock().idle.is_empty());
+
+		// once the held instances are released, they are kept again.
+		pool.put_back(first);
+		pool.put_back(second);
+		assert_eq!(pool.inner.lock().idle, vec![2, 1]);
+		assert_eq!(pool.inner.lock().in_use, 0);
+	}
+
+	#[test]
+	fn instance_pool_drops_failed_instances() {
+		let pool = InstancePool::new(1);
+		pool.inner.lock().idle = vec![1];
+
+		assert_eq!(pool.take(), Some(1));
+		// a failed instance is returned as `None`, leaving room for a fresh one.
+		pool.put_back(None);
+		assert!(pool.inner.lock().idle.is_empty());
+		assert_eq!(pool.take(), None);
+		pool.put_back(Some(2));
+		assert_eq!(pool.inner.lock().idle, vec![2]);
+	}
+}
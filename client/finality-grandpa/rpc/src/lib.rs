@@ -36,12 +36,15 @@ mod finality;
 mod notification;
 mod report;
 
-use sc_finality_grandpa::GrandpaJustificationStream;
+use sc_finality_grandpa::{GrandpaEquivocationStream, GrandpaJustificationStream};
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 
 use finality::{EncodedFinalityProof, RpcFinalityProofProvider};
-use report::{ReportAuthoritySet, ReportVoterState, ReportedRoundStates};
-use notification::JustificationNotification;
+use report::{
+	ReportAuthoritySet, ReportVoterState, ReportedAuthoritySetChange, ReportedRoundStates,
+	ReportedVoterState,
+};
+use notification::{EquivocationNotification, JustificationNotification};
 
 type FutureResult<T> =
 	Box<dyn jsonrpc_core::futures::Future<Item = T, Error = jsonrpc_core::Error> + Send>;
@@ -57,6 +60,16 @@ pub trait GrandpaApi<Notification, Hash, Number> {
 	#[rpc(name = "grandpa_roundState")]
 	fn round_state(&self) -> FutureResult<ReportedRoundStates>;
 
+	/// Returns the prevote and precommit targets of every authority in the
+	/// current best round as well as the ongoing background rounds.
+	#[rpc(name = "grandpa_voterState")]
+	fn voter_state(&self) -> FutureResult<ReportedVoterState>;
+
+	/// Returns the id of every past authority set along with the number of
+	/// its last block.
+	#[rpc(name = "grandpa_authoritySetChanges")]
+	fn authority_set_changes(&self) -> FutureResult<Vec<ReportedAuthoritySetChange>>;
+
 	/// Returns the block most recently finalized by Grandpa, alongside
 	/// side its justification.
 	#[pubsub(
@@ -82,6 +95,31 @@ pub trait GrandpaApi<Notification, Hash, Number> {
 		id: SubscriptionId
 	) -> jsonrpc_core::Result<bool>;
 
+	/// Returns the equivocations detected by the local voter, along with the
+	/// outcome of reporting them on-chain.
+	#[pubsub(
+		subscription = "grandpa_equivocations",
+		subscribe,
+		name = "grandpa_subscribeEquivocations"
+	)]
+	fn subscribe_equivocations(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<EquivocationNotification>
+	);
+
+	/// Unsubscribe from receiving notifications about detected equivocations.
+	#[pubsub(
+		subscription = "grandpa_equivocations",
+		unsubscribe,
+		name = "grandpa_unsubscribeEquivocations"
+	)]
+	fn unsubscribe_equivocations(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId
+	) -> jsonrpc_core::Result<bool>;
+
 	/// Prove finality for the given block number by returning the Justification for the last block
 	/// in the set and all the intermediary headers to link them together.
 	#[rpc(name = "grandpa_proveFinality")]
@@ -96,6 +134,7 @@ pub struct GrandpaRpcHandler<AuthoritySet, VoterState, Block: BlockT, ProofProvi
 	authority_set: AuthoritySet,
 	voter_state: VoterState,
 	justification_stream: GrandpaJustificationStream<Block>,
+	equivocation_stream: GrandpaEquivocationStream<Block>,
 	manager: SubscriptionManager,
 	finality_proof_provider: Arc<ProofProvider>,
 }
@@ -108,6 +147,7 @@ impl<AuthoritySet, VoterState, Block: BlockT, ProofProvider>
 		authority_set: AuthoritySet,
 		voter_state: VoterState,
 		justification_stream: GrandpaJustificationStream<Block>,
+		equivocation_stream: GrandpaEquivocationStream<Block>,
 		executor: E,
 		finality_proof_provider: Arc<ProofProvider>,
 	) -> Self
@@ -119,6 +159,7 @@ impl<AuthoritySet, VoterState, Block: BlockT, ProofProvider>
 			authority_set,
 			voter_state,
 			justification_stream,
+			equivocation_stream,
 			manager,
 			finality_proof_provider,
 		}
@@ -142,6 +183,18 @@ where
 		Box::new(future.map_err(jsonrpc_core::Error::from).compat())
 	}
 
+	fn voter_state(&self) -> FutureResult<ReportedVoterState> {
+		let voter_state = ReportedVoterState::from(&self.authority_set, &self.voter_state);
+		let future = async move { voter_state }.boxed();
+		Box::new(future.map_err(jsonrpc_core::Error::from).compat())
+	}
+
+	fn authority_set_changes(&self) -> FutureResult<Vec<ReportedAuthoritySetChange>> {
+		let changes = ReportedAuthoritySetChange::all(&self.authority_set);
+		let future = async move { changes }.boxed();
+		Box::new(future.map_err(jsonrpc_core::Error::from).compat())
+	}
+
 	fn subscribe_justifications(
 		&self,
 		_metadata: Self::Metadata,
@@ -168,6 +221,32 @@ where
 		Ok(self.manager.cancel(id))
 	}
 
+	fn subscribe_equivocations(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<EquivocationNotification>
+	) {
+		let stream = self.equivocation_stream.subscribe()
+			.map(|x| Ok::<_,()>(EquivocationNotification::from(x)))
+			.map_err(|e| warn!("Notification stream error: {:?}", e))
+			.compat();
+
+		self.manager.add(subscriber, |sink| {
+			let stream = stream.map(|res| Ok(res));
+			sink.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(stream)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_equivocations(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId
+	) -> jsonrpc_core::Result<bool> {
+		Ok(self.manager.cancel(id))
+	}
+
 	fn prove_finality(
 		&self,
 		block: NumberFor<Block>,
@@ -196,7 +275,8 @@ mod tests {
 	use sc_block_builder::{BlockBuilder, RecordProof};
 	use sc_finality_grandpa::{
 		report, AuthorityId, GrandpaJustificationSender, GrandpaJustification,
-		FinalityProof,
+		FinalityProof, GrandpaEquivocationSender, EquivocationNotification as VoterEquivocation,
+		EquivocationReport, RoundVotes, VoteTarget,
 	};
 	use sp_blockchain::HeaderBackend;
 	use sp_core::crypto::Public;
@@ -228,12 +308,20 @@ mod tests {
 		fn get(&self) -> (u64, HashSet<AuthorityId>) {
 			(1, voters())
 		}
+
+		fn authority_set_changes(&self) -> Vec<(u64, u64)> {
+			vec![(0, 42)]
+		}
 	}
 
 	impl ReportVoterState for EmptyVoterState {
 		fn get(&self) -> Option<report::VoterState<AuthorityId>> {
 			None
 		}
+
+		fn round_votes(&self, _round: u64) -> Option<RoundVotes> {
+			None
+		}
 	}
 
 	fn header(number: u64) -> Header {
@@ -295,6 +383,18 @@ mod tests {
 				best_round: (2, best_round_state),
 			})
 		}
+
+		fn round_votes(&self, round: u64) -> Option<RoundVotes> {
+			let voter_id_1 = AuthorityId::from_slice(&[1; 32]);
+			let target = VoteTarget { hash: H256::from_low_u64_be(7).encode(), number: 7 };
+
+			let mut votes = RoundVotes::default();
+			votes.prevotes.insert(voter_id_1.clone(), target.clone());
+			if round == 1 {
+				votes.precommits.insert(voter_id_1, target);
+			}
+			Some(votes)
+		}
 	}
 
 	fn setup_io_handler<VoterState>(voter_state: VoterState) -> (
@@ -314,14 +414,31 @@ mod tests {
 		GrandpaJustificationSender<Block>,
 	) where
 		VoterState: ReportVoterState + Send + Sync + 'static,
+	{
+		let (io, justification_sender, _) =
+			setup_io_handler_with_senders(voter_state, finality_proof);
+		(io, justification_sender)
+	}
+
+	fn setup_io_handler_with_senders<VoterState>(
+		voter_state: VoterState,
+		finality_proof: Option<FinalityProof<Header>>,
+	) -> (
+		jsonrpc_core::MetaIoHandler<sc_rpc::Metadata>,
+		GrandpaJustificationSender<Block>,
+		GrandpaEquivocationSender<Block>,
+	) where
+		VoterState: ReportVoterState + Send + Sync + 'static,
 	{
 		let (justification_sender, justification_stream) = GrandpaJustificationStream::channel();
+		let (equivocation_sender, equivocation_stream) = GrandpaEquivocationStream::channel();
 		let finality_proof_provider = Arc::new(TestFinalityProofProvider { finality_proof });
 
 		let handler = GrandpaRpcHandler::new(
 			TestAuthoritySet,
 			voter_state,
 			justification_stream,
+			equivocation_stream,
 			sc_rpc::testing::TaskExecutor,
 			finality_proof_provider,
 		);
@@ -329,7 +446,7 @@ mod tests {
 		let mut io = jsonrpc_core::MetaIoHandler::default();
 		io.extend_with(GrandpaApi::to_delegate(handler));
 
-		(io, justification_sender)
+		(io, justification_sender, equivocation_sender)
 	}
 
 	#[test]
@@ -366,6 +483,55 @@ mod tests {
 		assert_eq!(io.handle_request_sync(request, meta), Some(response.into()));
 	}
 
+	#[test]
+	fn uninitialized_voter_state() {
+		let (io, _) = setup_io_handler(EmptyVoterState);
+
+		let request = r#"{"jsonrpc":"2.0","method":"grandpa_voterState","params":[],"id":1}"#;
+		let response = r#"{"jsonrpc":"2.0","error":{"code":1,"message":"GRANDPA RPC endpoint not ready"},"id":1}"#;
+
+		let meta = sc_rpc::Metadata::default();
+		assert_eq!(Some(response.into()), io.handle_request_sync(request, meta));
+	}
+
+	#[test]
+	fn voter_state_reports_vote_targets() {
+		let (io, _) = setup_io_handler(TestVoterState);
+
+		let request = r#"{"jsonrpc":"2.0","method":"grandpa_voterState","params":[],"id":1}"#;
+		let target = "{\"hash\":\"0x0000000000000000000000000000000000000000000000000000000000000007\",\"number\":7}";
+		let response = format!("{{\"jsonrpc\":\"2.0\",\"result\":{{\
+			\"background\":[{{\
+				\"round\":1,\"votes\":[\
+					{{\"id\":\"5C62Ck4UrFPiBtoCmeSrgF7x9yv9mn38446dhCpsi2mLHiFT\",\"precommit\":{target},\"prevote\":{target}}},\
+					{{\"id\":\"5C7LYpP2ZH3tpKbvVvwiVe54AapxErdPBbvkYhe6y9ZBkqWt\",\"precommit\":null,\"prevote\":null}}\
+				]\
+			}}],\
+			\"best\":{{\
+				\"round\":2,\"votes\":[\
+					{{\"id\":\"5C62Ck4UrFPiBtoCmeSrgF7x9yv9mn38446dhCpsi2mLHiFT\",\"precommit\":null,\"prevote\":{target}}},\
+					{{\"id\":\"5C7LYpP2ZH3tpKbvVvwiVe54AapxErdPBbvkYhe6y9ZBkqWt\",\"precommit\":null,\"prevote\":null}}\
+				]\
+			}},\
+			\"setId\":1\
+		}},\"id\":1}}", target = target);
+
+		let meta = sc_rpc::Metadata::default();
+		assert_eq!(io.handle_request_sync(request, meta), Some(response));
+	}
+
+	#[test]
+	fn authority_set_changes() {
+		let (io, _) = setup_io_handler(EmptyVoterState);
+
+		let request =
+			r#"{"jsonrpc":"2.0","method":"grandpa_authoritySetChanges","params":[],"id":1}"#;
+		let response = r#"{"jsonrpc":"2.0","result":[{"lastBlock":42,"setId":0}],"id":1}"#;
+
+		let meta = sc_rpc::Metadata::default();
+		assert_eq!(Some(response.into()), io.handle_request_sync(request, meta));
+	}
+
 	fn setup_session() -> (sc_rpc::Metadata, jsonrpc_core::futures::sync::mpsc::Receiver<String>) {
 		let (tx, rx) = jsonrpc_core::futures::sync::mpsc::channel(1);
 		let meta = sc_rpc::Metadata::new(tx);
@@ -535,4 +701,65 @@ mod tests {
 		let finality_proof_rpc: FinalityProof<Header> = Decode::decode(&mut &result[..]).unwrap();
 		assert_eq!(finality_proof_rpc, finality_proof);
 	}
+
+	#[test]
+	fn subscribe_and_listen_to_one_equivocation() {
+		let (io, _, equivocation_sender) = setup_io_handler_with_senders(TestVoterState, None);
+		let (meta, receiver) = setup_session();
+
+		// Subscribe
+		let sub_request =
+			r#"{"jsonrpc":"2.0","method":"grandpa_subscribeEquivocations","params":[],"id":1}"#;
+
+		let resp = io.handle_request_sync(sub_request, meta.clone());
+		let mut resp: serde_json::Value = serde_json::from_str(&resp.unwrap()).unwrap();
+		let sub_id: String = serde_json::from_value(resp["result"].take()).unwrap();
+
+		// Notify with an equivocation that failed to be reported
+		let alice = Ed25519Keyring::Alice;
+		let signed_prevote = (
+			finality_grandpa::Prevote { target_hash: H256::from_low_u64_be(7), target_number: 7 },
+			alice.sign(&[]).into(),
+		);
+		let equivocation = sp_finality_grandpa::Equivocation::Prevote(
+			finality_grandpa::Equivocation {
+				round_number: 3,
+				identity: alice.public().into(),
+				first: signed_prevote.clone(),
+				second: signed_prevote,
+			},
+		);
+		equivocation_sender.notify(VoterEquivocation::<Block> {
+			set_id: 1,
+			equivocation: equivocation.clone(),
+			report: EquivocationReport::Failed("no key ownership proof".into()),
+		});
+
+		// Inspect what we received
+		let recv = receiver.take(1).wait().flatten().collect::<Vec<_>>();
+		let recv: Notification = serde_json::from_str(&recv[0]).unwrap();
+		let mut json_map = match recv.params {
+			Params::Map(json_map) => json_map,
+			_ => panic!(),
+		};
+
+		let recv_sub_id: String =
+			serde_json::from_value(json_map["subscription"].take()).unwrap();
+		let mut result = json_map["result"].take();
+		let recv_equivocation: sp_core::Bytes =
+			serde_json::from_value(result["equivocation"].take()).unwrap();
+		let recv_equivocation: sp_finality_grandpa::Equivocation<H256, u64> =
+			Decode::decode(&mut &recv_equivocation[..]).unwrap();
+
+		assert_eq!(recv.method, "grandpa_equivocations");
+		assert_eq!(recv_sub_id, sub_id);
+		assert_eq!(recv_equivocation, equivocation);
+		assert_eq!(result["setId"], 1);
+		assert_eq!(result["round"], 3);
+		assert_eq!(result["stage"], "prevote");
+		assert_eq!(
+			result["report"],
+			serde_json::json!({ "status": "failed", "error": "no key ownership proof" }),
+		);
+	}
 }
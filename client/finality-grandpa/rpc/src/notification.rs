@@ -19,7 +19,8 @@
 use serde::{Serialize, Deserialize};
 use parity_scale_codec::Encode;
 use sp_runtime::traits::Block as BlockT;
-use sc_finality_grandpa::GrandpaJustification;
+use sp_finality_grandpa::Equivocation;
+use sc_finality_grandpa::{AuthorityId, EquivocationReport, GrandpaJustification};
 
 /// An encoded justification proving that the given header has been finalized
 #[derive(Clone, Serialize, Deserialize)]
@@ -30,3 +31,56 @@ impl<Block: BlockT> From<GrandpaJustification<Block>> for JustificationNotificat
 		JustificationNotification(notification.encode().into())
 	}
 }
+
+/// Outcome of reporting an equivocation on-chain.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status", content = "error")]
+pub enum EquivocationReportStatus {
+	/// The report was submitted.
+	Submitted,
+	/// The offender is not part of the authority set, nothing was reported.
+	OffenderNotInSet,
+	/// Reporting failed.
+	Failed(String),
+}
+
+/// An equivocation detected by the GRANDPA voter, and how it was reported.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquivocationNotification {
+	set_id: u64,
+	round: u64,
+	stage: String,
+	offender: AuthorityId,
+	/// The encoded `sp_finality_grandpa::Equivocation`, with both votes and their signatures.
+	equivocation: sp_core::Bytes,
+	report: EquivocationReportStatus,
+}
+
+impl<Block: BlockT> From<sc_finality_grandpa::EquivocationNotification<Block>>
+	for EquivocationNotification
+{
+	fn from(notification: sc_finality_grandpa::EquivocationNotification<Block>) -> Self {
+		let (stage, round, offender) = match &notification.equivocation {
+			Equivocation::Prevote(equivocation) =>
+				("prevote", equivocation.round_number, equivocation.identity.clone()),
+			Equivocation::Precommit(equivocation) =>
+				("precommit", equivocation.round_number, equivocation.identity.clone()),
+		};
+
+		let report = match notification.report {
+			EquivocationReport::Submitted => EquivocationReportStatus::Submitted,
+			EquivocationReport::OffenderNotInSet => EquivocationReportStatus::OffenderNotInSet,
+			EquivocationReport::Failed(error) => EquivocationReportStatus::Failed(error),
+		};
+
+		EquivocationNotification {
+			set_id: notification.set_id,
+			round,
+			stage: stage.into(),
+			offender,
+			equivocation: notification.equivocation.encode().into(),
+			report,
+		}
+	}
+}
@@ -24,23 +24,33 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use sc_finality_grandpa::{report, AuthorityId, SharedAuthoritySet, SharedVoterState};
+use sc_finality_grandpa::{
+	report, AuthorityId, RoundVotes, SharedAuthoritySet, SharedVoterState, VoteTarget,
+};
+use sp_core::Bytes;
+use sp_runtime::traits::UniqueSaturatedInto;
 
 use crate::error::Error;
 
 /// Utility trait to get reporting data for the current GRANDPA authority set.
 pub trait ReportAuthoritySet {
 	fn get(&self) -> (u64, HashSet<AuthorityId>);
+
+	/// The id of every past authority set along with the number of its last block.
+	fn authority_set_changes(&self) -> Vec<(u64, u64)>;
 }
 
 /// Utility trait to get reporting data for the current GRANDPA voter state.
 pub trait ReportVoterState {
 	fn get(&self) -> Option<report::VoterState<AuthorityId>>;
+
+	/// The prevote and precommit targets of the authorities that voted in the given round.
+	fn round_votes(&self, round: u64) -> Option<RoundVotes>;
 }
 
 impl<H, N> ReportAuthoritySet for SharedAuthoritySet<H, N>
 where
-	N: Add<Output = N> + Ord + Clone + Debug,
+	N: Add<Output = N> + Ord + Clone + Debug + UniqueSaturatedInto<u64>,
 	H: Clone + Debug + Eq,
 {
 	fn get(&self) -> (u64, HashSet<AuthorityId>) {
@@ -52,12 +62,23 @@ where
 
 		(self.set_id(), current_voters)
 	}
+
+	fn authority_set_changes(&self) -> Vec<(u64, u64)> {
+		self.authority_set_changes()
+			.iter()
+			.map(|(set_id, number)| (*set_id, number.clone().unique_saturated_into()))
+			.collect()
+	}
 }
 
 impl ReportVoterState for SharedVoterState {
 	fn get(&self) -> Option<report::VoterState<AuthorityId>> {
 		self.voter_state()
 	}
+
+	fn round_votes(&self, round: u64) -> Option<RoundVotes> {
+		SharedVoterState::round_votes(self, round)
+	}
 }
 
 #[derive(Serialize, Deserialize)]
@@ -159,3 +180,141 @@ impl ReportedRoundStates {
 		})
 	}
 }
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportedVoteTarget {
+	hash: Bytes,
+	number: u32,
+}
+
+impl ReportedVoteTarget {
+	fn from(target: &VoteTarget) -> Result<Self, Error> {
+		use std::convert::TryInto;
+
+		Ok(Self {
+			hash: target.hash.clone().into(),
+			number: target.number.try_into()?,
+		})
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthorityVotes {
+	id: AuthorityId,
+	prevote: Option<ReportedVoteTarget>,
+	precommit: Option<ReportedVoteTarget>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportedRoundVotes {
+	round: u32,
+	votes: Vec<AuthorityVotes>,
+}
+
+impl ReportedRoundVotes {
+	fn from<VoterState: ReportVoterState>(
+		round: u64,
+		voter_state: &VoterState,
+		voters: &BTreeSet<AuthorityId>,
+	) -> Result<Self, Error> {
+		use std::convert::TryInto;
+
+		let round_votes = voter_state.round_votes(round).unwrap_or_default();
+		let votes = voters
+			.iter()
+			.map(|id| -> Result<_, Error> {
+				Ok(AuthorityVotes {
+					id: id.clone(),
+					prevote: round_votes.prevotes.get(id)
+						.map(ReportedVoteTarget::from)
+						.transpose()?,
+					precommit: round_votes.precommits.get(id)
+						.map(ReportedVoteTarget::from)
+						.transpose()?,
+				})
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+
+		Ok(Self {
+			round: round.try_into()?,
+			votes,
+		})
+	}
+}
+
+/// The prevote and precommit targets of every authority in the current best
+/// round, as well as the background rounds in a form suitable for
+/// serialization. Authorities which did not vote (yet) have no target.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportedVoterState {
+	set_id: u32,
+	best: ReportedRoundVotes,
+	background: Vec<ReportedRoundVotes>,
+}
+
+impl ReportedVoterState {
+	pub fn from<AuthoritySet, VoterState>(
+		authority_set: &AuthoritySet,
+		voter_state: &VoterState,
+	) -> Result<Self, Error>
+	where
+		AuthoritySet: ReportAuthoritySet,
+		VoterState: ReportVoterState,
+	{
+		use std::convert::TryFrom;
+
+		let report = voter_state.get().ok_or(Error::EndpointNotReady)?;
+
+		let (set_id, current_voters) = authority_set.get();
+		let set_id =
+			u32::try_from(set_id).map_err(|_| Error::AuthoritySetIdReportedAsUnreasonablyLarge)?;
+		let current_voters = current_voters.into_iter().collect();
+
+		let best = ReportedRoundVotes::from(report.best_round.0, voter_state, &current_voters)?;
+		let background = report
+			.background_rounds
+			.keys()
+			.map(|round| ReportedRoundVotes::from(*round, voter_state, &current_voters))
+			.collect::<Result<Vec<_>, Error>>()?;
+
+		Ok(Self {
+			set_id,
+			best,
+			background,
+		})
+	}
+}
+
+/// The last block of a past authority set in a form suitable for
+/// serialization.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportedAuthoritySetChange {
+	set_id: u32,
+	last_block: u32,
+}
+
+impl ReportedAuthoritySetChange {
+	pub fn all<AuthoritySet: ReportAuthoritySet>(
+		authority_set: &AuthoritySet,
+	) -> Result<Vec<Self>, Error> {
+		use std::convert::TryInto;
+
+		authority_set
+			.authority_set_changes()
+			.into_iter()
+			.map(|(set_id, last_block)| -> Result<_, Error> {
+				Ok(Self {
+					set_id: set_id
+						.try_into()
+						.map_err(|_| Error::AuthoritySetIdReportedAsUnreasonablyLarge)?,
+					last_block: last_block.try_into()?,
+				})
+			})
+			.collect()
+	}
+}
@@ -708,6 +708,12 @@ impl<N: Ord + Clone> AuthoritySetChanges<N> {
 
 		self.0[idx..].iter()
 	}
+
+	/// Returns an iterator over all historical authority set changes, yielding the set id and the
+	/// block number of the last block in that set.
+	pub fn iter(&self) -> impl Iterator<Item = &(u64, N)> {
+		self.0.iter()
+	}
 }
 
 #[cfg(test)]
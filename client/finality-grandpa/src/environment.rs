@@ -43,14 +43,17 @@ use sc_telemetry::{telemetry, CONSENSUS_DEBUG, CONSENSUS_INFO};
 
 use crate::{
 	local_authority_id, CommandOrError, Commit, Config, Error, NewAuthoritySet, Precommit, Prevote,
-	PrimaryPropose, SignedMessage, VoterCommand,
+	PrimaryPropose, SharedVoterState, SignedMessage, VoterCommand,
 };
 
 use sp_consensus::SelectChain;
 
 use crate::authorities::{AuthoritySet, SharedAuthoritySet};
 use crate::communication::Network as NetworkT;
-use crate::notification::GrandpaJustificationSender;
+use crate::notification::{
+	EquivocationNotification, EquivocationReport, GrandpaEquivocationSender,
+	GrandpaJustificationSender,
+};
 use crate::justification::GrandpaJustification;
 use crate::until_imported::UntilVoteTargetImported;
 use crate::voting_rule::VotingRule;
@@ -242,6 +245,16 @@ impl<Block: BlockT> VoterSetState<Block> {
 		}
 	}
 
+	/// Returns the block finalized by the last completed round, if any.
+	pub(crate) fn last_finalized_in_rounds(&self) -> Option<(Block::Hash, NumberFor<Block>)> {
+		match self {
+			VoterSetState::Live { completed_rounds, .. } =>
+				completed_rounds.last().state.finalized.clone(),
+			VoterSetState::Paused { completed_rounds } =>
+				completed_rounds.last().state.finalized.clone(),
+		}
+	}
+
 	/// Returns the voter set state validating that it includes the given round
 	/// in current rounds and that the voter isn't paused.
 	pub fn with_current_round(&self, round: RoundNumber)
@@ -445,6 +458,8 @@ pub(crate) struct Environment<Backend, Block: BlockT, C, N: NetworkT<Block>, SC,
 	pub(crate) voting_rule: VR,
 	pub(crate) metrics: Option<Metrics>,
 	pub(crate) justification_sender: Option<GrandpaJustificationSender<Block>>,
+	pub(crate) equivocation_sender: Option<GrandpaEquivocationSender<Block>>,
+	pub(crate) shared_voter_state: SharedVoterState,
 	pub(crate) _phantom: PhantomData<Backend>,
 }
 
@@ -489,10 +504,13 @@ where
 	/// extrinsic to report the equivocation. In particular, the session membership
 	/// proof must be generated at the block at which the given set was active which
 	/// isn't necessarily the best block if there are pending authority set changes.
+	///
+	/// Returns whether a report was submitted, which isn't the case if the offender is not part of
+	/// the authority set.
 	pub(crate) fn report_equivocation(
 		&self,
 		equivocation: Equivocation<Block::Hash, NumberFor<Block>>,
-	) -> Result<(), Error> {
+	) -> Result<bool, Error> {
 		if let Some(local_id) = self.voter_set_state.voting_on(equivocation.round_number()) {
 			if *equivocation.offender() == local_id {
 				return Err(Error::Safety(
@@ -554,7 +572,7 @@ where
 			Some(proof) => proof,
 			None => {
 				debug!(target: "afg", "Equivocation offender is not part of the authority set.");
-				return Ok(());
+				return Ok(false);
 			}
 		};
 
@@ -573,7 +591,32 @@ where
 			)
 			.map_err(Error::RuntimeApi)?;
 
-		Ok(())
+		Ok(true)
+	}
+
+	/// Report the given equivocation and notify subscribers about it, along with the outcome of
+	/// the report.
+	fn on_equivocation(
+		&self,
+		stage: &str,
+		equivocation: Equivocation<Block::Hash, NumberFor<Block>>,
+	) {
+		let report = match self.report_equivocation(equivocation.clone()) {
+			Ok(true) => EquivocationReport::Submitted,
+			Ok(false) => EquivocationReport::OffenderNotInSet,
+			Err(err) => {
+				warn!(target: "afg", "Error reporting {} equivocation: {:?}", stage, err);
+				EquivocationReport::Failed(format!("{:?}", err))
+			},
+		};
+
+		if let Some(sender) = self.equivocation_sender.as_ref() {
+			sender.notify(EquivocationNotification {
+				set_id: self.set_id,
+				equivocation,
+				report,
+			});
+		}
 	}
 }
 
@@ -821,7 +864,12 @@ where
 		);
 
 		// schedule incoming messages from the network to be held until
-		// corresponding blocks are imported.
+		// corresponding blocks are imported. the votes are then noted in the
+		// shared voter state for reporting, including our own which are
+		// looped back into the incoming stream.
+		self.shared_voter_state.start_round(self.set_id, round);
+		let shared_voter_state = self.shared_voter_state.clone();
+		let set_id = self.set_id;
 		let incoming = Box::pin(UntilVoteTargetImported::new(
 			self.client.import_notification_stream(),
 			self.network.clone(),
@@ -829,7 +877,11 @@ where
 			incoming,
 			"round",
 			None,
-		).map_err(Into::into));
+		)
+		.map_err(Into::into)
+		.inspect_ok(move |vote: &SignedMessage<Block>| {
+			shared_voter_state.note_vote(set_id, round, vote)
+		}));
 
 		// schedule network message cleanup when sink drops.
 		let outgoing = Box::pin(outgoing.sink_err_into());
@@ -1163,9 +1215,7 @@ where
 		equivocation: finality_grandpa::Equivocation<Self::Id, Prevote<Block>, Self::Signature>,
 	) {
 		warn!(target: "afg", "Detected prevote equivocation in the finality worker: {:?}", equivocation);
-		if let Err(err) = self.report_equivocation(equivocation.into()) {
-			warn!(target: "afg", "Error reporting prevote equivocation: {:?}", err);
-		}
+		self.on_equivocation("prevote", equivocation.into());
 	}
 
 	fn precommit_equivocation(
@@ -1174,9 +1224,7 @@ where
 		equivocation: finality_grandpa::Equivocation<Self::Id, Precommit<Block>, Self::Signature>,
 	) {
 		warn!(target: "afg", "Detected precommit equivocation in the finality worker: {:?}", equivocation);
-		if let Err(err) = self.report_equivocation(equivocation.into()) {
-			warn!(target: "afg", "Error reporting precommit equivocation: {:?}", err);
-		}
+		self.on_equivocation("precommit", equivocation.into());
 	}
 }
 
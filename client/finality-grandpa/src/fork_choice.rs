@@ -0,0 +1,99 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GRANDPA-aware fork choice.
+//!
+//! A GRANDPA round may finalize a block before its justification is imported, and thus before
+//! the client considers it finalized. Building on forks that don't contain such a block is
+//! wasted effort, since they will be discarded once the block is finalized. The rule defined
+//! here makes these forks non-viable, and defers to an inner rule otherwise.
+
+use std::cmp::Ordering;
+
+use sc_client_api::backend::Backend;
+use sc_consensus::ForkChoiceRule;
+use sp_blockchain::tree_route;
+use sp_consensus::Error as ConsensusError;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+
+use crate::environment::SharedVoterSetState;
+
+/// A fork-choice rule that refuses to build on chains which don't contain the block finalized
+/// by the last completed GRANDPA round, and uses the inner rule to compare viable chains.
+///
+/// Create it with `LinkHalf::fork_choice_rule` and use it with `sc_consensus::ForkChoice`.
+pub struct GrandpaForkChoice<Block: BlockT, R> {
+	inner: R,
+	set_state: SharedVoterSetState<Block>,
+}
+
+impl<Block: BlockT, R> GrandpaForkChoice<Block, R> {
+	/// Create a new GRANDPA-aware rule on top of the given voter set state.
+	pub(crate) fn new(inner: R, set_state: SharedVoterSetState<Block>) -> Self {
+		GrandpaForkChoice { inner, set_state }
+	}
+
+	/// Get a reference to the inner fork-choice rule.
+	pub fn inner(&self) -> &R {
+		&self.inner
+	}
+
+	/// The block finalized by the last completed round, if any.
+	pub fn finalized_vote_target(&self) -> Option<(Block::Hash, NumberFor<Block>)> {
+		self.set_state.read().last_finalized_in_rounds()
+	}
+}
+
+impl<B, Block, R> ForkChoiceRule<B, Block> for GrandpaForkChoice<Block, R> where
+	B: Backend<Block>,
+	Block: BlockT,
+	R: ForkChoiceRule<B, Block>,
+{
+	fn is_viable(&self, backend: &B, head: &Block::Header) -> Result<bool, ConsensusError> {
+		if !self.inner.is_viable(backend, head)? {
+			return Ok(false);
+		}
+
+		let (target_hash, target_number) = match self.finalized_vote_target() {
+			Some(target) => target,
+			None => return Ok(true),
+		};
+
+		if *head.number() < target_number {
+			return Ok(false);
+		}
+
+		if head.hash() == target_hash {
+			return Ok(true);
+		}
+
+		let route = tree_route::<Block, _>(backend.blockchain(), target_hash, head.hash())
+			.map_err(|e| ConsensusError::ChainLookup(e.to_string()))?;
+
+		Ok(route.common_block().hash == target_hash)
+	}
+
+	fn compare(
+		&self,
+		backend: &B,
+		a: &Block::Header,
+		b: &Block::Header,
+	) -> Result<Ordering, ConsensusError> {
+		self.inner.compare(backend, a, b)
+	}
+}
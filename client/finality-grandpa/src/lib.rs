@@ -114,18 +114,25 @@ mod aux_schema;
 mod communication;
 mod environment;
 mod finality_proof;
+mod fork_choice;
 mod import;
 mod justification;
 mod notification;
 mod observer;
 mod until_imported;
+mod votes;
 mod voting_rule;
 
 pub use authorities::{AuthoritySet, AuthoritySetChanges, SharedAuthoritySet};
 pub use finality_proof::{FinalityProof, FinalityProofProvider, FinalityProofError};
-pub use notification::{GrandpaJustificationSender, GrandpaJustificationStream};
+pub use fork_choice::GrandpaForkChoice;
+pub use notification::{
+	EquivocationNotification, EquivocationReport, GrandpaEquivocationSender,
+	GrandpaEquivocationStream, GrandpaJustificationSender, GrandpaJustificationStream,
+};
 pub use import::{find_scheduled_change, find_forced_change, GrandpaBlockImport};
 pub use justification::GrandpaJustification;
+pub use votes::{RoundVotes, VoteTarget};
 pub use voting_rule::{
 	BeforeBestBlockBy, ThreeQuartersOfTheUnfinalizedChain, VotingRule, VotingRuleResult,
 	VotingRulesBuilder,
@@ -136,7 +143,7 @@ use aux_schema::PersistentData;
 use environment::{Environment, VoterSetState};
 use until_imported::UntilGlobalMessageBlocksImported;
 use communication::{NetworkBridge, Network as NetworkT};
-use sp_finality_grandpa::{AuthorityList, AuthoritySignature, SetId};
+use sp_finality_grandpa::{AuthorityList, AuthoritySignature, RoundNumber, SetId};
 
 // Re-export these two because it's just so damn convenient.
 pub use sp_finality_grandpa::{AuthorityId, AuthorityPair, GrandpaApi, ScheduledChange};
@@ -216,6 +223,7 @@ type CommunicationOutH<Block, H> = finality_grandpa::voter::CommunicationOut<
 /// Shared voter state for querying.
 pub struct SharedVoterState {
 	inner: Arc<RwLock<Option<Box<dyn voter::VoterState<AuthorityId> + Sync + Send>>>>,
+	vote_targets: Arc<RwLock<votes::VoteTargets>>,
 }
 
 impl SharedVoterState {
@@ -223,6 +231,7 @@ impl SharedVoterState {
 	pub fn empty() -> Self {
 		Self {
 			inner: Arc::new(RwLock::new(None)),
+			vote_targets: Arc::new(RwLock::new(Default::default())),
 		}
 	}
 
@@ -242,11 +251,33 @@ impl SharedVoterState {
 	pub fn voter_state(&self) -> Option<voter::report::VoterState<AuthorityId>> {
 		self.inner.read().as_ref().map(|vs| vs.get())
 	}
+
+	/// Get the prevote and precommit targets of every authority that voted in the given round of
+	/// the current authority set. Only the latest rounds are kept.
+	pub fn round_votes(&self, round: RoundNumber) -> Option<RoundVotes> {
+		self.vote_targets.read().round(round)
+	}
+
+	fn start_round(&self, set_id: SetId, round: RoundNumber) {
+		self.vote_targets.write().start_round(set_id, round);
+	}
+
+	fn note_vote<Block: BlockT>(
+		&self,
+		set_id: SetId,
+		round: RoundNumber,
+		message: &SignedMessage<Block>,
+	) {
+		self.vote_targets.write().note(set_id, round, message);
+	}
 }
 
 impl Clone for SharedVoterState {
 	fn clone(&self) -> Self {
-		SharedVoterState { inner: self.inner.clone() }
+		SharedVoterState {
+			inner: self.inner.clone(),
+			vote_targets: self.vote_targets.clone(),
+		}
 	}
 }
 
@@ -451,6 +482,8 @@ pub struct LinkHalf<Block: BlockT, C, SC> {
 	voter_commands_rx: TracingUnboundedReceiver<VoterCommand<Block::Hash, NumberFor<Block>>>,
 	justification_sender: GrandpaJustificationSender<Block>,
 	justification_stream: GrandpaJustificationStream<Block>,
+	equivocation_sender: GrandpaEquivocationSender<Block>,
+	equivocation_stream: GrandpaEquivocationStream<Block>,
 }
 
 impl<Block: BlockT, C, SC> LinkHalf<Block, C, SC> {
@@ -463,6 +496,17 @@ impl<Block: BlockT, C, SC> LinkHalf<Block, C, SC> {
 	pub fn justification_stream(&self) -> GrandpaJustificationStream<Block> {
 		self.justification_stream.clone()
 	}
+
+	/// Get the receiving end of equivocation notifications.
+	pub fn equivocation_stream(&self) -> GrandpaEquivocationStream<Block> {
+		self.equivocation_stream.clone()
+	}
+
+	/// Wrap the given fork-choice rule so that it only builds on chains containing the block
+	/// finalized by the last completed round of the voter.
+	pub fn fork_choice_rule<R>(&self, inner: R) -> GrandpaForkChoice<Block, R> {
+		GrandpaForkChoice::new(inner, self.persistent_data.set_state.clone())
+	}
 }
 
 /// Provider for the Grandpa authority set configured on the genesis block.
@@ -564,6 +608,9 @@ where
 	let (justification_sender, justification_stream) =
 		GrandpaJustificationStream::channel();
 
+	let (equivocation_sender, equivocation_stream) =
+		GrandpaEquivocationStream::channel();
+
 	// create pending change objects with 0 delay and enacted on finality
 	// (i.e. standard changes) for each authority set hard fork.
 	let authority_set_hard_forks = authority_set_hard_forks
@@ -598,6 +645,8 @@ where
 			voter_commands_rx,
 			justification_sender,
 			justification_stream,
+			equivocation_sender,
+			equivocation_stream,
 		},
 	))
 }
@@ -725,6 +774,8 @@ where
 		voter_commands_rx,
 		justification_sender,
 		justification_stream: _,
+		equivocation_sender,
+		equivocation_stream: _,
 	} = link;
 
 	let network = NetworkBridge::new(
@@ -778,6 +829,7 @@ where
 		prometheus_registry,
 		shared_voter_state,
 		justification_sender,
+		equivocation_sender,
 	);
 
 	let voter_work = voter_work.map(|res| match res {
@@ -843,6 +895,7 @@ where
 		prometheus_registry: Option<prometheus_endpoint::Registry>,
 		shared_voter_state: SharedVoterState,
 		justification_sender: GrandpaJustificationSender<Block>,
+		equivocation_sender: GrandpaEquivocationSender<Block>,
 	) -> Self {
 		let metrics = match prometheus_registry.as_ref().map(Metrics::register) {
 			Some(Ok(metrics)) => Some(metrics),
@@ -866,6 +919,8 @@ where
 			voter_set_state: persistent_data.set_state,
 			metrics: metrics.as_ref().map(|m| m.environment.clone()),
 			justification_sender: Some(justification_sender),
+			equivocation_sender: Some(equivocation_sender),
+			shared_voter_state: shared_voter_state.clone(),
 			_phantom: PhantomData,
 		});
 
@@ -1011,6 +1066,8 @@ where
 					voting_rule: self.env.voting_rule.clone(),
 					metrics: self.env.metrics.clone(),
 					justification_sender: self.env.justification_sender.clone(),
+					equivocation_sender: self.env.equivocation_sender.clone(),
+					shared_voter_state: self.env.shared_voter_state.clone(),
 					_phantom: PhantomData,
 				});
 
@@ -19,8 +19,9 @@
 use std::sync::Arc;
 use parking_lot::Mutex;
 
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
 use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
+use sp_finality_grandpa::{Equivocation, SetId};
 
 use crate::justification::GrandpaJustification;
 use crate::Error;
@@ -113,3 +114,73 @@ impl<Block: BlockT> GrandpaJustificationStream<Block> {
 		receiver
 	}
 }
+
+/// Outcome of reporting an equivocation on-chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EquivocationReport {
+	/// The report was submitted through `submit_report_equivocation_unsigned_extrinsic`.
+	Submitted,
+	/// The offender is not part of the authority set at the latest block of the set, so there is
+	/// nothing to report.
+	OffenderNotInSet,
+	/// Reporting failed.
+	Failed(String),
+}
+
+/// An equivocation detected by the voter, along with the outcome of reporting it.
+#[derive(Clone, Debug)]
+pub struct EquivocationNotification<Block: BlockT> {
+	/// The authority set the equivocation happened in.
+	pub set_id: SetId,
+	/// The equivocation.
+	pub equivocation: Equivocation<Block::Hash, NumberFor<Block>>,
+	/// The outcome of reporting the equivocation.
+	pub report: EquivocationReport,
+}
+
+// Sending endpoint for notifying about equivocations.
+type EquivocationSender<Block> = TracingUnboundedSender<EquivocationNotification<Block>>;
+
+// Collection of equivocation channel sending endpoints shared with the receiver side.
+type SharedEquivocationSenders<Block> = Arc<Mutex<Vec<EquivocationSender<Block>>>>;
+
+/// The sending half of the Grandpa equivocation channel(s).
+///
+/// Used to send notifications about equivocations detected by the voter.
+#[derive(Clone)]
+pub struct GrandpaEquivocationSender<Block: BlockT> {
+	subscribers: SharedEquivocationSenders<Block>
+}
+
+impl<Block: BlockT> GrandpaEquivocationSender<Block> {
+	/// Send out a notification about an equivocation to all subscribers.
+	pub fn notify(&self, notification: EquivocationNotification<Block>) {
+		self.subscribers.lock().retain(|n| n.unbounded_send(notification.clone()).is_ok());
+	}
+}
+
+/// The receiving half of the Grandpa equivocation channel.
+///
+/// Used to receive notifications about equivocations detected by the voter, and how they were
+/// reported.
+#[derive(Clone)]
+pub struct GrandpaEquivocationStream<Block: BlockT> {
+	subscribers: SharedEquivocationSenders<Block>
+}
+
+impl<Block: BlockT> GrandpaEquivocationStream<Block> {
+	/// Creates a new pair of receiver and sender of equivocation notifications.
+	pub fn channel() -> (GrandpaEquivocationSender<Block>, Self) {
+		let subscribers = Arc::new(Mutex::new(vec![]));
+		let receiver = GrandpaEquivocationStream { subscribers: subscribers.clone() };
+		let sender = GrandpaEquivocationSender { subscribers };
+		(sender, receiver)
+	}
+
+	/// Subscribe to a channel through which detected equivocations are sent.
+	pub fn subscribe(&self) -> TracingUnboundedReceiver<EquivocationNotification<Block>> {
+		let (sender, receiver) = tracing_unbounded("mpsc_equivocation_notification_stream");
+		self.subscribers.lock().push(sender);
+		receiver
+	}
+}
@@ -1349,6 +1349,8 @@ where
 		voting_rule,
 		metrics: None,
 		justification_sender: None,
+		equivocation_sender: None,
+		shared_voter_state: SharedVoterState::empty(),
 		_phantom: PhantomData,
 	}
 }
@@ -1642,3 +1644,126 @@ fn grandpa_environment_doesnt_send_equivocation_reports_for_itself() {
 	let equivocation_proof = sp_finality_grandpa::Equivocation::Prevote(equivocation);
 	assert!(environment.report_equivocation(equivocation_proof).is_ok());
 }
+
+#[test]
+fn grandpa_environment_notifies_equivocations_with_their_report() {
+	use finality_grandpa::voter::Environment;
+
+	let alice = Ed25519Keyring::Alice;
+	let voters = make_ids(&[alice]);
+
+	let mut environment = {
+		let mut net = GrandpaTestNet::new(TestApi::new(voters), 1);
+		let peer = net.peer(0);
+		let network_service = peer.network_service().clone();
+		let link = peer.data.lock().take().unwrap();
+		let (keystore, _keystore_path) = create_keystore(alice);
+		test_environment(&link, Some(keystore), network_service.clone(), ())
+	};
+
+	let (equivocation_sender, equivocation_stream) = GrandpaEquivocationStream::channel();
+	environment.equivocation_sender = Some(equivocation_sender);
+	let mut notifications = equivocation_stream.subscribe();
+
+	let signed_prevote = {
+		let prevote = finality_grandpa::Prevote {
+			target_hash: H256::random(),
+			target_number: 1,
+		};
+
+		let signed = alice.sign(&[]).into();
+		(prevote, signed)
+	};
+
+	let mut equivocation = finality_grandpa::Equivocation {
+		round_number: 1,
+		identity: alice.public().into(),
+		first: signed_prevote.clone(),
+		second: signed_prevote.clone(),
+	};
+
+	environment.round_data(1);
+
+	// our own equivocation isn't reported, but subscribers still hear about it
+	environment.prevote_equivocation(1, equivocation.clone());
+	let notification = notifications.try_next().unwrap().unwrap();
+	assert_eq!(notification.set_id, 0);
+	assert_eq!(
+		notification.equivocation,
+		sp_finality_grandpa::Equivocation::Prevote(equivocation.clone()),
+	);
+	assert_matches!(notification.report, EquivocationReport::Failed(_));
+
+	// the test runtime doesn't generate key ownership proofs
+	equivocation.identity = Default::default();
+	environment.prevote_equivocation(1, equivocation);
+	let notification = notifications.try_next().unwrap().unwrap();
+	assert_eq!(notification.report, EquivocationReport::OffenderNotInSet);
+}
+
+#[test]
+fn grandpa_environment_tracks_vote_targets() {
+	use finality_grandpa::voter::Environment;
+
+	let alice = Ed25519Keyring::Alice;
+	let voters = make_ids(&[alice]);
+
+	let mut net = GrandpaTestNet::new(TestApi::new(voters), 1);
+	let peer = net.peer(0);
+	let network_service = peer.network_service().clone();
+	let link = peer.data.lock().take().unwrap();
+	let environment = test_environment(&link, None, network_service, ());
+
+	// starting a round makes its votes available, even if none was seen yet.
+	assert_eq!(environment.shared_voter_state.round_votes(1), None);
+	environment.round_data(1);
+	assert_eq!(environment.shared_voter_state.round_votes(1), Some(RoundVotes::default()));
+}
+
+#[test]
+fn grandpa_fork_choice_only_builds_on_the_finalized_vote_target() {
+	use sc_consensus::{ForkChoice, LongestChainRule};
+	use sp_consensus::SelectChain;
+	use substrate_test_runtime_client::{
+		ClientBlockImportExt, DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
+	};
+
+	let (mut client, backend) = TestClientBuilder::new().build_with_backend();
+	let genesis_hash = client.chain_info().genesis_hash;
+
+	// G -> A1 -> A2
+	// G -> B1 -> B2 -> B3
+	let mut push_block = |parent, fork: u8| {
+		let mut digest = sp_runtime::generic::Digest::default();
+		digest.push(DigestItem::Other(vec![fork]));
+
+		let block = client.new_block_at(&BlockId::Hash(parent), digest, false).unwrap()
+			.build().unwrap().block;
+		client.import(BlockOrigin::Own, block.clone()).unwrap();
+		block.hash()
+	};
+
+	let a1 = push_block(genesis_hash, 0);
+	let a2 = push_block(a1, 0);
+	let b1 = push_block(genesis_hash, 1);
+	let b2 = push_block(b1, 1);
+	let b3 = push_block(b2, 1);
+
+	let authority_set = AuthoritySet::genesis(make_ids(&[Ed25519Keyring::Alice])).unwrap();
+	let fork_choice = |finalized| {
+		let set_state = VoterSetState::<Block>::live(0, &authority_set, finalized).into();
+		let rule = GrandpaForkChoice::new(LongestChainRule, set_state);
+		ForkChoice::<_, Block, _>::new(backend.clone(), rule)
+	};
+
+	// without any finalized vote target beyond genesis, the longest chain wins.
+	let select_chain = fork_choice((genesis_hash, 0));
+	assert_eq!(select_chain.best_chain().unwrap().hash(), b3);
+
+	// once A1 is finalized by a round, B forks are no longer built upon.
+	let select_chain = fork_choice((a1, 1));
+	assert_eq!(select_chain.best_chain().unwrap().hash(), a2);
+	assert_eq!(select_chain.leaves().unwrap(), vec![a2]);
+	assert_eq!(select_chain.finality_target(genesis_hash, None).unwrap(), Some(a2));
+	assert_eq!(select_chain.finality_target(b1, None).unwrap(), None);
+}
@@ -0,0 +1,191 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tracking of the prevote and precommit targets of every authority, for reporting.
+
+use std::collections::BTreeMap;
+
+use parity_scale_codec::Encode;
+use sp_runtime::traits::{Block as BlockT, NumberFor, UniqueSaturatedInto};
+use sp_finality_grandpa::{AuthorityId, RoundNumber, SetId};
+
+use crate::SignedMessage;
+
+/// Number of rounds before the latest one for which votes are kept.
+const KEPT_ROUNDS: RoundNumber = 2;
+
+/// Target of a prevote or precommit.
+///
+/// The hash is kept SCALE-encoded so that reporting does not depend on the block type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoteTarget {
+	/// Encoded hash of the target block.
+	pub hash: Vec<u8>,
+	/// Number of the target block.
+	pub number: u64,
+}
+
+impl VoteTarget {
+	fn new<Block: BlockT>(hash: &Block::Hash, number: NumberFor<Block>) -> Self {
+		VoteTarget { hash: hash.encode(), number: number.unique_saturated_into() }
+	}
+}
+
+/// Prevote and precommit targets of the authorities that voted in a round.
+///
+/// Only the first vote of each authority is kept, so equivocating authorities are reported with
+/// the target they voted for first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoundVotes {
+	/// Prevote targets, by authority.
+	pub prevotes: BTreeMap<AuthorityId, VoteTarget>,
+	/// Precommit targets, by authority.
+	pub precommits: BTreeMap<AuthorityId, VoteTarget>,
+}
+
+/// Votes seen in the latest rounds of the current authority set.
+#[derive(Debug, Default)]
+pub(crate) struct VoteTargets {
+	set_id: SetId,
+	rounds: BTreeMap<RoundNumber, RoundVotes>,
+}
+
+impl VoteTargets {
+	/// Start tracking the votes of a new round, forgetting the rounds that are too old or belong
+	/// to a previous authority set.
+	pub(crate) fn start_round(&mut self, set_id: SetId, round: RoundNumber) {
+		if set_id != self.set_id {
+			self.set_id = set_id;
+			self.rounds.clear();
+		}
+
+		self.rounds.entry(round).or_default();
+		self.rounds.retain(|tracked, _| tracked.saturating_add(KEPT_ROUNDS) >= round);
+	}
+
+	/// Note a vote received, or cast, in the given round.
+	pub(crate) fn note<Block: BlockT>(
+		&mut self,
+		set_id: SetId,
+		round: RoundNumber,
+		message: &SignedMessage<Block>,
+	) {
+		if set_id != self.set_id {
+			return
+		}
+
+		let votes = match self.rounds.get_mut(&round) {
+			Some(votes) => votes,
+			None => return,
+		};
+
+		match &message.message {
+			finality_grandpa::Message::Prevote(prevote) => {
+				votes.prevotes.entry(message.id.clone()).or_insert_with(|| {
+					VoteTarget::new::<Block>(&prevote.target_hash, prevote.target_number)
+				});
+			},
+			finality_grandpa::Message::Precommit(precommit) => {
+				votes.precommits.entry(message.id.clone()).or_insert_with(|| {
+					VoteTarget::new::<Block>(&precommit.target_hash, precommit.target_number)
+				});
+			},
+			finality_grandpa::Message::PrimaryPropose(_) => {},
+		}
+	}
+
+	/// Votes seen in the given round of the current authority set, if still tracked.
+	pub(crate) fn round(&self, round: RoundNumber) -> Option<RoundVotes> {
+		self.rounds.get(&round).cloned()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::crypto::Public;
+	use substrate_test_runtime_client::runtime::{Block, H256};
+
+	fn vote(id: u8, message: crate::Message<Block>) -> SignedMessage<Block> {
+		SignedMessage::<Block> {
+			message,
+			signature: sp_core::ed25519::Signature::from_raw([0; 64]).into(),
+			id: AuthorityId::from_slice(&[id; 32]),
+		}
+	}
+
+	fn prevote(id: u8, number: u64) -> SignedMessage<Block> {
+		let target_hash = H256::from_low_u64_be(number);
+		vote(id, finality_grandpa::Message::Prevote(crate::Prevote::<Block> {
+			target_hash,
+			target_number: number,
+		}))
+	}
+
+	fn precommit(id: u8, number: u64) -> SignedMessage<Block> {
+		let target_hash = H256::from_low_u64_be(number);
+		vote(id, finality_grandpa::Message::Precommit(crate::Precommit::<Block> {
+			target_hash,
+			target_number: number,
+		}))
+	}
+
+	#[test]
+	fn tracks_first_vote_of_each_authority() {
+		let mut targets = VoteTargets::default();
+		targets.start_round(0, 1);
+
+		targets.note(0, 1, &prevote(1, 10));
+		targets.note(0, 1, &prevote(1, 11));
+		targets.note(0, 1, &prevote(2, 12));
+		targets.note(0, 1, &precommit(2, 9));
+
+		let votes = targets.round(1).unwrap();
+		assert_eq!(votes.prevotes.len(), 2);
+		assert_eq!(votes.prevotes[&AuthorityId::from_slice(&[1; 32])].number, 10);
+		assert_eq!(
+			votes.precommits[&AuthorityId::from_slice(&[2; 32])].hash,
+			H256::from_low_u64_be(9).encode(),
+		);
+		assert!(votes.precommits.get(&AuthorityId::from_slice(&[1; 32])).is_none());
+	}
+
+	#[test]
+	fn forgets_old_rounds_and_sets() {
+		let mut targets = VoteTargets::default();
+		targets.start_round(0, 1);
+		targets.note(0, 1, &prevote(1, 10));
+
+		// votes of rounds not started are ignored.
+		targets.note(0, 5, &prevote(1, 10));
+		assert_eq!(targets.round(5), None);
+
+		targets.start_round(0, 3);
+		assert!(targets.round(1).is_some());
+		targets.start_round(0, 4);
+		assert_eq!(targets.round(1), None);
+
+		// votes of another set are ignored, and a new set starts afresh.
+		targets.note(1, 4, &prevote(1, 10));
+		assert_eq!(targets.round(4), Some(RoundVotes::default()));
+		targets.start_round(1, 1);
+		assert_eq!(targets.round(3), None);
+		assert_eq!(targets.round(4), None);
+		assert_eq!(targets.round(1), Some(RoundVotes::default()));
+	}
+}
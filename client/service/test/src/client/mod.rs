@@ -29,8 +29,9 @@ use substrate_test_runtime_client::{
 	BlockBuilderExt, DefaultTestClientBuilderExt, TestClientBuilderExt, ClientExt,
 };
 use sc_client_api::{
-	StorageProvider, BlockBackend, in_mem, BlockchainEvents,
+	StorageProvider, BlockBackend, in_mem, BlockchainEvents, AuxStore,
 };
+use sc_consensus::{LongestChain, ForkChoice, ForkChoiceRule, LongestChainRule};
 use sc_client_db::{
 	Backend, DatabaseSettings, DatabaseSettingsSrc, PruningMode, KeepBlocks, TransactionStorageMode
 };
@@ -43,6 +44,7 @@ use substrate_test_runtime::TestAPI;
 use sp_state_machine::backend::Backend as _;
 use sp_api::ProvideRuntimeApi;
 use sp_core::{H256, ChangesTrieConfiguration, blake2_256, testing::TaskExecutor};
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use sp_consensus::{
@@ -976,6 +978,118 @@ fn best_containing_on_longest_chain_with_max_depth_higher_than_best() {
 	assert_eq!(a2.hash(), longest_chain_select.finality_target(genesis_hash, Some(10)).unwrap().unwrap());
 }
 
+#[test]
+fn fork_choice_with_longest_chain_rule_matches_longest_chain() {
+	// block tree:
+	// G -> A1 -> A2 -> A3
+	//      A1 -> B2
+	let (mut client, backend) = TestClientBuilder::new().build_with_backend();
+	let longest_chain_select = LongestChain::new(backend.clone());
+	let fork_choice_select = ForkChoice::<_, Block, _>::new(backend, LongestChainRule);
+
+	let (a1, a2, a3, b2) = build_two_forks(&mut client);
+	let genesis_hash = client.chain_info().genesis_hash;
+
+	assert_eq!(
+		longest_chain_select.best_chain().unwrap(),
+		fork_choice_select.best_chain().unwrap(),
+	);
+	assert_eq!(fork_choice_select.leaves().unwrap(), vec![a3, b2]);
+
+	for target in &[genesis_hash, a1, a2, a3, b2] {
+		for max_number in &[None, Some(1), Some(2), Some(10)] {
+			assert_eq!(
+				longest_chain_select.finality_target(*target, *max_number).unwrap(),
+				fork_choice_select.finality_target(*target, *max_number).unwrap(),
+			);
+		}
+	}
+}
+
+#[test]
+fn fork_choice_rule_can_use_aux_data() {
+	// block tree:
+	// G -> A1 -> A2 -> A3
+	//      A1 -> B2
+	let (mut client, backend) = TestClientBuilder::new().build_with_backend();
+	let select_chain = ForkChoice::<_, Block, _>::new(backend.clone(), AuxWeightRule);
+
+	let (a1, a2, a3, b2) = build_two_forks(&mut client);
+	let set_weight = |hash: Hash, weight: u64| {
+		backend.insert_aux(&[(&hash.encode()[..], &weight.encode()[..])], &[]).unwrap();
+	};
+
+	// B2 is heavier than A3, even though it is shorter.
+	set_weight(b2, 5);
+	assert_eq!(select_chain.best_chain().unwrap().hash(), b2);
+	assert_eq!(select_chain.leaves().unwrap(), vec![b2, a3]);
+	assert_eq!(select_chain.finality_target(a1, None).unwrap(), Some(b2));
+	assert_eq!(select_chain.finality_target(a2, None).unwrap(), Some(a3));
+
+	// B2 is no longer viable.
+	set_weight(b2, 0);
+	assert_eq!(select_chain.best_chain().unwrap().hash(), a3);
+	assert_eq!(select_chain.leaves().unwrap(), vec![a3]);
+	assert_eq!(select_chain.finality_target(b2, None).unwrap(), None);
+}
+
+/// A fork-choice rule using the weights stored in the auxiliary storage under the block hash,
+/// where a zero weight marks a block that must not be built upon.
+struct AuxWeightRule;
+
+impl AuxWeightRule {
+	fn weight<B: AuxStore>(backend: &B, header: &Header) -> Result<u64, ConsensusError> {
+		let weight = backend.get_aux(&header.hash().encode())
+			.map_err(|e| ConsensusError::ChainLookup(e.to_string()))?;
+
+		Ok(weight.map_or(1, |weight| u64::decode(&mut &weight[..]).unwrap()))
+	}
+}
+
+impl<B: AuxStore> ForkChoiceRule<B, Block> for AuxWeightRule {
+	fn is_viable(&self, backend: &B, head: &Header) -> Result<bool, ConsensusError> {
+		Ok(Self::weight(backend, head)? != 0)
+	}
+
+	fn compare(&self, backend: &B, a: &Header, b: &Header) -> Result<Ordering, ConsensusError> {
+		Ok(Self::weight(backend, a)?.cmp(&Self::weight(backend, b)?))
+	}
+}
+
+fn build_two_forks(
+	client: &mut substrate_test_runtime_client::TestClient,
+) -> (Hash, Hash, Hash, Hash) {
+	// G -> A1
+	let a1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, a1.clone()).unwrap();
+
+	// A1 -> A2
+	let a2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, a2.clone()).unwrap();
+
+	// A2 -> A3
+	let a3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, a3.clone()).unwrap();
+
+	// A1 -> B2
+	let mut builder = client.new_block_at(
+		&BlockId::Hash(a1.hash()),
+		Default::default(),
+		false,
+	).unwrap();
+	// this push is required as otherwise B2 has the same hash as A2 and won't get imported
+	builder.push_transfer(Transfer {
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Ferdie.into(),
+		amount: 41,
+		nonce: 0,
+	}).unwrap();
+	let b2 = builder.build().unwrap().block;
+	client.import(BlockOrigin::Own, b2.clone()).unwrap();
+
+	(a1.hash(), a2.hash(), a3.hash(), b2.hash())
+}
+
 #[test]
 fn key_changes_works() {
 	let (client, _, test_cases) = prepare_client_with_key_changes();
@@ -0,0 +1,134 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementations for fungibles trait.
+
+use super::*;
+use frame_support::traits::tokens::fungibles;
+
+impl<T: Config> fungibles::Inspect<<T as frame_system::Config>::AccountId> for Pallet<T> {
+	type AssetId = T::AssetId;
+	type Balance = T::Balance;
+
+	fn total_issuance(asset: Self::AssetId) -> Self::Balance {
+		Asset::<T>::get(asset).map(|x| x.supply).unwrap_or_else(Zero::zero)
+	}
+
+	fn minimum_balance(asset: Self::AssetId) -> Self::Balance {
+		Asset::<T>::get(asset).map(|x| x.min_balance).unwrap_or_else(Zero::zero)
+	}
+
+	fn balance(asset: Self::AssetId, who: &<T as frame_system::Config>::AccountId) -> Self::Balance {
+		Pallet::<T>::balance(asset, who.clone())
+	}
+
+	fn reducible_balance(
+		asset: Self::AssetId,
+		who: &<T as frame_system::Config>::AccountId,
+		keep_alive: bool,
+	) -> Self::Balance {
+		Pallet::<T>::reducible_balance(asset, who, keep_alive).unwrap_or(Zero::zero())
+	}
+
+	fn can_deposit(
+		asset: Self::AssetId,
+		who: &<T as frame_system::Config>::AccountId,
+		amount: Self::Balance,
+	) -> DepositConsequence {
+		Pallet::<T>::can_increase(asset, who, amount)
+	}
+
+	fn can_withdraw(
+		asset: Self::AssetId,
+		who: &<T as frame_system::Config>::AccountId,
+		amount: Self::Balance,
+	) -> WithdrawConsequence<Self::Balance> {
+		Pallet::<T>::can_decrease(asset, who, amount, false)
+	}
+}
+
+impl<T: Config> fungibles::Mutate<<T as frame_system::Config>::AccountId> for Pallet<T> {
+	fn mint_into(
+		asset: Self::AssetId,
+		who: &<T as frame_system::Config>::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult {
+		Self::can_increase(asset, who, amount).into_result()?;
+		Self::do_mint(asset, who, amount, None)
+	}
+
+	fn burn_from(
+		asset: Self::AssetId,
+		who: &<T as frame_system::Config>::AccountId,
+		amount: Self::Balance,
+	) -> Result<Self::Balance, DispatchError> {
+		Self::can_decrease(asset, who, amount, false).into_result()?;
+		Self::do_burn(asset, who, amount, None, false)
+	}
+}
+
+impl<T: Config> fungibles::Transfer<T::AccountId> for Pallet<T> {
+	fn transfer(
+		asset: Self::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<T::Balance, DispatchError> {
+		Self::do_transfer(asset, source, dest, amount, None, keep_alive)
+	}
+}
+
+impl<T: Config> fungibles::Unbalanced<T::AccountId> for Pallet<T> {
+	fn set_balance(asset: Self::AssetId, who: &T::AccountId, amount: Self::Balance)
+		-> DispatchResult
+	{
+		Asset::<T>::try_mutate(asset, |maybe_details| {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			ensure!(amount.is_zero() || amount >= details.min_balance, Error::<T>::BalanceLow);
+
+			Account::<T>::try_mutate_exists(asset, who, |maybe_account| -> DispatchResult {
+				match maybe_account.take() {
+					Some(mut account) => {
+						ensure!(!account.is_frozen, Error::<T>::Frozen);
+						if amount.is_zero() {
+							Self::dead_account(who, details, account.sufficient);
+						} else {
+							account.balance = amount;
+							*maybe_account = Some(account);
+						}
+					}
+					None => if !amount.is_zero() {
+						let sufficient = Self::new_account(who, details)?;
+						*maybe_account = Some(AssetBalance {
+							balance: amount,
+							is_frozen: false,
+							sufficient,
+						});
+					},
+				}
+				Ok(())
+			})
+		})
+	}
+
+	fn set_total_issuance(asset: Self::AssetId, amount: Self::Balance) {
+		Asset::<T>::mutate_exists(asset, |maybe_asset| if let Some(ref mut asset) = maybe_asset {
+			asset.supply = amount
+		});
+	}
+}
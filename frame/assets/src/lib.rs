@@ -123,6 +123,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub mod weights;
+mod impl_fungibles;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 #[cfg(test)]
@@ -130,17 +131,21 @@ pub mod mock;
 #[cfg(test)]
 mod tests;
 
-use sp_std::prelude::*;
+use sp_std::{prelude::*, marker::PhantomData};
 use sp_runtime::{
-	RuntimeDebug,
+	RuntimeDebug, FixedU128, FixedPointNumber, FixedPointOperand,
 	traits::{
-		AtLeast32BitUnsigned, Zero, StaticLookup, Saturating, CheckedSub, CheckedAdd,
+		AtLeast32BitUnsigned, Zero, StaticLookup, Saturating, CheckedSub, CheckedAdd, Convert,
 	}
 };
 use codec::{Encode, Decode, HasCompact};
 use frame_support::{
 	ensure,
-	traits::{Currency, ReservableCurrency, BalanceStatus::Reserved},
+	traits::{
+		Currency, ReservableCurrency, BalanceStatus::Reserved,
+		tokens::{DepositConsequence, WithdrawConsequence, BalanceConversion},
+		fungible,
+	},
 	dispatch::{DispatchError, DispatchResult},
 };
 pub use weights::WeightInfo;
@@ -251,6 +256,64 @@ pub struct DestroyWitness {
 	approvals: u32,
 }
 
+/// Possible errors when converting between external and asset balances.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode)]
+pub enum ConversionError {
+	/// The external minimum balance must not be zero.
+	MinBalanceZero,
+	/// The asset is not present in storage.
+	AssetMissing,
+	/// The asset is not sufficient and thus does not have a reliable `min_balance` so it cannot be
+	/// converted.
+	AssetNotSufficient,
+}
+
+// Type alias for `frame_system`'s account id.
+type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+// This pallet's asset id and balance type.
+type AssetIdOf<T> = <T as Config>::AssetId;
+type AssetBalanceOf<T> = <T as Config>::Balance;
+// Generic fungible balance type.
+type BalanceOf<F, T> = <F as fungible::Inspect<AccountIdOf<T>>>::Balance;
+
+/// Converts a balance value into an asset balance based on the ratio between the fungible's
+/// minimum balance and the minimum asset balance.
+///
+/// Only sufficient assets have a `min_balance` which can be relied upon, so the conversion fails
+/// for insufficient ones.
+pub struct BalanceToAssetBalance<F, T, CON>(PhantomData<(F, T, CON)>);
+impl<F, T, CON> BalanceConversion<BalanceOf<F, T>, AssetIdOf<T>, AssetBalanceOf<T>>
+	for BalanceToAssetBalance<F, T, CON>
+where
+	F: fungible::Inspect<AccountIdOf<T>>,
+	T: Config,
+	CON: Convert<BalanceOf<F, T>, AssetBalanceOf<T>>,
+	BalanceOf<F, T>: FixedPointOperand + Zero,
+	AssetBalanceOf<T>: FixedPointOperand + Zero,
+{
+	type Error = ConversionError;
+
+	/// Convert the given balance value into an asset balance based on the ratio between the
+	/// fungible's minimum balance and the minimum asset balance.
+	///
+	/// Will return `Err` if the asset is not found, not sufficient or the fungible's minimum
+	/// balance is zero.
+	fn to_asset_balance(
+		balance: BalanceOf<F, T>,
+		asset_id: AssetIdOf<T>,
+	) -> Result<AssetBalanceOf<T>, ConversionError> {
+		let asset = Asset::<T>::get(asset_id).ok_or(ConversionError::AssetMissing)?;
+		ensure!(asset.is_sufficient, ConversionError::AssetNotSufficient);
+		let min_balance = CON::convert(F::minimum_balance());
+		// Make sure we don't divide by zero.
+		ensure!(!min_balance.is_zero(), ConversionError::MinBalanceZero);
+		let balance = CON::convert(balance);
+		// balance * asset.min_balance / min_balance
+		Ok(FixedU128::saturating_from_rational(asset.min_balance, min_balance)
+			.saturating_mul_int(balance))
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::{
@@ -611,25 +674,7 @@ pub mod pallet {
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 			let beneficiary = T::Lookup::lookup(beneficiary)?;
-
-			Asset::<T>::try_mutate(id, |maybe_details| {
-				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
-
-				ensure!(&origin == &details.issuer, Error::<T>::NoPermission);
-				details.supply = details.supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
-
-				Account::<T>::try_mutate(id, &beneficiary, |t| -> DispatchResult {
-					let new_balance = t.balance.saturating_add(amount);
-					ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
-					if t.balance.is_zero() {
-						t.sufficient = Self::new_account(&beneficiary, details)?;
-					}
-					t.balance = new_balance;
-					Ok(())
-				})?;
-				Self::deposit_event(Event::Issued(id, beneficiary, amount));
-				Ok(())
-			})
+			Self::do_mint(id, &beneficiary, amount, Some(origin))
 		}
 
 		/// Reduce the balance of `who` by as much as possible up to `amount` assets of `id`.
@@ -656,34 +701,7 @@ pub mod pallet {
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 			let who = T::Lookup::lookup(who)?;
-
-			Asset::<T>::try_mutate(id, |maybe_details| {
-				let d = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
-				ensure!(&origin == &d.admin, Error::<T>::NoPermission);
-
-				let burned = Account::<T>::try_mutate_exists(
-					id,
-					&who,
-					|maybe_account| -> Result<T::Balance, DispatchError> {
-						let mut account = maybe_account.take().ok_or(Error::<T>::BalanceZero)?;
-						let mut burned = amount.min(account.balance);
-						account.balance -= burned;
-						*maybe_account = if account.balance < d.min_balance {
-							burned += account.balance;
-							Self::dead_account(&who, d, account.sufficient);
-							None
-						} else {
-							Some(account)
-						};
-						Ok(burned)
-					}
-				)?;
-
-				d.supply = d.supply.saturating_sub(burned);
-
-				Self::deposit_event(Event::Burned(id, who, burned));
-				Ok(())
-			})
+			Self::do_burn(id, &who, amount, Some(origin), true).map(|_| ())
 		}
 
 		/// Move some assets from the sender account to another.
@@ -1400,6 +1418,175 @@ impl<T: Config> Pallet<T> {
 		d.accounts = d.accounts.saturating_sub(1);
 	}
 
+	/// Returns whether `amount` of asset `id` may be deposited into the account of `who`.
+	pub(super) fn can_increase(id: T::AssetId, who: &T::AccountId, amount: T::Balance)
+		-> DepositConsequence
+	{
+		let details = match Asset::<T>::get(id) {
+			Some(details) => details,
+			None => return DepositConsequence::UnknownAsset,
+		};
+		if details.supply.checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+		let account = Account::<T>::get(id, who);
+		if account.balance.checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+		if account.balance.is_zero() {
+			if amount < details.min_balance {
+				return DepositConsequence::BelowMinimum
+			}
+			if !details.is_sufficient && frame_system::Module::<T>::providers(who) == 0 {
+				return DepositConsequence::CannotCreate
+			}
+			if details.is_sufficient && details.sufficients.checked_add(1).is_none() {
+				return DepositConsequence::Overflow
+			}
+		}
+
+		DepositConsequence::Success
+	}
+
+	/// Returns the consequence of withdrawing `amount` of asset `id` from the account of `who`.
+	///
+	/// If `keep_alive` is `true`, the account may not be reduced below the minimum balance.
+	pub(super) fn can_decrease(
+		id: T::AssetId,
+		who: &T::AccountId,
+		amount: T::Balance,
+		keep_alive: bool,
+	) -> WithdrawConsequence<T::Balance> {
+		let details = match Asset::<T>::get(id) {
+			Some(details) => details,
+			None => return WithdrawConsequence::UnknownAsset,
+		};
+		if details.supply.checked_sub(&amount).is_none() {
+			return WithdrawConsequence::Underflow
+		}
+		if details.is_frozen {
+			return WithdrawConsequence::Frozen
+		}
+		let account = Account::<T>::get(id, who);
+		if account.is_frozen {
+			return WithdrawConsequence::Frozen
+		}
+		match account.balance.checked_sub(&amount) {
+			Some(rest) if rest < details.min_balance => if keep_alive {
+				WithdrawConsequence::WouldDie
+			} else {
+				WithdrawConsequence::ReducedToZero(rest)
+			},
+			Some(_) => WithdrawConsequence::Success,
+			None => WithdrawConsequence::NoFunds,
+		}
+	}
+
+	/// Returns the amount of asset `id` which may be withdrawn from the account of `who`.
+	///
+	/// If `keep_alive` is `true`, the minimum balance must be left in the account.
+	pub(super) fn reducible_balance(id: T::AssetId, who: &T::AccountId, keep_alive: bool)
+		-> Result<T::Balance, Error<T>>
+	{
+		let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+		ensure!(!details.is_frozen, Error::<T>::Frozen);
+
+		let account = Account::<T>::get(id, who);
+		ensure!(!account.is_frozen, Error::<T>::Frozen);
+
+		let amount = if keep_alive {
+			account.balance.saturating_sub(details.min_balance)
+		} else {
+			account.balance
+		};
+		Ok(amount)
+	}
+
+	/// Increase the balance of `beneficiary` by `amount` of asset `id`, increasing the supply.
+	///
+	/// If `maybe_check_issuer` is `Some`, it must be the issuer of the asset.
+	pub(super) fn do_mint(
+		id: T::AssetId,
+		beneficiary: &T::AccountId,
+		amount: T::Balance,
+		maybe_check_issuer: Option<T::AccountId>,
+	) -> DispatchResult {
+		Asset::<T>::try_mutate(id, |maybe_details| {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+
+			if let Some(check_issuer) = maybe_check_issuer {
+				ensure!(&check_issuer == &details.issuer, Error::<T>::NoPermission);
+			}
+			details.supply = details.supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+
+			Account::<T>::try_mutate(id, beneficiary, |t| -> DispatchResult {
+				let new_balance = t.balance.saturating_add(amount);
+				ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
+				if t.balance.is_zero() {
+					t.sufficient = Self::new_account(beneficiary, details)?;
+				}
+				t.balance = new_balance;
+				Ok(())
+			})?;
+			Self::deposit_event(Event::Issued(id, beneficiary.clone(), amount));
+			Ok(())
+		})
+	}
+
+	/// Reduce the balance of `who` by `amount` of asset `id`, reducing the supply. If this takes
+	/// the balance below the minimum for the asset, then the whole balance is burned.
+	///
+	/// If `best_effort` is `true`, as much as possible up to `amount` is burned; otherwise the
+	/// balance must cover `amount`. If `maybe_check_admin` is `Some`, it must be the admin of the
+	/// asset.
+	///
+	/// Returns the amount actually burned.
+	pub(super) fn do_burn(
+		id: T::AssetId,
+		who: &T::AccountId,
+		amount: T::Balance,
+		maybe_check_admin: Option<T::AccountId>,
+		best_effort: bool,
+	) -> Result<T::Balance, DispatchError> {
+		Asset::<T>::try_mutate(id, |maybe_details| {
+			let d = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			if let Some(check_admin) = maybe_check_admin {
+				ensure!(&check_admin == &d.admin, Error::<T>::NoPermission);
+			}
+
+			let burned = Account::<T>::try_mutate_exists(
+				id,
+				who,
+				|maybe_account| -> Result<T::Balance, DispatchError> {
+					let mut account = maybe_account.take().ok_or(Error::<T>::BalanceZero)?;
+					ensure!(best_effort || account.balance >= amount, Error::<T>::BalanceLow);
+					let mut burned = amount.min(account.balance);
+					account.balance -= burned;
+					*maybe_account = if account.balance < d.min_balance {
+						burned += account.balance;
+						Self::dead_account(who, d, account.sufficient);
+						None
+					} else {
+						Some(account)
+					};
+					Ok(burned)
+				}
+			)?;
+
+			d.supply = d.supply.saturating_sub(burned);
+
+			Self::deposit_event(Event::Burned(id, who.clone(), burned));
+			Ok(burned)
+		})
+	}
+
+	/// Move `amount` of asset `id` from `source` to `dest`.
+	///
+	/// If `maybe_need_admin` is `Some`, it must be the admin of the asset. If `keep_alive` is
+	/// `true`, `source` must be left with at least the minimum balance.
+	///
+	/// Returns the amount actually transferred, which may be greater than `amount` if `source`
+	/// would otherwise be left below the minimum balance.
 	fn do_transfer(
 		id: T::AssetId,
 		source: &T::AccountId,
@@ -1407,7 +1594,7 @@ impl<T: Config> Pallet<T> {
 		amount: T::Balance,
 		maybe_need_admin: Option<T::AccountId>,
 		keep_alive: bool,
-	) -> DispatchResult {
+	) -> Result<T::Balance, DispatchError> {
 		let mut source_account = Account::<T>::get(id, source);
 		ensure!(!source_account.is_frozen, Error::<T>::Frozen);
 
@@ -1423,7 +1610,7 @@ impl<T: Config> Pallet<T> {
 			}
 
 			if dest == source || amount.is_zero() {
-				return Ok(())
+				return Ok(amount)
 			}
 
 			let mut amount = amount;
@@ -1454,7 +1641,7 @@ impl<T: Config> Pallet<T> {
 				Account::<T>::insert(id, source, &source_account)
 			}
 
-			Ok(())
+			Ok(amount)
 		})
 	}
 }
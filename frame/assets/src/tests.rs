@@ -20,6 +20,7 @@
 use super::*;
 use crate::{Error, mock::*};
 use frame_support::{assert_ok, assert_noop, traits::Currency};
+use sp_runtime::TokenError;
 use pallet_balances::Error as BalancesError;
 
 fn last_event() -> mock::Event {
@@ -439,6 +440,46 @@ fn burning_asset_balance_with_zero_balance_should_not_work() {
 	});
 }
 
+#[test]
+fn fungibles_inspect_and_mutate_should_work() {
+	use frame_support::traits::tokens::{fungibles::{Inspect, Mutate, Transfer}, WithdrawConsequence};
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(<Assets as Mutate<_>>::mint_into(0, &1, 100));
+		assert_eq!(<Assets as Inspect<_>>::total_issuance(0), 100);
+		assert_eq!(<Assets as Inspect<_>>::minimum_balance(0), 10);
+		assert_eq!(<Assets as Inspect<_>>::reducible_balance(0, &1, true), 90);
+		assert_eq!(<Assets as Inspect<_>>::can_withdraw(0, &1, 95), WithdrawConsequence::ReducedToZero(5));
+		assert_noop!(<Assets as Mutate<_>>::mint_into(0, &2, 5), TokenError::BelowMinimum);
+
+		assert_noop!(<Assets as Transfer<_>>::transfer(0, &1, &2, 95, true), Error::<Test>::WouldDie);
+		assert_eq!(<Assets as Transfer<_>>::transfer(0, &1, &2, 95, false), Ok(100));
+		assert_eq!(Assets::balance(0, 1), 0);
+		assert_eq!(Assets::balance(0, 2), 100);
+
+		assert_eq!(<Assets as Mutate<_>>::burn_from(0, &2, 30), Ok(30));
+		assert_eq!(<Assets as Inspect<_>>::balance(0, &2), 70);
+		assert_eq!(<Assets as Inspect<_>>::total_issuance(0), 70);
+	});
+}
+
+#[test]
+fn fungibles_unbalanced_should_not_touch_supply() {
+	use frame_support::traits::tokens::fungibles::{Inspect, Unbalanced};
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(<Assets as Unbalanced<_>>::decrease_balance(0, &1, 95), Ok(100));
+		assert_eq!(Assets::balance(0, 1), 0);
+		assert_eq!(<Assets as Inspect<_>>::total_issuance(0), 100);
+		assert_noop!(<Assets as Unbalanced<_>>::set_balance(0, &2, 5), Error::<Test>::BalanceLow);
+		assert_ok!(<Assets as Unbalanced<_>>::increase_balance(0, &2, 50));
+		assert_eq!(Assets::balance(0, 2), 50);
+		<Assets as Unbalanced<_>>::set_total_issuance(0, 50);
+		assert_eq!(Assets::total_supply(0), 50);
+	});
+}
+
 #[test]
 fn set_metadata_should_work() {
 	new_test_ext().execute_with(|| {
@@ -490,4 +531,27 @@ fn set_metadata_should_work() {
 	});
 }
 
+#[test]
+fn balance_conversion_should_work() {
+	new_test_ext().execute_with(|| {
+		use frame_support::traits::tokens::BalanceConversion;
+		use sp_runtime::traits::ConvertInto;
+
+		type Conversion = BalanceToAssetBalance<Balances, Test, ConvertInto>;
+
+		let id = 42;
+		assert_ok!(Assets::force_create(Origin::root(), id, 1, true, 10));
+		let not_sufficient = 23;
+		assert_ok!(Assets::force_create(Origin::root(), not_sufficient, 1, false, 10));
+
+		assert_eq!(Conversion::to_asset_balance(100, 1234), Err(ConversionError::AssetMissing));
+		assert_eq!(
+			Conversion::to_asset_balance(100, not_sufficient),
+			Err(ConversionError::AssetNotSufficient),
+		);
+		// 10 / 1 == 10 -> the conversion should 10x the value
+		assert_eq!(Conversion::to_asset_balance(100, id), Ok(100 * 10));
+	});
+}
+
 // TODO: tests for force_set_metadata, force_clear_metadata, force_asset_status
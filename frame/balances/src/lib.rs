@@ -165,6 +165,7 @@ use frame_support::{
 		WithdrawReasons, LockIdentifier, LockableCurrency, ExistenceRequirement,
 		Imbalance, SignedImbalance, ReservableCurrency, Get, ExistenceRequirement::KeepAlive,
 		ExistenceRequirement::AllowDeath, BalanceStatus as Status,
+		tokens::{fungible, DepositConsequence, WithdrawConsequence},
 	}
 };
 #[cfg(feature = "std")]
@@ -658,6 +659,75 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		T::AccountStore::get(&who)
 	}
 
+	/// The consequence of depositing `amount` into the account of `who`, given its current state
+	/// `account`.
+	fn deposit_consequence(
+		_who: &T::AccountId,
+		amount: T::Balance,
+		account: &AccountData<T::Balance>,
+	) -> DepositConsequence {
+		if amount.is_zero() { return DepositConsequence::Success }
+
+		if TotalIssuance::<T, I>::get().checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+
+		let new_total_balance = match account.total().checked_add(&amount) {
+			Some(x) => x,
+			None => return DepositConsequence::Overflow,
+		};
+
+		if new_total_balance < T::ExistentialDeposit::get() {
+			return DepositConsequence::BelowMinimum
+		}
+
+		// NOTE: We assume that we are a provider, so don't need to do any checks in the
+		// case of account creation.
+
+		DepositConsequence::Success
+	}
+
+	/// The consequence of withdrawing `amount` from the free balance of `who`, given its current
+	/// state `account`.
+	fn withdraw_consequence(
+		who: &T::AccountId,
+		amount: T::Balance,
+		account: &AccountData<T::Balance>,
+	) -> WithdrawConsequence<T::Balance> {
+		if amount.is_zero() { return WithdrawConsequence::Success }
+
+		if TotalIssuance::<T, I>::get().checked_sub(&amount).is_none() {
+			return WithdrawConsequence::Underflow
+		}
+
+		let new_free_balance = match account.free.checked_sub(&amount) {
+			Some(x) => x,
+			None => return WithdrawConsequence::NoFunds,
+		};
+
+		// Provider restriction - total account balance cannot be reduced to zero if it cannot
+		// sustain the loss of a provider reference.
+		// NOTE: This assumes that the pallet is a provider (which is true). Is this ever changes,
+		// then this will need to adapt accordingly.
+		let ed = T::ExistentialDeposit::get();
+		let success = if new_free_balance.saturating_add(account.reserved) < ed {
+			if system::Pallet::<T>::is_provider_required(who) {
+				return WithdrawConsequence::WouldDie
+			} else {
+				WithdrawConsequence::ReducedToZero(new_free_balance)
+			}
+		} else {
+			WithdrawConsequence::Success
+		};
+
+		// Eventual free funds must be no less than the frozen balance.
+		if new_free_balance < account.frozen(Reasons::All) {
+			return WithdrawConsequence::Frozen
+		}
+
+		success
+	}
+
 	/// Handles any steps needed after mutating an account.
 	///
 	/// This includes DustRemoval unbalancing, in the case than the `new` account's total balance
@@ -1463,3 +1533,192 @@ where
 		Self::update_locks(who, &locks[..]);
 	}
 }
+
+impl<T: Config<I>, I: 'static> fungible::Inspect<T::AccountId> for Pallet<T, I> {
+	type Balance = T::Balance;
+
+	fn total_issuance() -> Self::Balance {
+		TotalIssuance::<T, I>::get()
+	}
+	fn minimum_balance() -> Self::Balance {
+		T::ExistentialDeposit::get()
+	}
+	fn balance(who: &T::AccountId) -> Self::Balance {
+		Self::account(who).total()
+	}
+	fn reducible_balance(who: &T::AccountId, keep_alive: bool) -> Self::Balance {
+		let a = Self::account(who);
+		// Liquid balance is what is neither reserved nor locked/frozen.
+		let liquid = a.free.saturating_sub(a.frozen(Reasons::All));
+		if keep_alive || system::Pallet::<T>::is_provider_required(who) {
+			// The part of the liquid balance which must remain to keep the total over ED.
+			let must_remain_to_exist = T::ExistentialDeposit::get()
+				.saturating_sub(a.total().saturating_sub(liquid));
+			liquid.saturating_sub(must_remain_to_exist)
+		} else {
+			liquid
+		}
+	}
+	fn can_deposit(who: &T::AccountId, amount: Self::Balance) -> DepositConsequence {
+		Self::deposit_consequence(who, amount, &Self::account(who))
+	}
+	fn can_withdraw(who: &T::AccountId, amount: Self::Balance) -> WithdrawConsequence<Self::Balance> {
+		Self::withdraw_consequence(who, amount, &Self::account(who))
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::Mutate<T::AccountId> for Pallet<T, I> {
+	fn mint_into(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		if amount.is_zero() { return Ok(()) }
+		Self::try_mutate_account(who, |account, _is_new| -> DispatchResult {
+			Self::deposit_consequence(who, amount, &account).into_result()?;
+			account.free += amount;
+			Ok(())
+		})?;
+		TotalIssuance::<T, I>::mutate(|t| *t += amount);
+		Ok(())
+	}
+
+	fn burn_from(who: &T::AccountId, amount: Self::Balance) -> Result<Self::Balance, DispatchError> {
+		if amount.is_zero() { return Ok(Self::Balance::zero()) }
+		let actual = Self::try_mutate_account(who, |account, _is_new| -> Result<T::Balance, DispatchError> {
+			let extra = Self::withdraw_consequence(who, amount, &account).into_result()?;
+			let actual = amount + extra;
+			account.free -= actual;
+			Ok(actual)
+		})?;
+		TotalIssuance::<T, I>::mutate(|t| *t -= actual);
+		Ok(actual)
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::Transfer<T::AccountId> for Pallet<T, I> {
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<T::Balance, DispatchError> {
+		let er = if keep_alive { KeepAlive } else { AllowDeath };
+		<Self as Currency<_>>::transfer(source, dest, amount, er)
+			.map(|_| amount)
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::Unbalanced<T::AccountId> for Pallet<T, I> {
+	fn set_balance(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		Self::try_mutate_account(who, |account, _is_new| -> DispatchResult {
+			// Reserved funds are left alone, so the new balance may not drop below them.
+			account.free = amount.checked_sub(&account.reserved)
+				.ok_or(Error::<T, I>::InsufficientBalance)?;
+			Ok(())
+		})
+	}
+
+	fn set_total_issuance(amount: Self::Balance) {
+		TotalIssuance::<T, I>::mutate(|t| *t = amount);
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::InspectHold<T::AccountId> for Pallet<T, I> {
+	fn balance_on_hold(who: &T::AccountId) -> T::Balance {
+		Self::account(who).reserved
+	}
+	fn can_hold(who: &T::AccountId, amount: T::Balance) -> bool {
+		let a = Self::account(who);
+		let min_balance = T::ExistentialDeposit::get().max(a.frozen(Reasons::All));
+		if a.reserved.checked_add(&amount).is_none() { return false }
+		// We require it to be min_balance + amount to ensure that the full reserved funds may be
+		// slashed without compromising locked funds or destroying the account.
+		let required_free = match min_balance.checked_add(&amount) {
+			Some(x) => x,
+			None => return false,
+		};
+		a.free >= required_free
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::MutateHold<T::AccountId> for Pallet<T, I> {
+	fn hold(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		if amount.is_zero() { return Ok(()) }
+		ensure!(
+			<Self as fungible::InspectHold<_>>::can_hold(who, amount),
+			Error::<T, I>::InsufficientBalance,
+		);
+		Self::mutate_account(who, |a| {
+			a.free -= amount;
+			a.reserved += amount;
+		})?;
+		Ok(())
+	}
+
+	fn release(who: &T::AccountId, amount: Self::Balance, best_effort: bool)
+		-> Result<T::Balance, DispatchError>
+	{
+		if amount.is_zero() { return Ok(amount) }
+		Self::try_mutate_account(who, |a, _| -> Result<T::Balance, DispatchError> {
+			let actual = amount.min(a.reserved);
+			ensure!(best_effort || actual == amount, Error::<T, I>::InsufficientBalance);
+			// Guaranteed to be <= amount and <= a.reserved.
+			a.free = a.free.checked_add(&actual).ok_or(Error::<T, I>::Overflow)?;
+			a.reserved -= actual;
+			Ok(actual)
+		})
+	}
+
+	/// Transfer held funds into a destination account.
+	///
+	/// The destination account must already exist, whether or not the funds stay on hold.
+	fn transfer_held(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: Self::Balance,
+		best_effort: bool,
+		on_hold: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		ensure!(
+			best_effort || Self::reserved_balance(source) >= amount,
+			Error::<T, I>::InsufficientBalance,
+		);
+		let status = if on_hold { Status::Reserved } else { Status::Free };
+		let remaining = <Self as ReservableCurrency<_>>::repatriate_reserved(source, dest, amount, status)?;
+		Ok(amount - remaining)
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::InspectFreeze<T::AccountId> for Pallet<T, I> {
+	type Id = LockIdentifier;
+
+	fn balance_frozen(id: &LockIdentifier, who: &T::AccountId) -> T::Balance {
+		Self::locks(who).into_iter()
+			.find(|l| &l.id == id)
+			.map_or_else(Zero::zero, |l| l.amount)
+	}
+
+	fn can_freeze(id: &LockIdentifier, who: &T::AccountId) -> bool {
+		let locks = Self::locks(who);
+		locks.iter().any(|l| &l.id == id) || (locks.len() as u32) < T::MaxLocks::get()
+	}
+}
+
+/// Freezes are implemented as locks which apply for all withdraw reasons.
+impl<T: Config<I>, I: 'static> fungible::MutateFreeze<T::AccountId> for Pallet<T, I> {
+	fn set_freeze(id: &LockIdentifier, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+		if amount.is_zero() {
+			<Self as LockableCurrency<_>>::remove_lock(*id, who);
+		} else {
+			<Self as LockableCurrency<_>>::set_lock(*id, who, amount, WithdrawReasons::all());
+		}
+		Ok(())
+	}
+
+	fn extend_freeze(id: &LockIdentifier, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+		<Self as LockableCurrency<_>>::extend_lock(*id, who, amount, WithdrawReasons::all());
+		Ok(())
+	}
+
+	fn thaw(id: &LockIdentifier, who: &T::AccountId) -> DispatchResult {
+		<Self as LockableCurrency<_>>::remove_lock(*id, who);
+		Ok(())
+	}
+}
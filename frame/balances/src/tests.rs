@@ -974,5 +974,61 @@ macro_rules! decl_tests {
 					assert_eq!(Balances::total_balance(&2), 100);
 				});
 		}
+
+		#[test]
+		fn fungible_mint_and_burn_work() {
+			use frame_support::traits::tokens::{fungible::{Inspect, Mutate}, WithdrawConsequence};
+			use sp_runtime::TokenError;
+
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				let issuance = <Balances as Inspect<_>>::total_issuance();
+
+				assert_ok!(<Balances as Mutate<_>>::mint_into(&1, 5));
+				assert_eq!(<Balances as Inspect<_>>::balance(&1), 15);
+				assert_eq!(<Balances as Inspect<_>>::total_issuance(), issuance + 5);
+
+				assert_eq!(<Balances as Mutate<_>>::burn_from(&1, 5), Ok(5));
+				assert_eq!(<Balances as Inspect<_>>::balance(&1), 10);
+				assert_eq!(<Balances as Inspect<_>>::total_issuance(), issuance);
+
+				assert_eq!(<Balances as Inspect<_>>::can_withdraw(&1, 11), WithdrawConsequence::NoFunds);
+				Balances::set_lock(ID_1, &1, 9, WithdrawReasons::all());
+				assert_eq!(<Balances as Inspect<_>>::reducible_balance(&1, false), 1);
+				assert_noop!(<Balances as Mutate<_>>::burn_from(&1, 2), TokenError::Frozen);
+			});
+		}
+
+		#[test]
+		fn fungible_hold_and_freeze_work() {
+			use frame_support::traits::tokens::fungible::{
+				Inspect, InspectHold, MutateHold, InspectFreeze, MutateFreeze,
+			};
+
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				assert_ok!(<Balances as MutateHold<_>>::hold(&1, 5));
+				assert_eq!(<Balances as InspectHold<_>>::balance_on_hold(&1), 5);
+				assert_eq!(<Balances as Inspect<_>>::balance(&1), 10);
+				// The existential deposit must stay free on top of anything held.
+				assert!(!<Balances as InspectHold<_>>::can_hold(&1, 5));
+
+				assert_noop!(
+					<Balances as MutateHold<_>>::release(&1, 10, false),
+					Error::<$test, _>::InsufficientBalance,
+				);
+				assert_eq!(<Balances as MutateHold<_>>::release(&1, 10, true), Ok(5));
+				assert_eq!(<Balances as InspectHold<_>>::balance_on_hold(&1), 0);
+
+				assert!(<Balances as InspectFreeze<_>>::can_freeze(&ID_1, &1));
+				assert_ok!(<Balances as MutateFreeze<_>>::set_freeze(&ID_1, &1, 6));
+				assert_eq!(<Balances as InspectFreeze<_>>::balance_frozen(&ID_1, &1), 6);
+				assert_eq!(<Balances as Inspect<_>>::reducible_balance(&1, false), 4);
+				assert_ok!(<Balances as MutateFreeze<_>>::extend_freeze(&ID_1, &1, 3));
+				assert_eq!(<Balances as InspectFreeze<_>>::balance_frozen(&ID_1, &1), 6);
+
+				assert_ok!(<Balances as MutateFreeze<_>>::thaw(&ID_1, &1));
+				assert_eq!(<Balances as InspectFreeze<_>>::balance_frozen(&ID_1, &1), 0);
+				assert_eq!(<Balances as Inspect<_>>::reducible_balance(&1, false), 10);
+			});
+		}
 	}
 }
@@ -0,0 +1,159 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # BEEFY module.
+//!
+//! This module manages the BEEFY authority set. The set follows the session validators: every
+//! time the session module reports a changed set, the BEEFY validator set id is incremented and
+//! a [`ConsensusLog::AuthoritiesChange`] digest is deposited so the client gadget can switch
+//! to the new set.
+//!
+//! The module also provides [`DepositMmrRoot`], which is meant to be used as the `OnNewRoot`
+//! hook of the MMR module. It deposits the MMR root of every block as a digest item, which is
+//! the payload BEEFY voters sign once the block is finalized.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Encode;
+use frame_support::{decl_module, decl_storage, traits::OneSessionHandler};
+use sp_runtime::generic::DigestItem;
+use sp_std::{marker::PhantomData, prelude::*};
+
+use sp_beefy::{
+	crypto::AuthorityId, AuthorityIndex, ConsensusLog, MmrRootHash, ValidatorSet,
+	BEEFY_ENGINE_ID, GENESIS_AUTHORITY_SET_ID,
+};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// The module's config trait.
+pub trait Config: frame_system::Config {}
+
+decl_storage! {
+	trait Store for Module<T: Config> as Beefy {
+		/// The current authorities set
+		Authorities get(fn authorities): Vec<AuthorityId>;
+		/// The current validator set id
+		ValidatorSetId get(fn validator_set_id): sp_beefy::ValidatorSetId;
+		/// Authorities set scheduled to be used with the next session
+		NextAuthorities get(fn next_authorities): Vec<AuthorityId>;
+	}
+	add_extra_genesis {
+		config(authorities): Vec<AuthorityId>;
+		build(|config| Module::<T>::initialize_authorities(&config.authorities))
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// Return the current active BEEFY validator set.
+	pub fn validator_set() -> ValidatorSet<AuthorityId> {
+		ValidatorSet::<AuthorityId> {
+			validators: Self::authorities(),
+			id: Self::validator_set_id(),
+		}
+	}
+
+	fn change_authorities(new: Vec<AuthorityId>, queued: Vec<AuthorityId>) {
+		// As in GRANDPA, we trigger a validator set change only if the validator
+		// set has actually changed.
+		if new != Self::authorities() {
+			Authorities::put(&new);
+
+			let next_id = Self::validator_set_id() + 1u64;
+			ValidatorSetId::put(next_id);
+
+			Self::deposit_log(ConsensusLog::AuthoritiesChange(ValidatorSet {
+				validators: new,
+				id: next_id,
+			}));
+		}
+
+		NextAuthorities::put(&queued);
+	}
+
+	fn initialize_authorities(authorities: &[AuthorityId]) {
+		if authorities.is_empty() {
+			return;
+		}
+
+		assert!(Self::authorities().is_empty(), "Authorities are already initialized!");
+
+		Authorities::put(authorities);
+		ValidatorSetId::put(GENESIS_AUTHORITY_SET_ID);
+		// Like `pallet_session`, initialize the next validator set as well.
+		NextAuthorities::put(authorities);
+	}
+
+	fn deposit_log(log: ConsensusLog<AuthorityId>) {
+		let log: DigestItem<T::Hash> = DigestItem::Consensus(BEEFY_ENGINE_ID, log.encode());
+		<frame_system::Module<T>>::deposit_log(log.into());
+	}
+}
+
+impl<T: Config> sp_runtime::BoundToRuntimeAppPublic for Module<T> {
+	type Public = AuthorityId;
+}
+
+impl<T: Config> OneSessionHandler<T::AccountId> for Module<T> {
+	type Key = AuthorityId;
+
+	fn on_genesis_session<'a, I: 'a>(validators: I)
+	where
+		I: Iterator<Item = (&'a T::AccountId, AuthorityId)>,
+	{
+		let authorities = validators.map(|(_, k)| k).collect::<Vec<_>>();
+		Self::initialize_authorities(&authorities);
+	}
+
+	fn on_new_session<'a, I: 'a>(changed: bool, validators: I, queued_validators: I)
+	where
+		I: Iterator<Item = (&'a T::AccountId, AuthorityId)>,
+	{
+		if changed {
+			let next_authorities = validators.map(|(_, k)| k).collect::<Vec<_>>();
+			let next_queued_authorities = queued_validators.map(|(_, k)| k).collect::<Vec<_>>();
+
+			Self::change_authorities(next_authorities, next_queued_authorities);
+		}
+	}
+
+	fn on_disabled(i: usize) {
+		Self::deposit_log(ConsensusLog::OnDisabled(i as AuthorityIndex))
+	}
+}
+
+/// Deposits the MMR root of every block as a BEEFY digest item.
+///
+/// Use this as the `OnNewRoot` hook of the MMR module so that the BEEFY gadget can find the
+/// payload to sign in the header of every finalized block.
+pub struct DepositMmrRoot<T>(PhantomData<T>);
+
+impl<T: Config> pallet_mmr_primitives::OnNewRoot<MmrRootHash> for DepositMmrRoot<T> {
+	fn on_new_root(root: &MmrRootHash) {
+		Module::<T>::deposit_log(ConsensusLog::MmrRoot(*root));
+	}
+}
@@ -0,0 +1,132 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Tests for BEEFY Pallet
+
+#![cfg(test)]
+
+use super::*;
+use crate::mock::*;
+
+use codec::Encode;
+use frame_support::traits::OneSessionHandler;
+use sp_core::H256;
+use pallet_mmr_primitives::OnNewRoot;
+
+fn beefy_log(log: ConsensusLog<AuthorityId>) -> DigestItem<H256> {
+	DigestItem::Consensus(BEEFY_ENGINE_ID, log.encode())
+}
+
+fn session_keys(authorities: &[AuthorityId]) -> Vec<(&'static u64, AuthorityId)> {
+	authorities.iter().map(|id| (&0, id.clone())).collect()
+}
+
+#[test]
+fn genesis_session_initializes_authorities() {
+	let want = mock_authorities(vec![1, 2, 3, 4]);
+
+	new_test_ext(vec![1, 2, 3, 4]).execute_with(|| {
+		assert_eq!(Beefy::authorities(), want);
+		assert_eq!(Beefy::next_authorities(), want);
+		assert_eq!(Beefy::validator_set_id(), GENESIS_AUTHORITY_SET_ID);
+	});
+}
+
+#[test]
+fn empty_genesis_session_is_ignored() {
+	new_test_ext(vec![]).execute_with(|| {
+		let authorities = mock_authorities(vec![1, 2]);
+		Beefy::on_genesis_session(session_keys(&authorities).into_iter());
+		assert_eq!(Beefy::authorities(), authorities);
+
+		// an empty genesis set does not touch the storage
+		Beefy::on_genesis_session(Vec::<(&u64, AuthorityId)>::new().into_iter());
+		assert_eq!(Beefy::authorities(), authorities);
+	});
+}
+
+#[test]
+fn session_change_updates_authorities() {
+	new_test_ext(vec![1, 2, 3, 4]).execute_with(|| {
+		let next = mock_authorities(vec![2, 3, 4]);
+		let queued = mock_authorities(vec![3, 4]);
+
+		// nothing happens when the session module reports no change
+		Beefy::on_new_session(
+			false,
+			session_keys(&next).into_iter(),
+			session_keys(&queued).into_iter(),
+		);
+		assert_eq!(Beefy::validator_set_id(), 0);
+		assert!(System::digest().logs.is_empty());
+
+		Beefy::on_new_session(
+			true,
+			session_keys(&next).into_iter(),
+			session_keys(&queued).into_iter(),
+		);
+
+		assert_eq!(Beefy::validator_set_id(), 1);
+		assert_eq!(Beefy::authorities(), next);
+		assert_eq!(Beefy::next_authorities(), queued);
+		assert_eq!(Beefy::validator_set(), ValidatorSet { validators: next.clone(), id: 1 });
+
+		let want = beefy_log(ConsensusLog::AuthoritiesChange(ValidatorSet {
+			validators: next,
+			id: 1,
+		}));
+		assert_eq!(System::digest().logs, vec![want]);
+	});
+}
+
+#[test]
+fn unchanged_authorities_keep_the_set_id() {
+	new_test_ext(vec![1, 2, 3, 4]).execute_with(|| {
+		let current = mock_authorities(vec![1, 2, 3, 4]);
+		let queued = mock_authorities(vec![1, 2, 3]);
+
+		Beefy::on_new_session(
+			true,
+			session_keys(&current).into_iter(),
+			session_keys(&queued).into_iter(),
+		);
+
+		assert_eq!(Beefy::validator_set_id(), 0);
+		assert_eq!(Beefy::authorities(), current);
+		assert_eq!(Beefy::next_authorities(), queued);
+		assert!(System::digest().logs.is_empty());
+	});
+}
+
+#[test]
+fn on_disabled_deposits_log() {
+	new_test_ext(vec![1, 2, 3, 4]).execute_with(|| {
+		Beefy::on_disabled(2);
+
+		assert_eq!(System::digest().logs, vec![beefy_log(ConsensusLog::OnDisabled(2))]);
+	});
+}
+
+#[test]
+fn new_mmr_root_deposits_log() {
+	new_test_ext(vec![1, 2, 3, 4]).execute_with(|| {
+		let root = H256::repeat_byte(7);
+		DepositMmrRoot::<Test>::on_new_root(&root);
+
+		assert_eq!(System::digest().logs, vec![beefy_log(ConsensusLog::MmrRoot(root))]);
+	});
+}
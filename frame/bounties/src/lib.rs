@@ -71,6 +71,9 @@
 //! - `claim_bounty` - Claim a specific bounty amount from the Payout Address.
 //! - `unassign_curator` - Unassign an accepted curator from a specific earmark.
 //! - `close_bounty` - Cancel the earmark for a specific treasury amount and close the bounty.
+//!
+//! An active bounty can be split into child bounties by its curator, see `pallet-child-bounties`.
+//! A bounty can't be awarded or closed while it has active child bounties.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -86,7 +89,7 @@ use frame_support::traits::{
 	Currency, Get, Imbalance, OnUnbalanced, ExistenceRequirement::{AllowDeath},
 	ReservableCurrency};
 
-use sp_runtime::{Permill, RuntimeDebug, DispatchResult, traits::{
+use sp_runtime::{Permill, RuntimeDebug, DispatchResult, DispatchError, traits::{
 	Zero, StaticLookup, AccountIdConversion, Saturating, BadOrigin
 }};
 
@@ -131,6 +134,29 @@ pub trait Config: frame_system::Config + pallet_treasury::Config {
 
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
+
+	/// The child bounty manager.
+	type ChildBountyManager: ChildBountyManager<BalanceOf<Self>>;
+}
+
+/// Interface to the child bounties of a bounty.
+pub trait ChildBountyManager<Balance> {
+	/// Get the number of active child bounties of a parent bounty.
+	fn child_bounties_count(bounty_id: BountyIndex) -> BountyIndex;
+
+	/// Take the total curator fees of all child bounties of a parent bounty. The fees are
+	/// forgotten afterwards.
+	fn children_curator_fees(bounty_id: BountyIndex) -> Balance;
+}
+
+impl<Balance: Zero> ChildBountyManager<Balance> for () {
+	fn child_bounties_count(_bounty_id: BountyIndex) -> BountyIndex {
+		Default::default()
+	}
+
+	fn children_curator_fees(_bounty_id: BountyIndex) -> Balance {
+		Zero::zero()
+	}
 }
 
 /// An index of a bounty. Just a `u32`.
@@ -253,6 +279,8 @@ decl_error! {
 		PendingPayout,
 		/// The bounties cannot be claimed/closed because it's still in the countdown period.
 		Premature,
+		/// The bounty cannot be closed because it has active child bounties.
+		HasActiveChildBounty,
 	}
 }
 
@@ -515,6 +543,11 @@ decl_module! {
 					},
 					_ => return Err(Error::<T>::UnexpectedStatus.into()),
 				}
+				// Ensure no active child bounties before processing the call.
+				ensure!(
+					T::ChildBountyManager::child_bounties_count(bounty_id) == 0,
+					Error::<T>::HasActiveChildBounty,
+				);
 				bounty.status = BountyStatus::PendingPayout {
 					curator: signer,
 					beneficiary: beneficiary.clone(),
@@ -547,6 +580,10 @@ decl_module! {
 					let bounty_account = Self::bounty_account_id(bounty_id);
 					let balance = T::Currency::free_balance(&bounty_account);
 					let fee = bounty.fee.min(balance); // just to be safe
+					// The curators of the child bounties have been paid out of the curator fee of
+					// this bounty.
+					let children_fee = T::ChildBountyManager::children_curator_fees(bounty_id);
+					let fee = fee.saturating_sub(children_fee);
 					let payout = balance.saturating_sub(fee);
 					let _ = T::Currency::unreserve(&curator, bounty.curator_deposit);
 					let _ = T::Currency::transfer(&bounty_account, &curator, fee, AllowDeath); // should not fail
@@ -600,9 +637,19 @@ decl_module! {
 					},
 					BountyStatus::Funded |
 					BountyStatus::CuratorProposed { .. } => {
+						// Child bounties outlive an unassigned curator, so check for them here too.
+						ensure!(
+							T::ChildBountyManager::child_bounties_count(bounty_id) == 0,
+							Error::<T>::HasActiveChildBounty,
+						);
 						// Nothing extra to do besides the removal of the bounty below.
 					},
 					BountyStatus::Active { curator, .. } => {
+						// Ensure no active child bounties before processing the call.
+						ensure!(
+							T::ChildBountyManager::child_bounties_count(bounty_id) == 0,
+							Error::<T>::HasActiveChildBounty,
+						);
 						// Cancelled by council, refund deposit of the working curator.
 						let _ = T::Currency::unreserve(&curator, bounty.curator_deposit);
 						// Then execute removal of the bounty below.
@@ -680,6 +727,19 @@ impl<T: Config> Module<T> {
 		T::ModuleId::get().into_sub_account(("bt", id))
 	}
 
+	/// Get the curator and the update due of an active bounty, or an error if the bounty is
+	/// unknown or not active.
+	pub fn ensure_bounty_active(
+		bounty_id: BountyIndex,
+	) -> Result<(T::AccountId, T::BlockNumber), DispatchError> {
+		let bounty = Self::bounties(bounty_id).ok_or(Error::<T>::InvalidIndex)?;
+		if let BountyStatus::Active { curator, update_due } = bounty.status {
+			Ok((curator, update_due))
+		} else {
+			Err(Error::<T>::UnexpectedStatus.into())
+		}
+	}
+
 	fn create_bounty(
 		proposer: T::AccountId,
 		description: Vec<u8>,
@@ -119,6 +119,7 @@ impl pallet_treasury::Config for Test {
 	type BurnDestination = ();  // Just gets burned.
 	type WeightInfo = ();
 	type SpendFunds = Bounties;
+	type SpendOrigin = frame_system::EnsureNever<u64>;
 }
 parameter_types! {
 	pub const BountyDepositBase: u64 = 80;
@@ -138,6 +139,7 @@ impl Config for Test {
 	type DataDepositPerByte = DataDepositPerByte;
 	type MaximumReasonLength = MaximumReasonLength;
 	type WeightInfo = ();
+	type ChildBountyManager = ();
 }
 
 type TreasuryError = pallet_treasury::Error::<Test, pallet_treasury::DefaultInstance>;
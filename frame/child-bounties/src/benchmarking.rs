@@ -0,0 +1,219 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Child bounties pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_system::{EventRecord, RawOrigin};
+use frame_benchmarking::{benchmarks, account, whitelisted_caller, impl_benchmark_test_suite};
+use frame_support::{ensure, traits::{Get, OnInitialize}};
+
+use crate::Module as ChildBounties;
+use pallet_bounties::Module as Bounties;
+use pallet_treasury::Module as Treasury;
+
+const SEED: u32 = 0;
+
+const MAX_BYTES: u32 = 16384;
+
+// The value of the parent bounty, large enough to fund a few child bounties.
+fn parent_bounty_value<T: Config>() -> BalanceOf<T> {
+	T::BountyValueMinimum::get()
+		.max(T::ChildBountyValueMinimum::get())
+		.max(T::Currency::minimum_balance())
+		.saturating_mul(100u32.into())
+}
+
+// The value of a child bounty.
+fn child_bounty_value<T: Config>() -> BalanceOf<T> {
+	parent_bounty_value::<T>() / 10u32.into()
+}
+
+// Create an active parent bounty, returning its curator and index.
+fn create_parent_bounty<T: Config>() -> Result<(T::AccountId, BountyIndex), &'static str> {
+	let pot_account = Treasury::<T>::account_id();
+	let pot = T::Currency::minimum_balance().saturating_mul(1_000_000_000u32.into());
+	let _ = T::Currency::make_free_balance_be(&pot_account, pot);
+
+	let proposer = account("proposer", 0, SEED);
+	let _ = T::Currency::make_free_balance_be(&proposer, T::BountyDepositBase::get());
+	let value = parent_bounty_value::<T>();
+	let fee = value / 2u32.into();
+	let curator: T::AccountId = account("curator", 0, SEED);
+	let _ = T::Currency::make_free_balance_be(&curator, fee);
+	let curator_lookup = T::Lookup::unlookup(curator.clone());
+
+	Bounties::<T>::propose_bounty(RawOrigin::Signed(proposer).into(), value, Vec::new())?;
+	let bounty_id = Bounties::<T>::bounty_count() - 1;
+	Bounties::<T>::approve_bounty(RawOrigin::Root.into(), bounty_id)?;
+	Treasury::<T>::on_initialize(T::BlockNumber::zero());
+	Bounties::<T>::propose_curator(RawOrigin::Root.into(), bounty_id, curator_lookup, fee)?;
+	Bounties::<T>::accept_curator(RawOrigin::Signed(curator.clone()).into(), bounty_id)?;
+	Ok((curator, bounty_id))
+}
+
+// Add a child bounty to a new active parent bounty, returning the parent curator, the parent
+// index and the child index.
+fn create_child_bounty<T: Config>(
+	d: u32,
+) -> Result<(T::AccountId, BountyIndex, BountyIndex), &'static str> {
+	let (curator, parent_bounty_id) = create_parent_bounty::<T>()?;
+	ChildBounties::<T>::add_child_bounty(
+		RawOrigin::Signed(curator.clone()).into(),
+		parent_bounty_id,
+		child_bounty_value::<T>(),
+		vec![0; d as usize],
+	)?;
+	let child_bounty_id = ChildBounties::<T>::child_bounty_count() - 1;
+	Ok((curator, parent_bounty_id, child_bounty_id))
+}
+
+// Create a child bounty with a proposed curator, returning the parent curator, the parent index,
+// the child index and the child bounty curator.
+fn create_child_bounty_with_curator<T: Config>()
+	-> Result<(T::AccountId, BountyIndex, BountyIndex, T::AccountId), &'static str>
+{
+	let (curator, parent_bounty_id, child_bounty_id) = create_child_bounty::<T>(MAX_BYTES)?;
+	let fee = child_bounty_value::<T>() / 2u32.into();
+	let child_curator: T::AccountId = account("child-curator", 0, SEED);
+	let _ = T::Currency::make_free_balance_be(
+		&child_curator,
+		fee.saturating_add(T::Currency::minimum_balance()),
+	);
+	ChildBounties::<T>::propose_curator(
+		RawOrigin::Signed(curator.clone()).into(),
+		parent_bounty_id,
+		child_bounty_id,
+		T::Lookup::unlookup(child_curator.clone()),
+		fee,
+	)?;
+	Ok((curator, parent_bounty_id, child_bounty_id, child_curator))
+}
+
+// Create a child bounty with an active curator.
+fn create_active_child_bounty<T: Config>()
+	-> Result<(T::AccountId, BountyIndex, BountyIndex, T::AccountId), &'static str>
+{
+	let (curator, parent_bounty_id, child_bounty_id, child_curator) =
+		create_child_bounty_with_curator::<T>()?;
+	ChildBounties::<T>::accept_curator(
+		RawOrigin::Signed(child_curator.clone()).into(),
+		parent_bounty_id,
+		child_bounty_id,
+	)?;
+	Ok((curator, parent_bounty_id, child_bounty_id, child_curator))
+}
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	let events = frame_system::Module::<T>::events();
+	let system_event: <T as frame_system::Config>::Event = generic_event.into();
+	// compare to the last event record
+	let EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
+benchmarks! {
+	add_child_bounty {
+		let d in 0 .. MAX_BYTES;
+
+		let (curator, parent_bounty_id) = create_parent_bounty::<T>()?;
+		let value = child_bounty_value::<T>();
+		let description = vec![0; d as usize];
+	}: _(RawOrigin::Signed(curator), parent_bounty_id, value, description)
+	verify {
+		assert_last_event::<T>(Event::Added(parent_bounty_id, 0).into())
+	}
+
+	propose_curator {
+		let (curator, parent_bounty_id, child_bounty_id) = create_child_bounty::<T>(MAX_BYTES)?;
+		let fee = child_bounty_value::<T>() / 2u32.into();
+		let child_curator = T::Lookup::unlookup(account("child-curator", 0, SEED));
+	}: _(RawOrigin::Signed(curator), parent_bounty_id, child_bounty_id, child_curator, fee)
+
+	accept_curator {
+		let (_, parent_bounty_id, child_bounty_id, child_curator) =
+			create_child_bounty_with_curator::<T>()?;
+	}: _(RawOrigin::Signed(child_curator), parent_bounty_id, child_bounty_id)
+
+	// Worst case when the parent curator is inactive and any sender unassigns the curator.
+	unassign_curator {
+		let (_, parent_bounty_id, child_bounty_id, _) = create_active_child_bounty::<T>()?;
+		frame_system::Module::<T>::set_block_number(
+			frame_system::Module::<T>::block_number()
+				+ T::BountyUpdatePeriod::get()
+				+ 1u32.into()
+		);
+		let caller = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), parent_bounty_id, child_bounty_id)
+
+	award_child_bounty {
+		let (_, parent_bounty_id, child_bounty_id, child_curator) =
+			create_active_child_bounty::<T>()?;
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+		let beneficiary_lookup = T::Lookup::unlookup(beneficiary.clone());
+	}: _(RawOrigin::Signed(child_curator), parent_bounty_id, child_bounty_id, beneficiary_lookup)
+	verify {
+		assert_last_event::<T>(
+			Event::Awarded(parent_bounty_id, child_bounty_id, beneficiary).into()
+		)
+	}
+
+	claim_child_bounty {
+		let (_, parent_bounty_id, child_bounty_id, child_curator) =
+			create_active_child_bounty::<T>()?;
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+		ChildBounties::<T>::award_child_bounty(
+			RawOrigin::Signed(child_curator.clone()).into(),
+			parent_bounty_id,
+			child_bounty_id,
+			T::Lookup::unlookup(beneficiary.clone()),
+		)?;
+		frame_system::Module::<T>::set_block_number(
+			frame_system::Module::<T>::block_number() + T::BountyDepositPayoutDelay::get()
+		);
+		ensure!(
+			T::Currency::free_balance(&beneficiary).is_zero(),
+			"Beneficiary already has balance",
+		);
+	}: _(RawOrigin::Signed(child_curator), parent_bounty_id, child_bounty_id)
+	verify {
+		ensure!(!T::Currency::free_balance(&beneficiary).is_zero(), "Beneficiary didn't get paid");
+	}
+
+	close_child_bounty_added {
+		let (_, parent_bounty_id, child_bounty_id) = create_child_bounty::<T>(MAX_BYTES)?;
+	}: close_child_bounty(RawOrigin::Root, parent_bounty_id, child_bounty_id)
+	verify {
+		assert_last_event::<T>(Event::Canceled(parent_bounty_id, child_bounty_id).into())
+	}
+
+	close_child_bounty_active {
+		let (_, parent_bounty_id, child_bounty_id, _) = create_active_child_bounty::<T>()?;
+	}: close_child_bounty(RawOrigin::Root, parent_bounty_id, child_bounty_id)
+	verify {
+		assert_last_event::<T>(Event::Canceled(parent_bounty_id, child_bounty_id).into())
+	}
+}
+
+impl_benchmark_test_suite!(
+	ChildBounties,
+	crate::tests::new_test_ext(),
+	crate::tests::Test,
+);
@@ -0,0 +1,705 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Child Bounties Module
+//!
+//! ## Overview
+//!
+//! With child bounties, a large bounty proposal can be divided into smaller chunks, for parallel
+//! execution and for efficient governance and tracking of spent funds.
+//!
+//! A child bounty is a smaller piece of work, extracted from a parent bounty. The curator of the
+//! parent bounty can add child bounties, funded out of the parent bounty's account, and propose a
+//! curator for each of them. Every child bounty curator gets their own fee, which is paid out of
+//! the parent curator's fee when the parent bounty is claimed.
+//!
+//! A parent bounty can't be awarded or closed while it has active child bounties.
+//!
+//! ### Terminology
+//!
+//! - **Parent bounty:** A bounty of `pallet-bounties` which is split into child bounties.
+//! - **Child bounty:** A part of the parent bounty's work, with its own curator and beneficiary.
+//! - **Child bounty curator:** A user assigned to a child bounty by the parent bounty curator,
+//!   who is responsible for awarding the child bounty.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `add_child_bounty` - Add a child bounty to an active parent bounty and fund it.
+//! - `propose_curator` - Propose a curator for a child bounty.
+//! - `accept_curator` - Accept the proposed curator role for a child bounty.
+//! - `unassign_curator` - Unassign the curator of a child bounty.
+//! - `award_child_bounty` - Award a child bounty to a beneficiary.
+//! - `claim_child_bounty` - Pay out an awarded child bounty after the payout delay.
+//! - `close_child_bounty` - Cancel a child bounty and return its funds to the parent bounty.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use sp_std::prelude::*;
+
+use codec::{Encode, Decode};
+use frame_support::traits::{
+	Currency, ExistenceRequirement::{AllowDeath, KeepAlive}, OnUnbalanced, ReservableCurrency,
+};
+use sp_runtime::{
+	DispatchResult, Permill, RuntimeDebug,
+	traits::{AccountIdConversion, BadOrigin, Saturating, StaticLookup, Zero},
+};
+use pallet_bounties::BountyIndex;
+
+pub use weights::WeightInfo;
+pub use pallet::*;
+
+type BalanceOf<T> = pallet_treasury::BalanceOf<T>;
+type BountiesError<T> = pallet_bounties::Error<T>;
+
+/// A child bounty.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ChildBounty<AccountId, Balance, BlockNumber> {
+	/// The parent of this child bounty.
+	parent_bounty: BountyIndex,
+	/// The (total) amount that should be paid if this child bounty is rewarded.
+	value: Balance,
+	/// The child bounty curator fee. Included in value.
+	fee: Balance,
+	/// The deposit of the child bounty curator.
+	curator_deposit: Balance,
+	/// The status of this child bounty.
+	status: ChildBountyStatus<AccountId, BlockNumber>,
+}
+
+/// The status of a child bounty.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ChildBountyStatus<AccountId, BlockNumber> {
+	/// The child bounty is added and funded, and waiting for a curator to be proposed.
+	Added,
+	/// A curator has been proposed by the parent bounty curator. Waiting for acceptance from the
+	/// child bounty curator.
+	CuratorProposed {
+		/// The assigned child bounty curator.
+		curator: AccountId,
+	},
+	/// The child bounty is active and waiting to be awarded.
+	Active {
+		/// The curator of this child bounty.
+		curator: AccountId,
+	},
+	/// The child bounty is awarded and waiting to be released after a delay.
+	PendingPayout {
+		/// The curator of this child bounty.
+		curator: AccountId,
+		/// The beneficiary of the child bounty.
+		beneficiary: AccountId,
+		/// When the child bounty can be claimed.
+		unlock_at: BlockNumber,
+	},
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_treasury::Config + pallet_bounties::Config {
+		/// Maximum number of child bounties that can be added to a parent bounty.
+		#[pallet::constant]
+		type MaxActiveChildBountyCount: Get<u32>;
+
+		/// Minimum value for a child bounty.
+		#[pallet::constant]
+		type ChildBountyValueMinimum: Get<BalanceOf<Self>>;
+
+		/// Percentage of the child bounty curator fee that will be reserved upfront as deposit
+		/// for the child bounty curator.
+		#[pallet::constant]
+		type ChildBountyCuratorDeposit: Get<Permill>;
+
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn child_bounty_count)]
+	/// Number of child bounties that have been added, used to assign child bounty indices.
+	pub type ChildBountyCount<T> = StorageValue<_, BountyIndex, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn parent_child_bounties)]
+	/// Number of active child bounties per parent bounty.
+	pub type ParentChildBounties<T> =
+		StorageMap<_, Twox64Concat, BountyIndex, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn child_bounties)]
+	/// Child bounties that have been added, indexed by parent bounty and child bounty index.
+	pub type ChildBounties<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		BountyIndex,
+		Twox64Concat,
+		BountyIndex,
+		ChildBounty<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn child_bounty_descriptions)]
+	/// The description of each child bounty.
+	pub type ChildBountyDescriptions<T> = StorageMap<_, Twox64Concat, BountyIndex, Vec<u8>>;
+
+	#[pallet::storage]
+	/// The cumulative curator fees of the child bounties of a parent bounty.
+	pub type ChildrenCuratorFees<T: Config> =
+		StorageMap<_, Twox64Concat, BountyIndex, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	pub enum Event<T: Config> {
+		/// A child bounty is added. \[parent_index, child_index\]
+		Added(BountyIndex, BountyIndex),
+		/// A child bounty is awarded to a beneficiary. \[parent_index, child_index, beneficiary\]
+		Awarded(BountyIndex, BountyIndex, T::AccountId),
+		/// A child bounty is claimed by the beneficiary.
+		/// \[parent_index, child_index, payout, beneficiary\]
+		Claimed(BountyIndex, BountyIndex, BalanceOf<T>, T::AccountId),
+		/// A child bounty is cancelled. \[parent_index, child_index\]
+		Canceled(BountyIndex, BountyIndex),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The parent bounty is not in the active state.
+		ParentBountyNotActive,
+		/// The parent bounty account doesn't have enough funds for the child bounty.
+		InsufficientBountyBalance,
+		/// The parent bounty already has the maximum number of active child bounties.
+		TooManyChildBounties,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Add a new child bounty to an active parent bounty.
+		///
+		/// The dispatch origin for this call must be the curator of the parent bounty. The value
+		/// of the child bounty is transferred from the parent bounty account to the child bounty
+		/// account.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `value`: Value of the child bounty.
+		/// - `description`: Text description of the child bounty.
+		#[pallet::weight(<T as Config>::WeightInfo::add_child_bounty(description.len() as u32))]
+		pub(super) fn add_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] value: BalanceOf<T>,
+			description: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let signer = ensure_signed(origin)?;
+
+			let (parent_curator, _) = Self::ensure_parent_active(parent_bounty_id)?;
+			ensure!(signer == parent_curator, BountiesError::<T>::RequireCurator);
+			ensure!(
+				description.len() <= T::MaximumReasonLength::get() as usize,
+				BountiesError::<T>::ReasonTooBig,
+			);
+			ensure!(
+				Self::parent_child_bounties(parent_bounty_id) < T::MaxActiveChildBountyCount::get(),
+				Error::<T>::TooManyChildBounties,
+			);
+			ensure!(value >= T::ChildBountyValueMinimum::get(), BountiesError::<T>::InvalidValue);
+
+			// The parent bounty account must stay alive.
+			let parent_bounty_account =
+				pallet_bounties::Module::<T>::bounty_account_id(parent_bounty_id);
+			let available = T::Currency::free_balance(&parent_bounty_account)
+				.saturating_sub(T::Currency::minimum_balance());
+			ensure!(value <= available, Error::<T>::InsufficientBountyBalance);
+
+			let child_bounty_id = Self::child_bounty_count();
+			T::Currency::transfer(
+				&parent_bounty_account,
+				&Self::child_bounty_account_id(child_bounty_id),
+				value,
+				KeepAlive,
+			)?;
+
+			ChildBountyCount::<T>::put(child_bounty_id + 1);
+			ParentChildBounties::<T>::mutate(parent_bounty_id, |count| *count += 1);
+			ChildBounties::<T>::insert(parent_bounty_id, child_bounty_id, ChildBounty {
+				parent_bounty: parent_bounty_id,
+				value,
+				fee: Zero::zero(),
+				curator_deposit: Zero::zero(),
+				status: ChildBountyStatus::Added,
+			});
+			ChildBountyDescriptions::<T>::insert(child_bounty_id, description);
+
+			Self::deposit_event(Event::Added(parent_bounty_id, child_bounty_id));
+			Ok(().into())
+		}
+
+		/// Propose a curator for a child bounty.
+		///
+		/// The dispatch origin for this call must be the curator of the parent bounty. The child
+		/// bounty must be in the `Added` state.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `child_bounty_id`: Index of the child bounty.
+		/// - `curator`: The proposed child bounty curator.
+		/// - `fee`: The child bounty curator fee, paid out of the parent curator fee.
+		#[pallet::weight(<T as Config>::WeightInfo::propose_curator())]
+		pub(super) fn propose_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+			curator: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] fee: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let signer = ensure_signed(origin)?;
+			let curator = T::Lookup::lookup(curator)?;
+
+			let (parent_curator, _) = Self::ensure_parent_active(parent_bounty_id)?;
+			ensure!(signer == parent_curator, BountiesError::<T>::RequireCurator);
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty = maybe_child_bounty.as_mut()
+						.ok_or(BountiesError::<T>::InvalidIndex)?;
+					ensure!(
+						child_bounty.status == ChildBountyStatus::Added,
+						BountiesError::<T>::UnexpectedStatus,
+					);
+					ensure!(fee < child_bounty.value, BountiesError::<T>::InvalidFee);
+
+					// A previously proposed fee is replaced by the new one.
+					Self::replace_curator_fee(parent_bounty_id, child_bounty.fee, fee);
+					child_bounty.fee = fee;
+					child_bounty.status = ChildBountyStatus::CuratorProposed { curator };
+					Ok(())
+				},
+			)?;
+			Ok(().into())
+		}
+
+		/// Accept the curator role for a child bounty.
+		///
+		/// A deposit is reserved from the curator and refunded upon successful payout or
+		/// cancellation of the child bounty.
+		///
+		/// The dispatch origin for this call must be the proposed child bounty curator.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `child_bounty_id`: Index of the child bounty.
+		#[pallet::weight(<T as Config>::WeightInfo::accept_curator())]
+		pub(super) fn accept_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResultWithPostInfo {
+			let signer = ensure_signed(origin)?;
+
+			let _ = Self::ensure_parent_active(parent_bounty_id)?;
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty = maybe_child_bounty.as_mut()
+						.ok_or(BountiesError::<T>::InvalidIndex)?;
+
+					match child_bounty.status {
+						ChildBountyStatus::CuratorProposed { ref curator } => {
+							ensure!(signer == *curator, BountiesError::<T>::RequireCurator);
+
+							let deposit = T::ChildBountyCuratorDeposit::get() * child_bounty.fee;
+							T::Currency::reserve(curator, deposit)?;
+							child_bounty.curator_deposit = deposit;
+
+							child_bounty.status =
+								ChildBountyStatus::Active { curator: curator.clone() };
+							Ok(())
+						},
+						_ => Err(BountiesError::<T>::UnexpectedStatus.into()),
+					}
+				},
+			)?;
+			Ok(().into())
+		}
+
+		/// Unassign the curator of a child bounty.
+		///
+		/// The dispatch origin for this call may be:
+		/// - the `RejectOrigin` or the parent bounty curator, who can always unassign the child
+		///   bounty curator. An active curator is slashed.
+		/// - the child bounty curator, who may give up the role. Their deposit is returned.
+		/// - any signed origin, if the parent bounty curator is inactive. The child bounty curator
+		///   is slashed.
+		///
+		/// The child bounty returns to the `Added` state.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `child_bounty_id`: Index of the child bounty.
+		#[pallet::weight(<T as Config>::WeightInfo::unassign_curator())]
+		pub(super) fn unassign_curator(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResultWithPostInfo {
+			let maybe_sender = ensure_signed(origin.clone())
+				.map(Some)
+				.or_else(|_| T::RejectOrigin::ensure_origin(origin).map(|_| None))?;
+
+			let (parent_curator, update_due) = Self::ensure_parent_active(parent_bounty_id)?;
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty = maybe_child_bounty.as_mut()
+						.ok_or(BountiesError::<T>::InvalidIndex)?;
+
+					let slash_curator = |curator: &T::AccountId, deposit: &mut BalanceOf<T>| {
+						let imbalance = T::Currency::slash_reserved(curator, *deposit).0;
+						T::OnSlash::on_unbalanced(imbalance);
+						*deposit = Zero::zero();
+					};
+
+					match child_bounty.status {
+						ChildBountyStatus::Added => {
+							// No curator to unassign at this point.
+							return Err(BountiesError::<T>::UnexpectedStatus.into())
+						},
+						ChildBountyStatus::CuratorProposed { ref curator } => {
+							// Either `RejectOrigin`, the parent curator or the proposed curator
+							// can unassign the curator.
+							ensure!(
+								maybe_sender.map_or(true, |sender| {
+									sender == *curator || sender == parent_curator
+								}),
+								BadOrigin,
+							);
+						},
+						ChildBountyStatus::Active { ref curator } => {
+							match maybe_sender {
+								// `RejectOrigin` slashes the curator.
+								None => slash_curator(curator, &mut child_bounty.curator_deposit),
+								// The curator is willingly giving up their role.
+								Some(sender) if sender == *curator => {
+									let _ = T::Currency::unreserve(
+										curator,
+										child_bounty.curator_deposit,
+									);
+									child_bounty.curator_deposit = Zero::zero();
+								},
+								// The parent curator slashes the curator.
+								Some(sender) if sender == parent_curator => {
+									slash_curator(curator, &mut child_bounty.curator_deposit);
+								},
+								// Anyone can slash the curator if the parent curator is inactive.
+								Some(_) => {
+									let block_number = frame_system::Module::<T>::block_number();
+									ensure!(
+										update_due < block_number,
+										BountiesError::<T>::Premature,
+									);
+									slash_curator(curator, &mut child_bounty.curator_deposit);
+								},
+							}
+						},
+						ChildBountyStatus::PendingPayout { ref curator, .. } => {
+							// Only `RejectOrigin` or the parent curator can unassign a curator
+							// whose child bounty is pending payout. This implies the curator is
+							// acting maliciously, so they are slashed.
+							ensure!(
+								maybe_sender.map_or(true, |sender| sender == parent_curator),
+								BadOrigin,
+							);
+							slash_curator(curator, &mut child_bounty.curator_deposit);
+						},
+					}
+
+					child_bounty.status = ChildBountyStatus::Added;
+					Ok(())
+				},
+			)?;
+			Ok(().into())
+		}
+
+		/// Award a child bounty to a beneficiary. The beneficiary will be able to claim the funds
+		/// after a delay of `BountyDepositPayoutDelay`.
+		///
+		/// The dispatch origin for this call must be the child bounty curator or the parent
+		/// bounty curator.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `child_bounty_id`: Index of the child bounty.
+		/// - `beneficiary`: The beneficiary account of the child bounty.
+		#[pallet::weight(<T as Config>::WeightInfo::award_child_bounty())]
+		pub(super) fn award_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			let signer = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			let (parent_curator, _) = Self::ensure_parent_active(parent_bounty_id)?;
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty = maybe_child_bounty.as_mut()
+						.ok_or(BountiesError::<T>::InvalidIndex)?;
+
+					match &child_bounty.status {
+						ChildBountyStatus::Active { curator } => {
+							ensure!(
+								signer == *curator || signer == parent_curator,
+								BountiesError::<T>::RequireCurator,
+							);
+							child_bounty.status = ChildBountyStatus::PendingPayout {
+								curator: curator.clone(),
+								beneficiary: beneficiary.clone(),
+								unlock_at: frame_system::Module::<T>::block_number()
+									+ T::BountyDepositPayoutDelay::get(),
+							};
+							Ok(())
+						},
+						_ => Err(BountiesError::<T>::UnexpectedStatus.into()),
+					}
+				},
+			)?;
+
+			Self::deposit_event(Event::Awarded(parent_bounty_id, child_bounty_id, beneficiary));
+			Ok(().into())
+		}
+
+		/// Claim the payout of an awarded child bounty after the payout delay.
+		///
+		/// The child bounty curator receives their fee and deposit, the beneficiary receives the
+		/// rest of the child bounty funds.
+		///
+		/// The dispatch origin for this call may be any signed origin.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `child_bounty_id`: Index of the child bounty.
+		#[pallet::weight(<T as Config>::WeightInfo::claim_child_bounty())]
+		pub(super) fn claim_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResultWithPostInfo {
+			let _ = ensure_signed(origin)?;
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty = maybe_child_bounty.as_ref()
+						.ok_or(BountiesError::<T>::InvalidIndex)?;
+
+					if let ChildBountyStatus::PendingPayout {
+						ref curator,
+						ref beneficiary,
+						ref unlock_at,
+					} = child_bounty.status {
+						ensure!(
+							frame_system::Module::<T>::block_number() >= *unlock_at,
+							BountiesError::<T>::Premature,
+						);
+
+						let child_bounty_account = Self::child_bounty_account_id(child_bounty_id);
+						let balance = T::Currency::free_balance(&child_bounty_account);
+						let fee = child_bounty.fee.min(balance); // just to be safe
+						let payout = balance.saturating_sub(fee);
+						let _ = T::Currency::unreserve(curator, child_bounty.curator_deposit);
+						let _ = T::Currency::transfer(
+							&child_bounty_account,
+							curator,
+							fee,
+							AllowDeath,
+						); // should not fail
+						let _ = T::Currency::transfer(
+							&child_bounty_account,
+							beneficiary,
+							payout,
+							AllowDeath,
+						); // should not fail
+
+						Self::deposit_event(Event::Claimed(
+							parent_bounty_id,
+							child_bounty_id,
+							payout,
+							beneficiary.clone(),
+						));
+
+						// The fee stays accounted for in `ChildrenCuratorFees`, it is deducted
+						// from the parent curator fee when the parent bounty is claimed.
+						Self::remove_child_bounty(parent_bounty_id, child_bounty_id);
+						*maybe_child_bounty = None;
+						Ok(())
+					} else {
+						Err(BountiesError::<T>::UnexpectedStatus.into())
+					}
+				},
+			)?;
+			Ok(().into())
+		}
+
+		/// Cancel a child bounty and return its funds to the parent bounty account.
+		///
+		/// The dispatch origin for this call must be the `RejectOrigin` or the parent bounty
+		/// curator. A child bounty pending payout can only be closed after its curator has been
+		/// unassigned.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `child_bounty_id`: Index of the child bounty.
+		#[pallet::weight(<T as Config>::WeightInfo::close_child_bounty_added()
+			.max(<T as Config>::WeightInfo::close_child_bounty_active()))]
+		pub(super) fn close_child_bounty(
+			origin: OriginFor<T>,
+			#[pallet::compact] parent_bounty_id: BountyIndex,
+			#[pallet::compact] child_bounty_id: BountyIndex,
+		) -> DispatchResultWithPostInfo {
+			let maybe_sender = ensure_signed(origin.clone())
+				.map(Some)
+				.or_else(|_| T::RejectOrigin::ensure_origin(origin).map(|_| None))?;
+
+			if let Some(sender) = maybe_sender {
+				let (parent_curator, _) = Self::ensure_parent_active(parent_bounty_id)?;
+				ensure!(sender == parent_curator, BadOrigin);
+			}
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResultWithPostInfo {
+					let child_bounty = maybe_child_bounty.as_ref()
+						.ok_or(BountiesError::<T>::InvalidIndex)?;
+
+					let weight = match &child_bounty.status {
+						ChildBountyStatus::Added |
+						ChildBountyStatus::CuratorProposed { .. } => {
+							// Nothing extra to do besides the removal of the child bounty below.
+							<T as Config>::WeightInfo::close_child_bounty_added()
+						},
+						ChildBountyStatus::Active { curator } => {
+							// Cancelled, refund the deposit of the working curator.
+							let _ = T::Currency::unreserve(curator, child_bounty.curator_deposit);
+							<T as Config>::WeightInfo::close_child_bounty_active()
+						},
+						ChildBountyStatus::PendingPayout { .. } => {
+							// The curator should be unassigned first, slashing their deposit.
+							return Err(BountiesError::<T>::PendingPayout.into())
+						},
+					};
+
+					// The curator won't be paid, so the fee goes back to the parent curator.
+					Self::replace_curator_fee(parent_bounty_id, child_bounty.fee, Zero::zero());
+
+					let child_bounty_account = Self::child_bounty_account_id(child_bounty_id);
+					let balance = T::Currency::free_balance(&child_bounty_account);
+					let _ = T::Currency::transfer(
+						&child_bounty_account,
+						&pallet_bounties::Module::<T>::bounty_account_id(parent_bounty_id),
+						balance,
+						AllowDeath,
+					); // should not fail
+
+					Self::remove_child_bounty(parent_bounty_id, child_bounty_id);
+					*maybe_child_bounty = None;
+
+					Self::deposit_event(Event::Canceled(parent_bounty_id, child_bounty_id));
+					Ok(Some(weight).into())
+				},
+			)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account ID of a child bounty account.
+	pub fn child_bounty_account_id(id: BountyIndex) -> T::AccountId {
+		// Only use a two byte prefix to support 16 byte account IDs (as the bounties pallet).
+		<T as pallet_treasury::Config>::ModuleId::get().into_sub_account(("cb", id))
+	}
+
+	/// Get the curator and update due of the parent bounty, which must be active.
+	fn ensure_parent_active(
+		parent_bounty_id: BountyIndex,
+	) -> Result<(T::AccountId, T::BlockNumber), Error<T>> {
+		pallet_bounties::Module::<T>::ensure_bounty_active(parent_bounty_id)
+			.map_err(|_| Error::<T>::ParentBountyNotActive)
+	}
+
+	/// Replace the curator fee `old_fee` of a child bounty by `new_fee` in the cumulative curator
+	/// fees of its parent bounty.
+	fn replace_curator_fee(
+		parent_bounty_id: BountyIndex,
+		old_fee: BalanceOf<T>,
+		new_fee: BalanceOf<T>,
+	) {
+		let fees = ChildrenCuratorFees::<T>::get(parent_bounty_id)
+			.saturating_sub(old_fee)
+			.saturating_add(new_fee);
+		if fees.is_zero() {
+			ChildrenCuratorFees::<T>::remove(parent_bounty_id);
+		} else {
+			ChildrenCuratorFees::<T>::insert(parent_bounty_id, fees);
+		}
+	}
+
+	/// Remove the bookkeeping of a child bounty which is claimed or closed.
+	fn remove_child_bounty(parent_bounty_id: BountyIndex, child_bounty_id: BountyIndex) {
+		ChildBountyDescriptions::<T>::remove(child_bounty_id);
+		ParentChildBounties::<T>::mutate(parent_bounty_id, |count| {
+			*count = count.saturating_sub(1)
+		});
+	}
+}
+
+impl<T: Config> pallet_bounties::ChildBountyManager<BalanceOf<T>> for Pallet<T> {
+	fn child_bounties_count(bounty_id: BountyIndex) -> BountyIndex {
+		Self::parent_child_bounties(bounty_id)
+	}
+
+	fn children_curator_fees(bounty_id: BountyIndex) -> BalanceOf<T> {
+		ChildrenCuratorFees::<T>::take(bounty_id)
+	}
+}
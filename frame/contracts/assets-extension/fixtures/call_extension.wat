@@ -0,0 +1,44 @@
+;; Pass the input to the chain extension and return its return value followed by its output
+(module
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) len of input buffer
+	(data (i32.const 0) "\00\01")
+
+	;; [4, 260) buffer for input: the func_id followed by the input of the extension
+
+	;; [260, 264) len of output buffer
+	(data (i32.const 260) "\00\01")
+
+	;; [264, 268) return value of the chain extension
+
+	;; [268, 524) buffer for output
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		(i32.store
+			(i32.const 264)
+			(call $seal_call_chain_extension
+				(i32.load (i32.const 4))						;; func_id
+				(i32.const 8)									;; input_ptr
+				(i32.sub (i32.load (i32.const 0)) (i32.const 4))	;; input_len
+				(i32.const 268)									;; output_ptr
+				(i32.const 260)									;; output_len_ptr
+			)
+		)
+
+		(call $seal_return
+			(i32.const 0)
+			(i32.const 264)
+			(i32.add (i32.load (i32.const 260)) (i32.const 4))
+		)
+	)
+)
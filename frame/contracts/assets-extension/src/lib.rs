@@ -0,0 +1,133 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A chain extension that allows contracts to query and transfer assets of `pallet-assets`.
+//!
+//! The extension registers itself with the extension id `1` and can therefore be combined
+//! with other extensions by declaring a tuple as the chain extension of `pallet-contracts`:
+//!
+//! ```ignore
+//! impl pallet_contracts::Config for Runtime {
+//! 	type ChainExtension = (AssetsExtension<Self>, MyOtherExtension);
+//! 	// ...
+//! }
+//! ```
+//!
+//! All arguments are passed SCALE encoded in the input buffer of `seal_call_chain_extension`.
+//! The following functions are available:
+//!
+//! | `func_id` | Input | Output |
+//! |-----------|-------|--------|
+//! | [`func::TOTAL_SUPPLY`] | `AssetId` | `Balance` written to the output buffer |
+//! | [`func::BALANCE_OF`] | `(AssetId, AccountId)` | `Balance` written to the output buffer |
+//! | [`func::TRANSFER`] | `(AssetId, AccountId, Balance)` | [`TRANSFER_FAILED`] on failure |
+//!
+//! Transfers are always made from the account of the calling contract.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod tests;
+
+use codec::Encode;
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	traits::{Get, UnfilteredDispatchable},
+};
+use frame_system::RawOrigin;
+use pallet_assets::WeightInfo;
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RegisteredChainExtension, RetVal, SysConfig,
+	UncheckedFrom, Result,
+};
+use sp_runtime::{DispatchError, traits::StaticLookup};
+use sp_std::marker::PhantomData;
+
+/// The function ids understood by [`AssetsExtension`].
+pub mod func {
+	/// Write the total supply of an asset to the output buffer.
+	pub const TOTAL_SUPPLY: u32 = 0;
+	/// Write the balance that an account holds of an asset to the output buffer.
+	pub const BALANCE_OF: u32 = 1;
+	/// Transfer some amount of an asset from the calling contract to another account.
+	pub const TRANSFER: u32 = 2;
+}
+
+/// Returned by [`func::TRANSFER`] when the transfer was not carried out.
+///
+/// All other functions and a successful transfer return `0`.
+pub const TRANSFER_FAILED: u32 = 1;
+
+/// A chain extension that exposes `pallet-assets` to contracts.
+///
+/// Consult the [crate documentation](self) for the available functions.
+pub struct AssetsExtension<T>(PhantomData<T>);
+
+impl<T> ChainExtension<T> for AssetsExtension<T>
+where
+	T: pallet_contracts::Config + pallet_assets::Config,
+{
+	fn call<E>(func_id: u32, env: Environment<E, InitState>) -> Result<RetVal>
+	where
+		E: Ext<T = T>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		let mut env = env.buf_in_buf_out();
+		match func_id {
+			func::TOTAL_SUPPLY => {
+				env.charge_weight(T::DbWeight::get().reads(1))?;
+				let id: T::AssetId = env.read_as()?;
+				let supply = pallet_assets::Pallet::<T>::total_supply(id);
+				env.write(&supply.encode(), false, None)?;
+				Ok(RetVal::Converging(0))
+			},
+			func::BALANCE_OF => {
+				env.charge_weight(T::DbWeight::get().reads(1))?;
+				let (id, who): (T::AssetId, T::AccountId) = env.read_as()?;
+				let balance = pallet_assets::Pallet::<T>::balance(id, who);
+				env.write(&balance.encode(), false, None)?;
+				Ok(RetVal::Converging(0))
+			},
+			func::TRANSFER => {
+				env.charge_weight(<T as pallet_assets::Config>::WeightInfo::transfer())?;
+				let (id, dest, amount): (T::AssetId, T::AccountId, T::Balance) = env.read_as()?;
+				let origin = RawOrigin::Signed(env.ext().address().clone());
+				let call = pallet_assets::Call::<T>::transfer(id, T::Lookup::unlookup(dest), amount);
+				// Contracts are not subject to the call filter of the runtime. A failed transfer
+				// must not leave any changes behind because the contract continues to execute.
+				let result = with_transaction(|| {
+					match call.dispatch_bypass_filter(origin.into()) {
+						Ok(_) => TransactionOutcome::Commit(Ok(())),
+						Err(_) => TransactionOutcome::Rollback(Err(())),
+					}
+				});
+				match result {
+					Ok(()) => Ok(RetVal::Converging(0)),
+					Err(()) => Ok(RetVal::Converging(TRANSFER_FAILED)),
+				}
+			},
+			_ => Err(DispatchError::Other("AssetsExtension: unknown func_id")),
+		}
+	}
+}
+
+impl<T> RegisteredChainExtension<T> for AssetsExtension<T>
+where
+	T: pallet_contracts::Config + pallet_assets::Config,
+{
+	const ID: u16 = 1;
+}
@@ -0,0 +1,263 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{AssetsExtension, TRANSFER_FAILED, func};
+use codec::{Encode, Decode};
+use frame_support::{
+	assert_ok, parameter_types,
+	traits::{Currency, UnfilteredDispatchable},
+	weights::{Weight, constants::WEIGHT_PER_SECOND},
+};
+use pallet_contracts::chain_extension::RegisteredChainExtension;
+use sp_core::H256;
+use sp_runtime::{
+	AccountId32, Perbill,
+	testing::Header,
+	traits::{BlakeTwo256, Hash, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
+		Randomness: pallet_randomness_collective_flip::{Module, Call, Storage},
+		Assets: pallet_assets::{Module, Call, Storage, Event<T>},
+		Contracts: pallet_contracts::{Module, Call, Config<T>, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub BlockWeights: frame_system::limits::BlockWeights =
+		frame_system::limits::BlockWeights::simple_max(2 * WEIGHT_PER_SECOND);
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = BlockWeights;
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Call = Call;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId32;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const AssetDeposit: u64 = 1;
+	pub const ApprovalDeposit: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+}
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId32>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const SignedClaimHandicap: u64 = 2;
+	pub const TombstoneDeposit: u64 = 16;
+	pub const DepositPerContract: u64 = 16;
+	pub const DepositPerStorageByte: u64 = 1;
+	pub const DepositPerStorageItem: u64 = 1;
+	pub RentFraction: Perbill = Perbill::from_rational_approximation(4u32, 10_000u32);
+	pub const SurchargeReward: u64 = 50;
+	pub const MaxDepth: u32 = 100;
+	pub const MaxValueSize: u32 = 16_384;
+	pub const DeletionQueueDepth: u32 = 1024;
+	pub const DeletionWeightLimit: Weight = 500_000_000_000;
+	pub const MaxCodeSize: u32 = 2 * 1024;
+	pub const StoragePricing: pallet_contracts::StoragePricing =
+		pallet_contracts::StoragePricing::Rent;
+}
+impl pallet_contracts::Config for Test {
+	type Time = Timestamp;
+	type Randomness = Randomness;
+	type Currency = Balances;
+	type Event = Event;
+	type RentPayment = ();
+	type StoragePricing = StoragePricing;
+	type SignedClaimHandicap = SignedClaimHandicap;
+	type TombstoneDeposit = TombstoneDeposit;
+	type DepositPerContract = DepositPerContract;
+	type DepositPerStorageByte = DepositPerStorageByte;
+	type DepositPerStorageItem = DepositPerStorageItem;
+	type RentFraction = RentFraction;
+	type SurchargeReward = SurchargeReward;
+	type MaxDepth = MaxDepth;
+	type MaxValueSize = MaxValueSize;
+	type WeightPrice = ();
+	type WeightInfo = ();
+	type ChainExtension = (AssetsExtension<Self>,);
+	type DeletionQueueDepth = DeletionQueueDepth;
+	type DeletionWeightLimit = DeletionWeightLimit;
+	type MaxCodeSize = MaxCodeSize;
+}
+
+const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
+const BOB: AccountId32 = AccountId32::new([2u8; 32]);
+const ASSET: u32 = 7;
+const GAS_LIMIT: Weight = 10_000_000_000;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Deploy the contract which passes its input to the chain extension.
+///
+/// It holds 1000 of `ASSET` which is owned by `ALICE`.
+fn deploy_contract() -> AccountId32 {
+	let wasm = wat::parse_file("fixtures/call_extension.wat").unwrap();
+	let code_hash = BlakeTwo256::hash(&wasm);
+	let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+	assert_ok!(Contracts::instantiate_with_code(
+		Origin::signed(ALICE),
+		100_000,
+		GAS_LIMIT,
+		None,
+		wasm,
+		vec![],
+		vec![],
+	));
+	let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+	assert_ok!(
+		pallet_assets::Call::<Test>::force_create(ASSET, ALICE, true, 1)
+			.dispatch_bypass_filter(Origin::root())
+	);
+	assert_ok!(
+		pallet_assets::Call::<Test>::mint(ASSET, addr.clone(), 1000)
+			.dispatch_bypass_filter(Origin::signed(ALICE))
+	);
+	addr
+}
+
+/// Call a function of the assets extension through the contract at `addr`.
+///
+/// Returns the return value of the extension and its output.
+fn call_extension(addr: &AccountId32, func_id: u32, input: impl Encode) -> (u32, Vec<u8>) {
+	let func_id = (u32::from(<AssetsExtension<Test> as RegisteredChainExtension<Test>>::ID) << 16)
+		| func_id;
+	let data = func_id.to_le_bytes().iter().cloned().chain(input.encode()).collect();
+	let result = Contracts::bare_call(ALICE, addr.clone(), 0, GAS_LIMIT, data)
+		.exec_result
+		.unwrap();
+	let ret = u32::from_le_bytes([result.data[0], result.data[1], result.data[2], result.data[3]]);
+	(ret, result.data[4..].to_vec())
+}
+
+#[test]
+fn total_supply_and_balance_are_reported() {
+	new_test_ext().execute_with(|| {
+		let addr = deploy_contract();
+
+		let (ret, output) = call_extension(&addr, func::TOTAL_SUPPLY, ASSET);
+		assert_eq!(ret, 0);
+		assert_eq!(u64::decode(&mut &output[..]).unwrap(), 1000);
+
+		let (ret, output) = call_extension(&addr, func::BALANCE_OF, (ASSET, addr.clone()));
+		assert_eq!(ret, 0);
+		assert_eq!(u64::decode(&mut &output[..]).unwrap(), 1000);
+
+		let (_, output) = call_extension(&addr, func::BALANCE_OF, (ASSET, BOB));
+		assert_eq!(u64::decode(&mut &output[..]).unwrap(), 0);
+	});
+}
+
+#[test]
+fn transfer_works_from_contract_account() {
+	new_test_ext().execute_with(|| {
+		let addr = deploy_contract();
+
+		let (ret, _) = call_extension(&addr, func::TRANSFER, (ASSET, BOB, 300u64));
+		assert_eq!(ret, 0);
+		assert_eq!(Assets::balance(ASSET, addr.clone()), 700);
+		assert_eq!(Assets::balance(ASSET, BOB), 300);
+
+		// The contract does not hold enough to make this transfer.
+		let (ret, _) = call_extension(&addr, func::TRANSFER, (ASSET, BOB, 701u64));
+		assert_eq!(ret, TRANSFER_FAILED);
+		assert_eq!(Assets::balance(ASSET, addr), 700);
+		assert_eq!(Assets::balance(ASSET, BOB), 300);
+	});
+}
+
+#[test]
+fn unknown_function_fails() {
+	new_test_ext().execute_with(|| {
+		let addr = deploy_contract();
+		let data = (1u32 << 16 | 42).to_le_bytes().to_vec();
+		let result = Contracts::bare_call(ALICE, addr, 0, GAS_LIMIT, data);
+		assert!(result.exec_result.is_err());
+	});
+}
@@ -31,8 +31,54 @@ use sp_std::prelude::*;
 pub struct ContractExecResult {
 	pub exec_result: ExecResult,
 	pub gas_consumed: u64,
+	/// Output of all `seal_println` calls made during execution.
+	///
+	/// Contains a newline terminated line for each printed message. Empty when
+	/// printing is disabled by the current schedule.
+	pub debug_message: Vec<u8>,
 }
 
+/// Result type of a `bare_call` call, as returned by version 1 of the `ContractsApi`.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ContractExecResultV1 {
+	pub exec_result: ExecResult,
+	pub gas_consumed: u64,
+}
+
+impl From<ContractExecResultV1> for ContractExecResult {
+	fn from(v1: ContractExecResultV1) -> Self {
+		Self {
+			exec_result: v1.exec_result,
+			gas_consumed: v1.gas_consumed,
+			debug_message: Vec::new(),
+		}
+	}
+}
+
+/// Result type of a `bare_instantiate` call.
+///
+/// The result of a contract instantiation along with the gas consumed, the gas that would be
+/// required to succeed and the events deposited during execution.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ContractInstantiateResult<AccountId> {
+	pub exec_result: Result<InstantiateReturnValue<AccountId>, ExecError>,
+	pub gas_consumed: u64,
+	/// The peak amount of gas that was used during execution.
+	///
+	/// This can be higher than `gas_consumed` because some gas is refunded after it was
+	/// charged. Supplying this value as `gas_limit` is enough for the execution to succeed.
+	pub gas_required: u64,
+	/// See [`ContractExecResult::debug_message`].
+	///
+	/// When the supplied code is rejected the reason is reported here.
+	pub debug_message: Vec<u8>,
+	/// The SCALE encoded event records that were deposited during execution.
+	pub events: Vec<Vec<u8>>,
+}
+
+/// Result type of a `bare_upload_code` call.
+pub type CodeUploadResult<CodeHash> = Result<CodeUploadReturnValue<CodeHash>, CodeRejected>;
+
 /// Result type of a `get_storage` call.
 pub type GetStorageResult = Result<Option<Vec<u8>>, ContractAccessError>;
 
@@ -84,6 +130,47 @@ impl ExecReturnValue {
 	}
 }
 
+/// The result of a successful contract instantiation.
+#[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct InstantiateReturnValue<AccountId> {
+	/// The output of the called constructor.
+	pub result: ExecReturnValue,
+	/// The account id of the new contract.
+	pub account_id: AccountId,
+}
+
+/// Reference to an existing code hash or a new wasm module.
+#[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub enum Code<Hash> {
+	/// A wasm module as raw bytes.
+	Upload(Vec<u8>),
+	/// The code hash of an on-chain wasm blob.
+	Existing(Hash),
+}
+
+/// The result of a code that passed all checks and was instrumented.
+#[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct CodeUploadReturnValue<CodeHash> {
+	/// The hash under which the code would be stored.
+	pub code_hash: CodeHash,
+	/// The size of the supplied, uninstrumented code in bytes.
+	pub original_code_len: u32,
+	/// The size of the code after instrumentation in bytes.
+	pub instrumented_code_len: u32,
+}
+
+/// Error returned when a code does not pass the checks performed before it can be stored.
+#[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct CodeRejected {
+	/// The error that would be returned by the dispatchable.
+	pub error: DispatchError,
+	/// Human readable, utf8 encoded reason why the code was rejected.
+	///
+	/// This is needed because the textual information of `DispatchError::Other` is not
+	/// part of its encoding.
+	pub reason: Vec<u8>,
+}
+
 /// Origin of the error.
 ///
 /// Call or instantiate both called into other contracts and pass through errors happening
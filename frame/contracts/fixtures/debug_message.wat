@@ -0,0 +1,21 @@
+;; Prints a message in the constructor and when called.
+(module
+	(import "seal0" "seal_println" (func $seal_println (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy")
+		(call $seal_println
+			(i32.const 0)	;; Pointer to the text buffer
+			(i32.const 12)	;; The size of the buffer
+		)
+	)
+
+	(func (export "call")
+		(call $seal_println
+			(i32.const 0)	;; Pointer to the text buffer
+			(i32.const 12)	;; The size of the buffer
+		)
+	)
+
+	(data (i32.const 0) "Hello World!")
+)
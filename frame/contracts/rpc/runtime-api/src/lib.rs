@@ -25,15 +25,33 @@
 
 use codec::Codec;
 use sp_std::vec::Vec;
-use pallet_contracts_primitives::{ContractExecResult, GetStorageResult, RentProjectionResult};
+use pallet_contracts_primitives::{
+	ContractExecResult, ContractExecResultV1, GetStorageResult, RentProjectionResult, Code,
+	ContractInstantiateResult, CodeUploadResult,
+};
 
 sp_api::decl_runtime_apis! {
 	/// The API to interact with contracts without using executive.
-	pub trait ContractsApi<AccountId, Balance, BlockNumber> where
+	///
+	/// Version 2 adds the debug message to the result of `call`, as well as `instantiate` and
+	/// `upload_code`.
+	#[api_version(2)]
+	pub trait ContractsApi<AccountId, Balance, BlockNumber, Hash> where
 		AccountId: Codec,
 		Balance: Codec,
 		BlockNumber: Codec,
+		Hash: Codec,
 	{
+		/// Perform a call from a specified account to a given contract.
+		#[changed_in(2)]
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: u64,
+			input_data: Vec<u8>,
+		) -> ContractExecResultV1;
+
 		/// Perform a call from a specified account to a given contract.
 		///
 		/// See the contracts' `call` dispatchable function for more details.
@@ -45,6 +63,24 @@ sp_api::decl_runtime_apis! {
 			input_data: Vec<u8>,
 		) -> ContractExecResult;
 
+		/// Instantiate a new contract.
+		///
+		/// See the contracts' `instantiate` and `instantiate_with_code` dispatchable functions
+		/// for more details.
+		fn instantiate(
+			origin: AccountId,
+			endowment: Balance,
+			gas_limit: u64,
+			code: Code<Hash>,
+			data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> ContractInstantiateResult<AccountId>;
+
+		/// Check and instrument the supplied code without storing it.
+		///
+		/// Returns `Err` with the reason if `instantiate_with_code` would reject the code.
+		fn upload_code(code: Vec<u8>) -> CodeUploadResult<Hash>;
+
 		/// Query a given storage key in a given contract.
 		///
 		/// Returns `Ok(Some(Vec<u8>))` if the storage value exists under the given key in the
@@ -24,7 +24,7 @@ use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
 use pallet_contracts_primitives::RentProjection;
 use serde::{Deserialize, Serialize};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::{Bytes, H256};
 use sp_rpc::number;
@@ -34,13 +34,17 @@ use sp_runtime::{
 	DispatchError,
 };
 use std::convert::{TryFrom, TryInto};
-use pallet_contracts_primitives::ContractExecResult;
+use pallet_contracts_primitives::{
+	ContractExecResult, ContractInstantiateResult, CodeUploadReturnValue, CodeRejected,
+};
 
 pub use pallet_contracts_rpc_runtime_api::ContractsApi as ContractsRuntimeApi;
 
 const RUNTIME_ERROR: i64 = 1;
 const CONTRACT_DOESNT_EXIST: i64 = 2;
 const CONTRACT_IS_A_TOMBSTONE: i64 = 3;
+const CODE_REJECTED: i64 = 4;
+const API_UNSUPPORTED: i64 = 5;
 
 /// A rough estimate of how much gas a decent hardware consumes per second,
 /// using native execution.
@@ -52,6 +56,15 @@ const CONTRACT_IS_A_TOMBSTONE: i64 = 3;
 /// https://github.com/paritytech/substrate/pull/5446
 const GAS_PER_SECOND: u64 = 1_000_000_000_000;
 
+/// The runtime API, as a trait object to query the version implemented by the runtime.
+type RuntimeApiOf<Block, AccountId, Balance, Hash> = dyn ContractsRuntimeApi<
+	Block,
+	AccountId,
+	Balance,
+	<<Block as BlockT>::Header as HeaderT>::Number,
+	Hash,
+>;
+
 /// A private newtype for converting `ContractAccessError` into an RPC error.
 struct ContractAccessError(pallet_contracts_primitives::ContractAccessError);
 impl From<ContractAccessError> for Error {
@@ -84,6 +97,39 @@ pub struct CallRequest<AccountId> {
 	input_data: Bytes,
 }
 
+/// A struct that encodes RPC parameters required to instantiate a new smart-contract.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct InstantiateRequest<AccountId, Hash> {
+	origin: AccountId,
+	endowment: number::NumberOrHex,
+	gas_limit: number::NumberOrHex,
+	code: Code<Hash>,
+	data: Bytes,
+	salt: Bytes,
+}
+
+/// Reference to an existing code hash or a new wasm module.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub enum Code<Hash> {
+	/// A wasm module as raw bytes.
+	Upload(Bytes),
+	/// The code hash of an on-chain wasm blob.
+	Existing(Hash),
+}
+
+impl<Hash> From<Code<Hash>> for pallet_contracts_primitives::Code<Hash> {
+	fn from(code: Code<Hash>) -> Self {
+		match code {
+			Code::Upload(binary) => Self::Upload(binary.to_vec()),
+			Code::Existing(hash) => Self::Existing(hash),
+		}
+	}
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
@@ -111,10 +157,11 @@ pub struct RpcContractExecResult {
 
 impl From<ContractExecResult> for RpcContractExecResult {
 	fn from(r: ContractExecResult) -> Self {
+		let debug_message = String::from_utf8_lossy(&r.debug_message).into_owned();
 		match r.exec_result {
 			Ok(val) => RpcContractExecResult {
 				gas_consumed: r.gas_consumed,
-				debug_message: String::new(),
+				debug_message,
 				result: Ok(RpcContractExecSuccess {
 					flags: val.flags.bits(),
 					data: val.data.into(),
@@ -122,16 +169,105 @@ impl From<ContractExecResult> for RpcContractExecResult {
 			},
 			Err(err) => RpcContractExecResult {
 				gas_consumed: r.gas_consumed,
-				debug_message: String::new(),
+				debug_message,
 				result: Err(err.error),
 			},
 		}
 	}
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+struct RpcInstantiateSuccess<AccountId> {
+	/// The return flags of the constructor. See `pallet_contracts_primitives::ReturnFlags`.
+	flags: u32,
+	/// Data as returned by the constructor.
+	data: Bytes,
+	/// The account id of the new contract.
+	account_id: AccountId,
+}
+
+/// An RPC serializable result of a contract instantiation.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContractInstantiateResult<AccountId> {
+	/// How much gas was consumed by the instantiation. In case of an error this is the amount
+	/// that was used up until the error occurred.
+	gas_consumed: u64,
+	/// The smallest gas limit with which the instantiation would not run out of gas.
+	gas_required: u64,
+	/// Additional dynamic human readable error information for debugging. An empty string
+	/// indicates that no additional information is available.
+	debug_message: String,
+	/// The SCALE encoded event records deposited during the instantiation.
+	events: Vec<Bytes>,
+	/// Indicates whether the instantiation was successful or not.
+	result: std::result::Result<RpcInstantiateSuccess<AccountId>, DispatchError>,
+}
+
+impl<AccountId> From<ContractInstantiateResult<AccountId>>
+	for RpcContractInstantiateResult<AccountId>
+{
+	fn from(r: ContractInstantiateResult<AccountId>) -> Self {
+		RpcContractInstantiateResult {
+			gas_consumed: r.gas_consumed,
+			gas_required: r.gas_required,
+			debug_message: String::from_utf8_lossy(&r.debug_message).into_owned(),
+			events: r.events.into_iter().map(Into::into).collect(),
+			result: r.exec_result
+				.map(|val| RpcInstantiateSuccess {
+					flags: val.result.flags.bits(),
+					data: val.result.data.into(),
+					account_id: val.account_id,
+				})
+				.map_err(|err| err.error),
+		}
+	}
+}
+
+/// An RPC serializable description of a code that passed all checks.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcCodeUploadResult<Hash> {
+	/// The hash under which the code would be stored.
+	code_hash: Hash,
+	/// The size of the supplied code in bytes.
+	original_code_len: u32,
+	/// The size of the code after instrumentation in bytes.
+	instrumented_code_len: u32,
+}
+
+impl<Hash> From<CodeUploadReturnValue<Hash>> for RpcCodeUploadResult<Hash> {
+	fn from(r: CodeUploadReturnValue<Hash>) -> Self {
+		RpcCodeUploadResult {
+			code_hash: r.code_hash,
+			original_code_len: r.original_code_len,
+			instrumented_code_len: r.instrumented_code_len,
+		}
+	}
+}
+
+/// A private newtype for converting `CodeRejected` into an RPC error.
+struct CodeRejectedError(CodeRejected);
+impl From<CodeRejectedError> for Error {
+	fn from(e: CodeRejectedError) -> Error {
+		Error {
+			code: ErrorCode::ServerError(CODE_REJECTED),
+			message: format!(
+				"The code was rejected: {}",
+				String::from_utf8_lossy(&e.0.reason),
+			),
+			data: Some(format!("{:?}", e.0.error).into()),
+		}
+	}
+}
+
 /// Contracts RPC methods.
 #[rpc]
-pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
+pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance, Hash> {
 	/// Executes a call to a contract.
 	///
 	/// This call is performed locally without submitting any transactions. Thus executing this
@@ -145,6 +281,31 @@ pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
 		at: Option<BlockHash>,
 	) -> Result<RpcContractExecResult>;
 
+	/// Instantiate a new contract.
+	///
+	/// This call is performed locally without submitting any transactions. Thus the contract
+	/// is not actually created.
+	///
+	/// This method is useful for estimating the gas limit and for predicting the address
+	/// of a contract before submitting `instantiate` or `instantiate_with_code`.
+	#[rpc(name = "contracts_instantiate")]
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, Hash>,
+		at: Option<BlockHash>,
+	) -> Result<RpcContractInstantiateResult<AccountId>>;
+
+	/// Check the supplied wasm code against the current schedule without storing it.
+	///
+	/// Returns an error describing the violation if `instantiate_with_code` would
+	/// reject the code.
+	#[rpc(name = "contracts_uploadCode")]
+	fn upload_code(
+		&self,
+		code: Bytes,
+		at: Option<BlockHash>,
+	) -> Result<RpcCodeUploadResult<Hash>>;
+
 	/// Returns the value under a specified storage `key` in a contract given by `address` param,
 	/// or `None` if it is not set.
 	#[rpc(name = "contracts_getStorage")]
@@ -184,12 +345,13 @@ impl<C, B> Contracts<C, B> {
 		}
 	}
 }
-impl<C, Block, AccountId, Balance>
+impl<C, Block, AccountId, Balance, Hash>
 	ContractsApi<
 		<Block as BlockT>::Hash,
 		<<Block as BlockT>::Header as HeaderT>::Number,
 		AccountId,
 		Balance,
+		Hash,
 	> for Contracts<C, Block>
 where
 	Block: BlockT,
@@ -199,9 +361,11 @@ where
 		AccountId,
 		Balance,
 		<<Block as BlockT>::Header as HeaderT>::Number,
+		Hash,
 	>,
 	AccountId: Codec,
 	Balance: Codec + TryFrom<number::NumberOrHex>,
+	Hash: Codec,
 {
 	fn call(
 		&self,
@@ -228,30 +392,87 @@ where
 			data: None,
 		})?;
 
-		// Make sure that gas_limit fits into 64 bits.
-		let gas_limit: u64 = gas_limit.try_into().map_err(|_| Error {
+		let gas_limit = limit_gas(gas_limit)?;
+
+		let has_v2 = api
+			.has_api_with::<RuntimeApiOf<Block, AccountId, Balance, Hash>, _>(&at, |v| v >= 2)
+			.map_err(runtime_error_into_rpc_err)?;
+		let exec_result = if has_v2 {
+			api.call(&at, origin, dest, value, gas_limit, input_data.to_vec())
+		} else {
+			#[allow(deprecated)] // old `call` without the debug message
+			api.call_before_version_2(&at, origin, dest, value, gas_limit, input_data.to_vec())
+				.map(Into::into)
+		};
+		let exec_result = exec_result.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(exec_result.into())
+	}
+
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, Hash>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcContractInstantiateResult<AccountId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let InstantiateRequest {
+			origin,
+			endowment,
+			gas_limit,
+			code,
+			data,
+			salt,
+		} = instantiate_request;
+
+		// Make sure that endowment fits into the balance type.
+		let endowment: Balance = endowment.try_into().map_err(|_| Error {
 			code: ErrorCode::InvalidParams,
-			message: format!("{:?} doesn't fit in 64 bit unsigned value", gas_limit),
+			message: format!("{:?} doesn't fit into the balance type", endowment),
 			data: None,
 		})?;
 
-		let max_gas_limit = 5 * GAS_PER_SECOND;
-		if gas_limit > max_gas_limit {
-			return Err(Error {
-				code: ErrorCode::InvalidParams,
-				message: format!(
-					"Requested gas limit is greater than maximum allowed: {} > {}",
-					gas_limit, max_gas_limit
-				),
-				data: None,
-			});
+		let gas_limit = limit_gas(gas_limit)?;
+		if !api
+			.has_api_with::<RuntimeApiOf<Block, AccountId, Balance, Hash>, _>(&at, |v| v >= 2)
+			.map_err(runtime_error_into_rpc_err)?
+		{
+			return Err(api_unsupported("contracts_instantiate"))
 		}
 
-		let exec_result = api
-			.call(&at, origin, dest, value, gas_limit, input_data.to_vec())
+		let result = api
+			.instantiate(&at, origin, endowment, gas_limit, code.into(), data.to_vec(), salt.to_vec())
 			.map_err(runtime_error_into_rpc_err)?;
 
-		Ok(exec_result.into())
+		Ok(result.into())
+	}
+
+	fn upload_code(
+		&self,
+		code: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcCodeUploadResult<Hash>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		if !api
+			.has_api_with::<RuntimeApiOf<Block, AccountId, Balance, Hash>, _>(&at, |v| v >= 2)
+			.map_err(runtime_error_into_rpc_err)?
+		{
+			return Err(api_unsupported("contracts_uploadCode"))
+		}
+
+		let result = api
+			.upload_code(&at, code.to_vec())
+			.map_err(runtime_error_into_rpc_err)?
+			.map_err(CodeRejectedError)?;
+
+		Ok(result.into())
 	}
 
 	fn get_storage(
@@ -296,6 +517,38 @@ where
 	}
 }
 
+/// Make sure that `gas_limit` fits into 64 bits and doesn't block the RPC for too long.
+fn limit_gas(gas_limit: number::NumberOrHex) -> Result<u64> {
+	let gas_limit: u64 = gas_limit.try_into().map_err(|_| Error {
+		code: ErrorCode::InvalidParams,
+		message: format!("{:?} doesn't fit in 64 bit unsigned value", gas_limit),
+		data: None,
+	})?;
+
+	let max_gas_limit = 5 * GAS_PER_SECOND;
+	if gas_limit > max_gas_limit {
+		return Err(Error {
+			code: ErrorCode::InvalidParams,
+			message: format!(
+				"Requested gas limit is greater than maximum allowed: {} > {}",
+				gas_limit, max_gas_limit
+			),
+			data: None,
+		});
+	}
+
+	Ok(gas_limit)
+}
+
+/// Error returned by methods which need a newer `ContractsApi` than the runtime implements.
+fn api_unsupported(method: &str) -> Error {
+	Error {
+		code: ErrorCode::ServerError(API_UNSUPPORTED),
+		message: format!("`{}` requires version 2 of the runtime's `ContractsApi`", method),
+		data: None,
+	}
+}
+
 /// Converts a runtime trap into an RPC error.
 fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
 	Error {
@@ -336,4 +589,41 @@ mod tests {
 		test(r#"{"gasConsumed":5000,"debugMessage":"helpOk","result":{"Ok":{"flags":5,"data":"0x1234"}}}"#);
 		test(r#"{"gasConsumed":3400,"debugMessage":"helpErr","result":{"Err":"BadOrigin"}}"#);
 	}
+
+	#[test]
+	fn instantiate_request_should_serialize_deserialize_properly() {
+		type Req = InstantiateRequest<String, String>;
+		let req: Req = serde_json::from_str(r#"
+		{
+			"origin": "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL",
+			"endowment": "0x88",
+			"gasLimit": 42,
+			"code": { "existing": "0x1122" },
+			"data": "0x4299",
+			"salt": "0x9988"
+		}
+		"#).unwrap();
+
+		assert_eq!(req.origin, "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL");
+		assert_eq!(req.endowment.into_u256(), U256::from(0x88u64));
+		assert_eq!(req.gas_limit.into_u256(), U256::from(42u64));
+		assert_eq!(&*req.data, [0x42, 0x99].as_ref());
+		assert_eq!(&*req.salt, [0x99, 0x88].as_ref());
+		let code = match req.code {
+			Code::Existing(hash) => hash,
+			_ => panic!("json encoded an existing hash"),
+		};
+		assert_eq!(&code, "0x1122");
+	}
+
+	#[test]
+	fn instantiate_result_should_serialize_deserialize_properly() {
+		fn test(expected: &str) {
+			let res: RpcContractInstantiateResult<String> = serde_json::from_str(expected).unwrap();
+			let actual = serde_json::to_string(&res).unwrap();
+			assert_eq!(actual, expected);
+		}
+		test(r#"{"gasConsumed":5000,"gasRequired":8000,"debugMessage":"helpOk","events":["0x0001"],"result":{"Ok":{"flags":5,"data":"0x1234","accountId":"5CiPP"}}}"#);
+		test(r#"{"gasConsumed":3400,"gasRequired":3400,"debugMessage":"helpErr","events":[],"result":{"Err":"BadOrigin"}}"#);
+	}
 }
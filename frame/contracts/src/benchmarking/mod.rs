@@ -143,6 +143,7 @@ where
 			RawOrigin::Signed(caller.clone()).into(),
 			endowment,
 			Weight::max_value(),
+			None,
 			module.hash,
 			data,
 			salt,
@@ -347,7 +348,7 @@ benchmarks! {
 		let WasmModule { code, hash, .. } = WasmModule::<T>::sized(c * 1024);
 		let origin = RawOrigin::Signed(caller.clone());
 		let addr = Contracts::<T>::contract_address(&caller, &hash, &salt);
-	}: _(origin, endowment, Weight::max_value(), code, vec![], salt)
+	}: _(origin, endowment, Weight::max_value(), None, code, vec![], salt)
 	verify {
 		// endowment was removed from the caller
 		assert_eq!(T::Currency::free_balance(&caller), caller_funding::<T>() - endowment);
@@ -371,7 +372,7 @@ benchmarks! {
 		let origin = RawOrigin::Signed(caller.clone());
 		let addr = Contracts::<T>::contract_address(&caller, &hash, &salt);
 		Contracts::<T>::store_code_raw(code)?;
-	}: _(origin, endowment, Weight::max_value(), hash, vec![], salt)
+	}: _(origin, endowment, Weight::max_value(), None, hash, vec![], salt)
 	verify {
 		// endowment was removed from the caller
 		assert_eq!(T::Currency::free_balance(&caller), caller_funding::<T>() - endowment);
@@ -400,7 +401,7 @@ benchmarks! {
 		// trigger rent collection for worst case performance of call
 		System::<T>::set_block_number(instance.eviction_at()? - 5u32.into());
 		let before = T::Currency::free_balance(&instance.account_id);
-	}: _(origin, callee, value, Weight::max_value(), data)
+	}: _(origin, callee, value, Weight::max_value(), None, data)
 	verify {
 		// endowment and value transfered via call should be removed from the caller
 		assert_eq!(
@@ -459,7 +460,7 @@ benchmarks! {
 			"seal_caller", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_address {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -467,7 +468,7 @@ benchmarks! {
 			"seal_address", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_gas_left {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -475,7 +476,7 @@ benchmarks! {
 			"seal_gas_left", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_balance {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -483,7 +484,7 @@ benchmarks! {
 			"seal_balance", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_value_transferred {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -491,7 +492,7 @@ benchmarks! {
 			"seal_value_transferred", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_minimum_balance {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -499,7 +500,7 @@ benchmarks! {
 			"seal_minimum_balance", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_tombstone_deposit {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -507,7 +508,7 @@ benchmarks! {
 			"seal_tombstone_deposit", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_rent_allowance {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -515,7 +516,7 @@ benchmarks! {
 			"seal_rent_allowance", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_block_number {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -523,7 +524,7 @@ benchmarks! {
 			"seal_block_number", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_now {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -531,7 +532,7 @@ benchmarks! {
 			"seal_now", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_weight_to_fee {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -557,7 +558,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_gas {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -576,7 +577,7 @@ benchmarks! {
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
 
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// We cannot call seal_input multiple times. Therefore our weight determination is not
 	// as precise as with other APIs. Because this function can only be called once per
@@ -605,7 +606,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_input_per_kb {
 		let n in 0 .. code::max_pages::<T>() * 64;
@@ -635,7 +636,7 @@ benchmarks! {
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let data = vec![42u8; (n * 1024).min(buffer_size) as usize];
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), data)
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, data)
 
 	// The same argument as for `seal_input` is true here.
 	seal_return {
@@ -657,7 +658,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_return_per_kb {
 		let n in 0 .. code::max_pages::<T>() * 64;
@@ -679,7 +680,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// The same argument as for `seal_input` is true here.
 	seal_terminate {
@@ -711,7 +712,7 @@ benchmarks! {
 		let origin = RawOrigin::Signed(instance.caller.clone());
 		assert_eq!(T::Currency::total_balance(&beneficiary), 0u32.into());
 		assert_eq!(T::Currency::total_balance(&instance.account_id), Endow::max::<T>());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 	verify {
 		if r > 0 {
 			assert_eq!(T::Currency::total_balance(&instance.account_id), 0u32.into());
@@ -749,7 +750,7 @@ benchmarks! {
 		let origin = RawOrigin::Signed(instance.caller.clone());
 		assert_eq!(T::Currency::total_balance(&beneficiary), 0u32.into());
 		assert_eq!(T::Currency::total_balance(&instance.account_id), Endow::max::<T>());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 	verify {
 		assert_eq!(T::Currency::total_balance(&instance.account_id), 0u32.into());
 		assert_eq!(T::Currency::total_balance(&beneficiary), Endow::max::<T>());
@@ -826,7 +827,7 @@ benchmarks! {
 		System::<T>::set_block_number(System::<T>::block_number() + 1u32.into());
 
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 	verify {
 		if r > 0 {
 			tombstone.contract.alive_info()?;
@@ -917,7 +918,7 @@ benchmarks! {
 		System::<T>::set_block_number(System::<T>::block_number() + 1u32.into());
 
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 	verify {
 		tombstone.contract.alive_info()?;
 	}
@@ -954,7 +955,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Overhead of calling the function without any topic.
 	// We benchmark for the worst case (largest event).
@@ -978,7 +979,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Benchmark the overhead that topics generate.
 	// `t`: Number of topics
@@ -1015,7 +1016,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_set_rent_allowance {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -1043,7 +1044,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Only the overhead of calling the function itself with minimal arguments.
 	// The contract is a bit more complex because I needs to use different keys in order
@@ -1078,7 +1079,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_set_storage_per_kb {
 		let n in 0 .. T::MaxValueSize::get() / 1024;
@@ -1107,7 +1108,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Similar to seal_set_storage. However, we store all the keys that we are about to
 	// delete beforehand in order to prevent any optimizations that could occur when
@@ -1150,7 +1151,7 @@ benchmarks! {
 			.map_err(|_| "Failed to write to storage during setup.")?;
 		}
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// We make sure that all storage accesses are to unique keys.
 	seal_get_storage {
@@ -1195,7 +1196,7 @@ benchmarks! {
 			.map_err(|_| "Failed to write to storage during setup.")?;
 		}
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_get_storage_per_kb {
 		let n in 0 .. T::MaxValueSize::get() / 1024;
@@ -1238,7 +1239,7 @@ benchmarks! {
 		)
 		.map_err(|_| "Failed to write to storage during setup.")?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// We transfer to unique accounts.
 	seal_transfer {
@@ -1284,7 +1285,7 @@ benchmarks! {
 		for account in &accounts {
 			assert_eq!(T::Currency::total_balance(account), 0u32.into());
 		}
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 	verify {
 		for account in &accounts {
 			assert_eq!(T::Currency::total_balance(account), value);
@@ -1347,7 +1348,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_call_per_code_transfer_input_output_kb {
 		let c in 0 .. T::MaxCodeSize::get() / 1024;
@@ -1432,7 +1433,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// We assume that every instantiate sends at least the subsistence amount.
 	seal_instantiate {
@@ -1538,7 +1539,7 @@ benchmarks! {
 				return Err("Expected that contract does not exist at this point.");
 			}
 		}
-	}: call(origin, callee, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, callee, 0u32.into(), Weight::max_value(), None, vec![])
 	verify {
 		for addr in &addresses {
 			ContractInfoOf::<T>::get(&addr).and_then(|c| c.get_alive())
@@ -1663,7 +1664,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Only the overhead of calling the function itself with minimal arguments.
 	seal_hash_sha2_256 {
@@ -1672,7 +1673,7 @@ benchmarks! {
 			"seal_hash_sha2_256", r * API_BENCHMARK_BATCH_SIZE, 0,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// `n`: Input to hash in kilobytes
 	seal_hash_sha2_256_per_kb {
@@ -1681,7 +1682,7 @@ benchmarks! {
 			"seal_hash_sha2_256", API_BENCHMARK_BATCH_SIZE, n * 1024,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Only the overhead of calling the function itself with minimal arguments.
 	seal_hash_keccak_256 {
@@ -1690,7 +1691,7 @@ benchmarks! {
 			"seal_hash_keccak_256", r * API_BENCHMARK_BATCH_SIZE, 0,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// `n`: Input to hash in kilobytes
 	seal_hash_keccak_256_per_kb {
@@ -1699,7 +1700,7 @@ benchmarks! {
 			"seal_hash_keccak_256", API_BENCHMARK_BATCH_SIZE, n * 1024,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Only the overhead of calling the function itself with minimal arguments.
 	seal_hash_blake2_256 {
@@ -1708,7 +1709,7 @@ benchmarks! {
 			"seal_hash_blake2_256", r * API_BENCHMARK_BATCH_SIZE, 0,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// `n`: Input to hash in kilobytes
 	seal_hash_blake2_256_per_kb {
@@ -1717,7 +1718,7 @@ benchmarks! {
 			"seal_hash_blake2_256", API_BENCHMARK_BATCH_SIZE, n * 1024,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Only the overhead of calling the function itself with minimal arguments.
 	seal_hash_blake2_128 {
@@ -1726,7 +1727,7 @@ benchmarks! {
 			"seal_hash_blake2_128", r * API_BENCHMARK_BATCH_SIZE, 0,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// `n`: Input to hash in kilobytes
 	seal_hash_blake2_128_per_kb {
@@ -1735,7 +1736,7 @@ benchmarks! {
 			"seal_hash_blake2_128", API_BENCHMARK_BATCH_SIZE, n * 1024,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// We make the assumption that pushing a constant and dropping a value takes roughly
 	// the same amount of time. We follow that `t.load` and `drop` both have the weight
@@ -29,6 +29,19 @@
 //! required for this endeavour are defined or re-exported in this module. There is an
 //! implementation on `()` which can be used to signal that no chain extension is available.
 //!
+//! # Composing Extensions
+//!
+//! Independently developed chain extensions can be combined by declaring a tuple of them as
+//! the pallet's chain extension. Every member of the tuple must implement
+//! [`RegisteredChainExtension`] which assigns it a unique 16 bit extension id. The upper 16 bits
+//! of the `func_id` passed to `seal_call_chain_extension` select the extension while the lower
+//! 16 bits are passed on to it as its own `func_id`. Using an extension id that is not part of
+//! the tuple results in [`NoChainExtension`](Error::NoChainExtension).
+//!
+//! A contract calling function `2` of the extension with id `1` therefore passes `0x0001_0002`
+//! as `func_id`. A single extension that is not wrapped into a tuple receives the `func_id`
+//! verbatim.
+//!
 //! # Security
 //!
 //! The chain author alone is responsible for the security of the chain extension.
@@ -126,6 +139,46 @@ impl<C: Config> ChainExtension<C> for () {
 	}
 }
 
+/// A [`ChainExtension`] that can be composed with other extensions into a tuple.
+///
+/// See the [module documentation](self#composing-extensions) for how calls are routed.
+pub trait RegisteredChainExtension<C: Config>: ChainExtension<C> {
+	/// The extension id that selects this extension.
+	///
+	/// It must be unique among all extensions that are part of the same tuple.
+	const ID: u16;
+}
+
+/// Routes every call to the member whose [`RegisteredChainExtension::ID`] is encoded in the
+/// upper 16 bits of `func_id`.
+#[impl_trait_for_tuples::impl_for_tuples(1, 10)]
+#[tuple_types_custom_trait_bound(RegisteredChainExtension<C>)]
+impl<C: Config> ChainExtension<C> for Tuple {
+	fn call<E>(func_id: u32, env: Environment<E, InitState>) -> Result<RetVal>
+	where
+		E: Ext<T = C>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		let ext_id = (func_id >> 16) as u16;
+		let func_id = func_id & 0x0000_FFFF;
+		for_tuples!( #(
+			if Tuple::ID == ext_id && Tuple::enabled() {
+				return Tuple::call(func_id, env);
+			}
+		)* );
+		Err(Error::<E::T>::NoChainExtension.into())
+	}
+
+	fn enabled() -> bool {
+		for_tuples!( #(
+			if Tuple::enabled() {
+				return true;
+			}
+		)* );
+		false
+	}
+}
+
 /// Determines the exit behaviour and return value of a chain extension.
 pub enum RetVal {
 	/// The chain extensions returns the supplied value to its calling contract.
@@ -0,0 +1,225 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A module responsible for charging and refunding storage deposits.
+//!
+//! It is only active when [`StoragePricing::Deposit`] is configured. In this case the origin
+//! of a call pays a deposit for every byte and item that is added to a contract's storage.
+//! The deposit is transferred to the contract account and cannot be spent by the contract.
+//! It is transferred back to the origin of the call that frees the storage again. Deposits
+//! that were taken from the contract's own balance when migrating to storage deposits are
+//! released back to the contract instead.
+//!
+//! Uploading code reserves a deposit from the uploader which is unreserved when the
+//! last contract using the code is removed.
+
+use crate::{
+	AliveContractInfo, BalanceOf, CodeDepositOf, CodeHash, Config, ContractInfo, ContractInfoOf,
+	Error, StoragePricing,
+};
+use sp_std::marker::PhantomData;
+use sp_runtime::{DispatchError, traits::{Bounded, Saturating, Zero}};
+use frame_support::{
+	dispatch::DispatchResult,
+	ensure,
+	traits::{Currency, ExistenceRequirement, Get, ReservableCurrency},
+};
+
+/// Returns whether storage deposits are used instead of rent.
+pub fn is_enabled<T: Config>() -> bool {
+	T::StoragePricing::get() == StoragePricing::Deposit
+}
+
+/// The deposit that a contract needs to hold for the storage it currently occupies.
+pub fn required<T: Config>(contract: &AliveContractInfo<T>) -> BalanceOf<T> {
+	T::DepositPerContract::get()
+		.saturating_add(
+			T::DepositPerStorageByte::get().saturating_mul(contract.storage_size.into())
+		)
+		.saturating_add(
+			T::DepositPerStorageItem::get().saturating_mul(contract.pair_count.into())
+		)
+}
+
+/// The deposit that is held by the contract at `account`.
+///
+/// This part of the balance cannot be transferred away by the contract. It is always zero
+/// when storage deposits are disabled.
+pub fn held_by<T: Config>(account: &T::AccountId) -> BalanceOf<T> {
+	<ContractInfoOf<T>>::get(account)
+		.and_then(|contract| contract.get_alive())
+		.map(|contract| contract.storage_deposit)
+		.unwrap_or_else(Zero::zero)
+}
+
+/// Tracks the storage deposits charged from and refunded to the origin of a call.
+///
+/// All contracts that are executed as part of the same call share one meter. The sum
+/// of all charges minus all refunds cannot exceed the limit passed to [`Self::new`].
+#[derive(Clone)]
+pub struct DepositMeter<T: Config> {
+	origin: T::AccountId,
+	limit: BalanceOf<T>,
+	charged: BalanceOf<T>,
+	refunded: BalanceOf<T>,
+}
+
+impl<T: Config> DepositMeter<T> {
+	/// Create a new meter that charges `origin` at most `limit`.
+	///
+	/// No limit is enforced when `limit` is `None`.
+	pub fn new(origin: T::AccountId, limit: Option<BalanceOf<T>>) -> Self {
+		DepositMeter {
+			origin,
+			limit: limit.unwrap_or_else(Bounded::max_value),
+			charged: Zero::zero(),
+			refunded: Zero::zero(),
+		}
+	}
+
+	/// The net amount that was charged from the origin so far.
+	pub fn consumed(&self) -> BalanceOf<T> {
+		self.charged.saturating_sub(self.refunded)
+	}
+
+	/// Adjust the deposit held by the contract at `account` to the storage it occupies.
+	///
+	/// The difference is either charged from or refunded to the origin. This is a no-op
+	/// when storage deposits are disabled.
+	pub fn settle(&mut self, account: &T::AccountId) -> DispatchResult {
+		if !is_enabled::<T>() {
+			return Ok(());
+		}
+		let mut contract = match <ContractInfoOf<T>>::get(account) {
+			Some(ContractInfo::Alive(contract)) => contract,
+			_ => return Err(Error::<T>::NotCallable.into()),
+		};
+		let required = required::<T>(&contract);
+		if required > contract.storage_deposit {
+			self.charge(account, required - contract.storage_deposit)?;
+		} else if required < contract.storage_deposit {
+			// Only what callers paid is refunded. The remainder is released from the
+			// contract's own deposit and simply stays with the contract.
+			let freed = contract.storage_deposit - required;
+			let paid = contract.storage_deposit.saturating_sub(contract.own_storage_deposit);
+			let refund = freed.min(paid);
+			contract.own_storage_deposit = contract.own_storage_deposit
+				.saturating_sub(freed - refund);
+			self.refund(account, refund)?;
+		} else {
+			return Ok(());
+		}
+		contract.storage_deposit = required;
+		<ContractInfoOf<T>>::insert(account, ContractInfo::Alive(contract));
+		Ok(())
+	}
+
+	/// Refund the whole deposit held by the contract at `account` to the origin, except for the
+	/// contract's own deposit which stays with the contract.
+	///
+	/// Used when a contract is removed and therefore frees all of its storage.
+	pub fn release(&mut self, account: &T::AccountId) -> DispatchResult {
+		if let Some(ContractInfo::Alive(mut contract)) = <ContractInfoOf<T>>::get(account) {
+			if !contract.storage_deposit.is_zero() {
+				self.refund(
+					account,
+					contract.storage_deposit.saturating_sub(contract.own_storage_deposit),
+				)?;
+				contract.storage_deposit = Zero::zero();
+				contract.own_storage_deposit = Zero::zero();
+				<ContractInfoOf<T>>::insert(account, ContractInfo::Alive(contract));
+			}
+		}
+		Ok(())
+	}
+
+	fn charge(&mut self, account: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		let charged = self.charged.saturating_add(amount);
+		ensure!(
+			charged.saturating_sub(self.refunded) <= self.limit,
+			Error::<T>::StorageDepositLimitExhausted,
+		);
+		T::Currency::transfer(&self.origin, account, amount, ExistenceRequirement::KeepAlive)
+			.map_err(|_| Error::<T>::StorageDepositNotEnoughFunds)?;
+		self.charged = charged;
+		Ok(())
+	}
+
+	fn refund(&mut self, account: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		T::Currency::transfer(account, &self.origin, amount, ExistenceRequirement::AllowDeath)
+			.map_err(|_| Error::<T>::TransferFailed)?;
+		self.refunded = self.refunded.saturating_add(amount);
+		Ok(())
+	}
+}
+
+/// Handles the deposit that is reserved from the uploader of a code.
+pub struct CodeDeposit<T>(PhantomData<T>);
+
+impl<T: Config> CodeDeposit<T> {
+	/// The deposit for storing a code with the given instrumented and pristine size.
+	///
+	/// Both versions of the code are stored in their own storage item.
+	pub fn amount(code_len: u32) -> BalanceOf<T> {
+		T::DepositPerStorageByte::get()
+			.saturating_mul(code_len.into())
+			.saturating_add(T::DepositPerStorageItem::get().saturating_mul(2u32.into()))
+	}
+
+	/// Reserve the deposit for the code at `code_hash` from `uploader`.
+	///
+	/// Nothing is reserved when storage deposits are disabled or the code is already stored.
+	/// `code_len` is the accumulated size of the instrumented and the pristine code.
+	///
+	/// Returns the reserved amount which must be passed to [`Self::finalize`] once it is
+	/// known whether the code was actually stored.
+	pub fn reserve(
+		uploader: &T::AccountId,
+		code_hash: &CodeHash<T>,
+		code_len: u32,
+	) -> Result<Option<BalanceOf<T>>, DispatchError> {
+		if !is_enabled::<T>() || <crate::CodeStorage<T>>::contains_key(code_hash) {
+			return Ok(None);
+		}
+		let amount = Self::amount(code_len);
+		T::Currency::reserve(uploader, amount)
+			.map_err(|_| Error::<T>::StorageDepositNotEnoughFunds)?;
+		Ok(Some(amount))
+	}
+
+	/// Record `uploader` as owner of the code if it was stored or unreserve the deposit otherwise.
+	pub fn finalize(
+		uploader: T::AccountId,
+		code_hash: CodeHash<T>,
+		reserved: Option<BalanceOf<T>>,
+	) {
+		if let Some(amount) = reserved {
+			if <crate::CodeStorage<T>>::contains_key(&code_hash) {
+				<CodeDepositOf<T>>::insert(code_hash, (uploader, amount));
+			} else {
+				T::Currency::unreserve(&uploader, amount);
+			}
+		}
+	}
+
+	/// Unreserve the deposit of a code that was removed from storage.
+	pub fn refund(code_hash: &CodeHash<T>) {
+		if let Some((owner, amount)) = <CodeDepositOf<T>>::take(code_hash) {
+			T::Currency::unreserve(&owner, amount);
+		}
+	}
+}
@@ -18,12 +18,13 @@
 use crate::{
 	CodeHash, Event, Config, Module as Contracts,
 	TrieId, BalanceOf, ContractInfo, gas::GasMeter, rent::Rent, storage::{self, Storage},
-	Error, ContractInfoOf, Schedule,
+	Error, ContractInfoOf, Schedule, deposit::{self, DepositMeter},
 };
 use sp_core::crypto::UncheckedFrom;
 use sp_std::{
 	prelude::*,
 	marker::PhantomData,
+	cell::RefCell,
 };
 use sp_runtime::traits::{Bounded, Zero, Convert, Saturating};
 use frame_support::{
@@ -198,6 +199,14 @@ pub trait Ext: sealing::Sealed {
 
 	/// Get a reference to the schedule used by the current call.
 	fn schedule(&self) -> &Schedule<Self::T>;
+
+	/// Append a line to the debug buffer.
+	///
+	/// This is a no-op when debug message recording is disabled which is always the case
+	/// when the code is executing on-chain.
+	///
+	/// Returns `true` if the message was recorded.
+	fn append_debug_buffer(&mut self, msg: &str) -> bool;
 }
 
 /// Describes the different functions that can be exported by an [`Executable`].
@@ -283,6 +292,8 @@ pub struct ExecutionContext<'a, T: Config + 'a, E> {
 	pub schedule: &'a Schedule<T>,
 	pub timestamp: MomentOf<T>,
 	pub block_number: T::BlockNumber,
+	pub debug_message: Option<&'a RefCell<Vec<u8>>>,
+	pub deposit_meter: Option<&'a RefCell<DepositMeter<T>>>,
 	_phantom: PhantomData<E>,
 }
 
@@ -305,10 +316,31 @@ where
 			schedule,
 			timestamp: T::Time::now(),
 			block_number: <frame_system::Module<T>>::block_number(),
+			debug_message: None,
+			deposit_meter: None,
 			_phantom: Default::default(),
 		}
 	}
 
+	/// Record the output of all `seal_println` calls of this and all nested executions
+	/// into `debug_message`.
+	///
+	/// This should only be used for off-chain executions (e.g. RPC calls) because
+	/// the collected message is not bounded in size.
+	pub fn with_debug_message(mut self, debug_message: &'a RefCell<Vec<u8>>) -> Self {
+		self.debug_message = Some(debug_message);
+		self
+	}
+
+	/// Charge and refund the storage deposits of this and all nested executions
+	/// using `deposit_meter`.
+	///
+	/// Without a meter no deposits are collected even if they are enabled.
+	pub fn with_deposit_meter(mut self, deposit_meter: &'a RefCell<DepositMeter<T>>) -> Self {
+		self.deposit_meter = Some(deposit_meter);
+		self
+	}
+
 	fn nested<'b, 'c: 'b>(&'c self, dest: T::AccountId, trie_id: TrieId)
 		-> ExecutionContext<'b, T, E>
 	{
@@ -320,6 +352,8 @@ where
 			schedule: self.schedule,
 			timestamp: self.timestamp.clone(),
 			block_number: self.block_number.clone(),
+			debug_message: self.debug_message,
+			deposit_meter: self.deposit_meter,
 			_phantom: Default::default(),
 		}
 	}
@@ -449,6 +483,10 @@ where
 				Rent::<T, E>::charge(&dest, contract, occupied_storage)?
 					.ok_or(Error::<T>::NewContractNotFunded)?;
 
+				// Collect the deposit for the contract itself and the storage that was
+				// added by the constructor.
+				nested.settle_deposit(&dest)?;
+
 				// Deposit an instantiation event.
 				deposit_event::<T>(vec![], Event::Instantiated(caller.clone(), dest.clone()));
 
@@ -488,14 +526,29 @@ where
 		where F: FnOnce(&mut ExecutionContext<T, E>) -> ExecResult
 	{
 		use frame_support::storage::TransactionOutcome::*;
+		// The deposits charged by a rolled back execution are refunded by the rollback.
+		// The meter needs to forget about them, too.
+		let deposit_snapshot = self.deposit_meter.map(|meter| meter.borrow().clone());
 		let mut nested = self.nested(dest, trie_id);
-		frame_support::storage::with_transaction(|| {
+		let output = frame_support::storage::with_transaction(|| {
 			let output = func(&mut nested);
 			match output {
 				Ok(ref rv) if !rv.flags.contains(ReturnFlags::REVERT) => Commit(output),
 				_ => Rollback(output),
 			}
-		})
+		});
+		match output {
+			Ok(ref rv) if !rv.flags.contains(ReturnFlags::REVERT) => (),
+			_ => if let (Some(meter), Some(snapshot)) = (self.deposit_meter, deposit_snapshot) {
+				*meter.borrow_mut() = snapshot;
+			},
+		}
+		output
+	}
+
+	/// Charge or refund the storage deposit of `account` according to its current storage.
+	fn settle_deposit(&self, account: &T::AccountId) -> DispatchResult {
+		self.deposit_meter.map_or(Ok(()), |meter| meter.borrow_mut().settle(account))
 	}
 
 	/// Returns whether a contract, identified by address, is currently live in the execution
@@ -548,9 +601,11 @@ where
 	let existence_requirement = match (cause, origin) {
 		(Terminate, _) => ExistenceRequirement::AllowDeath,
 		(_, Contract) => {
+			// The storage deposit held by a contract cannot be transferred away.
 			ensure!(
 				T::Currency::total_balance(transactor).saturating_sub(value) >=
-					Contracts::<T>::subsistence_threshold(),
+					Contracts::<T>::subsistence_threshold()
+						.saturating_add(deposit::held_by::<T>(transactor)),
 				Error::<T>::BelowSubsistenceThreshold,
 			);
 			ExistenceRequirement::KeepAlive
@@ -614,7 +669,8 @@ where
 		// the contract cannot be absent in storage;
 		// write cannot return `None`;
 		// qed
-		Storage::<T>::write(&self.ctx.self_account, trie_id, &key, value)
+		Storage::<T>::write(&self.ctx.self_account, trie_id, &key, value)?;
+		self.ctx.settle_deposit(&self.ctx.self_account)
 	}
 
 	fn instantiate(
@@ -652,12 +708,17 @@ where
 		beneficiary: &AccountIdOf<Self::T>,
 	) -> Result<u32, (DispatchError, u32)> {
 		let self_id = self.ctx.self_account.clone();
-		let value = T::Currency::free_balance(&self_id);
 		if let Some(caller_ctx) = self.ctx.caller {
 			if caller_ctx.is_live(&self_id) {
 				return Err((Error::<T>::ReentranceDenied.into(), 0));
 			}
 		}
+		// All storage of the contract is freed. The deposit goes back to the origin
+		// and only the remaining balance is sent to the beneficiary.
+		if let Some(meter) = self.ctx.deposit_meter {
+			meter.borrow_mut().release(&self_id).map_err(|e| (e, 0))?;
+		}
+		let value = T::Currency::free_balance(&self_id);
 		transfer::<T>(
 			TransferCause::Terminate,
 			TransactorKind::Contract,
@@ -791,6 +852,17 @@ where
 	fn schedule(&self) -> &Schedule<Self::T> {
 		&self.ctx.schedule
 	}
+
+	fn append_debug_buffer(&mut self, msg: &str) -> bool {
+		if let Some(buffer) = self.ctx.debug_message {
+			let mut buffer = buffer.borrow_mut();
+			buffer.extend(msg.as_bytes());
+			buffer.push(b'\n');
+			true
+		} else {
+			false
+		}
+	}
 }
 
 fn deposit_event<T: Config>(
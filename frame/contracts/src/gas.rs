@@ -83,6 +83,8 @@ pub struct GasMeter<T: Config> {
 	gas_limit: Weight,
 	/// Amount of gas left from initial gas limit. Can reach zero.
 	gas_left: Weight,
+	/// The lowest value `gas_left` ever had. Used to determine the peak gas usage.
+	gas_left_lowest: Weight,
 	_phantom: PhantomData<T>,
 	#[cfg(test)]
 	tokens: Vec<ErasedToken>,
@@ -96,6 +98,7 @@ where
 		GasMeter {
 			gas_limit,
 			gas_left: gas_limit,
+			gas_left_lowest: gas_limit,
 			_phantom: PhantomData,
 			#[cfg(test)]
 			tokens: Vec::new(),
@@ -135,6 +138,7 @@ where
 
 		// We always consume the gas even if there is not enough gas.
 		self.gas_left = new_value.unwrap_or_else(Zero::zero);
+		self.gas_left_lowest = self.gas_left_lowest.min(self.gas_left);
 
 		match new_value {
 			Some(_) => Ok(ChargedAmount(amount)),
@@ -187,6 +191,7 @@ where
 
 			let r = f(Some(&mut nested));
 
+			self.gas_left_lowest = self.gas_left_lowest.min(self.gas_left + nested.gas_left_lowest);
 			self.gas_left = self.gas_left + nested.gas_left;
 
 			r
@@ -198,6 +203,14 @@ where
 		self.gas_limit - self.gas_left
 	}
 
+	/// Returns the peak amount of gas that was used at any point in time.
+	///
+	/// This is the minimum gas limit with which the same execution would not run out of gas.
+	/// It is higher than [`Self::gas_spent`] when gas was refunded after being charged.
+	pub fn gas_required(&self) -> Weight {
+		self.gas_limit - self.gas_left_lowest
+	}
+
 	/// Returns how much gas left from the initial budget.
 	pub fn gas_left(&self) -> Weight {
 		self.gas_left
@@ -353,4 +366,24 @@ mod tests {
 		let mut gas_meter = GasMeter::<Test>::new(25);
 		assert!(!gas_meter.charge(&(), SimpleToken(25)).is_err());
 	}
+
+	// Refunded gas must not lower the amount of gas that is required for an execution.
+	#[test]
+	fn gas_required_tracks_peak_usage() {
+		let mut gas_meter = GasMeter::<Test>::new(1000);
+		let charged = gas_meter.charge(&(), SimpleToken(300)).unwrap();
+		gas_meter.refund(charged);
+		assert!(!gas_meter.charge(&(), SimpleToken(100)).is_err());
+		gas_meter.with_nested(500, |nested| {
+			assert!(!nested.unwrap().charge(&(), SimpleToken(250)).is_err());
+		});
+
+		assert_eq!(gas_meter.gas_spent(), 350);
+		assert_eq!(gas_meter.gas_required(), 350);
+
+		let charged = gas_meter.charge(&(), SimpleToken(200)).unwrap();
+		gas_meter.refund(charged);
+		assert_eq!(gas_meter.gas_spent(), 350);
+		assert_eq!(gas_meter.gas_required(), 550);
+	}
 }
@@ -49,6 +49,14 @@
 //! then all of B's calls are reverted. Assuming correct error handling by contract A, A's other calls and state
 //! changes still persist.
 //!
+//! ### Storage Pricing
+//!
+//! Contracts pay for the storage they occupy according to [`Config::StoragePricing`]. Either
+//! the contract pays rent per block and is evicted into a tombstone when it runs out of funds
+//! ([`StoragePricing::Rent`]), or the origin of a call pays a refundable deposit for the storage
+//! it adds ([`StoragePricing::Deposit`]). The amount of deposit a call may charge is limited by
+//! the `storage_deposit_limit` that is passed to every dispatchable.
+//!
 //! ### Notable Scenarios
 //!
 //! Contract call failures are not always cascading. When failures occur in a sub-call, they do not "bubble up",
@@ -88,6 +96,8 @@ mod storage;
 mod exec;
 mod wasm;
 mod rent;
+mod deposit;
+mod migration;
 mod benchmarking;
 mod schedule;
 
@@ -106,26 +116,29 @@ use crate::{
 	gas::GasMeter,
 	exec::{ExecutionContext, Executable},
 	rent::Rent,
+	deposit::{DepositMeter, CodeDeposit},
 	storage::{Storage, DeletedContract},
 	weights::WeightInfo,
 };
 use sp_core::crypto::UncheckedFrom;
-use sp_std::{prelude::*, marker::PhantomData, fmt::Debug};
+use sp_std::{prelude::*, marker::PhantomData, fmt::Debug, cell::RefCell};
 use codec::{Codec, Encode, Decode};
 use sp_runtime::{
 	traits::{
 		Hash, StaticLookup, MaybeSerializeDeserialize, Member, Convert, Saturating, Zero,
 	},
-	RuntimeDebug, Perbill,
+	RuntimeDebug, Perbill, DispatchError,
 };
 use frame_support::{
 	storage::child::ChildInfo,
-	traits::{OnUnbalanced, Currency, Get, Time, Randomness},
+	traits::{OnUnbalanced, Currency, ReservableCurrency, Get, Time, Randomness},
 	weights::{Weight, PostDispatchInfo, WithPostDispatchInfo},
 };
 use frame_system::Module as System;
 use pallet_contracts_primitives::{
 	RentProjectionResult, GetStorageResult, ContractAccessError, ContractExecResult,
+	ContractInstantiateResult, InstantiateReturnValue, Code, CodeUploadResult,
+	CodeUploadReturnValue, CodeRejected, ExecError,
 };
 
 pub type CodeHash<T> = <T as frame_system::Config>::Hash;
@@ -154,7 +167,7 @@ pub mod pallet {
 		type Randomness: Randomness<Self::Hash>;
 
 		/// The currency in which fees are paid and contract balances are held.
-		type Currency: Currency<Self::AccountId>;
+		type Currency: ReservableCurrency<Self::AccountId>;
 
 		/// The overarching event type.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
@@ -162,6 +175,17 @@ pub mod pallet {
 		/// Handler for rent payments.
 		type RentPayment: OnUnbalanced<NegativeImbalanceOf<Self>>;
 
+		/// Whether contracts pay rent or the callers pay storage deposits.
+		///
+		/// In both cases the costs are derived from [`Self::DepositPerContract`],
+		/// [`Self::DepositPerStorageByte`] and [`Self::DepositPerStorageItem`]. The rent related
+		/// parameters are ignored when [`StoragePricing::Deposit`] is selected.
+		///
+		/// Switching an existing chain to deposits is handled by the storage migration
+		/// that runs in `on_runtime_upgrade`.
+		#[pallet::constant]
+		type StoragePricing: Get<StoragePricing>;
+
 		/// Number of block delay an extrinsic claim surcharge has.
 		///
 		/// When claim surcharge is called by an extrinsic the rent is checked
@@ -256,6 +280,10 @@ pub mod pallet {
 		T::AccountId: UncheckedFrom<T::Hash>,
 		T::AccountId: AsRef<[u8]>,
 	{
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate::<T>()
+		}
+
 		fn on_initialize(_block: T::BlockNumber) -> Weight {
 			// We do not want to go above the block limit and rather avoid lazy deletion
 			// in that case. This should only happen on runtime upgrades.
@@ -297,19 +325,28 @@ pub mod pallet {
 		/// * If the account is a regular account, any value will be transferred.
 		/// * If no account exists and the call value is not less than `existential_deposit`,
 		/// a regular account will be created and any value will be transferred.
+		///
+		/// `storage_deposit_limit` is the maximum amount of storage deposit that can be charged
+		/// from the origin. `None` means that no limit is enforced. It is ignored when contracts
+		/// pay rent instead.
 		#[pallet::weight(T::WeightInfo::call(T::MaxCodeSize::get() / 1024).saturating_add(*gas_limit))]
 		pub fn call(
 			origin: OriginFor<T>,
 			dest: <T::Lookup as StaticLookup>::Source,
 			#[pallet::compact] value: BalanceOf<T>,
 			#[pallet::compact] gas_limit: Weight,
+			storage_deposit_limit: Option<BalanceOf<T>>,
 			data: Vec<u8>
 		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
 			let dest = T::Lookup::lookup(dest)?;
 			let mut gas_meter = GasMeter::new(gas_limit);
 			let schedule = <Module<T>>::current_schedule();
-			let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(origin, &schedule);
+			let deposit_meter = RefCell::new(
+				DepositMeter::new(origin.clone(), storage_deposit_limit)
+			);
+			let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(origin, &schedule)
+				.with_deposit_meter(&deposit_meter);
 			let (result, code_len) = match ctx.call(dest, value, &mut gas_meter, data) {
 				Ok((output, len)) => (Ok(output), len),
 				Err((err, len)) => (Err(err), len),
@@ -326,6 +363,9 @@ pub mod pallet {
 		///
 		/// * `endowment`: The balance to transfer from the `origin` to the newly created contract.
 		/// * `gas_limit`: The gas limit enforced when executing the constructor.
+		/// * `storage_deposit_limit`: The maximum amount of storage deposit that can be charged
+		///   from the `origin`. See [`Self::call`]. The deposit for the uploaded code is
+		///   reserved separately and is not subject to this limit.
 		/// * `code`: The contract code to deploy in raw bytes.
 		/// * `data`: The input data to pass to the contract constructor.
 		/// * `salt`: Used for the address derivation. See [`Self::contract_address`].
@@ -349,6 +389,7 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			#[pallet::compact] endowment: BalanceOf<T>,
 			#[pallet::compact] gas_limit: Weight,
+			storage_deposit_limit: Option<BalanceOf<T>>,
 			code: Vec<u8>,
 			data: Vec<u8>,
 			salt: Vec<u8>,
@@ -361,9 +402,20 @@ pub mod pallet {
 			let executable = PrefabWasmModule::from_code(code, &schedule)?;
 			let code_len = executable.code_len();
 			ensure!(code_len <= T::MaxCodeSize::get(), Error::<T>::CodeTooLarge);
-			let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(origin, &schedule);
+			let code_hash = *executable.code_hash();
+			let code_deposit = CodeDeposit::<T>::reserve(
+				&origin, &code_hash, executable.occupied_storage(),
+			)?;
+			let deposit_meter = RefCell::new(
+				DepositMeter::new(origin.clone(), storage_deposit_limit)
+			);
+			let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(
+				origin.clone(), &schedule,
+			)
+				.with_deposit_meter(&deposit_meter);
 			let result = ctx.instantiate(endowment, &mut gas_meter, executable, data, &salt)
 				.map(|(_address, output)| output);
+			CodeDeposit::<T>::finalize(origin, code_hash, code_deposit);
 			gas_meter.into_dispatch_result(
 				result,
 				T::WeightInfo::instantiate_with_code(code_len / 1024, salt.len() as u32 / 1024)
@@ -383,6 +435,7 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			#[pallet::compact] endowment: BalanceOf<T>,
 			#[pallet::compact] gas_limit: Weight,
+			storage_deposit_limit: Option<BalanceOf<T>>,
 			code_hash: CodeHash<T>,
 			data: Vec<u8>,
 			salt: Vec<u8>,
@@ -391,7 +444,11 @@ pub mod pallet {
 			let mut gas_meter = GasMeter::new(gas_limit);
 			let schedule = <Module<T>>::current_schedule();
 			let executable = PrefabWasmModule::from_storage(code_hash, &schedule, &mut gas_meter)?;
-			let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(origin, &schedule);
+			let deposit_meter = RefCell::new(
+				DepositMeter::new(origin.clone(), storage_deposit_limit)
+			);
+			let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(origin, &schedule)
+				.with_deposit_meter(&deposit_meter);
 			let code_len = executable.code_len();
 			let result = ctx.instantiate(endowment, &mut gas_meter, executable, data, &salt)
 				.map(|(_address, output)| output);
@@ -602,6 +659,10 @@ pub mod pallet {
 		StorageExhausted,
 		/// A contract with the same AccountId already exists.
 		DuplicateContract,
+		/// More storage was added than the `storage_deposit_limit` passed to the call allows.
+		StorageDepositLimitExhausted,
+		/// The origin does not have enough free balance to pay the storage deposit.
+		StorageDepositNotEnoughFunds,
 	}
 
 	/// Current cost schedule for contracts.
@@ -627,6 +688,20 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type ContractInfoOf<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, ContractInfo<T>>;
 
+	/// The uploader of a code and the deposit that was reserved from them.
+	///
+	/// Only populated when storage deposits are used. The deposit is unreserved when the code
+	/// is removed because no contract uses it anymore.
+	#[pallet::storage]
+	pub type CodeDepositOf<T: Config> =
+		StorageMap<_, Identity, CodeHash<T>, (T::AccountId, BalanceOf<T>)>;
+
+	/// Storage version of the pallet.
+	///
+	/// New networks start with the last version.
+	#[pallet::storage]
+	pub(super) type StorageVersion<T: Config> = StorageValue<_, Releases, ValueQuery>;
+
 	/// Evicted contracts that await child trie deletion.
 	///
 	/// Child trie deletion is a heavy operation depending on the amount of storage items
@@ -653,6 +728,7 @@ pub mod pallet {
 	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
 		fn build(&self) {
 			<CurrentSchedule<T>>::put(&self.current_schedule);
+			<StorageVersion<T>>::put(Releases::V2);
 		}
 	}
 }
@@ -676,15 +752,97 @@ where
 	) -> ContractExecResult {
 		let mut gas_meter = GasMeter::new(gas_limit);
 		let schedule = <Module<T>>::current_schedule();
-		let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(origin, &schedule);
+		let debug_message = RefCell::new(Vec::new());
+		let deposit_meter = RefCell::new(DepositMeter::new(origin.clone(), None));
+		let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(origin, &schedule)
+			.with_debug_message(&debug_message)
+			.with_deposit_meter(&deposit_meter);
 		let result = ctx.call(dest, value, &mut gas_meter, input_data);
 		let gas_consumed = gas_meter.gas_spent();
 		ContractExecResult {
 			exec_result: result.map(|r| r.0).map_err(|r| r.0),
 			gas_consumed,
+			debug_message: debug_message.into_inner(),
 		}
 	}
 
+	/// Instantiate a new contract.
+	///
+	/// This function is similar to [`Self::instantiate`] and [`Self::instantiate_with_code`]
+	/// but returns more information about the execution and is meant to be used by RPCs.
+	/// Uploaded code is not stored unless the instantiation succeeds.
+	///
+	/// # Note
+	///
+	/// All events in the current block are decoded in order to report the ones deposited
+	/// by this execution. Do not call this function from within an extrinsic.
+	pub fn bare_instantiate(
+		origin: T::AccountId,
+		endowment: BalanceOf<T>,
+		gas_limit: Weight,
+		code: Code<CodeHash<T>>,
+		data: Vec<u8>,
+		salt: Vec<u8>,
+	) -> ContractInstantiateResult<T::AccountId> {
+		let mut gas_meter = GasMeter::new(gas_limit);
+		let schedule = <Module<T>>::current_schedule();
+		let debug_message = RefCell::new(Vec::new());
+		let events_before = System::<T>::event_count() as usize;
+		let executable = match code {
+			Code::Upload(binary) => Self::prepare_code(binary, &schedule).map_err(|rejected| {
+				debug_message.borrow_mut().extend(rejected.reason);
+				rejected.error
+			}),
+			Code::Existing(hash) => PrefabWasmModule::from_storage(hash, &schedule, &mut gas_meter),
+		};
+		let deposit_meter = RefCell::new(DepositMeter::new(origin.clone(), None));
+		let exec_result = executable
+			.map_err(ExecError::from)
+			.and_then(|executable| {
+				let code_hash = *executable.code_hash();
+				let code_deposit = CodeDeposit::<T>::reserve(
+					&origin, &code_hash, executable.occupied_storage(),
+				)?;
+				let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(
+					origin.clone(), &schedule,
+				)
+					.with_debug_message(&debug_message)
+					.with_deposit_meter(&deposit_meter);
+				let result = ctx.instantiate(endowment, &mut gas_meter, executable, data, &salt);
+				CodeDeposit::<T>::finalize(origin, code_hash, code_deposit);
+				result
+			})
+			.map(|(account_id, result)| InstantiateReturnValue { result, account_id });
+		let events = System::<T>::events()
+			.into_iter()
+			.skip(events_before)
+			.map(|record| record.encode())
+			.collect();
+		ContractInstantiateResult {
+			exec_result,
+			gas_consumed: gas_meter.gas_spent(),
+			gas_required: gas_meter.gas_required(),
+			debug_message: debug_message.into_inner(),
+			events,
+		}
+	}
+
+	/// Check and instrument the supplied `code` without storing it.
+	///
+	/// This performs the same checks as [`Self::instantiate_with_code`] and therefore
+	/// allows to find out whether a code is accepted by the current schedule before
+	/// submitting it.
+	pub fn bare_upload_code(code: Vec<u8>) -> CodeUploadResult<CodeHash<T>> {
+		let schedule = <Module<T>>::current_schedule();
+		let original_code_len = code.len() as u32;
+		let executable = Self::prepare_code(code, &schedule)?;
+		Ok(CodeUploadReturnValue {
+			code_hash: *executable.code_hash(),
+			original_code_len,
+			instrumented_code_len: executable.code_len(),
+		})
+	}
+
 	/// Query storage of a specified contract under a specified key.
 	pub fn get_storage(address: T::AccountId, key: [u8; 32]) -> GetStorageResult {
 		let contract_info = ContractInfoOf::<T>::get(&address)
@@ -731,10 +889,38 @@ where
 	/// than the subsistence threshold in order to guarantee that a tombstone is created.
 	///
 	/// The only way to completely kill a contract without a tombstone is calling `seal_terminate`.
+	///
+	/// No tombstones are created when storage deposits are used. The threshold is just the
+	/// minimum balance in this case.
 	pub fn subsistence_threshold() -> BalanceOf<T> {
+		if deposit::is_enabled::<T>() {
+			return T::Currency::minimum_balance();
+		}
 		T::Currency::minimum_balance().saturating_add(T::TombstoneDeposit::get())
 	}
 
+	/// Check and instrument `code` in the same way `instantiate_with_code` does.
+	fn prepare_code(
+		code: Vec<u8>,
+		schedule: &Schedule<T>,
+	) -> Result<PrefabWasmModule<T>, CodeRejected> {
+		let reject = |error: DispatchError| {
+			let reason = match &error {
+				DispatchError::Other(reason) => reason.as_bytes().to_vec(),
+				_ => Vec::new(),
+			};
+			CodeRejected { error, reason }
+		};
+		if code.len() as u32 > T::MaxCodeSize::get() {
+			return Err(reject(Error::<T>::CodeTooLarge.into()));
+		}
+		let executable = PrefabWasmModule::from_code(code, schedule).map_err(reject)?;
+		if executable.code_len() > T::MaxCodeSize::get() {
+			return Err(reject(Error::<T>::CodeTooLarge.into()));
+		}
+		Ok(executable)
+	}
+
 	/// Store code for benchmarks which does not check nor instrument the code.
 	#[cfg(feature = "runtime-benchmarks")]
 	fn store_code_raw(code: Vec<u8>) -> frame_support::dispatch::DispatchResult {
@@ -753,6 +939,37 @@ where
 	}
 }
 
+/// Determines how contracts pay for the storage they occupy.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum StoragePricing {
+	/// Contracts pay rent for their storage every block.
+	///
+	/// A contract that cannot pay its rent anymore is evicted and leaves a tombstone behind.
+	Rent,
+	/// The origin of a call pays a deposit for every byte and item it adds to storage.
+	///
+	/// The deposit is held by the contract and refunded to the origin of the call that
+	/// frees the storage again. Contracts are never evicted.
+	Deposit,
+}
+
+// A value placed in storage that represents the current version of the contracts storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	/// Contract infos without a storage deposit.
+	V1,
+	/// Contract infos hold their storage deposit.
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
 /// Information for managing an account and its sub trie abstraction.
 /// This is the required info to cache for an account
 #[derive(Encode, Decode, RuntimeDebug)]
@@ -833,6 +1050,17 @@ pub struct RawAliveContractInfo<CodeHash, Balance, BlockNumber> {
 	///
 	/// A restored contract starts with a value of zero just like a new contract.
 	pub rent_payed: Balance,
+	/// The storage deposit held by this contract.
+	///
+	/// This part of the contract's balance cannot be transferred away. It is always zero
+	/// when contracts pay rent.
+	pub storage_deposit: Balance,
+	/// The part of `storage_deposit` that was taken from the contract's own balance by the
+	/// migration introducing storage deposits.
+	///
+	/// No caller ever paid it, so it is never refunded to one. Freeing storage only releases
+	/// this part back to the contract once everything paid by callers has been refunded.
+	pub own_storage_deposit: Balance,
 	/// Last block rent has been payed.
 	pub deduct_block: BlockNumber,
 	/// Last block child storage has been written.
@@ -0,0 +1,97 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the contracts pallet.
+
+use crate::{
+	AliveContractInfo, BalanceOf, CodeHash, Config, ContractInfo, ContractInfoOf, Releases,
+	StorageVersion, TombstoneContractInfo, TrieId, deposit,
+};
+use codec::Decode;
+use sp_runtime::traits::{Saturating, Zero};
+use frame_support::{traits::{Currency, Get}, weights::Weight};
+
+/// The layout of [`AliveContractInfo`] before `storage_deposit` was added.
+#[derive(Decode)]
+struct OldAliveContractInfo<T: Config> {
+	trie_id: TrieId,
+	storage_size: u32,
+	pair_count: u32,
+	code_hash: CodeHash<T>,
+	rent_allowance: BalanceOf<T>,
+	rent_payed: BalanceOf<T>,
+	deduct_block: T::BlockNumber,
+	last_write: Option<T::BlockNumber>,
+	_reserved: Option<()>,
+}
+
+#[derive(Decode)]
+enum OldContractInfo<T: Config> {
+	Alive(OldAliveContractInfo<T>),
+	Tombstone(TombstoneContractInfo<T>),
+}
+
+/// Run all migrations that are required by the currently stored [`Releases`].
+pub fn migrate<T: Config>() -> Weight {
+	let mut weight = T::DbWeight::get().reads(1);
+	if StorageVersion::<T>::get() == Releases::V1 {
+		weight = weight.saturating_add(add_storage_deposit::<T>());
+		StorageVersion::<T>::put(Releases::V2);
+		weight = weight.saturating_add(T::DbWeight::get().writes(1));
+	}
+	weight
+}
+
+/// Add the `storage_deposit` field to all alive contracts.
+///
+/// When rent is used the deposit is zero. Otherwise the deposit that the contract should
+/// hold for its current storage is taken from its own balance. A contract whose transferable
+/// balance does not cover the full deposit holds all of it as deposit.
+///
+/// The deposit is recorded as the contract's own, so that freeing the storage later on
+/// releases it back to the contract instead of refunding it to the caller.
+fn add_storage_deposit<T: Config>() -> Weight {
+	let mut migrated: Weight = 0;
+	<ContractInfoOf<T>>::translate::<OldContractInfo<T>, _>(|account, old| {
+		migrated = migrated.saturating_add(1);
+		let old = match old {
+			OldContractInfo::Alive(old) => old,
+			OldContractInfo::Tombstone(tombstone) => return Some(ContractInfo::Tombstone(tombstone)),
+		};
+		let mut contract = AliveContractInfo::<T> {
+			trie_id: old.trie_id,
+			storage_size: old.storage_size,
+			pair_count: old.pair_count,
+			code_hash: old.code_hash,
+			rent_allowance: old.rent_allowance,
+			rent_payed: old.rent_payed,
+			storage_deposit: Zero::zero(),
+			own_storage_deposit: Zero::zero(),
+			deduct_block: old.deduct_block,
+			last_write: old.last_write,
+			_reserved: old._reserved,
+		};
+		if deposit::is_enabled::<T>() {
+			let available = T::Currency::free_balance(&account)
+				.saturating_sub(T::Currency::minimum_balance());
+			contract.storage_deposit = deposit::required::<T>(&contract).min(available);
+			contract.own_storage_deposit = contract.storage_deposit;
+		}
+		Some(ContractInfo::Alive(contract))
+	});
+	T::DbWeight::get().reads_writes(migrated.saturating_mul(2), migrated)
+}
@@ -20,7 +20,7 @@
 use crate::{
 	AliveContractInfo, BalanceOf, ContractInfo, ContractInfoOf, Module, Event,
 	TombstoneContractInfo, Config, CodeHash, Error,
-	storage::Storage, wasm::PrefabWasmModule, exec::Executable,
+	storage::Storage, wasm::PrefabWasmModule, exec::Executable, deposit,
 };
 use sp_std::prelude::*;
 use sp_io::hashing::blake2_256;
@@ -151,6 +151,11 @@ where
 		contract: &AliveContractInfo<T>,
 		code_size: u32,
 	) -> Verdict<T> {
+		// Storage is paid for by deposits which are charged when storage is added.
+		if deposit::is_enabled::<T>() {
+			return Verdict::Exempt;
+		}
+
 		// How much block has passed since the last deduction for the contract.
 		let blocks_passed = {
 			// Calculate an effective block number, i.e. after adjusting for handicap.
@@ -381,6 +386,9 @@ where
 			None | Some(ContractInfo::Tombstone(_)) => return Err(IsTombstone),
 			Some(ContractInfo::Alive(contract)) => contract,
 		};
+		if deposit::is_enabled::<T>() {
+			return Ok(RentProjection::NoEviction);
+		}
 		let module = PrefabWasmModule::from_storage_noinstr(alive_contract_info.code_hash)
 			.map_err(|_| IsTombstone)?;
 		let code_size = module.occupied_storage();
@@ -179,6 +179,8 @@ where
 					<frame_system::Module<T>>::block_number().saturating_sub(1u32.into()),
 				rent_allowance: <BalanceOf<T>>::max_value(),
 				rent_payed: <BalanceOf<T>>::zero(),
+				storage_deposit: <BalanceOf<T>>::zero(),
+				own_storage_deposit: <BalanceOf<T>>::zero(),
 				pair_count: 0,
 				last_write: None,
 				_reserved: None,
@@ -17,22 +17,22 @@
 
 use crate::{
 	BalanceOf, ContractInfo, ContractInfoOf, Module,
-	RawAliveContractInfo, Config, Schedule,
-	Error, storage::Storage,
+	RawAliveContractInfo, Config, Schedule, StoragePricing, CodeDepositOf,
+	Error, Releases, StorageVersion, storage::Storage, migration,
 	chain_extension::{
 		Result as ExtensionResult, Environment, ChainExtension, Ext, SysConfig, RetVal,
-		UncheckedFrom, InitState, ReturnFlags,
+		UncheckedFrom, InitState, ReturnFlags, RegisteredChainExtension,
 	},
 	exec::{AccountIdOf, Executable}, wasm::PrefabWasmModule,
 	weights::WeightInfo,
 	wasm::ReturnCode as RuntimeReturnCode,
 };
 use assert_matches::assert_matches;
-use codec::Encode;
+use codec::{Encode, Decode};
 use sp_runtime::{
 	traits::{BlakeTwo256, Hash, IdentityLookup, Convert},
 	testing::{Header, H256},
-	AccountId32, Perbill,
+	AccountId32, Perbill, DispatchError,
 };
 use sp_io::hashing::blake2_256;
 use frame_support::{
@@ -41,10 +41,11 @@ use frame_support::{
 	traits::{Currency, ReservableCurrency, OnInitialize, GenesisBuild},
 	weights::{Weight, PostDispatchInfo, DispatchClass, constants::WEIGHT_PER_SECOND},
 	dispatch::DispatchErrorWithPostInfo,
-	storage::child,
+	storage::{child, unhashed},
 };
 use frame_system::{self as system, EventRecord, Phase};
 use pretty_assertions::assert_eq;
+use pallet_contracts_primitives::{Code, CodeRejected};
 
 use crate as pallet_contracts;
 
@@ -192,6 +193,36 @@ impl ChainExtension<Test> for TestExtension {
 	}
 }
 
+impl RegisteredChainExtension<Test> for TestExtension {
+	const ID: u16 = 0;
+}
+
+/// A second extension that reverts and returns the `func_id` it received.
+///
+/// It is only used to check that calls are routed to the right extension.
+pub struct RevertingExtension;
+
+impl ChainExtension<Test> for RevertingExtension {
+	fn call<E>(func_id: u32, _env: Environment<E, InitState>) -> ExtensionResult<RetVal>
+	where
+		E: Ext<T = Test>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		Ok(RetVal::Diverging {
+			flags: ReturnFlags::REVERT,
+			data: func_id.to_le_bytes().to_vec(),
+		})
+	}
+
+	fn enabled() -> bool {
+		TestExtension::enabled()
+	}
+}
+
+impl RegisteredChainExtension<Test> for RevertingExtension {
+	const ID: u16 = 1;
+}
+
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
 	pub BlockWeights: frame_system::limits::BlockWeights =
@@ -253,6 +284,7 @@ parameter_types! {
 	pub const DeletionQueueDepth: u32 = 1024;
 	pub const DeletionWeightLimit: Weight = 500_000_000_000;
 	pub const MaxCodeSize: u32 = 2 * 1024;
+	pub static Pricing: StoragePricing = StoragePricing::Rent;
 }
 
 parameter_types! {
@@ -271,6 +303,7 @@ impl Config for Test {
 	type Currency = Balances;
 	type Event = Event;
 	type RentPayment = ();
+	type StoragePricing = Pricing;
 	type SignedClaimHandicap = SignedClaimHandicap;
 	type TombstoneDeposit = TombstoneDeposit;
 	type DepositPerContract = DepositPerContract;
@@ -282,7 +315,7 @@ impl Config for Test {
 	type MaxValueSize = MaxValueSize;
 	type WeightPrice = Self;
 	type WeightInfo = ();
-	type ChainExtension = TestExtension;
+	type ChainExtension = (TestExtension, RevertingExtension);
 	type DeletionQueueDepth = DeletionQueueDepth;
 	type DeletionWeightLimit = DeletionWeightLimit;
 	type MaxCodeSize = MaxCodeSize;
@@ -297,11 +330,13 @@ const GAS_LIMIT: Weight = 10_000_000_000;
 
 pub struct ExtBuilder {
 	existential_deposit: u64,
+	storage_pricing: StoragePricing,
 }
 impl Default for ExtBuilder {
 	fn default() -> Self {
 		Self {
 			existential_deposit: 1,
+			storage_pricing: StoragePricing::Rent,
 		}
 	}
 }
@@ -310,8 +345,13 @@ impl ExtBuilder {
 		self.existential_deposit = existential_deposit;
 		self
 	}
+	pub fn storage_pricing(mut self, storage_pricing: StoragePricing) -> Self {
+		self.storage_pricing = storage_pricing;
+		self
+	}
 	pub fn set_associated_consts(&self) {
 		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
+		PRICING.with(|v| *v.borrow_mut() = self.storage_pricing);
 	}
 	pub fn build(self) -> sp_io::TestExternalities {
 		self.set_associated_consts();
@@ -357,7 +397,7 @@ fn calling_plain_account_fails() {
 		let base_cost = <<Test as Config>::WeightInfo as WeightInfo>::call(0);
 
 		assert_eq!(
-			Contracts::call(Origin::signed(ALICE), BOB, 0, GAS_LIMIT, Vec::new()),
+			Contracts::call(Origin::signed(ALICE), BOB, 0, GAS_LIMIT, None, Vec::new()),
 			Err(
 				DispatchErrorWithPostInfo {
 					error: Error::<Test>::NotCallable.into(),
@@ -391,6 +431,8 @@ fn account_removal_does_not_remove_storage() {
 				code_hash: H256::repeat_byte(1),
 				rent_allowance: 40,
 				rent_payed: 0,
+				storage_deposit: 0,
+				own_storage_deposit: 0,
 				last_write: None,
 				_reserved: None,
 			});
@@ -407,6 +449,8 @@ fn account_removal_does_not_remove_storage() {
 				code_hash: H256::repeat_byte(2),
 				rent_allowance: 40,
 				rent_payed: 0,
+				storage_deposit: 0,
+				own_storage_deposit: 0,
 				last_write: None,
 				_reserved: None,
 			});
@@ -464,6 +508,7 @@ fn instantiate_and_call_and_deposit_event() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -540,6 +585,7 @@ fn deposit_event_max_value_limit() {
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -552,6 +598,7 @@ fn deposit_event_max_value_limit() {
 				addr.clone(),
 				0,
 				GAS_LIMIT * 2, // we are copying a huge buffer,
+				None,
 				<Test as Config>::MaxValueSize::get().encode(),
 			));
 
@@ -562,6 +609,7 @@ fn deposit_event_max_value_limit() {
 					addr,
 					0,
 					GAS_LIMIT,
+					None,
 					(<Test as Config>::MaxValueSize::get() + 1).encode(),
 				),
 				Error::<Test>::ValueTooLarge,
@@ -584,6 +632,7 @@ fn run_out_of_gas() {
 				Origin::signed(ALICE),
 				100 * subsistence,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -598,6 +647,7 @@ fn run_out_of_gas() {
 					addr, // newly created account
 					0,
 					67_500_000,
+					None,
 					vec![],
 				),
 				Error::<Test>::OutOfGas,
@@ -632,6 +682,7 @@ fn storage_size() {
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				// rent_allowance
 				<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
@@ -656,6 +707,7 @@ fn storage_size() {
 				addr.clone(),
 				0,
 				GAS_LIMIT,
+				None,
 				call::set_storage_4_byte()
 			));
 			let bob_contract = ContractInfoOf::<Test>::get(&addr)
@@ -676,6 +728,7 @@ fn storage_size() {
 				addr.clone(),
 				0,
 				GAS_LIMIT,
+				None,
 				call::remove_storage_4_byte()
 			));
 			let bob_contract = ContractInfoOf::<Test>::get(&addr)
@@ -705,6 +758,7 @@ fn empty_kv_pairs() {
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -751,6 +805,7 @@ fn deduct_blocks() {
 				Origin::signed(ALICE),
 				endowment,
 				GAS_LIMIT,
+				None,
 				wasm,
 				allowance.encode(),
 				vec![],
@@ -780,7 +835,7 @@ fn deduct_blocks() {
 
 			// Trigger rent through call
 			assert_ok!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null())
+				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, None, call::null())
 			);
 
 			// Check result
@@ -797,7 +852,7 @@ fn deduct_blocks() {
 
 			// Trigger rent through call
 			assert_ok!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null())
+				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, None, call::null())
 			);
 
 			// Check result
@@ -811,7 +866,7 @@ fn deduct_blocks() {
 
 			// Second call on same block should have no effect on rent
 			assert_ok!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null())
+				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, None, call::null())
 			);
 			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
 			assert_eq!(contract.rent_allowance, allowance - rent0 - rent - rent_2);
@@ -860,6 +915,7 @@ fn claim_surcharge(blocks: u64, trigger_call: impl Fn(AccountIdOf<Test>) -> bool
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				<Test as pallet_balances::Config>::Balance::from(30_000u32).encode(), // rent allowance
 				vec![],
@@ -899,6 +955,7 @@ fn removals(trigger_call: impl Fn(AccountIdOf<Test>) -> bool) {
 				Origin::signed(ALICE),
 				70_000,
 				GAS_LIMIT,
+				None,
 				wasm.clone(),
 				<Test as pallet_balances::Config>::Balance::from(100_000u32).encode(), // rent allowance
 				vec![],
@@ -946,6 +1003,7 @@ fn removals(trigger_call: impl Fn(AccountIdOf<Test>) -> bool) {
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				wasm.clone(),
 				<Test as pallet_balances::Config>::Balance::from(70_000u32).encode(), // rent allowance
 				vec![],
@@ -1003,6 +1061,7 @@ fn removals(trigger_call: impl Fn(AccountIdOf<Test>) -> bool) {
 				Origin::signed(ALICE),
 				subsistence_threshold * 100,
 				GAS_LIMIT,
+				None,
 				wasm,
 				(subsistence_threshold * 100).encode(), // rent allowance
 				vec![],
@@ -1064,6 +1123,7 @@ fn call_removed_contract() {
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				// rent allowance
 				<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
@@ -1073,7 +1133,7 @@ fn call_removed_contract() {
 
 			// Calling contract should succeed.
 			assert_ok!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null())
+				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, None, call::null())
 			);
 
 			// Advance blocks
@@ -1081,7 +1141,7 @@ fn call_removed_contract() {
 
 			// Calling contract should deny access because rent cannot be paid.
 			assert_err_ignore_postinfo!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null()),
+				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, None, call::null()),
 				Error::<Test>::NotCallable
 			);
 			// No event is generated because the contract is not actually removed.
@@ -1089,7 +1149,7 @@ fn call_removed_contract() {
 
 			// Subsequent contract calls should also fail.
 			assert_err_ignore_postinfo!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null()),
+				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, None, call::null()),
 				Error::<Test>::NotCallable
 			);
 
@@ -1113,6 +1173,7 @@ fn default_rent_allowance_on_instantiate() {
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1138,7 +1199,7 @@ fn default_rent_allowance_on_instantiate() {
 
 			// Trigger rent through call
 			assert_ok!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null())
+				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, None, call::null())
 			);
 
 			// Check contract is still alive
@@ -1193,6 +1254,7 @@ fn restoration(
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				set_rent_wasm.clone(),
 				allowance.encode(),
 				vec![],
@@ -1254,6 +1316,7 @@ fn restoration(
 					Origin::signed(ALICE),
 					20_000,
 					GAS_LIMIT,
+					None,
 					set_rent_wasm,
 					allowance.encode(),
 					vec![1],
@@ -1301,6 +1364,7 @@ fn restoration(
 				assert_ok!(Contracts::call(
 					Origin::signed(ALICE),
 					addr_bob.clone(), 0, GAS_LIMIT,
+					None,
 					call::set_storage_4_byte())
 				);
 			}
@@ -1313,7 +1377,7 @@ fn restoration(
 			// calls `claim_surcharge`.
 			assert_err_ignore_postinfo!(
 				Contracts::call(
-					Origin::signed(ALICE), addr_bob.clone(), 0, GAS_LIMIT, call::null()
+					Origin::signed(ALICE), addr_bob.clone(), 0, GAS_LIMIT, None, call::null()
 				),
 				Error::<Test>::NotCallable
 			);
@@ -1336,6 +1400,7 @@ fn restoration(
 				Origin::signed(CHARLIE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				restoration_wasm,
 				vec![],
 				vec![],
@@ -1360,6 +1425,7 @@ fn restoration(
 					addr_django.clone(),
 					0,
 					GAS_LIMIT,
+					None,
 					set_rent_code_hash
 						.as_ref()
 						.iter()
@@ -1519,6 +1585,7 @@ fn storage_max_value_limit() {
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1532,6 +1599,7 @@ fn storage_max_value_limit() {
 				addr.clone(),
 				0,
 				GAS_LIMIT * 2, // we are copying a huge buffer
+				None,
 				<Test as Config>::MaxValueSize::get().encode(),
 			));
 
@@ -1542,6 +1610,7 @@ fn storage_max_value_limit() {
 					addr,
 					0,
 					GAS_LIMIT,
+					None,
 					(<Test as Config>::MaxValueSize::get() + 1).encode(),
 				),
 				Error::<Test>::ValueTooLarge,
@@ -1564,6 +1633,7 @@ fn deploy_and_call_other_contract() {
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				caller_wasm,
 				vec![],
 				vec![],
@@ -1572,6 +1642,7 @@ fn deploy_and_call_other_contract() {
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				callee_wasm,
 				0u32.to_le_bytes().encode(),
 				vec![42],
@@ -1584,6 +1655,7 @@ fn deploy_and_call_other_contract() {
 				Contracts::contract_address(&ALICE, &caller_code_hash, &[]),
 				0,
 				GAS_LIMIT,
+				None,
 				callee_code_hash.as_ref().to_vec(),
 			));
 		});
@@ -1603,6 +1675,7 @@ fn cannot_self_destruct_through_draning() {
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1623,6 +1696,7 @@ fn cannot_self_destruct_through_draning() {
 					addr,
 					0,
 					GAS_LIMIT,
+					None,
 					vec![],
 				)
 			);
@@ -1643,6 +1717,7 @@ fn cannot_self_destruct_while_live() {
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1663,6 +1738,7 @@ fn cannot_self_destruct_while_live() {
 					addr.clone(),
 					0,
 					GAS_LIMIT,
+					None,
 					vec![0],
 				),
 				Error::<Test>::ContractTrapped,
@@ -1691,6 +1767,7 @@ fn self_destruct_works() {
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1713,6 +1790,7 @@ fn self_destruct_works() {
 					addr.clone(),
 					0,
 					GAS_LIMIT,
+					None,
 					vec![],
 				),
 				Ok(_)
@@ -1773,6 +1851,7 @@ fn destroy_contract_and_transfer_funds() {
 				Origin::signed(ALICE),
 				200_000,
 				GAS_LIMIT,
+				None,
 				callee_wasm,
 				vec![],
 				vec![42]
@@ -1784,6 +1863,7 @@ fn destroy_contract_and_transfer_funds() {
 				Origin::signed(ALICE),
 				200_000,
 				GAS_LIMIT,
+				None,
 				caller_wasm,
 				callee_code_hash.as_ref().to_vec(),
 				vec![],
@@ -1805,6 +1885,7 @@ fn destroy_contract_and_transfer_funds() {
 				addr_bob,
 				0,
 				GAS_LIMIT,
+				None,
 				addr_charlie.encode(),
 			));
 
@@ -1828,6 +1909,7 @@ fn cannot_self_destruct_in_constructor() {
 					Origin::signed(ALICE),
 					100_000,
 					GAS_LIMIT,
+					None,
 					wasm,
 					vec![],
 					vec![],
@@ -1852,6 +1934,7 @@ fn crypto_hashes() {
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1904,6 +1987,7 @@ fn transfer_return_code() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1952,6 +2036,7 @@ fn call_return_code() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				caller_code,
 				vec![0],
 				vec![],
@@ -1975,6 +2060,7 @@ fn call_return_code() {
 				Origin::signed(CHARLIE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				callee_code,
 				vec![0],
 				vec![],
@@ -2046,6 +2132,7 @@ fn instantiate_return_code() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				callee_code,
 				vec![],
 				vec![],
@@ -2057,6 +2144,7 @@ fn instantiate_return_code() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				caller_code,
 				vec![],
 				vec![],
@@ -2135,6 +2223,7 @@ fn disabled_chain_extension_wont_deploy() {
 				Origin::signed(ALICE),
 				3 * subsistence,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2155,6 +2244,7 @@ fn disabled_chain_extension_errors_on_call() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2168,6 +2258,7 @@ fn disabled_chain_extension_errors_on_call() {
 				addr.clone(),
 				0,
 				GAS_LIMIT,
+				None,
 				vec![],
 			),
 			Error::<Test>::NoChainExtension,
@@ -2186,6 +2277,7 @@ fn chain_extension_works() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2244,6 +2336,62 @@ fn chain_extension_works() {
 	});
 }
 
+#[test]
+fn chain_extension_registry_routes_by_extension_id() {
+	let (code, hash) = compile_module::<Test>("chain_extension_registry").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = Module::<Test>::subsistence_threshold();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		assert_ok!(
+			Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				subsistence * 100,
+				GAS_LIMIT,
+				None,
+				code,
+				vec![],
+				vec![],
+			),
+		);
+		let addr = Contracts::contract_address(&ALICE, &hash, &[]);
+
+		// extension 0, function 0 = the pass through function of the test extension
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			0x0000_0000u32.to_le_bytes().to_vec(),
+		).exec_result.unwrap();
+		assert_eq!(TestExtension::last_seen_buffer(), vec![0, 0]);
+		assert_eq!(result.data, vec![0, 0]);
+
+		// extension 1 only receives the lower 16 bits as func_id
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			0x0001_0007u32.to_le_bytes().to_vec(),
+		).exec_result.unwrap();
+		assert_eq!(result.flags, ReturnFlags::REVERT);
+		assert_eq!(result.data, 7u32.to_le_bytes().to_vec());
+
+		// there is no extension with id 2
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			0x0002_0000u32.to_le_bytes().to_vec(),
+		);
+		assert_eq!(
+			result.exec_result.unwrap_err().error,
+			Error::<Test>::NoChainExtension.into(),
+		);
+	});
+}
+
 #[test]
 fn lazy_removal_works() {
 	let (code, hash) = compile_module::<Test>("self_destruct").unwrap();
@@ -2256,6 +2404,7 @@ fn lazy_removal_works() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2275,6 +2424,7 @@ fn lazy_removal_works() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
 		));
 
@@ -2316,6 +2466,7 @@ fn lazy_removal_partial_remove_works() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2342,6 +2493,7 @@ fn lazy_removal_partial_remove_works() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
 		));
 
@@ -2397,6 +2549,7 @@ fn lazy_removal_does_no_run_on_full_block() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2430,6 +2583,7 @@ fn lazy_removal_does_no_run_on_full_block() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
 		));
 
@@ -2481,6 +2635,7 @@ fn lazy_removal_does_not_use_all_weight() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2515,6 +2670,7 @@ fn lazy_removal_does_not_use_all_weight() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
 		));
 
@@ -2551,6 +2707,7 @@ fn deletion_queue_full() {
 				Origin::signed(ALICE),
 				subsistence * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2569,6 +2726,7 @@ fn deletion_queue_full() {
 				addr.clone(),
 				0,
 				GAS_LIMIT,
+				None,
 				vec![],
 			),
 			Error::<Test>::DeletionQueueFull,
@@ -2610,6 +2768,7 @@ fn not_deployed_if_endowment_too_low_for_first_rent() {
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				(BalanceOf::<Test>::from(first_rent) - BalanceOf::<Test>::from(1u32))
 					.encode(), // rent allowance
@@ -2631,6 +2790,7 @@ fn surcharge_reward_is_capped() {
 			Origin::signed(ALICE),
 			30_000,
 			GAS_LIMIT,
+			None,
 			wasm,
 			<BalanceOf<Test>>::from(10_000u32).encode(), // rent allowance
 			vec![],
@@ -2676,6 +2836,7 @@ fn refcounter() {
 			Origin::signed(ALICE),
 			subsistence * 100,
 			GAS_LIMIT,
+			None,
 			wasm.clone(),
 			vec![],
 			vec![0],
@@ -2684,6 +2845,7 @@ fn refcounter() {
 			Origin::signed(ALICE),
 			subsistence * 100,
 			GAS_LIMIT,
+			None,
 			wasm.clone(),
 			vec![],
 			vec![1],
@@ -2695,6 +2857,7 @@ fn refcounter() {
 			Origin::signed(ALICE),
 			subsistence * 100,
 			GAS_LIMIT,
+			None,
 			code_hash,
 			vec![],
 			vec![2],
@@ -2712,6 +2875,7 @@ fn refcounter() {
 			addr0,
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
 		));
 		assert_refcount!(code_hash, 2);
@@ -2750,6 +2914,7 @@ fn reinstrument_does_charge() {
 			Origin::signed(ALICE),
 			subsistence * 100,
 			GAS_LIMIT,
+			None,
 			wasm,
 			zero.clone(),
 			vec![],
@@ -2801,3 +2966,421 @@ fn reinstrument_does_charge() {
 		);
 	});
 }
+
+#[test]
+fn bare_instantiate_reports_result_events_and_debug_message() {
+	let (wasm, code_hash) = compile_module::<Test>("debug_message").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = Module::<Test>::subsistence_threshold();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		let events_before = System::events().len();
+
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			subsistence * 100,
+			GAS_LIMIT,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+		);
+
+		assert_eq!(result.exec_result.unwrap().account_id, addr);
+		assert!(result.gas_consumed > 0);
+		assert!(result.gas_required >= result.gas_consumed);
+		assert_eq!(result.debug_message, b"Hello World!\n".to_vec());
+		assert_eq!(result.events.len(), System::events().len() - events_before);
+		let last_event = EventRecord::<Event, H256>::decode(
+			&mut &result.events.last().unwrap()[..]
+		).unwrap();
+		assert_eq!(
+			last_event.event,
+			Event::pallet_contracts(crate::Event::Instantiated(ALICE, addr.clone())),
+		);
+
+		// The code is now on-chain and can be instantiated again with another salt.
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			subsistence * 100,
+			GAS_LIMIT,
+			Code::Existing(code_hash),
+			vec![],
+			vec![1],
+		);
+		assert_eq!(
+			result.exec_result.unwrap().account_id,
+			Contracts::contract_address(&ALICE, &code_hash, &[1]),
+		);
+
+		// The call reports the debug message, too.
+		let result = Contracts::bare_call(ALICE, addr, 0, GAS_LIMIT, vec![]);
+		assert!(result.exec_result.unwrap().is_success());
+		assert_eq!(result.debug_message, b"Hello World!\n".to_vec());
+	});
+}
+
+#[test]
+fn bare_instantiate_reports_rejected_code() {
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = Module::<Test>::subsistence_threshold();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			subsistence * 100,
+			GAS_LIMIT,
+			Code::Upload(vec![0, 1, 2, 3]),
+			vec![],
+			vec![],
+		);
+
+		assert!(result.exec_result.is_err());
+		assert_eq!(result.debug_message, b"Can't decode wasm code".to_vec());
+		assert!(result.events.is_empty());
+	});
+}
+
+#[test]
+fn bare_upload_code_checks_without_storing() {
+	let (wasm, code_hash) = compile_module::<Test>("debug_message").unwrap();
+
+	ExtBuilder::default().build().execute_with(|| {
+		let original_code_len = wasm.len() as u32;
+		let result = Contracts::bare_upload_code(wasm).unwrap();
+		assert_eq!(result.code_hash, code_hash);
+		assert_eq!(result.original_code_len, original_code_len);
+		assert!(result.instrumented_code_len > 0);
+		assert!(!crate::CodeStorage::<Test>::contains_key(&code_hash));
+
+		assert_eq!(
+			Contracts::bare_upload_code(vec![0, 1, 2, 3]),
+			Err(CodeRejected {
+				error: DispatchError::Other("Can't decode wasm code"),
+				reason: b"Can't decode wasm code".to_vec(),
+			}),
+		);
+	});
+}
+
+#[test]
+fn storage_deposit_is_charged_and_refunded() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_pricing(StoragePricing::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				30_000,
+				GAS_LIMIT,
+				None,
+				wasm,
+				<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+			let code_deposit = Balances::reserved_balance(&ALICE);
+			assert!(code_deposit > 0);
+			assert_eq!(CodeDepositOf::<Test>::get(code_hash), Some((ALICE, code_deposit)));
+
+			// The constructor stored one item of 4 bytes.
+			let deposit = DepositPerContract::get() +
+				4 * DepositPerStorageByte::get() +
+				DepositPerStorageItem::get();
+			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+			assert_eq!(contract.storage_deposit, deposit);
+			assert_eq!(Balances::free_balance(&addr), 30_000 + deposit);
+			assert_eq!(
+				Balances::free_balance(&ALICE),
+				1_000_000 - 30_000 - deposit - code_deposit,
+			);
+
+			// Adding another item is paid by the origin of the call.
+			let item_deposit = 4 * DepositPerStorageByte::get() + DepositPerStorageItem::get();
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				call::set_storage_4_byte(),
+			));
+			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+			assert_eq!(contract.storage_deposit, deposit + item_deposit);
+			assert_eq!(
+				Balances::free_balance(&ALICE),
+				1_000_000 - 30_000 - deposit - item_deposit - code_deposit,
+			);
+
+			// Removing it again refunds the deposit.
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				call::remove_storage_4_byte(),
+			));
+			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+			assert_eq!(contract.storage_deposit, deposit);
+			assert_eq!(
+				Balances::free_balance(&ALICE),
+				1_000_000 - 30_000 - deposit - code_deposit,
+			);
+		});
+}
+
+#[test]
+fn migration_takes_storage_deposit_from_contract() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_pricing(StoragePricing::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				30_000,
+				GAS_LIMIT,
+				None,
+				wasm,
+				<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				call::set_storage_4_byte(),
+			));
+			let item_deposit = 4 * DepositPerStorageByte::get() + DepositPerStorageItem::get();
+			let deposit = DepositPerContract::get() + 2 * item_deposit;
+
+			// Store the contract info in its layout from before storage deposits and give the
+			// deposit to the contract as if it had always owned it.
+			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+			let old = (
+				0u8,
+				contract.trie_id,
+				contract.storage_size,
+				contract.pair_count,
+				contract.code_hash,
+				contract.rent_allowance,
+				contract.rent_payed,
+				contract.deduct_block,
+				contract.last_write,
+				contract._reserved,
+			);
+			unhashed::put_raw(&ContractInfoOf::<Test>::hashed_key_for(&addr), &old.encode());
+			StorageVersion::<Test>::put(Releases::V1);
+			let alice_balance = Balances::free_balance(&ALICE);
+			let contract_balance = Balances::free_balance(&addr);
+
+			migration::migrate::<Test>();
+
+			assert_eq!(StorageVersion::<Test>::get(), Releases::V2);
+			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+			assert_eq!(contract.storage_deposit, deposit);
+			assert_eq!(contract.own_storage_deposit, deposit);
+
+			// Freeing the storage releases the deposit to the contract and not to the caller.
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				call::remove_storage_4_byte(),
+			));
+			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+			assert_eq!(contract.storage_deposit, deposit - item_deposit);
+			assert_eq!(contract.own_storage_deposit, deposit - item_deposit);
+			assert_eq!(Balances::free_balance(&ALICE), alice_balance);
+			assert_eq!(Balances::free_balance(&addr), contract_balance);
+
+			// Storage added after the migration is paid by and refunded to the caller.
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				call::set_storage_4_byte(),
+			));
+			assert_eq!(Balances::free_balance(&ALICE), alice_balance - item_deposit);
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				call::remove_storage_4_byte(),
+			));
+			assert_eq!(Balances::free_balance(&ALICE), alice_balance);
+			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+			assert_eq!(contract.own_storage_deposit, deposit - item_deposit);
+		});
+}
+
+#[test]
+fn storage_deposit_limit_is_enforced() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_pricing(StoragePricing::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+			// The constructor cannot store anything with a zero limit.
+			assert_err_ignore_postinfo!(
+				Contracts::instantiate_with_code(
+					Origin::signed(ALICE),
+					30_000,
+					GAS_LIMIT,
+					Some(0),
+					wasm.clone(),
+					<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
+					vec![],
+				),
+				Error::<Test>::StorageDepositLimitExhausted,
+			);
+			// The code deposit is not held when the instantiation failed.
+			assert_eq!(Balances::reserved_balance(&ALICE), 0);
+
+			assert_ok!(Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				30_000,
+				GAS_LIMIT,
+				None,
+				wasm,
+				<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+			let item_deposit = 4 * DepositPerStorageByte::get() + DepositPerStorageItem::get();
+
+			assert_err_ignore_postinfo!(
+				Contracts::call(
+					Origin::signed(ALICE),
+					addr.clone(),
+					0,
+					GAS_LIMIT,
+					Some(item_deposit - 1),
+					call::set_storage_4_byte(),
+				),
+				Error::<Test>::StorageDepositLimitExhausted,
+			);
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				Some(item_deposit),
+				call::set_storage_4_byte(),
+			));
+
+			// Refunds do not count against the limit.
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr,
+				0,
+				GAS_LIMIT,
+				Some(0),
+				call::remove_storage_4_byte(),
+			));
+		});
+}
+
+#[test]
+fn storage_deposit_cannot_be_transferred() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_pricing(StoragePricing::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				99,
+				GAS_LIMIT,
+				None,
+				wasm,
+				<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+			// The contract holds way more than 50 but only 49 of it are not part of the deposit.
+			assert_err_ignore_postinfo!(
+				Contracts::call(
+					Origin::signed(ALICE),
+					addr.clone(),
+					0,
+					GAS_LIMIT,
+					None,
+					call::transfer(&CHARLIE),
+				),
+				Error::<Test>::ContractTrapped,
+			);
+
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr.clone(),
+				1,
+				GAS_LIMIT,
+				None,
+				call::transfer(&CHARLIE),
+			));
+			assert_eq!(Balances::free_balance(&CHARLIE), 50);
+		});
+}
+
+#[test]
+fn terminate_refunds_storage_and_code_deposit() {
+	let (wasm, code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_pricing(StoragePricing::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				30_000,
+				GAS_LIMIT,
+				None,
+				wasm,
+				vec![],
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+			assert!(Balances::reserved_balance(&ALICE) > 0);
+			assert_eq!(Balances::free_balance(&addr), 30_000 + DepositPerContract::get());
+
+			// Terminating the only contract removes the code.
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				vec![],
+			));
+			assert_refcount!(code_hash, 0);
+
+			// The beneficiary only receives the balance that was not part of the deposit.
+			assert_eq!(Balances::free_balance(&DJANGO), 30_000);
+			assert_eq!(Balances::free_balance(&ALICE), 1_000_000 - 30_000);
+			assert_eq!(Balances::reserved_balance(&ALICE), 0);
+			assert_eq!(CodeDepositOf::<Test>::get(code_hash), None);
+		});
+}
@@ -31,6 +31,7 @@ use crate::{
 	CodeHash, CodeStorage, PristineCode, Schedule, Config, Error, Weight,
 	wasm::{prepare, PrefabWasmModule}, Module as Contracts, Event,
 	gas::{GasMeter, Token},
+	deposit::CodeDeposit,
 	weights::WeightInfo,
 };
 use sp_core::crypto::UncheckedFrom;
@@ -170,6 +171,7 @@ where
 	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>
 {
 	<PristineCode<T>>::remove(code_hash);
+	CodeDeposit::<T>::refund(&code_hash);
 	Contracts::<T>::deposit_event(Event::CodeRemoved(code_hash))
 }
 
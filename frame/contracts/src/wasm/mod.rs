@@ -295,6 +295,7 @@ mod tests {
 		// (topics, data)
 		events: Vec<(Vec<H256>, Vec<u8>)>,
 		schedule: Schedule<Test>,
+		debug_buffer: Vec<u8>,
 	}
 
 	impl Ext for MockExt {
@@ -435,6 +436,12 @@ mod tests {
 		fn schedule(&self) -> &Schedule<Self::T> {
 			&self.schedule
 		}
+
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			self.debug_buffer.extend(msg.as_bytes());
+			self.debug_buffer.push(b'\n');
+			true
+		}
 	}
 
 	impl Ext for &mut MockExt {
@@ -537,6 +544,9 @@ mod tests {
 		fn schedule(&self) -> &Schedule<Self::T> {
 			(**self).schedule()
 		}
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			(**self).append_debug_buffer(msg)
+		}
 	}
 
 	fn execute<E: Ext>(
@@ -18,7 +18,7 @@
 //! Environment definition of the wasm smart-contract runtime.
 
 use crate::{
-	HostFnWeights, Config, CodeHash, BalanceOf, Error,
+	HostFnWeights, Config, CodeHash, BalanceOf, Error, deposit,
 	exec::{Ext, StorageKey, TopicOf},
 	gas::{GasMeter, Token, ChargedAmount},
 	wasm::env_def::ConvertibleToWasm,
@@ -181,6 +181,9 @@ pub enum RuntimeToken {
 	SetStorage(u32),
 	/// Weight of calling `seal_clear_storage`.
 	ClearStorage,
+	/// Weight of settling the storage deposit after `seal_set_storage` or `seal_clear_storage`.
+	/// Settling transfers the deposit difference between the origin and the contract.
+	SettleDeposit,
 	/// Weight of calling `seal_get_storage` without output weight.
 	GetStorageBase,
 	/// Weight of an item received via `seal_get_storage` for the given size.
@@ -258,6 +261,7 @@ where
 			SetStorage(len) => s.set_storage
 				.saturating_add(s.set_storage_per_byte.saturating_mul(len.into())),
 			ClearStorage => s.clear_storage,
+			SettleDeposit => s.transfer,
 			GetStorageBase => s.get_storage,
 			GetStorageCopyOut(len) => s.get_storage_per_byte.saturating_mul(len.into()),
 			Transfer => s.transfer,
@@ -648,6 +652,9 @@ define_env!(Env, <E: Ext>,
 	// - Upon trying to set an empty storage entry (value length is 0).
 	seal_set_storage(ctx, key_ptr: u32, value_ptr: u32, value_len: u32) => {
 		ctx.charge_gas(RuntimeToken::SetStorage(value_len))?;
+		if deposit::is_enabled::<E::T>() {
+			ctx.charge_gas(RuntimeToken::SettleDeposit)?;
+		}
 		if value_len > ctx.ext.max_value_size() {
 			Err(Error::<E::T>::ValueTooLarge)?;
 		}
@@ -664,6 +671,9 @@ define_env!(Env, <E: Ext>,
 	// - `key_ptr`: pointer into the linear memory where the location to clear the value is placed.
 	seal_clear_storage(ctx, key_ptr: u32) => {
 		ctx.charge_gas(RuntimeToken::ClearStorage)?;
+		if deposit::is_enabled::<E::T>() {
+			ctx.charge_gas(RuntimeToken::SettleDeposit)?;
+		}
 		let mut key: StorageKey = [0; 32];
 		ctx.read_sandbox_memory_into_buf(key_ptr, &mut key)?;
 		ctx.ext.set_storage(key, None).map_err(Into::into)
@@ -1359,11 +1369,14 @@ define_env!(Env, <E: Ext>,
 
 	// Prints utf8 encoded string from the data buffer.
 	// Only available on `--dev` chains.
+	// When the contract is executed through an RPC the string is also returned as part of
+	// the debug message of the call.
 	// This function may be removed at any time, superseded by a more general contract debugging feature.
 	seal_println(ctx, str_ptr: u32, str_len: u32) => {
 		let data = ctx.read_sandbox_memory(str_ptr, str_len)?;
 		if let Ok(utf8) = core::str::from_utf8(&data) {
 			sp_runtime::print(utf8);
+			ctx.ext.append_debug_buffer(utf8);
 		}
 		Ok(())
 	},
@@ -25,7 +25,7 @@ use frame_support::{
 	traits::{Currency, Get, EnsureOrigin, OnInitialize, UnfilteredDispatchable, schedule::DispatchTime},
 };
 use frame_system::{RawOrigin, Module as System, self, EventRecord};
-use sp_runtime::traits::{Bounded, One};
+use sp_runtime::traits::{Bounded, Hash};
 
 use crate::Module as Democracy;
 
@@ -549,68 +549,6 @@ benchmarks! {
 
 	}: _(RawOrigin::Root)
 
-	note_preimage {
-		// Num of bytes in encoded proposal
-		let b in 0 .. MAX_BYTES;
-
-		let caller = funded_account::<T>("caller", 0);
-		let encoded_proposal = vec![1; b as usize];
-		whitelist_account!(caller);
-	}: _(RawOrigin::Signed(caller), encoded_proposal.clone())
-	verify {
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		match Preimages::<T>::get(proposal_hash) {
-			Some(PreimageStatus::Available { .. }) => (),
-			_ => return Err("preimage not available")
-		}
-	}
-
-	note_imminent_preimage {
-		// Num of bytes in encoded proposal
-		let b in 0 .. MAX_BYTES;
-
-		// d + 1 to include the one we are testing
-		let encoded_proposal = vec![1; b as usize];
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		let block_number = T::BlockNumber::one();
-		Preimages::<T>::insert(&proposal_hash, PreimageStatus::Missing(block_number));
-
-		let caller = funded_account::<T>("caller", 0);
-		let encoded_proposal = vec![1; b as usize];
-		whitelist_account!(caller);
-	}: _(RawOrigin::Signed(caller), encoded_proposal.clone())
-	verify {
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		match Preimages::<T>::get(proposal_hash) {
-			Some(PreimageStatus::Available { .. }) => (),
-			_ => return Err("preimage not available")
-		}
-	}
-
-	reap_preimage {
-		// Num of bytes in encoded proposal
-		let b in 0 .. MAX_BYTES;
-
-		let encoded_proposal = vec![1; b as usize];
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-
-		let submitter = funded_account::<T>("submitter", b);
-		Democracy::<T>::note_preimage(RawOrigin::Signed(submitter.clone()).into(), encoded_proposal.clone())?;
-
-		// We need to set this otherwise we get `Early` error.
-		let block_number = T::VotingPeriod::get() + T::EnactmentPeriod::get() + T::BlockNumber::one();
-		System::<T>::set_block_number(block_number.into());
-
-		assert!(Preimages::<T>::contains_key(proposal_hash));
-
-		let caller = funded_account::<T>("caller", 0);
-		whitelist_account!(caller);
-	}: _(RawOrigin::Signed(caller), proposal_hash.clone(), u32::max_value())
-	verify {
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		assert!(!Preimages::<T>::contains_key(proposal_hash));
-	}
-
 	// Test when unlock will remove locks
 	unlock_remove {
 		let r in 1 .. MAX_REFERENDUMS;
@@ -738,41 +676,25 @@ benchmarks! {
 
 	#[extra]
 	enact_proposal_execute {
-		// Num of bytes in encoded proposal
-		let b in 0 .. MAX_BYTES;
-
-		let proposer = funded_account::<T>("proposer", 0);
-		let raw_call = Call::note_preimage(vec![1; b as usize]);
-		let generic_call: T::Proposal = raw_call.into();
+		let generic_call: T::Proposal = Call::<T>::clear_public_proposals().into();
 		let encoded_proposal = generic_call.encode();
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		Democracy::<T>::note_preimage(RawOrigin::Signed(proposer).into(), encoded_proposal)?;
-
-		match Preimages::<T>::get(proposal_hash) {
-			Some(PreimageStatus::Available { .. }) => (),
-			_ => return Err("preimage not available")
-		}
+		let proposal_hash = T::Preimages::note(encoded_proposal)?;
+		T::Preimages::request(&proposal_hash);
 	}: enact_proposal(RawOrigin::Root, proposal_hash, 0)
 	verify {
-		// Fails due to mismatched origin
-		assert_last_event::<T>(RawEvent::Executed(0, false).into());
+		assert_last_event::<T>(RawEvent::Executed(0, true).into());
+		ensure!(!T::Preimages::is_requested(&proposal_hash), "preimage still requested");
 	}
 
 	#[extra]
-	enact_proposal_slash {
+	enact_proposal_invalid {
 		// Num of bytes in encoded proposal
 		let b in 0 .. MAX_BYTES;
 
-		let proposer = funded_account::<T>("proposer", 0);
 		// Random invalid bytes
 		let encoded_proposal = vec![200; b as usize];
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		Democracy::<T>::note_preimage(RawOrigin::Signed(proposer).into(), encoded_proposal)?;
-
-		match Preimages::<T>::get(proposal_hash) {
-			Some(PreimageStatus::Available { .. }) => (),
-			_ => return Err("preimage not available")
-		}
+		let proposal_hash = T::Preimages::note(encoded_proposal)?;
+		T::Preimages::request(&proposal_hash);
 	}: {
 		assert_eq!(
 			Democracy::<T>::enact_proposal(RawOrigin::Root.into(), proposal_hash, 0),
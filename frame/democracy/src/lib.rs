@@ -91,16 +91,10 @@
 //! - `reap_vote` - Remove some account's expired votes.
 //! - `unlock` - Redetermine the account's balance lock, potentially making tokens available.
 //!
-//! Preimage actions:
-//! - `note_preimage` - Registers the preimage for an upcoming proposal, requires
-//!   a deposit that is returned once the proposal is enacted.
-//! - `note_preimage_operational` - same but provided by `T::OperationalPreimageOrigin`.
-//! - `note_imminent_preimage` - Registers the preimage for an upcoming proposal.
-//!   Does not require a deposit, but the proposal must be in the dispatch queue.
-//! - `note_imminent_preimage_operational` - same but provided by `T::OperationalPreimageOrigin`.
-//! - `reap_preimage` - Removes the preimage for an expired proposal. Will only
-//!   work under the condition that it's the same account that noted it and
-//!   after the voting period, OR it's a different account after the enactment period.
+//! Preimages:
+//! Proposals are referred to by the hash of their encoded call. The preimage is stored and
+//! provided by `T::Preimages` (e.g. the preimage pallet); it is requested when a referendum
+//! starts and dropped once the referendum is over or its proposal has been enacted.
 //!
 //! #### Cancellation Origin
 //!
@@ -155,15 +149,16 @@
 use sp_std::prelude::*;
 use sp_runtime::{
 	DispatchResult, DispatchError, RuntimeDebug,
-	traits::{Zero, Hash, Dispatchable, Saturating, Bounded},
+	traits::{Zero, Dispatchable, Saturating, Bounded},
 };
-use codec::{Encode, Decode, Input};
+use codec::{Encode, Decode};
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, ensure, Parameter,
-	weights::{Weight, DispatchClass, Pays},
+	weights::{Weight, DispatchClass},
 	traits::{
 		Currency, ReservableCurrency, LockableCurrency, WithdrawReasons, LockIdentifier, Get,
-		OnUnbalanced, BalanceStatus, schedule::{Named as ScheduleNamed, DispatchTime}, EnsureOrigin
+		OnUnbalanced, schedule::{Named as ScheduleNamed, DispatchTime}, EnsureOrigin,
+		QueryPreimage, StorePreimage,
 	},
 	dispatch::DispatchResultWithPostInfo,
 };
@@ -277,13 +272,10 @@ pub trait Config: frame_system::Config + Sized {
 	/// Period in blocks where an external proposal may not be re-submitted after being vetoed.
 	type CooloffPeriod: Get<Self::BlockNumber>;
 
-	/// The amount of balance that must be deposited per byte of preimage stored.
-	type PreimageByteDeposit: Get<BalanceOf<Self>>;
+	/// The provider of the proposal preimages.
+	type Preimages: QueryPreimage<Self::Hash> + StorePreimage<Self::Hash>;
 
-	/// An origin that can provide a preimage using operational extrinsics.
-	type OperationalPreimageOrigin: EnsureOrigin<Self::Origin, Success=Self::AccountId>;
-
-	/// Handler for the unbalanced reduction when slashing a preimage deposit.
+	/// Handler for the unbalanced reduction when slashing a deposit.
 	type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
 
 	/// The Scheduler.
@@ -305,36 +297,92 @@ pub trait Config: frame_system::Config + Sized {
 	type MaxProposals: Get<u32>;
 }
 
-#[derive(Clone, Encode, Decode, RuntimeDebug)]
-pub enum PreimageStatus<AccountId, Balance, BlockNumber> {
-	/// The preimage is imminently needed at the argument.
-	Missing(BlockNumber),
-	/// The preimage is available.
-	Available {
-		data: Vec<u8>,
-		provider: AccountId,
-		deposit: Balance,
-		since: BlockNumber,
-		/// None if it's not imminent.
-		expiry: Option<BlockNumber>,
-	},
-}
-
-impl<AccountId, Balance, BlockNumber> PreimageStatus<AccountId, Balance, BlockNumber> {
-	fn to_missing_expiry(self) -> Option<BlockNumber> {
-		match self {
-			PreimageStatus::Missing(expiry) => Some(expiry),
-			_ => None,
-		}
-	}
-}
-
 // A value placed in storage that represents the current version of the Democracy storage.
-// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// This value is used by the `on_initialize` logic to determine whether we run
 // storage migration logic.
 #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
 enum Releases {
 	V1,
+	V2,
+}
+
+pub mod migrations {
+	use super::*;
+	use frame_support::{IterableStorageMap, Identity, storage::migration::StorageKeyIterator};
+
+	/// The status of a preimage, as stored in `Democracy::Preimages` before `Releases::V2`.
+	#[derive(Decode)]
+	enum OldPreimageStatus<AccountId, Balance, BlockNumber> {
+		Missing(BlockNumber),
+		Available {
+			data: Vec<u8>,
+			provider: AccountId,
+			deposit: Balance,
+			since: BlockNumber,
+			expiry: Option<BlockNumber>,
+		},
+	}
+
+	/// The maximum number of preimages that [`migrate_preimages_to_provider`] moves per block.
+	pub const MAX_PREIMAGES_PER_BLOCK: u32 = 32;
+
+	/// Move up to `limit` of the preimages stored by this pallet over to `T::Preimages`.
+	///
+	/// Deposits are returned to the providers. Preimages that are still needed, by an ongoing
+	/// referendum or a scheduled enactment, are requested. Once no preimage is left the storage
+	/// version is set to `Releases::V2`; until then this is called again on every block.
+	///
+	/// Returns the weight of the storage reads and writes that were performed.
+	pub fn migrate_preimages_to_provider<T: Config>(limit: u32) -> Weight {
+		let (mut reads, mut writes): (Weight, Weight) = (0, 0);
+		let old = StorageKeyIterator::<
+			T::Hash,
+			OldPreimageStatus<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+			Identity,
+		>::new(b"Democracy", b"Preimages").drain();
+		for (hash, status) in old.take(limit as usize) {
+			reads += 1;
+			writes += 1;
+			match status {
+				OldPreimageStatus::Missing(_) => {
+					T::Preimages::request(&hash);
+					reads += 1;
+					writes += 1;
+				},
+				OldPreimageStatus::Available { data, provider, deposit, expiry, .. } => {
+					T::Currency::unreserve(&provider, deposit);
+					if T::Preimages::note(data).is_err() {
+						sp_runtime::print("Failed to move a democracy preimage");
+					}
+					reads += 2;
+					writes += 3;
+					if expiry.is_some() {
+						T::Preimages::request(&hash);
+						reads += 1;
+						writes += 1;
+					}
+				}
+			}
+		}
+
+		let remaining = StorageKeyIterator::<T::Hash, (), Identity>::new(b"Democracy", b"Preimages")
+			.next()
+			.is_some();
+		reads += 1;
+		if !remaining {
+			for (_, info) in ReferendumInfoOf::<T>::iter() {
+				reads += 1;
+				if let ReferendumInfo::Ongoing(status) = info {
+					T::Preimages::request(&status.proposal_hash);
+					reads += 1;
+					writes += 1;
+				}
+			}
+			StorageVersion::put(Releases::V2);
+			writes += 1;
+		}
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
 }
 
 decl_storage! {
@@ -351,14 +399,6 @@ decl_storage! {
 		pub DepositOf get(fn deposit_of):
 			map hasher(twox_64_concat) PropIndex => Option<(Vec<T::AccountId>, BalanceOf<T>)>;
 
-		/// Map of hashes to the proposal preimage, along with who registered it and their deposit.
-		/// The block number is the block at which it was deposited.
-		// TODO: Refactor Preimages into its own pallet.
-		// https://github.com/paritytech/substrate/issues/5322
-		pub Preimages:
-			map hasher(identity) T::Hash
-			=> Option<PreimageStatus<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
-
 		/// The next free referendum index, aka the number of referenda started so far.
 		pub ReferendumCount get(fn referendum_count) build(|_| 0 as ReferendumIndex): ReferendumIndex;
 		/// The lowest referendum index representing an unbaked referendum. Equal to
@@ -406,7 +446,7 @@ decl_storage! {
 		/// Storage version of the pallet.
 		///
 		/// New networks start with last version.
-		StorageVersion build(|_| Some(Releases::V1)): Option<Releases>;
+		StorageVersion build(|_| Some(Releases::V2)): Option<Releases>;
 	}
 }
 
@@ -439,20 +479,12 @@ decl_event! {
 		Undelegated(AccountId),
 		/// An external proposal has been vetoed. \[who, proposal_hash, until\]
 		Vetoed(AccountId, Hash, BlockNumber),
-		/// A proposal's preimage was noted, and the deposit taken. \[proposal_hash, who, deposit\]
-		PreimageNoted(Hash, AccountId, Balance),
-		/// A proposal preimage was removed and used (the deposit was returned).
-		/// \[proposal_hash, provider, deposit\]
-		PreimageUsed(Hash, AccountId, Balance),
 		/// A proposal could not be executed because its preimage was invalid.
 		/// \[proposal_hash, ref_index\]
 		PreimageInvalid(Hash, ReferendumIndex),
 		/// A proposal could not be executed because its preimage was missing.
 		/// \[proposal_hash, ref_index\]
 		PreimageMissing(Hash, ReferendumIndex),
-		/// A registered preimage was removed and the deposit collected by the reaper.
-		/// \[proposal_hash, provider, deposit, reaper\]
-		PreimageReaped(Hash, AccountId, Balance, AccountId),
 		/// An \[account\] has been unlocked successfully.
 		Unlocked(AccountId),
 		/// A proposal \[hash\] has been blacklisted permanently.
@@ -484,14 +516,6 @@ decl_error! {
 		AlreadyVetoed,
 		/// Not delegated
 		NotDelegated,
-		/// Preimage already noted
-		DuplicatePreimage,
-		/// Not imminent
-		NotImminent,
-		/// Too early
-		TooEarly,
-		/// Imminent
-		Imminent,
 		/// Preimage not found
 		PreimageMissing,
 		/// Vote given for invalid referendum
@@ -562,9 +586,6 @@ decl_module! {
 		/// Period in blocks where an external proposal may not be re-submitted after being vetoed.
 		const CooloffPeriod: T::BlockNumber = T::CooloffPeriod::get();
 
-		/// The amount of balance that must be deposited per byte of preimage stored.
-		const PreimageByteDeposit: BalanceOf<T> = T::PreimageByteDeposit::get();
-
 		/// The maximum number of votes for an account.
 		const MaxVotes: u32 = T::MaxVotes::get();
 
@@ -832,6 +853,9 @@ decl_module! {
 		///
 		/// - `which`: The index of the referendum to cancel.
 		///
+		/// The request for the proposal's preimage is left in place; it can be dropped through
+		/// `T::Preimages` if the preimage is no longer needed.
+		///
 		/// Weight: `O(D)` where `D` is the items in the dispatch queue. Weighted as `D = 10`.
 		#[weight = (T::WeightInfo::cancel_queued(10), DispatchClass::Operational)]
 		fn cancel_queued(origin, which: ReferendumIndex) {
@@ -841,11 +865,20 @@ decl_module! {
 		}
 
 		/// Weight: see `begin_block`
+		///
+		/// Until all preimages are moved to `T::Preimages`, at most
+		/// `migrations::MAX_PREIMAGES_PER_BLOCK` of them are moved on every block.
 		fn on_initialize(n: T::BlockNumber) -> Weight {
-			Self::begin_block(n).unwrap_or_else(|e| {
+			let mut weight = T::DbWeight::get().reads(1);
+			if StorageVersion::get() == Some(Releases::V1) {
+				weight = weight.saturating_add(migrations::migrate_preimages_to_provider::<T>(
+					migrations::MAX_PREIMAGES_PER_BLOCK,
+				));
+			}
+			weight.saturating_add(Self::begin_block(n).unwrap_or_else(|e| {
 				sp_runtime::print(e);
 				0
-			})
+			}))
 		}
 
 		/// Delegate the voting power (with some given conviction) of the sending account.
@@ -915,106 +948,6 @@ decl_module! {
 			<PublicProps<T>>::kill();
 		}
 
-		/// Register the preimage for an upcoming proposal. This doesn't require the proposal to be
-		/// in the dispatch queue but does require a deposit, returned once enacted.
-		///
-		/// The dispatch origin of this call must be _Signed_.
-		///
-		/// - `encoded_proposal`: The preimage of a proposal.
-		///
-		/// Emits `PreimageNoted`.
-		///
-		/// Weight: `O(E)` with E size of `encoded_proposal` (protected by a required deposit).
-		#[weight = T::WeightInfo::note_preimage(encoded_proposal.len() as u32)]
-		fn note_preimage(origin, encoded_proposal: Vec<u8>) {
-			Self::note_preimage_inner(ensure_signed(origin)?, encoded_proposal)?;
-		}
-
-		/// Same as `note_preimage` but origin is `OperationalPreimageOrigin`.
-		#[weight = (
-			T::WeightInfo::note_preimage(encoded_proposal.len() as u32),
-			DispatchClass::Operational,
-		)]
-		fn note_preimage_operational(origin, encoded_proposal: Vec<u8>) {
-			let who = T::OperationalPreimageOrigin::ensure_origin(origin)?;
-			Self::note_preimage_inner(who, encoded_proposal)?;
-		}
-
-		/// Register the preimage for an upcoming proposal. This requires the proposal to be
-		/// in the dispatch queue. No deposit is needed. When this call is successful, i.e.
-		/// the preimage has not been uploaded before and matches some imminent proposal,
-		/// no fee is paid.
-		///
-		/// The dispatch origin of this call must be _Signed_.
-		///
-		/// - `encoded_proposal`: The preimage of a proposal.
-		///
-		/// Emits `PreimageNoted`.
-		///
-		/// Weight: `O(E)` with E size of `encoded_proposal` (protected by a required deposit).
-		#[weight = T::WeightInfo::note_imminent_preimage(encoded_proposal.len() as u32)]
-		fn note_imminent_preimage(origin, encoded_proposal: Vec<u8>) -> DispatchResultWithPostInfo {
-			Self::note_imminent_preimage_inner(ensure_signed(origin)?, encoded_proposal)?;
-			// We check that this preimage was not uploaded before in `note_imminent_preimage_inner`,
-			// thus this call can only be successful once. If successful, user does not pay a fee.
-			Ok(Pays::No.into())
-		}
-
-		/// Same as `note_imminent_preimage` but origin is `OperationalPreimageOrigin`.
-		#[weight = (
-			T::WeightInfo::note_imminent_preimage(encoded_proposal.len() as u32),
-			DispatchClass::Operational,
-		)]
-		fn note_imminent_preimage_operational(origin, encoded_proposal: Vec<u8>) -> DispatchResultWithPostInfo {
-			let who = T::OperationalPreimageOrigin::ensure_origin(origin)?;
-			Self::note_imminent_preimage_inner(who, encoded_proposal)?;
-			// We check that this preimage was not uploaded before in `note_imminent_preimage_inner`,
-			// thus this call can only be successful once. If successful, user does not pay a fee.
-			Ok(Pays::No.into())
-		}
-
-		/// Remove an expired proposal preimage and collect the deposit.
-		///
-		/// The dispatch origin of this call must be _Signed_.
-		///
-		/// - `proposal_hash`: The preimage hash of a proposal.
-		/// - `proposal_length_upper_bound`: an upper bound on length of the proposal.
-		///   Extrinsic is weighted according to this value with no refund.
-		///
-		/// This will only work after `VotingPeriod` blocks from the time that the preimage was
-		/// noted, if it's the same account doing it. If it's a different account, then it'll only
-		/// work an additional `EnactmentPeriod` later.
-		///
-		/// Emits `PreimageReaped`.
-		///
-		/// Weight: `O(D)` where D is length of proposal.
-		#[weight = T::WeightInfo::reap_preimage(*proposal_len_upper_bound)]
-		fn reap_preimage(origin, proposal_hash: T::Hash, #[compact] proposal_len_upper_bound: u32) {
-			let who = ensure_signed(origin)?;
-
-			ensure!(
-				Self::pre_image_data_len(proposal_hash)? <= proposal_len_upper_bound,
-				Error::<T>::WrongUpperBound,
-			);
-
-			let (provider, deposit, since, expiry) = <Preimages<T>>::get(&proposal_hash)
-				.and_then(|m| match m {
-					PreimageStatus::Available { provider, deposit, since, expiry, .. }
-						=> Some((provider, deposit, since, expiry)),
-					_ => None,
-				}).ok_or(Error::<T>::PreimageMissing)?;
-
-			let now = <frame_system::Module<T>>::block_number();
-			let (voting, enactment) = (T::VotingPeriod::get(), T::EnactmentPeriod::get());
-			let additional = if who == provider { Zero::zero() } else { enactment };
-			ensure!(now >= since + voting + additional, Error::<T>::TooEarly);
-			ensure!(expiry.map_or(true, |e| now > e), Error::<T>::Imminent);
-
-			let _ = T::Currency::repatriate_reserved(&provider, &who, deposit, BalanceStatus::Free);
-			<Preimages<T>>::remove(&proposal_hash);
-			Self::deposit_event(RawEvent::PreimageReaped(proposal_hash, provider, deposit, who));
-		}
-
 		/// Unlock tokens that have an expired lock.
 		///
 		/// The dispatch origin of this call must be _Signed_.
@@ -1219,7 +1152,9 @@ impl<T: Config> Module<T> {
 	/// Remove a referendum.
 	pub fn internal_cancel_referendum(ref_index: ReferendumIndex) {
 		Self::deposit_event(RawEvent::Cancelled(ref_index));
-		ReferendumInfoOf::<T>::remove(ref_index);
+		if let Some(ReferendumInfo::Ongoing(status)) = ReferendumInfoOf::<T>::take(ref_index) {
+			T::Preimages::unrequest(&status.proposal_hash);
+		}
 	}
 
 	// private.
@@ -1477,6 +1412,8 @@ impl<T: Config> Module<T> {
 		let status = ReferendumStatus { end, proposal_hash, threshold, delay, tally: Default::default() };
 		let item = ReferendumInfo::Ongoing(status);
 		<ReferendumInfoOf<T>>::insert(ref_index, item);
+		// The preimage is needed until the referendum is over or its proposal enacted.
+		T::Preimages::request(&proposal_hash);
 		Self::deposit_event(RawEvent::Started(ref_index, threshold));
 		ref_index
 	}
@@ -1538,18 +1475,15 @@ impl<T: Config> Module<T> {
 	}
 
 	fn do_enact_proposal(proposal_hash: T::Hash, index: ReferendumIndex) -> DispatchResult {
-		let preimage = <Preimages<T>>::take(&proposal_hash);
-		if let Some(PreimageStatus::Available { data, provider, deposit, .. }) = preimage {
+		let maybe_data = T::Preimages::fetch(&proposal_hash);
+		T::Preimages::unrequest(&proposal_hash);
+		if let Some(data) = maybe_data {
 			if let Ok(proposal) = T::Proposal::decode(&mut &data[..]) {
-				let _ = T::Currency::unreserve(&provider, deposit);
-				Self::deposit_event(RawEvent::PreimageUsed(proposal_hash, provider, deposit));
-
 				let ok = proposal.dispatch(frame_system::RawOrigin::Root.into()).is_ok();
 				Self::deposit_event(RawEvent::Executed(index, ok));
 
 				Ok(())
 			} else {
-				T::Slash::on_unbalanced(T::Currency::slash_reserved(&provider, deposit).0);
 				Self::deposit_event(RawEvent::PreimageInvalid(proposal_hash, index));
 				Err(Error::<T>::PreimageInvalid.into())
 			}
@@ -1572,13 +1506,8 @@ impl<T: Config> Module<T> {
 			if status.delay.is_zero() {
 				let _ = Self::do_enact_proposal(status.proposal_hash, index);
 			} else {
+				// The preimage stays requested until the proposal is enacted.
 				let when = now + status.delay;
-				// Note that we need the preimage now.
-				Preimages::<T>::mutate_exists(&status.proposal_hash, |maybe_pre| match *maybe_pre {
-					Some(PreimageStatus::Available { ref mut expiry, .. }) => *expiry = Some(when),
-					ref mut a => *a = Some(PreimageStatus::Missing(when)),
-				});
-
 				if T::Scheduler::schedule_named(
 					(DEMOCRACY_ID, index).encode(),
 					DispatchTime::At(when),
@@ -1592,6 +1521,7 @@ impl<T: Config> Module<T> {
 			}
 		} else {
 			Self::deposit_event(RawEvent::NotPassed(index));
+			T::Preimages::unrequest(&status.proposal_hash);
 		}
 
 		Ok(approved)
@@ -1642,117 +1572,6 @@ impl<T: Config> Module<T> {
 		// `Compact<u32>`.
 		decode_compact_u32_at(&<DepositOf<T>>::hashed_key_for(proposal))
 	}
-
-	/// Check that pre image exists and its value is variant `PreimageStatus::Missing`.
-	///
-	/// This check is done without getting the complete value in the runtime to avoid copying a big
-	/// value in the runtime.
-	fn check_pre_image_is_missing(proposal_hash: T::Hash) -> DispatchResult {
-		// To decode the enum variant we only need the first byte.
-		let mut buf = [0u8; 1];
-		let key = <Preimages<T>>::hashed_key_for(proposal_hash);
-		let bytes = match sp_io::storage::read(&key, &mut buf, 0) {
-			Some(bytes) => bytes,
-			None => return Err(Error::<T>::NotImminent.into()),
-		};
-		// The value may be smaller that 1 byte.
-		let mut input = &buf[0..buf.len().min(bytes as usize)];
-
-		match input.read_byte() {
-			Ok(0) => Ok(()), // PreimageStatus::Missing is variant 0
-			Ok(1) => Err(Error::<T>::DuplicatePreimage.into()),
-			_ => {
-				sp_runtime::print("Failed to decode `PreimageStatus` variant");
-				Err(Error::<T>::NotImminent.into())
-			}
-		}
-	}
-
-	/// Check that pre image exists, its value is variant `PreimageStatus::Available` and decode
-	/// the length of `data: Vec<u8>` fields.
-	///
-	/// This check is done without getting the complete value in the runtime to avoid copying a big
-	/// value in the runtime.
-	///
-	/// If the pre image is missing variant or doesn't exist then the error `PreimageMissing` is
-	/// returned.
-	fn pre_image_data_len(proposal_hash: T::Hash) -> Result<u32, DispatchError> {
-		// To decode the `data` field of Available variant we need:
-		// * one byte for the variant
-		// * at most 5 bytes to decode a `Compact<u32>`
-		let mut buf = [0u8; 6];
-		let key = <Preimages<T>>::hashed_key_for(proposal_hash);
-		let bytes = match sp_io::storage::read(&key, &mut buf, 0) {
-			Some(bytes) => bytes,
-			None => return Err(Error::<T>::PreimageMissing.into()),
-		};
-		// The value may be smaller that 6 bytes.
-		let mut input = &buf[0..buf.len().min(bytes as usize)];
-
-		match input.read_byte() {
-			Ok(1) => (), // Check that input exists and is second variant.
-			Ok(0) => return Err(Error::<T>::PreimageMissing.into()),
-			_ => {
-				sp_runtime::print("Failed to decode `PreimageStatus` variant");
-				return Err(Error::<T>::PreimageMissing.into());
-			}
-		}
-
-		// Decode the length of the vector.
-		let len = codec::Compact::<u32>::decode(&mut input).map_err(|_| {
-			sp_runtime::print("Failed to decode `PreimageStatus` variant");
-			DispatchError::from(Error::<T>::PreimageMissing)
-		})?.0;
-
-		Ok(len)
-	}
-
-	// See `note_preimage`
-	fn note_preimage_inner(who: T::AccountId, encoded_proposal: Vec<u8>) -> DispatchResult {
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		ensure!(!<Preimages<T>>::contains_key(&proposal_hash), Error::<T>::DuplicatePreimage);
-
-		let deposit = <BalanceOf<T>>::from(encoded_proposal.len() as u32)
-			.saturating_mul(T::PreimageByteDeposit::get());
-		T::Currency::reserve(&who, deposit)?;
-
-		let now = <frame_system::Module<T>>::block_number();
-		let a = PreimageStatus::Available {
-			data: encoded_proposal,
-			provider: who.clone(),
-			deposit,
-			since: now,
-			expiry: None,
-		};
-		<Preimages<T>>::insert(proposal_hash, a);
-
-		Self::deposit_event(RawEvent::PreimageNoted(proposal_hash, who, deposit));
-
-		Ok(())
-	}
-
-	// See `note_imminent_preimage`
-	fn note_imminent_preimage_inner(who: T::AccountId, encoded_proposal: Vec<u8>) -> DispatchResult {
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		Self::check_pre_image_is_missing(proposal_hash)?;
-		let status = Preimages::<T>::get(&proposal_hash).ok_or(Error::<T>::NotImminent)?;
-		let expiry = status.to_missing_expiry().ok_or(Error::<T>::DuplicatePreimage)?;
-
-		let now = <frame_system::Module<T>>::block_number();
-		let free = <BalanceOf<T>>::zero();
-		let a = PreimageStatus::Available {
-			data: encoded_proposal,
-			provider: who.clone(),
-			deposit: Zero::zero(),
-			since: now,
-			expiry: Some(expiry),
-		};
-		<Preimages<T>>::insert(proposal_hash, a);
-
-		Self::deposit_event(RawEvent::PreimageNoted(proposal_hash, who, free));
-
-		Ok(())
-	}
 }
 
 /// Decode `Compact<u32>` from the trie at given key.
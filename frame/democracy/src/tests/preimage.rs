@@ -18,114 +18,56 @@
 //! The preimage tests.
 
 use super::*;
+use frame_support::{storage::migration, weights::Pays};
 
 #[test]
 fn missing_preimage_should_fail() {
 	new_test_ext().execute_with(|| {
-		let r = Democracy::inject_referendum(
-			2,
-			set_balance_proposal_hash(2),
-			VoteThreshold::SuperMajorityApprove,
-			0
-		);
+		let h = set_balance_proposal_hash(2);
+		let r = Democracy::inject_referendum(2, h, VoteThreshold::SuperMajorityApprove, 0);
+		assert!(Preimage::is_requested(&h));
 		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
 
 		next_block();
 		next_block();
 
 		assert_eq!(Balances::free_balance(42), 0);
+		assert!(!Preimage::is_requested(&h));
 	});
 }
 
 #[test]
-fn preimage_deposit_should_be_required_and_returned() {
-	new_test_ext_execute_with_cond(|operational| {
+fn preimage_deposit_should_be_returned_when_referendum_starts() {
+	new_test_ext().execute_with(|| {
 		// fee of 100 is too much.
 		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 100);
 		assert_noop!(
-			if operational { Democracy::note_preimage_operational(Origin::signed(6), vec![0; 500]) }
-			else { Democracy::note_preimage(Origin::signed(6), vec![0; 500]) },
+			Preimage::note_preimage(Origin::signed(6), vec![0; 500]),
 			BalancesError::<Test, _>::InsufficientBalance,
 		);
 		// fee of 1 is reasonable.
 		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 1);
-		let r = Democracy::inject_referendum(
-			2,
-			set_balance_proposal_hash_and_note(2),
-			VoteThreshold::SuperMajorityApprove,
-			0
-		);
-		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
-
+		let h = set_balance_proposal_hash_and_note(2);
 		assert_eq!(Balances::reserved_balance(6), 12);
 
-		next_block();
-		next_block();
-
+		let r = Democracy::inject_referendum(2, h, VoteThreshold::SuperMajorityApprove, 0);
 		assert_eq!(Balances::reserved_balance(6), 0);
 		assert_eq!(Balances::free_balance(6), 60);
-		assert_eq!(Balances::free_balance(42), 2);
-	});
-}
-
-#[test]
-fn preimage_deposit_should_be_reapable_earlier_by_owner() {
-	new_test_ext_execute_with_cond(|operational| {
-		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 1);
-		assert_ok!(
-			if operational { Democracy::note_preimage_operational(Origin::signed(6), set_balance_proposal(2)) }
-			else { Democracy::note_preimage(Origin::signed(6), set_balance_proposal(2)) }
-		);
-
-		assert_eq!(Balances::reserved_balance(6), 12);
-
-		next_block();
-		assert_noop!(
-			Democracy::reap_preimage(Origin::signed(6), set_balance_proposal_hash(2), u32::max_value()),
-			Error::<Test>::TooEarly
-		);
-		next_block();
-		assert_ok!(Democracy::reap_preimage(Origin::signed(6), set_balance_proposal_hash(2), u32::max_value()));
-
-		assert_eq!(Balances::free_balance(6), 60);
-		assert_eq!(Balances::reserved_balance(6), 0);
-	});
-}
-
-#[test]
-fn preimage_deposit_should_be_reapable() {
-	new_test_ext_execute_with_cond(|operational| {
-		assert_noop!(
-				Democracy::reap_preimage(Origin::signed(5), set_balance_proposal_hash(2), u32::max_value()),
-				Error::<Test>::PreimageMissing
-			);
-
-		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 1);
-		assert_ok!(
-			if operational { Democracy::note_preimage_operational(Origin::signed(6), set_balance_proposal(2)) }
-			else { Democracy::note_preimage(Origin::signed(6), set_balance_proposal(2)) }
-		);
-		assert_eq!(Balances::reserved_balance(6), 12);
+		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
 
 		next_block();
 		next_block();
-		next_block();
-		assert_noop!(
-				Democracy::reap_preimage(Origin::signed(5), set_balance_proposal_hash(2), u32::max_value()),
-				Error::<Test>::TooEarly
-			);
 
-		next_block();
-		assert_ok!(Democracy::reap_preimage(Origin::signed(5), set_balance_proposal_hash(2), u32::max_value()));
-		assert_eq!(Balances::reserved_balance(6), 0);
-		assert_eq!(Balances::free_balance(6), 48);
-		assert_eq!(Balances::free_balance(5), 62);
+		assert_eq!(Balances::free_balance(42), 2);
+		// The preimage is cleared once enacted.
+		assert_eq!(Preimage::fetch(&h), None);
+		assert!(!Preimage::is_requested(&h));
 	});
 }
 
 #[test]
-fn noting_imminent_preimage_for_free_should_work() {
-	new_test_ext_execute_with_cond(|operational| {
+fn noting_requested_preimage_for_free_should_work() {
+	new_test_ext().execute_with(|| {
 		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 1);
 
 		let r = Democracy::inject_referendum(
@@ -136,16 +78,12 @@ fn noting_imminent_preimage_for_free_should_work() {
 		);
 		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
 
-		assert_noop!(
-			if operational { Democracy::note_imminent_preimage_operational(Origin::signed(6), set_balance_proposal(2)) }
-			else { Democracy::note_imminent_preimage(Origin::signed(6), set_balance_proposal(2)) },
-			Error::<Test>::NotImminent
-		);
-
 		next_block();
 
-		// Now we're in the dispatch queue it's all good.
-		assert_ok!(Democracy::note_imminent_preimage(Origin::signed(6), set_balance_proposal(2)));
+		// The referendum passed and its preimage is needed, so it is free to note.
+		let info = Preimage::note_preimage(Origin::signed(6), set_balance_proposal(2)).unwrap();
+		assert_eq!(info.pays_fee, Pays::No);
+		assert_eq!(Balances::reserved_balance(6), 0);
 
 		next_block();
 
@@ -154,44 +92,89 @@ fn noting_imminent_preimage_for_free_should_work() {
 }
 
 #[test]
-fn reaping_imminent_preimage_should_fail() {
+fn preimage_request_should_be_dropped_when_referendum_is_cancelled() {
 	new_test_ext().execute_with(|| {
 		let h = set_balance_proposal_hash_and_note(2);
-		let r = Democracy::inject_referendum(3, h, VoteThreshold::SuperMajorityApprove, 1);
-		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
+		let r = Democracy::inject_referendum(2, h, VoteThreshold::SuperMajorityApprove, 0);
+		assert!(Preimage::is_requested(&h));
+
+		assert_ok!(Democracy::cancel_referendum(Origin::root(), r));
+		assert!(!Preimage::is_requested(&h));
+		assert_eq!(Preimage::fetch(&h), None);
+	});
+}
+
+#[test]
+fn preimage_request_should_be_dropped_when_referendum_fails() {
+	new_test_ext().execute_with(|| {
+		let h = set_balance_proposal_hash_and_note(2);
+		let r = Democracy::inject_referendum(2, h, VoteThreshold::SuperMajorityApprove, 0);
+		assert_ok!(Democracy::vote(Origin::signed(1), r, nay(1)));
+
 		next_block();
 		next_block();
-		assert_noop!(Democracy::reap_preimage(Origin::signed(6), h, u32::max_value()), Error::<Test>::Imminent);
+
+		assert_eq!(Balances::free_balance(42), 0);
+		assert!(!Preimage::is_requested(&h));
+		assert_eq!(Preimage::fetch(&h), None);
 	});
 }
 
 #[test]
-fn note_imminent_preimage_can_only_be_successful_once() {
+fn invalid_preimage_should_fail() {
 	new_test_ext().execute_with(|| {
-		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 1);
-
-		let r = Democracy::inject_referendum(
-			2,
-			set_balance_proposal_hash(2),
-			VoteThreshold::SuperMajorityApprove,
-			1
-		);
+		let p = vec![200; 3];
+		let h = BlakeTwo256::hash(&p[..]);
+		assert_ok!(Preimage::note_preimage(Origin::signed(6), p));
+		let r = Democracy::inject_referendum(2, h, VoteThreshold::SuperMajorityApprove, 0);
 		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
-		next_block();
 
-		// First time works
-		assert_ok!(Democracy::note_imminent_preimage(Origin::signed(6), set_balance_proposal(2)));
+		next_block();
+		next_block();
 
-		// Second time fails
-		assert_noop!(
-			Democracy::note_imminent_preimage(Origin::signed(6), set_balance_proposal(2)),
-			Error::<Test>::DuplicatePreimage
-		);
+		assert!(!Preimage::is_requested(&h));
+		assert_eq!(Preimage::fetch(&h), None);
+	});
+}
 
-		// Fails from any user
-		assert_noop!(
-			Democracy::note_imminent_preimage(Origin::signed(5), set_balance_proposal(2)),
-			Error::<Test>::DuplicatePreimage
-		);
+#[test]
+fn preimages_should_be_migrated_to_provider() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::put(Releases::V1);
+
+		// An unused preimage, noted with a deposit.
+		let (p2, h2) = (set_balance_proposal(2), set_balance_proposal_hash(2));
+		assert_ok!(Balances::reserve(&6, 12));
+		let old = (1u8, p2.clone(), 6u64, 12u64, 1u64, None::<u64>);
+		migration::put_storage_value(b"Democracy", b"Preimages", &h2.encode(), old);
+		// A preimage about to be enacted.
+		let (p3, h3) = (set_balance_proposal(3), set_balance_proposal_hash(3));
+		let old = (1u8, p3.clone(), 5u64, 0u64, 1u64, Some(3u64));
+		migration::put_storage_value(b"Democracy", b"Preimages", &h3.encode(), old);
+		// A preimage that is about to be enacted but is still missing.
+		let h4 = set_balance_proposal_hash(4);
+		migration::put_storage_value(b"Democracy", b"Preimages", &h4.encode(), (0u8, 3u64));
+
+		// The migration moves at most `limit` preimages at a time.
+		migrations::migrate_preimages_to_provider::<Test>(2);
+		assert_eq!(StorageVersion::get(), Some(Releases::V1));
+		let left = [h2, h3, h4].iter()
+			.filter(|h| migration::have_storage_value(b"Democracy", b"Preimages", &h.encode()))
+			.count();
+		assert_eq!(left, 1);
+
+		// The rest is moved on the next block.
+		Democracy::on_initialize(System::block_number());
+		assert_eq!(StorageVersion::get(), Some(Releases::V2));
+		for h in &[h2, h3, h4] {
+			assert!(!migration::have_storage_value(b"Democracy", b"Preimages", &h.encode()));
+		}
+		assert_eq!(Balances::reserved_balance(6), 0);
+		assert_eq!(Preimage::fetch(&h2), Some(p2));
+		assert!(!Preimage::is_requested(&h2));
+		assert_eq!(Preimage::fetch(&h3), Some(p3));
+		assert!(Preimage::is_requested(&h3));
+		assert_eq!(Preimage::fetch(&h4), None);
+		assert!(Preimage::is_requested(&h4));
 	});
 }
@@ -61,9 +61,6 @@ pub trait WeightInfo {
 	fn delegate(r: u32, ) -> Weight;
 	fn undelegate(r: u32, ) -> Weight;
 	fn clear_public_proposals() -> Weight;
-	fn note_preimage(b: u32, ) -> Weight;
-	fn note_imminent_preimage(b: u32, ) -> Weight;
-	fn reap_preimage(b: u32, ) -> Weight;
 	fn unlock_remove(r: u32, ) -> Weight;
 	fn unlock_set(r: u32, ) -> Weight;
 	fn remove_vote(r: u32, ) -> Weight;
@@ -174,24 +171,6 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		(4_404_000 as Weight)
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
-	fn note_preimage(b: u32, ) -> Weight {
-		(60_073_000 as Weight)
-			.saturating_add((4_000 as Weight).saturating_mul(b as Weight))
-			.saturating_add(T::DbWeight::get().reads(1 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
-	}
-	fn note_imminent_preimage(b: u32, ) -> Weight {
-		(38_896_000 as Weight)
-			.saturating_add((4_000 as Weight).saturating_mul(b as Weight))
-			.saturating_add(T::DbWeight::get().reads(1 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
-	}
-	fn reap_preimage(b: u32, ) -> Weight {
-		(54_861_000 as Weight)
-			.saturating_add((3_000 as Weight).saturating_mul(b as Weight))
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
-	}
 	fn unlock_remove(r: u32, ) -> Weight {
 		(52_956_000 as Weight)
 			.saturating_add((126_000 as Weight).saturating_mul(r as Weight))
@@ -321,24 +300,6 @@ impl WeightInfo for () {
 		(4_404_000 as Weight)
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
-	fn note_preimage(b: u32, ) -> Weight {
-		(60_073_000 as Weight)
-			.saturating_add((4_000 as Weight).saturating_mul(b as Weight))
-			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
-	}
-	fn note_imminent_preimage(b: u32, ) -> Weight {
-		(38_896_000 as Weight)
-			.saturating_add((4_000 as Weight).saturating_mul(b as Weight))
-			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
-	}
-	fn reap_preimage(b: u32, ) -> Weight {
-		(54_861_000 as Weight)
-			.saturating_add((3_000 as Weight).saturating_mul(b as Weight))
-			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
-	}
 	fn unlock_remove(r: u32, ) -> Weight {
 		(52_956_000 as Weight)
 			.saturating_add((126_000 as Weight).saturating_mul(r as Weight))
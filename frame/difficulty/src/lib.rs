@@ -0,0 +1,202 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Difficulty Pallet
+//!
+//! The Difficulty pallet keeps the proof-of-work difficulty of a chain on-chain, adjusting it at
+//! the end of every block so that blocks are mined at the configured target block time.
+//!
+//! ## Overview
+//!
+//! Blocks are timed with the [Timestamp](../pallet_timestamp/index.html) pallet. The timestamps and
+//! difficulties of the last [`Config::RetargetWindow`] blocks are kept in storage and handed to
+//! the [`Config::Retarget`] algorithm, which computes the difficulty of the next block. Two
+//! algorithms are provided:
+//!
+//! * [`Lwma`]: linearly weighted moving average of the solve times.
+//! * [`DigiShield`]: dampened and clamped timespan of the whole window.
+//!
+//! The difficulty never drops below [`Config::MinDifficulty`].
+//!
+//! Every PoW algorithm has its own difficulty and retarget window. On chains mined with several
+//! algorithms (`sc_consensus_pow::MultiAlgorithm`), a block is attributed to the algorithm of its
+//! `MultiAlgorithmPreDigest`, and blocks without one to [`Config::DefaultAlgorithm`]. Only the
+//! blocks of an algorithm are used to retarget it, so [`Config::TargetBlockTime`] is the time
+//! between two blocks mined with the same algorithm.
+//!
+//! ## Usage
+//!
+//! The difficulties are exposed to the node by implementing `sp_consensus_pow::DifficultyApi` in
+//! the runtime with [`Pallet::difficulty`]:
+//!
+//! ```ignore
+//! impl sp_consensus_pow::DifficultyApi<Block, pallet_difficulty::Difficulty> for Runtime {
+//! 	fn difficulty(algorithm: sp_consensus_pow::AlgorithmId) -> pallet_difficulty::Difficulty {
+//! 		Difficulty::difficulty(algorithm)
+//! 	}
+//! }
+//! ```
+//!
+//! `sc_consensus_pow::RuntimeDifficulty` then lets any `PowAlgorithm` read the difficulty of the
+//! algorithm it selects from there.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod retarget;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use codec::DecodeAll;
+use sp_std::prelude::*;
+use sp_runtime::traits::SaturatedConversion;
+use sp_consensus_pow::{AlgorithmId, MultiAlgorithmPreDigest, POW_ENGINE_ID};
+
+pub use retarget::{Retarget, Lwma, DigiShield};
+pub use pallet::*;
+
+/// Proof-of-work difficulty.
+pub type Difficulty = sp_core::U256;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	/// The pallet configuration trait
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_timestamp::Config {
+		/// Algorithm computing the next difficulty out of the last blocks.
+		type Retarget: Retarget;
+
+		/// Desired time between two blocks mined with the same algorithm.
+		#[pallet::constant]
+		type TargetBlockTime: Get<Self::Moment>;
+
+		/// Number of past blocks the next difficulty is computed from.
+		#[pallet::constant]
+		type RetargetWindow: Get<u32>;
+
+		/// Lowest difficulty the chain can be retargeted to.
+		#[pallet::constant]
+		type MinDifficulty: Get<Difficulty>;
+
+		/// Algorithm of the blocks without a `MultiAlgorithmPreDigest`.
+		///
+		/// This is `sp_consensus_pow::DEFAULT_ALGORITHM` on chains mined with a single algorithm,
+		/// or the default algorithm of their `sc_consensus_pow::MultiAlgorithm`.
+		#[pallet::constant]
+		type DefaultAlgorithm: Get<AlgorithmId>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	/// Difficulty of every algorithm until it is retargeted for the first time.
+	#[pallet::storage]
+	#[pallet::getter(fn initial_difficulty)]
+	pub type InitialDifficulty<T: Config> = StorageValue<_, Difficulty, ValueQuery>;
+
+	/// Difficulty the next block mined with an algorithm must be mined at.
+	#[pallet::storage]
+	pub type CurrentDifficulty<T: Config> = StorageMap<_, Twox64Concat, AlgorithmId, Difficulty>;
+
+	/// Timestamps and difficulties of the blocks of an algorithm in its retarget window, oldest
+	/// first.
+	#[pallet::storage]
+	#[pallet::getter(fn past_blocks)]
+	pub type PastBlocks<T: Config> =
+		StorageMap<_, Twox64Concat, AlgorithmId, Vec<(u64, Difficulty)>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig {
+		/// Difficulty of the first block of every algorithm.
+		pub initial_difficulty: Difficulty,
+	}
+
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			Self { initial_difficulty: Difficulty::one() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			InitialDifficulty::<T>::put(self.initial_difficulty.max(T::MinDifficulty::get()));
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Return the weight used in `on_finalize`.
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			T::DbWeight::get().reads_writes(5, 2)
+		}
+
+		/// Record the block and retarget the difficulty of its algorithm.
+		fn on_finalize(_n: BlockNumberFor<T>) {
+			let now = pallet_timestamp::Module::<T>::now().saturated_into::<u64>();
+			Self::note_block(Self::block_algorithm(), now);
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Difficulty the next block mined with `algorithm` must be mined at.
+	pub fn difficulty(algorithm: AlgorithmId) -> Difficulty {
+		CurrentDifficulty::<T>::get(algorithm).unwrap_or_else(Self::initial_difficulty)
+	}
+
+	/// The algorithm the current block is mined with, out of its PoW pre-runtime digest.
+	fn block_algorithm() -> AlgorithmId {
+		let digest = frame_system::Pallet::<T>::digest();
+		digest.logs.iter()
+			.filter_map(|d| d.as_pre_runtime())
+			.find(|(id, _)| *id == POW_ENGINE_ID)
+			.and_then(|(_, mut data)| MultiAlgorithmPreDigest::decode_all(&mut data).ok())
+			.map(|pre_digest| pre_digest.algorithm)
+			.unwrap_or_else(T::DefaultAlgorithm::get)
+	}
+
+	/// Push the current block into the retarget window of `algorithm` and compute the next
+	/// difficulty of the algorithm.
+	fn note_block(algorithm: AlgorithmId, now: u64) {
+		let difficulty = Self::difficulty(algorithm);
+		let window = T::RetargetWindow::get() as usize;
+
+		let history = PastBlocks::<T>::mutate(algorithm, |history| {
+			history.push((now, difficulty));
+			// `window` solve times need `window + 1` timestamps.
+			let excess = history.len().saturating_sub(window.saturating_add(1));
+			history.drain(..excess);
+			history.clone()
+		});
+
+		let target_block_time = T::TargetBlockTime::get().saturated_into::<u64>();
+		if let Some(next) = T::Retarget::next_difficulty(&history, target_block_time) {
+			CurrentDifficulty::<T>::insert(algorithm, next.max(T::MinDifficulty::get()));
+		}
+	}
+}
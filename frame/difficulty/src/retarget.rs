@@ -0,0 +1,122 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Difficulty retargeting algorithms.
+//!
+//! Every algorithm computes the difficulty of the next block out of the timestamps and
+//! difficulties of the last blocks of the retarget window, ordered from oldest to newest.
+
+use sp_core::U256;
+use crate::Difficulty;
+
+/// Algorithm adjusting the difficulty to the observed block times.
+pub trait Retarget {
+	/// Compute the difficulty of the next block.
+	///
+	/// `history` contains the timestamp (in milliseconds) and difficulty of the last blocks,
+	/// oldest first. `target_block_time` is the desired time between two blocks, in milliseconds.
+	///
+	/// Returns `None` if `history` is too short to retarget, in which case the difficulty is left
+	/// unchanged.
+	fn next_difficulty(
+		history: &[(u64, Difficulty)],
+		target_block_time: u64,
+	) -> Option<Difficulty>;
+}
+
+/// Never retarget, keeping the genesis difficulty forever.
+impl Retarget for () {
+	fn next_difficulty(_: &[(u64, Difficulty)], _: u64) -> Option<Difficulty> {
+		None
+	}
+}
+
+/// Average difficulty of the blocks whose solve times are measured by `history`.
+fn average_difficulty(history: &[(u64, Difficulty)]) -> Difficulty {
+	let sum = history.iter().skip(1)
+		.fold(U256::zero(), |sum, (_, difficulty)| sum.saturating_add(*difficulty));
+	sum / U256::from(history.len() - 1)
+}
+
+/// Linearly weighted moving average.
+///
+/// The solve time of each block is weighted by its position in the window, so recent blocks
+/// count more than old ones. Solve times are clamped to `[1, 6 * target_block_time]` to limit the
+/// effect of out of order or forged timestamps.
+pub struct Lwma;
+
+impl Retarget for Lwma {
+	fn next_difficulty(
+		history: &[(u64, Difficulty)],
+		target_block_time: u64,
+	) -> Option<Difficulty> {
+		if history.len() < 2 || target_block_time == 0 {
+			return None
+		}
+
+		let max_solve_time = target_block_time.saturating_mul(6);
+		let weighted_solve_times = history.windows(2).enumerate()
+			.fold(0u128, |sum, (i, blocks)| {
+				let solve_time = blocks[1].0.saturating_sub(blocks[0].0)
+					.max(1)
+					.min(max_solve_time);
+				sum.saturating_add((i as u128 + 1) * solve_time as u128)
+			});
+
+		// The weighted sum of solve times, should every block have been mined in exactly
+		// `target_block_time`, is `target_block_time * n * (n + 1) / 2`.
+		let n = history.len() as u128 - 1;
+		let expected = U256::from(target_block_time) * U256::from(n * (n + 1) / 2);
+
+		Some(
+			average_difficulty(history).saturating_mul(expected)
+				/ U256::from(weighted_solve_times)
+		)
+	}
+}
+
+/// DigiShield v3.
+///
+/// The time taken to mine the window is dampened by a factor of four towards its target and
+/// clamped to `[84%, 132%]` of it, so the difficulty reacts quickly but cannot swing wildly.
+pub struct DigiShield;
+
+impl Retarget for DigiShield {
+	fn next_difficulty(
+		history: &[(u64, Difficulty)],
+		target_block_time: u64,
+	) -> Option<Difficulty> {
+		if history.len() < 2 || target_block_time == 0 {
+			return None
+		}
+
+		let first = history.first().map(|(timestamp, _)| *timestamp).unwrap_or_default();
+		let last = history.last().map(|(timestamp, _)| *timestamp).unwrap_or_default();
+		let actual_timespan = last.saturating_sub(first) as i128;
+		let target_timespan = target_block_time as i128 * (history.len() as i128 - 1);
+
+		let damped_timespan = (target_timespan + (actual_timespan - target_timespan) / 4)
+			.max(target_timespan * 84 / 100)
+			.min(target_timespan * 132 / 100)
+			.max(1);
+
+		Some(
+			average_difficulty(history).saturating_mul(U256::from(target_timespan as u128))
+				/ U256::from(damped_timespan as u128)
+		)
+	}
+}
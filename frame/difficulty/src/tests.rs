@@ -0,0 +1,175 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the difficulty pallet.
+
+use crate::{mock::*, Difficulty, Retarget, Lwma, DigiShield};
+use codec::Encode;
+use frame_support::traits::OnFinalize;
+use frame_system::InitKind;
+use sp_consensus_pow::{AlgorithmId, MultiAlgorithmPreDigest, POW_ENGINE_ID};
+use sp_runtime::{Digest, DigestItem};
+
+/// `blocks` timestamps `block_time` apart, all mined at `difficulty`.
+fn history(blocks: u64, block_time: u64, difficulty: u64) -> Vec<(u64, Difficulty)> {
+	(0..blocks).map(|i| (i * block_time, Difficulty::from(difficulty))).collect()
+}
+
+/// Finalize `blocks` blocks, each `block_time` after the previous one.
+fn mine_blocks(blocks: u64, block_time: u64) {
+	mine_blocks_with(None, blocks, block_time)
+}
+
+/// Finalize `blocks` blocks, each `block_time` after the previous one, selecting `algorithm`
+/// through their pre-runtime digest.
+fn mine_blocks_with(algorithm: Option<AlgorithmId>, blocks: u64, block_time: u64) {
+	let logs = algorithm.map(|algorithm| {
+		let pre_digest = MultiAlgorithmPreDigest { algorithm, inner: None };
+		DigestItem::PreRuntime(POW_ENGINE_ID, pre_digest.encode())
+	});
+	for _ in 0..blocks {
+		let number = System::block_number() + 1;
+		let digest = Digest { logs: logs.clone().into_iter().collect() };
+		System::initialize(&number, &Default::default(), &digest, InitKind::Full);
+		Timestamp::set_timestamp(Timestamp::now() + block_time);
+		PowDifficulty::on_finalize(number);
+	}
+}
+
+#[test]
+fn retarget_needs_two_blocks() {
+	assert_eq!(Lwma::next_difficulty(&history(1, 1_000, 1_000), 1_000), None);
+	assert_eq!(DigiShield::next_difficulty(&history(1, 1_000, 1_000), 1_000), None);
+	assert_eq!(<()>::next_difficulty(&history(10, 1_000, 1_000), 1_000), None);
+}
+
+#[test]
+fn lwma_follows_block_time() {
+	let on_target = Lwma::next_difficulty(&history(11, 1_000, 1_000), 1_000);
+	assert_eq!(on_target, Some(Difficulty::from(1_000)));
+
+	let twice_as_fast = Lwma::next_difficulty(&history(11, 500, 1_000), 1_000);
+	assert_eq!(twice_as_fast, Some(Difficulty::from(2_000)));
+
+	let twice_as_slow = Lwma::next_difficulty(&history(11, 2_000, 1_000), 1_000);
+	assert_eq!(twice_as_slow, Some(Difficulty::from(500)));
+}
+
+#[test]
+fn lwma_clamps_solve_times() {
+	// Solve times are capped at six times the target.
+	let stalled = Lwma::next_difficulty(&history(11, 60_000, 6_000), 1_000);
+	assert_eq!(stalled, Some(Difficulty::from(1_000)));
+
+	// Identical timestamps count as one millisecond.
+	let instant = Lwma::next_difficulty(&history(3, 0, 1), 1_000);
+	assert_eq!(instant, Some(Difficulty::from(1_000)));
+}
+
+#[test]
+fn lwma_weights_recent_blocks() {
+	// One slow block at the start of the window moves the difficulty less than at its end.
+	let mut slow_first = history(11, 1_000, 1_000);
+	slow_first.iter_mut().skip(1).for_each(|(timestamp, _)| *timestamp += 5_000);
+	let mut slow_last = history(11, 1_000, 1_000);
+	slow_last.last_mut().unwrap().0 += 5_000;
+
+	let slow_first = Lwma::next_difficulty(&slow_first, 1_000).unwrap();
+	let slow_last = Lwma::next_difficulty(&slow_last, 1_000).unwrap();
+	assert!(slow_last < slow_first);
+	assert!(slow_first < Difficulty::from(1_000));
+}
+
+#[test]
+fn digishield_dampens_and_clamps() {
+	let on_target = DigiShield::next_difficulty(&history(11, 1_000, 1_000), 1_000);
+	assert_eq!(on_target, Some(Difficulty::from(1_000)));
+
+	// Twice as slow: the timespan is dampened to 125% of its target.
+	let twice_as_slow = DigiShield::next_difficulty(&history(11, 2_000, 1_000), 1_000);
+	assert_eq!(twice_as_slow, Some(Difficulty::from(800)));
+
+	// Ten times as slow: the dampened timespan is clamped to 132% of its target.
+	let stalled = DigiShield::next_difficulty(&history(11, 10_000, 1_320), 1_000);
+	assert_eq!(stalled, Some(Difficulty::from(1_000)));
+
+	// Instant blocks: the dampened timespan is clamped to 84% of its target.
+	let instant = DigiShield::next_difficulty(&history(11, 0, 840), 1_000);
+	assert_eq!(instant, Some(Difficulty::from(1_000)));
+}
+
+#[test]
+fn genesis_difficulty_respects_minimum() {
+	new_test_ext(1).execute_with(|| {
+		assert_eq!(PowDifficulty::difficulty(SHA3), Difficulty::from(100));
+	});
+	new_test_ext(1_000).execute_with(|| {
+		assert_eq!(PowDifficulty::difficulty(SHA3), Difficulty::from(1_000));
+	});
+}
+
+#[test]
+fn difficulty_is_retargeted_every_block() {
+	new_test_ext(1_000).execute_with(|| {
+		mine_blocks(20, 1_000);
+		assert_eq!(PowDifficulty::difficulty(SHA3), Difficulty::from(1_000));
+
+		mine_blocks(20, 500);
+		assert!(PowDifficulty::difficulty(SHA3) > Difficulty::from(1_000));
+	});
+}
+
+#[test]
+fn past_blocks_are_bounded_by_window() {
+	new_test_ext(1_000).execute_with(|| {
+		mine_blocks(5, 1_000);
+		assert_eq!(PowDifficulty::past_blocks(SHA3).len(), 5);
+
+		mine_blocks(20, 1_000);
+		let past_blocks = PowDifficulty::past_blocks(SHA3);
+		assert_eq!(past_blocks.len(), 11);
+		assert_eq!(past_blocks.last().unwrap().0, Timestamp::now());
+	});
+}
+
+#[test]
+fn difficulty_never_drops_below_minimum() {
+	new_test_ext(200).execute_with(|| {
+		mine_blocks(50, 60_000);
+		assert_eq!(PowDifficulty::difficulty(SHA3), Difficulty::from(100));
+	});
+}
+
+#[test]
+fn every_algorithm_is_retargeted_on_its_own() {
+	new_test_ext(1_000).execute_with(|| {
+		// BLAKE starts at the initial difficulty as well.
+		assert_eq!(PowDifficulty::difficulty(BLAKE), Difficulty::from(1_000));
+
+		mine_blocks_with(Some(BLAKE), 20, 500);
+		assert!(PowDifficulty::difficulty(BLAKE) > Difficulty::from(1_000));
+		assert_eq!(PowDifficulty::past_blocks(BLAKE).len(), 11);
+		// SHA3 was not mined at all.
+		assert_eq!(PowDifficulty::difficulty(SHA3), Difficulty::from(1_000));
+		assert!(PowDifficulty::past_blocks(SHA3).is_empty());
+
+		// Blocks without pre-runtime digest are mined with the default algorithm.
+		mine_blocks(20, 2_000);
+		assert!(PowDifficulty::difficulty(SHA3) < Difficulty::from(1_000));
+		assert_eq!(PowDifficulty::past_blocks(SHA3).len(), 11);
+	});
+}
@@ -0,0 +1,241 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![warn(missing_docs)]
+
+//! Node-specific RPC methods for interaction with Merkle Mountain Range pallet.
+//!
+//! The pallet keeps only the MMR peaks in on-chain storage, all the other nodes are pushed to
+//! the Off-chain DB through the Indexing API. This RPC gives the runtime read access to the
+//! Off-chain DB while generating the proof, so proofs can be generated against the MMR of any
+//! block whose state is still available, not just the best one.
+//!
+//! Note that the Off-chain DB is not fork-aware: nodes are keyed by their position in the MMR
+//! only, so proofs generated at blocks that are not finalized yet may not be valid.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::{Codec, Encode};
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+
+use pallet_mmr_primitives::{Error as MmrError, Proof};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{offchain::OffchainStorage, Bytes, ExecutionContext};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_mmr_primitives::MmrApi as MmrRuntimeApi;
+pub use self::gen_client::Client as MmrClient;
+
+mod offchain;
+
+/// Retrieved MMR leaf and its proof.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeafProof<BlockHash> {
+	/// Block hash the proof was generated for.
+	pub block_hash: BlockHash,
+	/// SCALE-encoded leaf data.
+	pub leaf: Bytes,
+	/// SCALE-encoded proof data. See [pallet_mmr_primitives::Proof].
+	pub proof: Bytes,
+}
+
+impl<BlockHash> LeafProof<BlockHash> {
+	/// Create new `LeafProof` from given concrete `leaf` and `proof`.
+	pub fn new<Leaf, MmrHash>(
+		block_hash: BlockHash,
+		leaf: Leaf,
+		proof: Proof<MmrHash>,
+	) -> Self where
+		Leaf: Encode,
+		MmrHash: Encode,
+	{
+		Self {
+			block_hash,
+			leaf: Bytes(leaf.encode()),
+			proof: Bytes(proof.encode()),
+		}
+	}
+}
+
+/// MMR RPC methods.
+#[rpc]
+pub trait MmrApi<BlockHash> {
+	/// Generate MMR proof for given leaf index.
+	///
+	/// This method calls into a runtime with MMR pallet included and attempts to generate
+	/// MMR proof for leaf at given `leaf_index`.
+	/// Optionally, a block hash at which the runtime should be queried can be specified.
+	/// The proof is generated against the MMR root of that block, so proofs about old leaves
+	/// can be requested for any historical block.
+	///
+	/// Returns the (full) leaf itself and a proof for this leaf (compact encoding, i.e. hash of
+	/// the leaf). Both parameters are SCALE-encoded.
+	#[rpc(name = "mmr_generateProof")]
+	fn generate_proof(
+		&self,
+		leaf_index: u64,
+		at: Option<BlockHash>,
+	) -> Result<LeafProof<BlockHash>>;
+}
+
+/// An implementation of MMR specific RPC methods.
+pub struct Mmr<C, B, S, Leaf, MmrHash> {
+	client: Arc<C>,
+	offchain_storage: S,
+	_marker: PhantomData<(B, Leaf, MmrHash)>,
+}
+
+impl<C, B, S, Leaf, MmrHash> Mmr<C, B, S, Leaf, MmrHash> {
+	/// Create new `Mmr` with the given reference to the client and the Off-chain DB.
+	pub fn new(client: Arc<C>, offchain_storage: S) -> Self {
+		Self {
+			client,
+			offchain_storage,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, S, Leaf, MmrHash> MmrApi<<Block as BlockT>::Hash>
+	for Mmr<C, Block, S, Leaf, MmrHash>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: MmrRuntimeApi<Block, Leaf, MmrHash>,
+	S: OffchainStorage + 'static,
+	Leaf: Codec + Send + Sync + 'static,
+	MmrHash: Codec + Send + Sync + 'static,
+{
+	fn generate_proof(
+		&self,
+		leaf_index: u64,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<LeafProof<<Block as BlockT>::Hash>> {
+		let api = self.client.runtime_api();
+		let block_hash = at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		);
+
+		// The proof is generated from within the runtime, which needs access to the MMR
+		// nodes stored in the Off-chain DB.
+		let context = ExecutionContext::OffchainCall(Some((
+			Box::new(offchain::OffchainDb::new(self.offchain_storage.clone())),
+			offchain::OffchainDb::<S>::capabilities(),
+		)));
+
+		let (leaf, proof) = api
+			.generate_proof_with_context(&BlockId::hash(block_hash), context, leaf_index)
+			.map_err(runtime_error_into_rpc_error)?
+			.map_err(mmr_error_into_rpc_error)?;
+
+		Ok(LeafProof::new(block_hash, leaf, proof))
+	}
+}
+
+const RUNTIME_ERROR: i64 = 8000;
+const MMR_ERROR: i64 = 8010;
+
+/// Converts a mmr-specific error into an RPC error.
+fn mmr_error_into_rpc_error(err: MmrError) -> Error {
+	match err {
+		MmrError::LeafNotFound => Error {
+			code: ErrorCode::ServerError(MMR_ERROR + 1),
+			message: "Leaf was not found".into(),
+			data: Some(format!("{:?}", err).into()),
+		},
+		MmrError::GenerateProof => Error {
+			code: ErrorCode::ServerError(MMR_ERROR + 2),
+			message: "Error while generating the proof".into(),
+			data: Some(format!("{:?}", err).into()),
+		},
+		_ => Error {
+			code: ErrorCode::ServerError(MMR_ERROR),
+			message: "Unexpected MMR error".into(),
+			data: Some(format!("{:?}", err).into()),
+		},
+	}
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_error(err: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Runtime trapped".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+
+	#[test]
+	fn should_serialize_leaf_proof() {
+		// given
+		let leaf = vec![1_u8, 2, 3, 4];
+		let proof = Proof {
+			leaf_index: 1,
+			leaf_count: 9,
+			items: vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+		};
+
+		let leaf_proof = LeafProof::new(H256::repeat_byte(0), leaf, proof);
+
+		// when
+		let actual = serde_json::to_string(&leaf_proof).unwrap();
+
+		// then
+		assert_eq!(
+			actual,
+			concat!(
+				r#"{"blockHash":"#,
+				r#""0x0000000000000000000000000000000000000000000000000000000000000000","#,
+				r#""leaf":"0x1001020304","#,
+				r#""proof":"0x01000000000000000900000000000000"#,
+				r#"080101010101010101010101010101010101010101010101010101010101010101"#,
+				r#"0202020202020202020202020202020202020202020202020202020202020202"}"#,
+			),
+		);
+	}
+
+	#[test]
+	fn should_deserialize_leaf_proof() {
+		// given
+		let expected = LeafProof {
+			block_hash: H256::repeat_byte(0),
+			leaf: Bytes(vec![1_u8, 2, 3, 4].encode()),
+			proof: Bytes(Proof {
+				leaf_index: 1,
+				leaf_count: 9,
+				items: vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+			}.encode()),
+		};
+
+		// when
+		let json = serde_json::to_string(&expected).unwrap();
+		let actual: LeafProof<H256> = serde_json::from_str(&json).unwrap();
+
+		// then
+		assert_eq!(actual, expected);
+	}
+}
@@ -0,0 +1,189 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read-only access to the Off-chain DB for runtime API calls.
+
+use sp_core::{
+	offchain::{
+		self, HttpError, HttpRequestId, HttpRequestStatus, OffchainStorage, OpaqueNetworkState,
+		StorageKind, Timestamp, STORAGE_PREFIX,
+	},
+	OpaquePeerId,
+};
+
+/// Offchain externalities exposing the persistent Off-chain DB storage only.
+///
+/// The MMR pallet reads the nodes it pushed through the Indexing API from the Off-chain DB
+/// when generating proofs. This is all the runtime is allowed to do from within the RPC, so the
+/// externalities are wrapped in [`offchain::LimitedExternalities`] granting just
+/// [`offchain::Capability::OffchainWorkerDbRead`]; any other call panics before reaching them.
+pub(crate) struct OffchainDb<Storage> {
+	storage: Storage,
+}
+
+impl<Storage> OffchainDb<Storage> {
+	/// Create new read-only externalities on top of the given storage.
+	pub fn new(storage: Storage) -> Self {
+		Self { storage }
+	}
+
+	/// The capabilities required by the MMR proof generation.
+	pub fn capabilities() -> offchain::Capabilities {
+		offchain::Capabilities::from(&[offchain::Capability::OffchainWorkerDbRead][..])
+	}
+}
+
+fn unavailable(name: &str) -> ! {
+	unreachable!("`{}` is guarded by `LimitedExternalities` and never reached; qed", name)
+}
+
+impl<Storage: OffchainStorage> offchain::Externalities for OffchainDb<Storage> {
+	fn is_validator(&self) -> bool {
+		unavailable("is_validator")
+	}
+
+	fn network_state(&self) -> Result<OpaqueNetworkState, ()> {
+		unavailable("network_state")
+	}
+
+	fn timestamp(&mut self) -> Timestamp {
+		unavailable("timestamp")
+	}
+
+	fn sleep_until(&mut self, _deadline: Timestamp) {
+		unavailable("sleep_until")
+	}
+
+	fn random_seed(&mut self) -> [u8; 32] {
+		unavailable("random_seed")
+	}
+
+	fn local_storage_set(&mut self, _kind: StorageKind, _key: &[u8], _value: &[u8]) {
+		unavailable("local_storage_set")
+	}
+
+	fn local_storage_clear(&mut self, _kind: StorageKind, _key: &[u8]) {
+		unavailable("local_storage_clear")
+	}
+
+	fn local_storage_compare_and_set(
+		&mut self,
+		_kind: StorageKind,
+		_key: &[u8],
+		_old_value: Option<&[u8]>,
+		_new_value: &[u8],
+	) -> bool {
+		unavailable("local_storage_compare_and_set")
+	}
+
+	fn local_storage_get(&mut self, kind: StorageKind, key: &[u8]) -> Option<Vec<u8>> {
+		match kind {
+			StorageKind::PERSISTENT => self.storage.get(STORAGE_PREFIX, key),
+			// The local storage is not shared with block import, so the MMR nodes
+			// never end up there.
+			StorageKind::LOCAL => None,
+		}
+	}
+
+	fn http_request_start(
+		&mut self,
+		_method: &str,
+		_uri: &str,
+		_meta: &[u8],
+	) -> Result<HttpRequestId, ()> {
+		unavailable("http_request_start")
+	}
+
+	fn http_request_add_header(
+		&mut self,
+		_request_id: HttpRequestId,
+		_name: &str,
+		_value: &str,
+	) -> Result<(), ()> {
+		unavailable("http_request_add_header")
+	}
+
+	fn http_request_write_body(
+		&mut self,
+		_request_id: HttpRequestId,
+		_chunk: &[u8],
+		_deadline: Option<Timestamp>,
+	) -> Result<(), HttpError> {
+		unavailable("http_request_write_body")
+	}
+
+	fn http_response_wait(
+		&mut self,
+		_ids: &[HttpRequestId],
+		_deadline: Option<Timestamp>,
+	) -> Vec<HttpRequestStatus> {
+		unavailable("http_response_wait")
+	}
+
+	fn http_response_headers(&mut self, _request_id: HttpRequestId) -> Vec<(Vec<u8>, Vec<u8>)> {
+		unavailable("http_response_headers")
+	}
+
+	fn http_response_read_body(
+		&mut self,
+		_request_id: HttpRequestId,
+		_buffer: &mut [u8],
+		_deadline: Option<Timestamp>,
+	) -> Result<usize, HttpError> {
+		unavailable("http_response_read_body")
+	}
+
+	fn set_authorized_nodes(&mut self, _nodes: Vec<OpaquePeerId>, _authorized_only: bool) {
+		unavailable("set_authorized_nodes")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::offchain::{Externalities, storage::InMemOffchainStorage};
+
+	#[test]
+	fn reads_persistent_storage() {
+		let mut storage = InMemOffchainStorage::default();
+		storage.set(STORAGE_PREFIX, b"mmr-node", b"hash");
+		storage.set(b"other-prefix", b"other", b"value");
+
+		let mut db = offchain::LimitedExternalities::new(
+			OffchainDb::<InMemOffchainStorage>::capabilities(),
+			OffchainDb::new(storage),
+		);
+
+		assert_eq!(
+			db.local_storage_get(StorageKind::PERSISTENT, b"mmr-node"),
+			Some(b"hash".to_vec()),
+		);
+		assert_eq!(db.local_storage_get(StorageKind::PERSISTENT, b"other"), None);
+		assert_eq!(db.local_storage_get(StorageKind::LOCAL, b"mmr-node"), None);
+	}
+
+	#[test]
+	#[should_panic(expected = "Accessing a forbidden API: local_storage_set.")]
+	fn writes_are_forbidden() {
+		let mut db = offchain::LimitedExternalities::new(
+			OffchainDb::<InMemOffchainStorage>::capabilities(),
+			OffchainDb::new(InMemOffchainStorage::default()),
+		);
+
+		db.local_storage_set(StorageKind::PERSISTENT, b"mmr-node", b"hash");
+	}
+}
@@ -232,11 +232,15 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 	/// It will return `Ok(())` if the proof is valid
 	/// and an `Err(..)` if MMR is inconsistent (some leaves are missing)
 	/// or the proof is invalid.
+	///
+	/// Only the peaks of the current MMR are kept on-chain, so the proof must have been
+	/// generated against the current MMR. Use [`verify_leaf_proof`] with a known root to verify
+	/// proofs against older MMRs.
 	pub fn verify_leaf(
 		leaf: LeafOf<T, I>,
 		proof: primitives::Proof<<T as Config<I>>::Hash>,
 	) -> Result<(), primitives::Error> {
-		if proof.leaf_count > Self::mmr_leaves()
+		if proof.leaf_count != Self::mmr_leaves()
 			|| proof.leaf_count == 0
 			|| proof.items.len() as u32 > mmr::utils::NodesUtils::new(proof.leaf_count).depth()
 		{
@@ -18,7 +18,7 @@
 //! A MMR storage implementations.
 
 use codec::Encode;
-use crate::mmr::{NodeOf, Node};
+use crate::mmr::{NodeOf, Node, utils::NodesUtils};
 use crate::{NumberOfLeaves, Nodes, Module, Config, Instance, primitives};
 use frame_support::{StorageMap, StorageValue};
 #[cfg(not(feature = "std"))]
@@ -28,7 +28,8 @@ use sp_std::prelude::Vec;
 ///
 /// Allows appending new items to the MMR and proof verification.
 /// MMR nodes are appended to two different storages:
-/// 1. We add nodes (leaves) hashes to the on-chain storge (see [crate::Nodes]).
+/// 1. We add the hashes of the peaks to the on-chain storage (see [crate::Nodes]) and prune
+///    the nodes which stop being peaks.
 /// 2. We add full leaves (and all inner nodes as well) into the `IndexingAPI` during block
 ///    processing, so the values end up in the Offchain DB if indexing is enabled.
 pub struct RuntimeStorage;
@@ -85,27 +86,37 @@ impl<T, I, L> mmr_lib::MMRStore<NodeOf<T, I, L>> for Storage<RuntimeStorage, T,
 	}
 
 	fn append(&mut self, pos: u64, elems: Vec<NodeOf<T, I, L>>) -> mmr_lib::Result<()> {
-		let mut leaves = crate::NumberOfLeaves::<I>::get();
-		let mut size = crate::mmr::utils::NodesUtils::new(leaves).size();
+		let leaves = crate::NumberOfLeaves::<I>::get();
+		let size = NodesUtils::new(leaves).size();
 		if pos != size {
 			return Err(mmr_lib::Error::InconsistentStore);
 		}
 
+		let new_leaves = leaves + elems.iter()
+			.filter(|elem| if let Node::Data(..) = elem { true } else { false })
+			.count() as u64;
+		let peaks_before = NodesUtils::new(leaves).peaks();
+		let peaks_after = NodesUtils::new(new_leaves).peaks();
+
+		let mut node_index = size;
 		for elem in elems {
-			// on-chain we only store the hash (even if it's a leaf)
-			<Nodes<T, I>>::insert(size, elem.hash());
+			// On-chain we only store the peaks, and only their hash (even if it's a leaf).
+			if peaks_after.contains(&node_index) {
+				<Nodes<T, I>>::insert(node_index, elem.hash());
+			}
 			// Indexing API is used to store the full leaf content.
 			elem.using_encoded(|elem| {
-				sp_io::offchain_index::set(&Module::<T, I>::offchain_key(size), elem)
+				sp_io::offchain_index::set(&Module::<T, I>::offchain_key(node_index), elem)
 			});
-			size += 1;
-
-			if let Node::Data(..) = elem {
-				leaves += 1;
-			}
+			node_index += 1;
 		}
 
-		NumberOfLeaves::<I>::put(leaves);
+		NumberOfLeaves::<I>::put(new_leaves);
+
+		// Prune the peaks which got merged into the new ones.
+		for peak in peaks_before.into_iter().filter(|peak| !peaks_after.contains(peak)) {
+			<Nodes<T, I>>::remove(peak);
+		}
 
 		Ok(())
 	}
@@ -17,6 +17,9 @@
 
 //! Merkle Mountain Range utilities.
 
+#[cfg(not(feature = "std"))]
+use sp_std::prelude::Vec;
+
 /// MMR nodes & size -related utilities.
 pub struct NodesUtils {
 	no_of_leaves: u64,
@@ -43,6 +46,19 @@ impl NodesUtils {
 		2 * self.no_of_leaves - self.number_of_peaks()
 	}
 
+	/// Calculate the positions of the peaks, from the leftmost (highest) one to the rightmost.
+	pub fn peaks(&self) -> Vec<u64> {
+		let mut peaks = Vec::with_capacity(self.number_of_peaks() as usize);
+		let mut offset = 0;
+		// Every bit set in the number of leaves stands for a perfect tree of `2^height` leaves.
+		for height in (0..64).rev().filter(|height| self.no_of_leaves & (1 << height) != 0) {
+			let tree_size = (1u64 << (height + 1)) - 1;
+			peaks.push(offset + tree_size - 1);
+			offset += tree_size;
+		}
+		peaks
+	}
+
 	/// Calculate maximal depth of the MMR.
 	pub fn depth(&self) -> u32 {
 		if self.no_of_leaves == 0 {
@@ -92,6 +108,26 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn should_calculate_peaks_correctly() {
+		assert_eq!(
+			vec![0, 1, 2, 3, 4, 7, 8, 21]
+				.into_iter()
+				.map(|n| NodesUtils::new(n).peaks())
+				.collect::<Vec<_>>(),
+			vec![
+				vec![],
+				vec![0],
+				vec![2],
+				vec![2, 3],
+				vec![6],
+				vec![6, 9, 10],
+				vec![14],
+				vec![30, 37, 38],
+			]
+		);
+	}
+
 	#[test]
 	fn should_calculate_the_size_correctly() {
 		let _ = env_logger::try_init();
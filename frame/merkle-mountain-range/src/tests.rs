@@ -18,7 +18,7 @@
 use crate::*;
 use crate::mock::*;
 
-use frame_support::traits::OnInitialize;
+use frame_support::{traits::OnInitialize, IterableStorageMap};
 use sp_core::{
 	H256,
 	offchain::{
@@ -119,6 +119,7 @@ fn should_append_to_mmr_when_on_initialize_is_called() {
 
 		// then
 		assert_eq!(crate::NumberOfLeaves::<DefaultInstance>::get(), 2);
+		// the leaves are pruned on-chain, only the peak is kept
 		assert_eq!((
 			crate::Nodes::<Test>::get(0),
 			crate::Nodes::<Test>::get(1),
@@ -126,8 +127,8 @@ fn should_append_to_mmr_when_on_initialize_is_called() {
 			crate::Nodes::<Test>::get(3),
 			crate::RootHash::<Test>::get(),
 		), (
-			Some(hex("4320435e8c3318562dba60116bdbcc0b82ffcecb9bb39aae3300cfda3ad0b8b0")),
-			Some(hex("ad4cbc033833612ccd4626d5f023b9dfc50a35e838514dd1f3c86f8506728705")),
+			None,
+			None,
 			Some(hex("672c04a9cd05a644789d769daa552d35d8de7c33129f8a7cbf49e595234c4854")),
 			None,
 			hex("672c04a9cd05a644789d769daa552d35d8de7c33129f8a7cbf49e595234c4854"),
@@ -165,13 +166,29 @@ fn should_construct_larger_mmr_correctly() {
 			crate::Nodes::<Test>::get(10),
 			crate::RootHash::<Test>::get(),
 		), (
-			Some(hex("4320435e8c3318562dba60116bdbcc0b82ffcecb9bb39aae3300cfda3ad0b8b0")),
+			None,
 			Some(hex("611c2174c6164952a66d985cfe1ec1a623794393e3acff96b136d198f37a648c")),
 			hex("e45e25259f7930626431347fa4dd9aae7ac83b4966126d425ca70ab343709d2c"),
 		));
 	});
 }
 
+#[test]
+fn should_only_keep_peaks_on_chain() {
+	let _ = env_logger::try_init();
+	new_test_ext().execute_with(|| {
+		for leaves in 1..=21u64 {
+			// when
+			new_block();
+
+			// then
+			let mut nodes = crate::Nodes::<Test>::iter().map(|(pos, _)| pos).collect::<Vec<_>>();
+			nodes.sort();
+			assert_eq!(nodes, mmr::utils::NodesUtils::new(leaves).peaks());
+		}
+	});
+}
+
 #[test]
 fn should_generate_proofs_correctly() {
 	let _ = env_logger::try_init();
@@ -258,6 +275,41 @@ fn should_verify() {
 	});
 }
 
+#[test]
+fn should_generate_proofs_at_historical_blocks() {
+	let _ = env_logger::try_init();
+
+	// The node keeps appending to the Off-chain DB as the chain grows.
+	let mut ext = new_test_ext();
+	ext.execute_with(|| init_chain(7));
+	ext.persist_offchain_overlay();
+
+	// The state of an older block only has the peaks of its own MMR, while the proof is
+	// generated from the nodes in the (newer) Off-chain DB, as the RPC does.
+	let mut historical = new_test_ext();
+	historical.execute_with(|| init_chain(5));
+	let (offchain, _offchain_state) = TestOffchainExt::with_offchain_db(ext.offchain_db());
+	historical.register_extension(OffchainExt::new(offchain));
+
+	historical.execute_with(|| {
+		// when
+		let (leaf, proof) = crate::Module::<Test>::generate_proof(3).unwrap();
+
+		// then
+		assert_eq!(proof.leaf_count, 5);
+		assert_eq!(crate::Module::<Test>::verify_leaf(leaf.clone(), proof.clone()), Ok(()));
+		let root = crate::Module::<Test>::mmr_root_hash();
+		let leaf = crate::primitives::DataOrHash::Data(leaf);
+		assert_eq!(
+			crate::verify_leaf_proof::<<Test as Config>::Hashing, _>(root, leaf, proof),
+			Ok(()),
+		);
+
+		// leaves added after the block are unknown to its MMR
+		assert!(crate::Module::<Test>::generate_proof(5).is_err());
+	});
+}
+
 #[test]
 fn verification_should_be_stateless() {
 	let _ = env_logger::try_init();
@@ -283,7 +335,7 @@ fn verification_should_be_stateless() {
 }
 
 #[test]
-fn should_verify_on_the_next_block_since_there_is_no_pruning_yet() {
+fn should_not_verify_against_older_mmr_since_its_peaks_are_pruned() {
 	let _ = env_logger::try_init();
 	let mut ext = new_test_ext();
 	// given
@@ -295,9 +347,19 @@ fn should_verify_on_the_next_block_since_there_is_no_pruning_yet() {
 	ext.execute_with(|| {
 		// when
 		let (leaf, proof5) = crate::Module::<Test>::generate_proof(5).unwrap();
+		let root = crate::Module::<Test>::mmr_root_hash();
 		new_block();
 
 		// then
-		assert_eq!(crate::Module::<Test>::verify_leaf(leaf, proof5), Ok(()));
+		assert_eq!(
+			crate::Module::<Test>::verify_leaf(leaf.clone(), proof5.clone()),
+			Err(primitives::Error::Verify),
+		);
+		// the proof is still valid against the old root
+		let leaf = crate::primitives::DataOrHash::Data(leaf);
+		assert_eq!(
+			crate::verify_leaf_proof::<<Test as Config>::Hashing, _>(root, leaf, proof5),
+			Ok(()),
+		);
 	});
 }
@@ -22,13 +22,50 @@
 use super::*;
 use frame_system::RawOrigin;
 use frame_benchmarking::{benchmarks, account, impl_benchmark_test_suite};
-use sp_runtime::traits::Bounded;
+use sp_runtime::{MultiSignature, MultiSigner, traits::{Bounded, IdentifyAccount}};
+use sp_core::crypto::{AccountId32, KeyTypeId};
 use core::convert::TryInto;
 
 use crate::Module as Multisig;
 
 const SEED: u32 = 0;
 
+/// Signature types which can sign approval payloads in the `as_multi_signed` benchmark.
+pub trait BenchmarkSignature<AccountId>: Sized {
+	/// Create a new signatory, whose key can sign.
+	fn new_signer(index: u32) -> AccountId;
+	/// Sign `payload` with the key of `signer`, created by `new_signer`.
+	fn sign(signer: &AccountId, payload: &[u8]) -> Self;
+}
+
+const BENCH_KEY_TYPE: KeyTypeId = KeyTypeId(*b"msig");
+
+impl BenchmarkSignature<AccountId32> for MultiSignature {
+	fn new_signer(_index: u32) -> AccountId32 {
+		let public = sp_io::crypto::sr25519_generate(BENCH_KEY_TYPE, None);
+		MultiSigner::from(public).into_account()
+	}
+
+	fn sign(signer: &AccountId32, payload: &[u8]) -> Self {
+		let raw: &[u8; 32] = signer.as_ref();
+		let public = sp_core::sr25519::Public::from_raw(*raw);
+		sp_io::crypto::sr25519_sign(BENCH_KEY_TYPE, &public, payload)
+			.expect("the key was generated by `new_signer`; qed")
+			.into()
+	}
+}
+
+#[cfg(test)]
+impl BenchmarkSignature<u64> for sp_runtime::testing::TestSignature {
+	fn new_signer(index: u32) -> u64 {
+		1_000 + index as u64
+	}
+
+	fn sign(signer: &u64, payload: &[u8]) -> Self {
+		sp_runtime::testing::TestSignature(*signer, payload.to_vec())
+	}
+}
+
 fn setup_multi<T: Config>(s: u32, z: u32)
 	-> Result<(Vec<T::AccountId>, Vec<u8>), &'static str>
 {
@@ -48,6 +85,8 @@ fn setup_multi<T: Config>(s: u32, z: u32)
 }
 
 benchmarks! {
+	where_clause { where T::OffchainSignature: BenchmarkSignature<T::AccountId> }
+
 	as_multi_threshold_1 {
 		// Transaction Length
 		let z in 0 .. 10_000;
@@ -296,6 +335,31 @@ benchmarks! {
 		assert!(!Multisigs::<T>::contains_key(multi_account_id, call_hash));
 		assert!(!Calls::<T>::contains_key(call_hash));
 	}
+
+	as_multi_signed {
+		// Signatories, need at least 2 people, all of them sign
+		let s in 2 .. T::MaxSignatories::get() as u32;
+		// Transaction Length
+		let z in 0 .. 10_000;
+		let mut signatories = (0 .. s)
+			.map(|i| T::OffchainSignature::new_signer(i))
+			.collect::<Vec<T::AccountId>>();
+		signatories.sort();
+		let call: <T as Config>::Call = frame_system::Call::<T>::remark(vec![0; z as usize]).into();
+		let call_hash = call.using_encoded(blake2_256);
+		let multi_account_id = Multisig::<T>::multi_account_id(&signatories, s.try_into().unwrap());
+		let payload = Multisig::<T>::signed_approval_payload(&multi_account_id, &call_hash, 0);
+		let signatures = signatories.iter()
+			.map(|who| (who.clone(), T::OffchainSignature::sign(who, &payload)))
+			.collect::<Vec<_>>();
+		let caller = signatories.pop().ok_or("signatories should have len 2 or more")?;
+		// Whitelist caller account from further DB operations.
+		let caller_key = frame_system::Account::<T>::hashed_key_for(&caller);
+		frame_benchmarking::benchmarking::add_to_whitelist(caller_key.into());
+	}: _(RawOrigin::Signed(caller), s as u16, signatories, Box::new(call), signatures)
+	verify {
+		assert_eq!(Nonces::<T>::get(&multi_account_id), 1);
+	}
 }
 
 impl_benchmark_test_suite!(
@@ -31,6 +31,12 @@
 //! operation. This is useful for multisig wallets where cryptographic threshold signatures are
 //! not available or desired.
 //!
+//! Signatories who coordinate off-chain may instead sign the approval payload
+//! `(genesis_hash, id, call_hash, nonce)`, where `id` is the multisig account, with their keys
+//! and have any one of them submit all the signatures at once. This needs neither approval
+//! storage nor a deposit. The per-multisig nonce prevents the signatures from being replayed
+//! and the genesis hash prevents them from being used on another chain.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
@@ -39,6 +45,8 @@
 //!   number of signed origins.
 //! * `approve_as_multi` - Approve a call from a composite origin.
 //! * `cancel_as_multi` - Cancel a call from a composite origin.
+//! * `as_multi_signed` - Dispatch a call from a composite origin, given the off-chain signatures
+//!   of a threshold of its signatories.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Config`]: ./trait.Config.html
@@ -59,7 +67,10 @@ use frame_support::{traits::{Get, ReservableCurrency, Currency},
 	dispatch::{DispatchResultWithPostInfo, DispatchErrorWithPostInfo, PostDispatchInfo},
 };
 use frame_system::{self as system, ensure_signed, RawOrigin};
-use sp_runtime::{DispatchError, DispatchResult, traits::{Dispatchable, Zero}};
+use sp_runtime::{
+	DispatchError, DispatchResult,
+	traits::{Dispatchable, Zero, Verify, IdentifyAccount},
+};
 pub use weights::WeightInfo;
 
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -94,6 +105,17 @@ pub trait Config: frame_system::Config {
 	/// The maximum amount of signatories allowed in the multisig.
 	type MaxSignatories: Get<u16>;
 
+	/// Off-chain signature type used to approve a multisig operation with `as_multi_signed`.
+	///
+	/// Can verify whether an `Self::OffchainPublic` created a signature. With `MultiSignature`,
+	/// any of the supported schemes may be used, including Sm2.
+	type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+
+	/// Off-chain public key.
+	///
+	/// Must identify as an on-chain `Self::AccountId`.
+	type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -130,6 +152,10 @@ decl_storage! {
 			=> Option<Multisig<T::BlockNumber, BalanceOf<T>, T::AccountId>>;
 
 		pub Calls: map hasher(identity) [u8; 32] => Option<(OpaqueCall, T::AccountId, BalanceOf<T>)>;
+
+		/// The nonce of each multisig account, to be included in the payload signed for
+		/// `as_multi_signed`.
+		pub Nonces: map hasher(twox_64_concat) T::AccountId => u64;
 	}
 }
 
@@ -163,6 +189,12 @@ decl_error! {
 		MaxWeightTooLow,
 		/// The data to be stored is already stored.
 		AlreadyStored,
+		/// Fewer than `threshold` signatures were provided.
+		NotEnoughSignatures,
+		/// A signature was provided by an account which is not a signatory.
+		UnknownSignatory,
+		/// A signature does not match the approval payload.
+		InvalidSignature,
 	}
 }
 
@@ -182,6 +214,9 @@ decl_event! {
 		MultisigExecuted(AccountId, Timepoint<BlockNumber>, AccountId, CallHash, DispatchResult),
 		/// A multisig operation has been cancelled. \[cancelling, timepoint, multisig, call_hash\]
 		MultisigCancelled(AccountId, Timepoint<BlockNumber>, AccountId, CallHash),
+		/// A multisig operation approved by off-chain signatures has been executed.
+		/// \[submitter, multisig, nonce, call_hash, result\]
+		SignedMultisigExecuted(AccountId, AccountId, u64, CallHash, DispatchResult),
 	}
 }
 
@@ -443,6 +478,88 @@ decl_module! {
 			Self::deposit_event(RawEvent::MultisigCancelled(who, timepoint, id, call_hash));
 			Ok(())
 		}
+
+		/// Immediately dispatch a multi-signature call, approved by the off-chain signatures of
+		/// `threshold` of the signatories.
+		///
+		/// Each signature is over the payload `(genesis_hash, id, call_hash, nonce)`, see
+		/// `signed_approval_payload`, where `id` is the multisig account and `nonce` its current
+		/// nonce.
+		/// The nonce is bumped by the dispatch, so the signatures can only be used once. No
+		/// approval is stored and no deposit is taken.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The total number of approvals for this dispatch before it is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve this
+		/// dispatch. May not be empty.
+		/// - `call`: The call to be executed.
+		/// - `signatures`: The signatories and their signatures of the approval payload, sorted
+		/// by signatory. There must be at least `threshold` of them; the sender needs to sign as
+		/// well to be counted.
+		///
+		/// On success, result is `Ok` and the result from the interior call may be found in the
+		/// deposited `SignedMultisigExecuted` event.
+		///
+		/// # <weight>
+		/// - `O(S + Z + Call)`, where `S` is the number of signatures.
+		/// - One encode & hash, both of complexity `O(S)`.
+		/// - One call encode & hash, both of complexity `O(Z)` where `Z` is tx-len.
+		/// - `S` signature verifications.
+		/// - One event.
+		/// - The weight of the `call`.
+		/// -------------------------------
+		/// - DB Weight:
+		///     - Read: Nonces
+		///     - Write: Nonces
+		/// - Plus Call Weight
+		/// # </weight>
+		#[weight = {
+			let dispatch_info = call.get_dispatch_info();
+			(
+				T::WeightInfo::as_multi_signed(
+					signatures.len() as u32,
+					call.using_encoded(|c| c.len() as u32),
+				)
+				.saturating_add(dispatch_info.weight)
+				// AccountData for inner call origin accountdata.
+				.saturating_add(T::DbWeight::get().reads_writes(1, 1)),
+				dispatch_info.class,
+			)
+		}]
+		fn as_multi_signed(origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			call: Box<<T as Config>::Call>,
+			signatures: Vec<(T::AccountId, T::OffchainSignature)>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(threshold >= 2, Error::<T>::MinimumThreshold);
+			let max_sigs = T::MaxSignatories::get() as usize;
+			ensure!(!other_signatories.is_empty(), Error::<T>::TooFewSignatories);
+			ensure!(other_signatories.len() < max_sigs, Error::<T>::TooManySignatories);
+			let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
+
+			let id = Self::multi_account_id(&signatories, threshold);
+
+			let (call_hash, call_len) = call.using_encoded(|c| (blake2_256(c), c.len()));
+			let nonce = Nonces::<T>::get(&id);
+			let payload = Self::signed_approval_payload(&id, &call_hash, nonce);
+			Self::ensure_approved_by(&signatories, threshold, &payload, &signatures)?;
+
+			// Bump the nonce before executing the call, so that the signatures cannot be used
+			// again, not even by the call itself.
+			Nonces::<T>::insert(&id, nonce.saturating_add(1));
+
+			let result = call.dispatch(RawOrigin::Signed(id.clone()).into());
+			Self::deposit_event(RawEvent::SignedMultisigExecuted(
+				who, id, nonce, call_hash, result.map(|_| ()).map_err(|e| e.error)
+			));
+			Ok(get_result_weight(result).map(|actual_weight|
+				T::WeightInfo::as_multi_signed(signatures.len() as u32, call_len as u32)
+					.saturating_add(actual_weight)
+			).into())
+		}
 	}
 }
 
@@ -456,6 +573,39 @@ impl<T: Config> Module<T> {
 		T::AccountId::decode(&mut &entropy[..]).unwrap_or_default()
 	}
 
+	/// The payload that the signatories of the multisig account `id` sign off-chain to approve
+	/// the call with hash `call_hash` through `as_multi_signed`, given the account's current
+	/// `nonce`.
+	///
+	/// The payload includes the genesis hash, so that approvals cannot be replayed on another
+	/// chain where the same multisig account exists.
+	pub fn signed_approval_payload(id: &T::AccountId, call_hash: &[u8; 32], nonce: u64) -> Vec<u8> {
+		let genesis_hash = system::Module::<T>::block_hash(T::BlockNumber::zero());
+		(genesis_hash, id, call_hash, nonce).encode()
+	}
+
+	/// Check that `signatures` are at least `threshold` valid signatures of `payload` by
+	/// distinct members of the sorted `signatories`.
+	fn ensure_approved_by(
+		signatories: &[T::AccountId],
+		threshold: u16,
+		payload: &[u8],
+		signatures: &[(T::AccountId, T::OffchainSignature)],
+	) -> DispatchResult {
+		ensure!(signatures.len() >= threshold as usize, Error::<T>::NotEnoughSignatures);
+		let mut maybe_last = None;
+		for (signer, signature) in signatures.iter() {
+			// Sorted signers cannot approve twice.
+			if let Some(last) = maybe_last {
+				ensure!(last < signer, Error::<T>::SignatoriesOutOfOrder);
+			}
+			ensure!(signatories.binary_search(signer).is_ok(), Error::<T>::UnknownSignatory);
+			ensure!(signature.verify(payload, signer), Error::<T>::InvalidSignature);
+			maybe_last = Some(signer);
+		}
+		Ok(())
+	}
+
 	fn operate(
 		who: T::AccountId,
 		threshold: u16,
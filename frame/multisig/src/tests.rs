@@ -25,7 +25,10 @@ use frame_support::{
 	assert_ok, assert_noop, parameter_types, traits::Filter,
 };
 use sp_core::H256;
-use sp_runtime::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	testing::{Header, TestSignature, UintAuthorityId},
+};
 use crate as pallet_multisig;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -107,6 +110,8 @@ impl Config for Test {
 	type DepositBase = DepositBase;
 	type DepositFactor = DepositFactor;
 	type MaxSignatories = MaxSignatories;
+	type OffchainSignature = TestSignature;
+	type OffchainPublic = UintAuthorityId;
 	type WeightInfo = ();
 }
 
@@ -559,3 +564,247 @@ fn multisig_handles_no_preimage_after_all_approve() {
 		assert_eq!(Balances::free_balance(6), 15);
 	});
 }
+
+fn sign(who: u64, multi: u64, call: &Call, nonce: u64) -> (u64, TestSignature) {
+	let payload = Multisig::signed_approval_payload(&multi, &call.using_encoded(blake2_256), nonce);
+	(who, TestSignature(who, payload))
+}
+
+#[test]
+fn multisig_signed_works() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let call = Call::Balances(BalancesCall::transfer(6, 10));
+		let signatures = vec![sign(1, multi, &call, 0), sign(3, multi, &call, 0)];
+		// Anyone in the multisig may submit, also without signing.
+		assert_ok!(Multisig::as_multi_signed(
+			Origin::signed(2), 2, vec![1, 3], Box::new(call.clone()), signatures.clone()
+		));
+		assert_eq!(Balances::free_balance(6), 10);
+		assert_eq!(Nonces::<Test>::get(multi), 1);
+		// Nothing is stored or reserved.
+		assert_eq!(Multisigs::<Test>::iter().count(), 0);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		expect_event(RawEvent::SignedMultisigExecuted(
+			2, multi, 0, call.using_encoded(blake2_256), Ok(())
+		));
+
+		// Signatures cannot be replayed.
+		assert_noop!(
+			Multisig::as_multi_signed(
+				Origin::signed(2), 2, vec![1, 3], Box::new(call.clone()), signatures
+			),
+			Error::<Test>::InvalidSignature,
+		);
+		let signatures = vec![sign(1, multi, &call, 1), sign(2, multi, &call, 1)];
+		assert_ok!(Multisig::as_multi_signed(
+			Origin::signed(3), 2, vec![1, 2], Box::new(call), signatures
+		));
+		assert_eq!(Balances::free_balance(6), 15);
+		assert_eq!(Nonces::<Test>::get(multi), 2);
+	});
+}
+
+#[test]
+fn multisig_signed_reports_call_failure() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		let call = Call::Balances(BalancesCall::transfer(6, 10));
+		let signatures = vec![sign(1, multi, &call, 0), sign(2, multi, &call, 0)];
+		assert_ok!(Multisig::as_multi_signed(
+			Origin::signed(1), 2, vec![2, 3], Box::new(call.clone()), signatures
+		));
+		expect_event(RawEvent::SignedMultisigExecuted(
+			1, multi, 0, call.using_encoded(blake2_256),
+			Err(BalancesError::<Test, _>::InsufficientBalance.into()),
+		));
+		// The approval is used up nonetheless.
+		assert_eq!(Nonces::<Test>::get(multi), 1);
+	});
+}
+
+#[test]
+fn multisig_signed_checks_signatures() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		let call = Call::Balances(BalancesCall::transfer(6, 10));
+		let other_call = Call::Balances(BalancesCall::transfer(6, 15));
+		let other_multi = Multisig::multi_account_id(&[1, 2, 3][..], 3);
+		let submit = |signatures| Multisig::as_multi_signed(
+			Origin::signed(1), 2, vec![2, 3], Box::new(call.clone()), signatures
+		);
+
+		assert_noop!(submit(vec![sign(1, multi, &call, 0)]), Error::<Test>::NotEnoughSignatures);
+		assert_noop!(
+			submit(vec![sign(1, multi, &call, 0), sign(1, multi, &call, 0)]),
+			Error::<Test>::SignatoriesOutOfOrder,
+		);
+		assert_noop!(
+			submit(vec![sign(2, multi, &call, 0), sign(1, multi, &call, 0)]),
+			Error::<Test>::SignatoriesOutOfOrder,
+		);
+		assert_noop!(
+			submit(vec![sign(1, multi, &call, 0), sign(4, multi, &call, 0)]),
+			Error::<Test>::UnknownSignatory,
+		);
+		assert_noop!(
+			submit(vec![sign(1, multi, &call, 0), sign(2, multi, &other_call, 0)]),
+			Error::<Test>::InvalidSignature,
+		);
+		assert_noop!(
+			submit(vec![sign(1, multi, &call, 0), sign(2, other_multi, &call, 0)]),
+			Error::<Test>::InvalidSignature,
+		);
+		assert_noop!(
+			submit(vec![sign(1, multi, &call, 0), (2, TestSignature(3, vec![]))]),
+			Error::<Test>::InvalidSignature,
+		);
+		assert_noop!(
+			Multisig::as_multi_signed(
+				Origin::signed(1), 1, vec![2, 3], Box::new(call.clone()),
+				vec![sign(1, multi, &call, 0)],
+			),
+			Error::<Test>::MinimumThreshold,
+		);
+		assert_noop!(
+			Multisig::as_multi_signed(
+				Origin::signed(4), 2, vec![2, 3], Box::new(call.clone()), vec![]
+			),
+			Error::<Test>::NotEnoughSignatures,
+		);
+	});
+}
+
+#[test]
+fn signed_approval_payload_is_bound_to_the_chain() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		let call = Call::Balances(BalancesCall::transfer(6, 10));
+		let signatures = vec![sign(1, multi, &call, 0), sign(2, multi, &call, 0)];
+
+		// The same approvals are not valid on a chain with another genesis hash.
+		frame_system::BlockHash::<Test>::insert(0, H256::repeat_byte(1));
+		assert_noop!(
+			Multisig::as_multi_signed(Origin::signed(1), 2, vec![2, 3], Box::new(call), signatures),
+			Error::<Test>::InvalidSignature,
+		);
+	});
+}
+
+/// A runtime verifying approvals with `MultiSignature`, as the node does.
+mod multi_signature {
+	use super::*;
+	use sp_core::{sm2, Pair};
+	use sp_runtime::{AccountId32, MultiSignature, MultiSigner};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Module, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+			Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
+		}
+	);
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = ();
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Call = Call;
+		type Hashing = BlakeTwo256;
+		type AccountId = AccountId32;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+	}
+	impl pallet_balances::Config for Test {
+		type MaxLocks = ();
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type WeightInfo = ();
+	}
+	impl Config for Test {
+		type Event = Event;
+		type Call = Call;
+		type Currency = Balances;
+		type DepositBase = DepositBase;
+		type DepositFactor = DepositFactor;
+		type MaxSignatories = MaxSignatories;
+		type OffchainSignature = MultiSignature;
+		type OffchainPublic = MultiSigner;
+		type WeightInfo = ();
+	}
+
+	fn account(pair: &sm2::Pair) -> AccountId32 {
+		MultiSigner::from(pair.public()).into_account()
+	}
+
+	#[test]
+	fn multisig_signed_works_with_sm2_signatures() {
+		let pairs = (1..=3u8).map(|i| sm2::Pair::from_seed(&[i; 32])).collect::<Vec<_>>();
+		let mut who = pairs.iter().map(account).collect::<Vec<_>>();
+		who.sort();
+		let pair_of = |a: &AccountId32| pairs.iter().find(|p| &account(p) == a).unwrap();
+
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(Multisig::multi_account_id(&who, 2), 10)],
+		}.assimilate_storage(&mut t).unwrap();
+		sp_io::TestExternalities::new(t).execute_with(|| {
+			System::set_block_number(1);
+			let multi = Multisig::multi_account_id(&who, 2);
+			let dest = AccountId32::new([42; 32]);
+			let call = Call::Balances(BalancesCall::transfer(dest.clone(), 5));
+			let payload = Multisig::signed_approval_payload(
+				&multi, &call.using_encoded(blake2_256), 0,
+			);
+			let sign = |a: &AccountId32| {
+				(a.clone(), MultiSignature::Sm2(pair_of(a).sign(&payload)))
+			};
+
+			// A signature by a key which is not the signatory's is rejected.
+			assert_noop!(
+				Multisig::as_multi_signed(
+					Origin::signed(who[0].clone()), 2, vec![who[1].clone(), who[2].clone()],
+					Box::new(call.clone()),
+					vec![sign(&who[0]), (who[1].clone(), sign(&who[2]).1)],
+				),
+				Error::<Test>::InvalidSignature,
+			);
+
+			assert_ok!(Multisig::as_multi_signed(
+				Origin::signed(who[0].clone()), 2, vec![who[1].clone(), who[2].clone()],
+				Box::new(call),
+				vec![sign(&who[0]), sign(&who[2])],
+			));
+			assert_eq!(Balances::free_balance(&dest), 5);
+			assert_eq!(Nonces::<Test>::get(&multi), 1);
+		});
+	}
+}
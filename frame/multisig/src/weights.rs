@@ -53,6 +53,7 @@ pub trait WeightInfo {
 	fn approve_as_multi_approve(s: u32, ) -> Weight;
 	fn approve_as_multi_complete(s: u32, ) -> Weight;
 	fn cancel_as_multi(s: u32, ) -> Weight;
+	fn as_multi_signed(s: u32, z: u32, ) -> Weight;
 	
 }
 
@@ -132,6 +133,14 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 			
 	}
+	fn as_multi_signed(s: u32, z: u32, ) -> Weight {
+		(30_214_000 as Weight)
+			.saturating_add((58_312_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((2_000 as Weight).saturating_mul(z as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			
+	}
 	
 }
 
@@ -210,5 +219,13 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 			
 	}
+	fn as_multi_signed(s: u32, z: u32, ) -> Weight {
+		(30_214_000 as Weight)
+			.saturating_add((58_312_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((2_000 as Weight).saturating_mul(z as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			
+	}
 	
 }
@@ -0,0 +1,94 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Preimage pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, whitelisted_caller, impl_benchmark_test_suite};
+use frame_support::{ensure, traits::{EnsureOrigin, UnfilteredDispatchable}};
+use sp_runtime::traits::{Bounded, Zero};
+
+use crate::Module as Preimage;
+
+fn funded_account<T: Config>() -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	caller
+}
+
+fn preimage_and_hash<T: Config>(size: u32) -> (Vec<u8>, T::Hash) {
+	let preimage = vec![1; size as usize];
+	let hash = T::Hashing::hash(&preimage[..]);
+	(preimage, hash)
+}
+
+benchmarks! {
+	// Expensive note - will reserve.
+	note_preimage {
+		let s in 0 .. MAX_SIZE;
+		let caller = funded_account::<T>();
+		let (preimage, hash) = preimage_and_hash::<T>(s);
+	}: _(RawOrigin::Signed(caller), preimage)
+	verify {
+		ensure!(PreimageFor::<T>::contains_key(&hash), "preimage not noted");
+	}
+
+	// Unnote a preimage noted with a deposit.
+	unnote_preimage {
+		let caller = funded_account::<T>();
+		let (preimage, hash) = preimage_and_hash::<T>(MAX_SIZE);
+		Preimage::<T>::note_preimage(RawOrigin::Signed(caller.clone()).into(), preimage)?;
+	}: _(RawOrigin::Signed(caller), hash)
+	verify {
+		ensure!(!PreimageFor::<T>::contains_key(&hash), "preimage not cleared");
+	}
+
+	// Request an already noted preimage, returning its deposit.
+	request_preimage {
+		let noter = funded_account::<T>();
+		let (preimage, hash) = preimage_and_hash::<T>(MAX_SIZE);
+		Preimage::<T>::note_preimage(RawOrigin::Signed(noter.clone()).into(), preimage)?;
+		let call = Call::<T>::request_preimage(hash);
+		let origin = T::ManagerOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		ensure!(Preimage::<T>::is_requested(&hash), "preimage not requested");
+		ensure!(T::Currency::reserved_balance(&noter).is_zero(), "deposit not returned");
+	}
+
+	// Drop the last request of a noted preimage, clearing it.
+	unrequest_preimage {
+		let (preimage, hash) = preimage_and_hash::<T>(MAX_SIZE);
+		Preimage::<T>::do_request_preimage(&hash);
+		Preimage::<T>::do_note_preimage(preimage, None)?;
+		let call = Call::<T>::unrequest_preimage(hash);
+		let origin = T::ManagerOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		ensure!(!PreimageFor::<T>::contains_key(&hash), "preimage not cleared");
+	}
+}
+
+impl_benchmark_test_suite!(
+	Preimage,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
@@ -0,0 +1,375 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Preimage Module
+//!
+//! ## Overview
+//!
+//! The Preimage module stores preimages: encoded values, usually calls, which other modules
+//! refer to by the hash of their encoding. Large calls, such as runtime upgrades, can then be
+//! scheduled or voted upon by their hash, while the call itself is only uploaded once.
+//!
+//! Anyone can note a preimage by placing a deposit, which is returned when the preimage is
+//! unnoted. Other modules can request a preimage through the [`QueryPreimage`] trait; requests
+//! are reference counted, and while a preimage is requested it can be noted for free and can't be
+//! removed. Modules may also store preimages directly, without a deposit, through the
+//! [`StorePreimage`] trait.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `note_preimage` - Register a preimage on-chain, placing a deposit unless it is requested.
+//! - `unnote_preimage` - Clear an unrequested preimage and return its deposit.
+//! - `request_preimage` - Request a preimage be uploaded and kept on-chain. Only callable by
+//!   `ManagerOrigin`.
+//! - `unrequest_preimage` - Drop a previous request for a preimage. Only callable by
+//!   `ManagerOrigin`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use sp_std::prelude::*;
+
+use codec::{Encode, Decode};
+use frame_support::{
+	ensure,
+	traits::{Currency, QueryPreimage, ReservableCurrency, StorePreimage},
+	weights::Pays,
+};
+use sp_runtime::{
+	DispatchError, RuntimeDebug,
+	traits::{Hash, Saturating},
+};
+
+pub use weights::WeightInfo;
+pub use pallet::*;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The maximum size of a preimage, in bytes.
+pub const MAX_SIZE: u32 = 4 * 1024 * 1024;
+
+/// The request status of a preimage.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum RequestStatus<AccountId, Balance> {
+	/// The preimage is noted but not requested. It was noted either with a deposit by the given
+	/// account, or without one by the manager origin or another module.
+	Unrequested(Option<(AccountId, Balance)>),
+	/// The preimage is requested the given number of times. It may or may not be noted yet.
+	Requested(u32),
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency in which deposits are held.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// An origin that can request a preimage, and note or unnote preimages without a deposit.
+		type ManagerOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The base deposit for noting a preimage.
+		#[pallet::constant]
+		type BaseDeposit: Get<BalanceOf<Self>>;
+
+		/// The deposit per byte for noting a preimage.
+		#[pallet::constant]
+		type ByteDeposit: Get<BalanceOf<Self>>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn status_for)]
+	/// The request status of a preimage, by its hash.
+	pub type StatusFor<T: Config> =
+		StorageMap<_, Identity, T::Hash, RequestStatus<T::AccountId, BalanceOf<T>>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn preimage_for)]
+	/// The preimages, by their hash.
+	pub type PreimageFor<T: Config> = StorageMap<_, Identity, T::Hash, Vec<u8>>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::Hash = "Hash")]
+	pub enum Event<T: Config> {
+		/// A preimage has been noted. \[hash\]
+		Noted(T::Hash),
+		/// A preimage has been requested. \[hash\]
+		Requested(T::Hash),
+		/// A preimage has been cleared. \[hash\]
+		Cleared(T::Hash),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The preimage is larger than `MAX_SIZE`.
+		TooLarge,
+		/// The preimage has already been noted.
+		AlreadyNoted,
+		/// The sender is not the account which noted the preimage.
+		NotAuthorized,
+		/// The preimage can't be unnoted because it isn't noted.
+		NotNoted,
+		/// The preimage can't be unnoted because it is requested.
+		Requested,
+		/// The preimage request can't be removed because it isn't requested.
+		NotRequested,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a preimage on-chain.
+		///
+		/// If the preimage was previously requested, no fees or deposits are taken for providing
+		/// it. Otherwise a deposit is reserved from the sender, unless the origin is the
+		/// `ManagerOrigin`.
+		#[pallet::weight(T::WeightInfo::note_preimage(bytes.len() as u32))]
+		pub fn note_preimage(
+			origin: OriginFor<T>,
+			bytes: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let maybe_sender = Self::ensure_signed_or_manager(origin)?;
+			let (requested, _) = Self::do_note_preimage(bytes, maybe_sender.as_ref())?;
+			if requested {
+				Ok(Pays::No.into())
+			} else {
+				Ok(().into())
+			}
+		}
+
+		/// Clear an unrequested preimage from storage, returning its deposit.
+		///
+		/// The dispatch origin must be the account which noted the preimage, or the
+		/// `ManagerOrigin`.
+		///
+		/// - `hash`: The hash of the preimage to be removed.
+		#[pallet::weight(T::WeightInfo::unnote_preimage())]
+		pub fn unnote_preimage(
+			origin: OriginFor<T>,
+			hash: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let maybe_sender = Self::ensure_signed_or_manager(origin)?;
+			Self::do_unnote_preimage(&hash, maybe_sender)?;
+			Ok(().into())
+		}
+
+		/// Request a preimage be uploaded to the chain without paying any fees or deposits.
+		///
+		/// If the preimage is already noted, its deposit is returned. The preimage can't be
+		/// removed until every request for it is dropped.
+		#[pallet::weight(T::WeightInfo::request_preimage())]
+		pub fn request_preimage(
+			origin: OriginFor<T>,
+			hash: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			Self::do_request_preimage(&hash);
+			Ok(().into())
+		}
+
+		/// Drop a previous request for a preimage.
+		///
+		/// NOTE: This should only be used by the same origin which made the request.
+		#[pallet::weight(T::WeightInfo::unrequest_preimage())]
+		pub fn unrequest_preimage(
+			origin: OriginFor<T>,
+			hash: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			Self::do_unrequest_preimage(&hash)?;
+			Ok(().into())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Ensure that the origin is either the `ManagerOrigin` or a signed origin, returning the
+	/// signer in the latter case.
+	fn ensure_signed_or_manager(
+		origin: T::Origin,
+	) -> Result<Option<T::AccountId>, DispatchError> {
+		if T::ManagerOrigin::ensure_origin(origin.clone()).is_ok() {
+			return Ok(None)
+		}
+		let who = frame_system::ensure_signed(origin)?;
+		Ok(Some(who))
+	}
+
+	/// Store `bytes` as a preimage, taking a deposit from `maybe_depositor` unless the preimage is
+	/// requested.
+	///
+	/// Returns whether the preimage was requested, along with its hash.
+	fn do_note_preimage(
+		bytes: Vec<u8>,
+		maybe_depositor: Option<&T::AccountId>,
+	) -> Result<(bool, T::Hash), DispatchError> {
+		ensure!(bytes.len() as u32 <= MAX_SIZE, Error::<T>::TooLarge);
+		let hash = T::Hashing::hash(&bytes);
+		ensure!(!PreimageFor::<T>::contains_key(hash), Error::<T>::AlreadyNoted);
+
+		let status = match (StatusFor::<T>::get(hash), maybe_depositor) {
+			(Some(RequestStatus::Requested(n)), _) => RequestStatus::Requested(n),
+			(Some(RequestStatus::Unrequested(_)), _) => return Err(Error::<T>::AlreadyNoted.into()),
+			(None, None) => RequestStatus::Unrequested(None),
+			(None, Some(depositor)) => {
+				let length: BalanceOf<T> = (bytes.len() as u32).into();
+				let deposit = T::BaseDeposit::get()
+					.saturating_add(T::ByteDeposit::get().saturating_mul(length));
+				T::Currency::reserve(depositor, deposit)?;
+				RequestStatus::Unrequested(Some((depositor.clone(), deposit)))
+			},
+		};
+		let was_requested = matches!(status, RequestStatus::Requested(_));
+		StatusFor::<T>::insert(hash, status);
+		PreimageFor::<T>::insert(hash, bytes);
+
+		Self::deposit_event(Event::Noted(hash));
+		Ok((was_requested, hash))
+	}
+
+	/// Clear an unrequested preimage. If `maybe_check_owner` is given, the preimage must have been
+	/// noted with a deposit by that account.
+	fn do_unnote_preimage(
+		hash: &T::Hash,
+		maybe_check_owner: Option<T::AccountId>,
+	) -> Result<(), DispatchError> {
+		match StatusFor::<T>::get(hash).ok_or(Error::<T>::NotNoted)? {
+			RequestStatus::Unrequested(Some((owner, deposit))) => {
+				ensure!(
+					maybe_check_owner.map_or(true, |c| c == owner),
+					Error::<T>::NotAuthorized,
+				);
+				T::Currency::unreserve(&owner, deposit);
+			},
+			RequestStatus::Unrequested(None) => {
+				ensure!(maybe_check_owner.is_none(), Error::<T>::NotAuthorized);
+			},
+			RequestStatus::Requested(_) => return Err(Error::<T>::Requested.into()),
+		}
+		StatusFor::<T>::remove(hash);
+		PreimageFor::<T>::remove(hash);
+		Self::deposit_event(Event::Cleared(*hash));
+		Ok(())
+	}
+
+	/// Add a request for the preimage of `hash`, returning the deposit of an already noted
+	/// preimage.
+	fn do_request_preimage(hash: &T::Hash) {
+		let count = StatusFor::<T>::get(hash).map_or(1, |status| match status {
+			RequestStatus::Requested(n) => n.saturating_add(1),
+			RequestStatus::Unrequested(None) => 1,
+			RequestStatus::Unrequested(Some((owner, deposit))) => {
+				// Requested preimages are stored for free, so the deposit can be returned.
+				T::Currency::unreserve(&owner, deposit);
+				1
+			},
+		});
+		StatusFor::<T>::insert(hash, RequestStatus::Requested(count));
+		if count == 1 {
+			Self::deposit_event(Event::Requested(*hash));
+		}
+	}
+
+	/// Drop a request for the preimage of `hash`, clearing the preimage when it was the last one.
+	///
+	/// A preimage which was noted before it was requested is cleared as well: its deposit has
+	/// already been returned.
+	fn do_unrequest_preimage(hash: &T::Hash) -> Result<(), DispatchError> {
+		match StatusFor::<T>::get(hash).ok_or(Error::<T>::NotRequested)? {
+			RequestStatus::Requested(n) if n > 1 => {
+				StatusFor::<T>::insert(hash, RequestStatus::Requested(n - 1));
+			},
+			RequestStatus::Requested(_) => {
+				StatusFor::<T>::remove(hash);
+				if PreimageFor::<T>::take(hash).is_some() {
+					Self::deposit_event(Event::Cleared(*hash));
+				}
+			},
+			RequestStatus::Unrequested(_) => return Err(Error::<T>::NotRequested.into()),
+		}
+		Ok(())
+	}
+}
+
+impl<T: Config> QueryPreimage<T::Hash> for Pallet<T> {
+	fn len(hash: &T::Hash) -> Option<u32> {
+		PreimageFor::<T>::decode_len(hash).map(|len| len as u32)
+	}
+
+	fn fetch(hash: &T::Hash) -> Option<Vec<u8>> {
+		PreimageFor::<T>::get(hash)
+	}
+
+	fn is_requested(hash: &T::Hash) -> bool {
+		matches!(StatusFor::<T>::get(hash), Some(RequestStatus::Requested(_)))
+	}
+
+	fn request(hash: &T::Hash) {
+		Self::do_request_preimage(hash)
+	}
+
+	fn unrequest(hash: &T::Hash) {
+		let res = Self::do_unrequest_preimage(hash);
+		debug_assert!(res.is_ok(), "`unrequest` called on a hash which isn't requested");
+	}
+}
+
+impl<T: Config> StorePreimage<T::Hash> for Pallet<T> {
+	const MAX_LENGTH: usize = MAX_SIZE as usize;
+
+	fn note(bytes: Vec<u8>) -> Result<T::Hash, DispatchError> {
+		// An already noted preimage is not an error here: the caller only needs it to exist.
+		let hash = T::Hashing::hash(&bytes);
+		if PreimageFor::<T>::contains_key(hash) {
+			return Ok(hash)
+		}
+		Self::do_note_preimage(bytes, None).map(|(_, hash)| hash)
+	}
+
+	fn unnote(hash: &T::Hash) {
+		// Requested or deposit-backed preimages are left alone.
+		if let Some(RequestStatus::Unrequested(None)) = StatusFor::<T>::get(hash) {
+			let _ = Self::do_unnote_preimage(hash, None);
+		}
+	}
+}
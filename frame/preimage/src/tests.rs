@@ -0,0 +1,224 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for Preimage pallet.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_ok, assert_noop};
+use pallet_balances::Error as BalancesError;
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn user_note_preimage_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_eq!(Balances::reserved_balance(2), 3);
+		assert_eq!(Balances::free_balance(2), 97);
+
+		let h = hashed([1]);
+		assert!(PreimageFor::<Test>::contains_key(&h));
+		assert_eq!(Preimage::fetch(&h), Some(vec![1]));
+		assert_eq!(Preimage::len(&h), Some(1));
+
+		assert_noop!(
+			Preimage::note_preimage(Origin::signed(2), vec![1]),
+			Error::<Test>::AlreadyNoted
+		);
+		assert_noop!(
+			Preimage::note_preimage(Origin::signed(0), vec![2]),
+			BalancesError::<Test, _>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn manager_note_preimage_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(1), vec![1]));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+
+		let h = hashed([1]);
+		assert!(PreimageFor::<Test>::contains_key(&h));
+		assert_eq!(Preimage::fetch(&h), Some(vec![1]));
+
+		assert_noop!(
+			Preimage::note_preimage(Origin::signed(1), vec![1]),
+			Error::<Test>::AlreadyNoted
+		);
+	});
+}
+
+#[test]
+fn too_large_preimage_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Preimage::note_preimage(Origin::signed(1), vec![0; MAX_SIZE as usize + 1]),
+			Error::<Test>::TooLarge
+		);
+	});
+}
+
+#[test]
+fn user_unnote_preimage_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_noop!(
+			Preimage::unnote_preimage(Origin::signed(3), hashed([1])),
+			Error::<Test>::NotAuthorized
+		);
+		assert_noop!(
+			Preimage::unnote_preimage(Origin::signed(2), hashed([2])),
+			Error::<Test>::NotNoted
+		);
+
+		assert_ok!(Preimage::unnote_preimage(Origin::signed(2), hashed([1])));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 100);
+
+		let h = hashed([1]);
+		assert!(!PreimageFor::<Test>::contains_key(&h));
+		assert_eq!(Preimage::fetch(&h), None);
+	});
+}
+
+#[test]
+fn manager_unnote_preimage_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(1), vec![1]));
+		assert_noop!(
+			Preimage::unnote_preimage(Origin::signed(2), hashed([1])),
+			Error::<Test>::NotAuthorized
+		);
+		assert_ok!(Preimage::unnote_preimage(Origin::signed(1), hashed([1])));
+
+		let h = hashed([1]);
+		assert!(!PreimageFor::<Test>::contains_key(&h));
+		assert_eq!(Preimage::fetch(&h), None);
+	});
+}
+
+#[test]
+fn manager_unnote_user_preimage_returns_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_ok!(Preimage::unnote_preimage(Origin::signed(1), hashed([1])));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 100);
+	});
+}
+
+#[test]
+fn requested_then_noted_preimage_is_free() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Preimage::request_preimage(Origin::signed(2), hashed([1])), BadOrigin);
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), hashed([1])));
+		assert!(Preimage::is_requested(&hashed([1])));
+
+		let info = Preimage::note_preimage(Origin::signed(2), vec![1]).unwrap();
+		assert_eq!(info.pays_fee, Pays::No);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 100);
+
+		// A requested preimage can't be unnoted.
+		assert_noop!(
+			Preimage::unnote_preimage(Origin::signed(1), hashed([1])),
+			Error::<Test>::Requested
+		);
+		assert!(PreimageFor::<Test>::contains_key(&hashed([1])));
+	});
+}
+
+#[test]
+fn request_returns_deposit_of_noted_preimage() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_eq!(Balances::reserved_balance(2), 3);
+
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), hashed([1])));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 100);
+		assert!(PreimageFor::<Test>::contains_key(&hashed([1])));
+	});
+}
+
+#[test]
+fn requests_are_reference_counted() {
+	new_test_ext().execute_with(|| {
+		let h = hashed([1]);
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), h));
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), h));
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+
+		assert_ok!(Preimage::unrequest_preimage(Origin::signed(1), h));
+		assert!(Preimage::is_requested(&h));
+		assert!(PreimageFor::<Test>::contains_key(&h));
+
+		// Dropping the last request clears the preimage.
+		assert_ok!(Preimage::unrequest_preimage(Origin::signed(1), h));
+		assert!(!Preimage::is_requested(&h));
+		assert!(!PreimageFor::<Test>::contains_key(&h));
+		assert_eq!(StatusFor::<Test>::get(&h), None);
+
+		assert_noop!(
+			Preimage::unrequest_preimage(Origin::signed(1), h),
+			Error::<Test>::NotRequested
+		);
+	});
+}
+
+#[test]
+fn unrequest_of_unrequested_preimage_fails() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_noop!(
+			Preimage::unrequest_preimage(Origin::signed(1), hashed([1])),
+			Error::<Test>::NotRequested
+		);
+	});
+}
+
+#[test]
+fn store_preimage_works() {
+	new_test_ext().execute_with(|| {
+		let h = <Preimage as StorePreimage<_>>::note(vec![1]).unwrap();
+		assert_eq!(h, hashed([1]));
+		assert!(PreimageFor::<Test>::contains_key(&h));
+		// Noting it again is fine.
+		assert_eq!(<Preimage as StorePreimage<_>>::note(vec![1]), Ok(h));
+
+		// A requested preimage is kept when unnoted.
+		Preimage::request(&h);
+		<Preimage as StorePreimage<_>>::unnote(&h);
+		assert!(PreimageFor::<Test>::contains_key(&h));
+
+		// Once it is no longer requested, it is cleared.
+		Preimage::unrequest(&h);
+		assert!(!PreimageFor::<Test>::contains_key(&h));
+	});
+}
+
+#[test]
+fn store_preimage_does_not_unnote_deposited_preimage() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		<Preimage as StorePreimage<_>>::unnote(&hashed([1]));
+		assert!(PreimageFor::<Test>::contains_key(&hashed([1])));
+		assert_eq!(Balances::reserved_balance(2), 3);
+	});
+}
@@ -30,22 +30,27 @@ use frame_system::Module as System;
 
 const BLOCK_NUMBER: u32 = 2;
 
-// Add `n` named items to the schedule
+// Add `n` named items to the schedule.
+//
+// The items are inserted directly, so that the number of periodic tasks of the origin is not
+// limited by `MaxPeriodicPerOrigin`.
 fn fill_schedule<T: Config> (when: T::BlockNumber, n: u32) -> Result<(), &'static str> {
 	// Essentially a no-op call.
 	let call = frame_system::Call::set_storage(vec![]);
 	for i in 0..n {
 		// Named schedule is strictly heavier than anonymous
-		Scheduler::<T>::do_schedule_named(
-			i.encode(),
-			DispatchTime::At(when),
-			// Add periodicity
-			Some((T::BlockNumber::one(), 100)),
+		let s: ScheduledOf<T> = Scheduled {
+			maybe_id: Some(i.encode()),
 			// HARD_DEADLINE priority means it gets executed no matter what
-			0,
-			frame_system::RawOrigin::Root.into(),
-			call.clone().into(),
-		)?;
+			priority: 0,
+			call: MaybeHashed::Value(call.clone().into()),
+			// Add periodicity
+			maybe_periodic: Some((T::BlockNumber::one(), 100)),
+			origin: frame_system::RawOrigin::Root.into(),
+			_phantom: Default::default(),
+		};
+		Agenda::<T>::append(when, Some(s));
+		Lookup::<T>::insert(i.encode(), (when, i));
 	}
 	ensure!(Agenda::<T>::get(when).len() == n as usize, "didn't fill schedule");
 	Ok(())
@@ -53,12 +58,12 @@ fn fill_schedule<T: Config> (when: T::BlockNumber, n: u32) -> Result<(), &'stati
 
 benchmarks! {
 	schedule {
-		let s in 0 .. T::MaxScheduledPerBlock::get();
+		let s in 0 .. T::MaxScheduledPerBlock::get() - 1;
 		let when = BLOCK_NUMBER.into();
 		let periodic = Some((T::BlockNumber::one(), 100));
 		let priority = 0;
 		// Essentially a no-op call.
-		let call = Box::new(frame_system::Call::set_storage(vec![]).into());
+		let call = Box::new(MaybeHashed::Value(frame_system::Call::set_storage(vec![]).into()));
 
 		fill_schedule::<T>(when, s)?;
 	}: _(RawOrigin::Root, when, periodic, priority, call)
@@ -89,13 +94,13 @@ benchmarks! {
 	}
 
 	schedule_named {
-		let s in 0 .. T::MaxScheduledPerBlock::get();
+		let s in 0 .. T::MaxScheduledPerBlock::get() - 1;
 		let id = s.encode();
 		let when = BLOCK_NUMBER.into();
 		let periodic = Some((T::BlockNumber::one(), 100));
 		let priority = 0;
 		// Essentially a no-op call.
-		let call = Box::new(frame_system::Call::set_storage(vec![]).into());
+		let call = Box::new(MaybeHashed::Value(frame_system::Call::set_storage(vec![]).into()));
 
 		fill_schedule::<T>(when, s)?;
 	}: _(RawOrigin::Root, id, when, periodic, priority, call)
@@ -124,6 +129,48 @@ benchmarks! {
 		);
 	}
 
+	set_retry {
+		let s = T::MaxScheduledPerBlock::get();
+		let when = BLOCK_NUMBER.into();
+
+		fill_schedule::<T>(when, s)?;
+	}: _(RawOrigin::Root, (when, 0), 10, T::BlockNumber::one())
+	verify {
+		ensure!(Retries::<T>::get((when, 0)).is_some(), "didn't set retry");
+	}
+
+	set_retry_named {
+		let s = T::MaxScheduledPerBlock::get();
+		let when = BLOCK_NUMBER.into();
+
+		fill_schedule::<T>(when, s)?;
+	}: _(RawOrigin::Root, 0.encode(), 10, T::BlockNumber::one())
+	verify {
+		ensure!(Retries::<T>::get((when, 0)).is_some(), "didn't set retry");
+	}
+
+	cancel_retry {
+		let s = T::MaxScheduledPerBlock::get();
+		let when = BLOCK_NUMBER.into();
+
+		fill_schedule::<T>(when, s)?;
+		Scheduler::<T>::set_retry(RawOrigin::Root.into(), (when, 0), 10, T::BlockNumber::one())?;
+	}: _(RawOrigin::Root, (when, 0))
+	verify {
+		ensure!(Retries::<T>::get((when, 0)).is_none(), "didn't cancel retry");
+	}
+
+	cancel_retry_named {
+		let s = T::MaxScheduledPerBlock::get();
+		let when = BLOCK_NUMBER.into();
+
+		fill_schedule::<T>(when, s)?;
+		Scheduler::<T>::set_retry(RawOrigin::Root.into(), (when, 0), 10, T::BlockNumber::one())?;
+	}: _(RawOrigin::Root, 0.encode())
+	verify {
+		ensure!(Retries::<T>::get((when, 0)).is_none(), "didn't cancel retry");
+	}
+
 	// TODO [#7141]: Make this more complex and flexible so it can be used in automation.
 	#[extra]
 	on_initialize {
@@ -47,6 +47,21 @@
 //! * `schedule_named` - augments the `schedule` interface with an additional
 //!   `Vec<u8>` parameter that can be used for identification.
 //! * `cancel_named` - the named complement to the cancel function.
+//!
+//! * `set_retry` - set a retry configuration for a scheduled task, so that it is retried a
+//!   number of times if its dispatch fails.
+//! * `set_retry_named` - the named complement to the set_retry function.
+//! * `cancel_retry` - remove the retry configuration of a scheduled task.
+//! * `cancel_retry_named` - the named complement to the cancel_retry function.
+//!
+//! Calls may be scheduled either inline or by the hash of their encoding. The preimage of a
+//! hashed call is requested from `Config::PreimageProvider` when it is scheduled and looked up
+//! when it is due; if it is not available yet, the task is postponed by
+//! `Config::NoPreimagePostponement` blocks.
+//!
+//! The agenda of a block holds at most `Config::MaxScheduledPerBlock` tasks, and an origin may
+//! have at most `Config::MaxPeriodicPerOrigin` periodic tasks. Tasks which do not fit in
+//! `Config::MaximumWeight` stay in their agenda and are serviced first in the following blocks.
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -58,9 +73,12 @@ use sp_std::{prelude::*, marker::PhantomData, borrow::Borrow};
 use codec::{Encode, Decode, Codec};
 use sp_runtime::{RuntimeDebug, traits::{Zero, One, BadOrigin, Saturating}};
 use frame_support::{
-	decl_module, decl_storage, decl_event, decl_error, IterableStorageMap,
+	decl_module, decl_storage, decl_event, decl_error, ensure, IterableStorageMap,
 	dispatch::{Dispatchable, DispatchError, DispatchResult, Parameter},
-	traits::{Get, schedule::{self, DispatchTime}, OriginTrait, EnsureOrigin, IsType},
+	traits::{
+		Get, schedule::{self, DispatchTime, MaybeHashed}, OriginTrait, EnsureOrigin, IsType,
+		QueryPreimage,
+	},
 	weights::{GetDispatchInfo, Weight},
 };
 use frame_system::{self as system, ensure_signed};
@@ -93,9 +111,18 @@ pub trait Config: system::Config {
 	type ScheduleOrigin: EnsureOrigin<<Self as system::Config>::Origin>;
 
 	/// The maximum number of scheduled calls in the queue for a single block.
-	/// Not strictly enforced, but used for weight estimation.
 	type MaxScheduledPerBlock: Get<u32>;
 
+	/// The maximum number of periodic tasks that a single origin may have scheduled.
+	type MaxPeriodicPerOrigin: Get<u32>;
+
+	/// The provider of the preimages of hashed calls.
+	type PreimageProvider: QueryPreimage<Self::Hash>;
+
+	/// The number of blocks by which a hashed call is postponed when its preimage is not
+	/// available at the time it is due. If `None`, the task is dropped instead.
+	type NoPreimagePostponement: Get<Option<Self::BlockNumber>>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -131,8 +158,45 @@ pub struct ScheduledV2<Call, BlockNumber, PalletsOrigin, AccountId> {
 	_phantom: PhantomData<AccountId>,
 }
 
+/// Information regarding an item to be executed in the future. Identical to `ScheduledV2`, but
+/// the call may be given by its hash.
+pub type ScheduledV3<Call, BlockNumber, PalletsOrigin, AccountId> =
+	ScheduledV2<Call, BlockNumber, PalletsOrigin, AccountId>;
+
 /// The current version of Scheduled struct.
-pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> = ScheduledV2<Call, BlockNumber, PalletsOrigin, AccountId>;
+pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> =
+	ScheduledV3<Call, BlockNumber, PalletsOrigin, AccountId>;
+
+/// A call of the runtime, either inline or by the hash of its encoding.
+pub type CallOrHashOf<T> = MaybeHashed<<T as Config>::Call, <T as system::Config>::Hash>;
+
+/// The scheduled item type of the runtime.
+pub type ScheduledOf<T> = Scheduled<
+	CallOrHashOf<T>,
+	<T as system::Config>::BlockNumber,
+	<T as Config>::PalletsOrigin,
+	<T as system::Config>::AccountId,
+>;
+
+/// The configuration of the retries of a task whose dispatch failed.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct RetryConfig<BlockNumber> {
+	/// The number of retries set for the task.
+	pub total_retries: u8,
+	/// The number of retries left.
+	pub remaining: u8,
+	/// The number of blocks between a failed dispatch and its retry.
+	pub period: BlockNumber,
+}
+
+/// The reason why the call of a task could not be looked up.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum LookupError {
+	/// The preimage of the call is not available.
+	Unknown,
+	/// The preimage is available, but it is not a valid call.
+	BadFormat,
+}
 
 // A value placed in storage that represents the current version of the Scheduler storage.
 // This value is used by the `on_runtime_upgrade` logic to determine whether we run
@@ -141,6 +205,7 @@ pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> = ScheduledV2<Ca
 enum Releases {
 	V1,
 	V2,
+	V3,
 }
 
 impl Default for Releases {
@@ -152,16 +217,26 @@ impl Default for Releases {
 decl_storage! {
 	trait Store for Module<T: Config> as Scheduler {
 		/// Items to be executed, indexed by the block number that they should be executed on.
-		pub Agenda: map hasher(twox_64_concat) T::BlockNumber
-			=> Vec<Option<Scheduled<<T as Config>::Call, T::BlockNumber, T::PalletsOrigin, T::AccountId>>>;
+		pub Agenda: map hasher(twox_64_concat) T::BlockNumber => Vec<Option<ScheduledOf<T>>>;
+
+		/// The block from which the agendas have not been fully serviced yet, if any.
+		pub IncompleteSince: Option<T::BlockNumber>;
 
 		/// Lookup from identity to the block number and index of the task.
 		Lookup: map hasher(twox_64_concat) Vec<u8> => Option<TaskAddress<T::BlockNumber>>;
 
+		/// Retry configurations of scheduled tasks.
+		pub Retries:
+			map hasher(twox_64_concat) TaskAddress<T::BlockNumber>
+			=> Option<RetryConfig<T::BlockNumber>>;
+
+		/// The number of periodic tasks scheduled by each origin.
+		pub PeriodicCount: map hasher(blake2_128_concat) T::PalletsOrigin => u32;
+
 		/// Storage version of the pallet.
 		///
 		/// New networks start with last version.
-		StorageVersion build(|_| Releases::V2): Releases;
+		StorageVersion build(|_| Releases::V3): Releases;
 	}
 }
 
@@ -173,6 +248,19 @@ decl_event!(
 		Canceled(BlockNumber, u32),
 		/// Dispatched some task. \[task, id, result\]
 		Dispatched(TaskAddress<BlockNumber>, Option<Vec<u8>>, DispatchResult),
+		/// The call for the provided hash was not found, so the task has been postponed or
+		/// dropped. \[task, id, error\]
+		CallLookupFailed(TaskAddress<BlockNumber>, Option<Vec<u8>>, LookupError),
+		/// Set a retry configuration for some task. \[task, id, period, retries\]
+		RetrySet(TaskAddress<BlockNumber>, Option<Vec<u8>>, BlockNumber, u8),
+		/// Cancelled the retry configuration of some task. \[task, id\]
+		RetryCancelled(TaskAddress<BlockNumber>, Option<Vec<u8>>),
+		/// The retry of a failed task could not be scheduled, as the agenda was full.
+		/// \[task, id\]
+		RetryFailed(TaskAddress<BlockNumber>, Option<Vec<u8>>),
+		/// The next run of a periodic task could not be scheduled, as the agenda was full.
+		/// \[task, id\]
+		PeriodicFailed(TaskAddress<BlockNumber>, Option<Vec<u8>>),
 	}
 );
 
@@ -186,6 +274,10 @@ decl_error! {
 		TargetBlockNumberInPast,
 		/// Reschedule failed because it does not change scheduled time.
 		RescheduleNoChange,
+		/// The agenda of the target block is full.
+		AgendaFull,
+		/// The origin has too many periodic tasks scheduled.
+		TooManyPeriodicTasks,
 	}
 }
 
@@ -210,7 +302,7 @@ decl_module! {
 			when: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -250,7 +342,7 @@ decl_module! {
 			when: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -286,7 +378,7 @@ decl_module! {
 			after: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -306,7 +398,7 @@ decl_module! {
 			after: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -315,129 +407,145 @@ decl_module! {
 			)?;
 		}
 
+		/// Set a retry configuration for a task, so that if its dispatch fails it is retried
+		/// after `period` blocks, up to `retries` times or until it succeeds.
+		///
+		/// Retries are subject to weight metering and agenda space like any other task. If a
+		/// periodic task fails, its retries are scheduled as a separate, non-periodic and
+		/// anonymous task, while the periodic task keeps running on its own schedule.
+		///
+		/// A `period` of zero is treated as one block.
+		///
+		/// # <weight>
+		/// - DB Weight:
+		///     - Read: Agenda
+		///     - Write: Retries
+		/// # </weight>
+		#[weight = T::WeightInfo::set_retry()]
+		fn set_retry(origin,
+			task: TaskAddress<T::BlockNumber>,
+			retries: u8,
+			period: T::BlockNumber,
+		) {
+			T::ScheduleOrigin::ensure_origin(origin.clone())?;
+			let origin = <T as Config>::Origin::from(origin);
+			Self::do_set_retry(Some(origin.caller().clone()), task, retries, period)?;
+		}
+
+		/// Set a retry configuration for a named task. See [`set_retry`].
+		///
+		/// # <weight>
+		/// - DB Weight:
+		///     - Read: Agenda, Lookup
+		///     - Write: Retries
+		/// # </weight>
+		#[weight = T::WeightInfo::set_retry_named()]
+		fn set_retry_named(origin, id: Vec<u8>, retries: u8, period: T::BlockNumber) {
+			T::ScheduleOrigin::ensure_origin(origin.clone())?;
+			let origin = <T as Config>::Origin::from(origin);
+			let task = Lookup::<T>::get(&id).ok_or(Error::<T>::NotFound)?;
+			Self::do_set_retry(Some(origin.caller().clone()), task, retries, period)?;
+		}
+
+		/// Remove the retry configuration of a task.
+		///
+		/// # <weight>
+		/// - DB Weight:
+		///     - Read: Agenda
+		///     - Write: Retries
+		/// # </weight>
+		#[weight = T::WeightInfo::cancel_retry()]
+		fn cancel_retry(origin, task: TaskAddress<T::BlockNumber>) {
+			T::ScheduleOrigin::ensure_origin(origin.clone())?;
+			let origin = <T as Config>::Origin::from(origin);
+			Self::do_cancel_retry(Some(origin.caller().clone()), task)?;
+		}
+
+		/// Remove the retry configuration of a named task.
+		///
+		/// # <weight>
+		/// - DB Weight:
+		///     - Read: Agenda, Lookup
+		///     - Write: Retries
+		/// # </weight>
+		#[weight = T::WeightInfo::cancel_retry_named()]
+		fn cancel_retry_named(origin, id: Vec<u8>) {
+			T::ScheduleOrigin::ensure_origin(origin.clone())?;
+			let origin = <T as Config>::Origin::from(origin);
+			let task = Lookup::<T>::get(&id).ok_or(Error::<T>::NotFound)?;
+			Self::do_cancel_retry(Some(origin.caller().clone()), task)?;
+		}
+
 		/// Execute the scheduled calls
 		///
+		/// The agendas are serviced from `IncompleteSince`, or the current block if all of them
+		/// have been serviced, up to the current block. Tasks which do not fit in
+		/// `MaximumWeight` are left in their agenda and `IncompleteSince` is set accordingly.
+		///
 		/// # <weight>
-		/// - S = Number of already scheduled calls
+		/// - A = Number of serviced agendas
+		/// - S = Number of dispatched calls
 		/// - N = Named scheduled calls
 		/// - P = Periodic Calls
-		/// - Base Weight: 9.243 + 23.45 * S µs
 		/// - DB Weight:
-		///     - Read: Agenda + Lookup * N + Agenda(Future) * P
-		///     - Write: Agenda + Lookup * N  + Agenda(future) * P
+		///     - Read: IncompleteSince + Agenda * A + Retries * S + Lookup * N
+		///       + Agenda(Future) * P
+		///     - Write: IncompleteSince + Agenda * A + Lookup * N  + Agenda(future) * P
 		/// # </weight>
 		fn on_initialize(now: T::BlockNumber) -> Weight {
 			let limit = T::MaximumWeight::get();
-			let mut queued = Agenda::<T>::take(now).into_iter()
-				.enumerate()
-				.filter_map(|(index, s)| s.map(|inner| (index as u32, inner)))
-				.collect::<Vec<_>>();
-			if queued.len() as u32 > T::MaxScheduledPerBlock::get() {
-				log::warn!(
-					target: "runtime::scheduler",
-					"Warning: This block has more items queued in Scheduler than \
-					expected from the runtime configuration. An update might be needed."
-				);
+			// IncompleteSince
+			let mut weight = T::DbWeight::get().reads_writes(1, 1);
+			let mut executed = 0;
+			let mut incomplete_since = None;
+			let mut when = IncompleteSince::<T>::take().unwrap_or(now);
+			while when <= now {
+				// Agenda
+				let agenda_weight = weight.saturating_add(T::DbWeight::get().reads(1));
+				if agenda_weight > limit {
+					break
+				}
+				weight = agenda_weight;
+				if !Self::service_agenda(&mut weight, &mut executed, now, when, limit) {
+					incomplete_since = incomplete_since.or(Some(when));
+				}
+				when = when.saturating_add(One::one());
+			}
+			if when <= now {
+				incomplete_since = incomplete_since.or(Some(when));
+			}
+			if let Some(since) = incomplete_since {
+				IncompleteSince::<T>::put(since);
 			}
-			queued.sort_by_key(|(_, s)| s.priority);
-			let base_weight: Weight = T::DbWeight::get().reads_writes(1, 2); // Agenda + Agenda(next)
-			let mut total_weight: Weight = 0;
-			queued.into_iter()
-				.enumerate()
-				.scan(base_weight, |cumulative_weight, (order, (index, s))| {
-					*cumulative_weight = cumulative_weight
-						.saturating_add(s.call.get_dispatch_info().weight);
-
-					let origin = <<T as Config>::Origin as From<T::PalletsOrigin>>::from(
-						s.origin.clone()
-					).into();
-
-					if ensure_signed(origin).is_ok() {
-						 // AccountData for inner call origin accountdata.
-						*cumulative_weight = cumulative_weight
-							.saturating_add(T::DbWeight::get().reads_writes(1, 1));
-					}
-
-					if s.maybe_id.is_some() {
-						// Remove/Modify Lookup
-						*cumulative_weight = cumulative_weight.saturating_add(T::DbWeight::get().writes(1));
-					}
-					if s.maybe_periodic.is_some() {
-						// Read/Write Agenda for future block
-						*cumulative_weight = cumulative_weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
-					}
-
-					Some((order, index, *cumulative_weight, s))
-				})
-				.filter_map(|(order, index, cumulative_weight, mut s)| {
-					// We allow a scheduled call if any is true:
-					// - It's priority is `HARD_DEADLINE`
-					// - It does not push the weight past the limit.
-					// - It is the first item in the schedule
-					if s.priority <= schedule::HARD_DEADLINE || cumulative_weight <= limit || order == 0 {
-						let r = s.call.clone().dispatch(s.origin.clone().into());
-						let maybe_id = s.maybe_id.clone();
-						if let &Some((period, count)) = &s.maybe_periodic {
-							if count > 1 {
-								s.maybe_periodic = Some((period, count - 1));
-							} else {
-								s.maybe_periodic = None;
-							}
-							let next = now + period;
-							// If scheduled is named, place it's information in `Lookup`
-							if let Some(ref id) = s.maybe_id {
-								let next_index = Agenda::<T>::decode_len(now + period).unwrap_or(0);
-								Lookup::<T>::insert(id, (next, next_index as u32));
-							}
-							Agenda::<T>::append(next, Some(s));
-						} else {
-							if let Some(ref id) = s.maybe_id {
-								Lookup::<T>::remove(id);
-							}
-						}
-						Self::deposit_event(RawEvent::Dispatched(
-							(now, index),
-							maybe_id,
-							r.map(|_| ()).map_err(|e| e.error)
-						));
-						total_weight = cumulative_weight;
-						None
-					} else {
-						Some(Some(s))
-					}
-				})
-				.for_each(|unused| {
-					let next = now + One::one();
-					Agenda::<T>::append(next, unused);
-				});
 
-			total_weight
+			weight
 		}
 	}
 }
 
 impl<T: Config> Module<T> {
-	/// Migrate storage format from V1 to V2.
+	/// Migrate storage format from V1 to V3.
 	/// Return true if migration is performed.
-	pub fn migrate_v1_to_t2() -> bool {
+	pub fn migrate_v1_to_v3() -> bool {
 		if StorageVersion::get() == Releases::V1 {
-			StorageVersion::put(Releases::V2);
+			StorageVersion::put(Releases::V3);
 
 			Agenda::<T>::translate::<
 				Vec<Option<ScheduledV1<<T as Config>::Call, T::BlockNumber>>>, _
 			>(|_, agenda| Some(
 				agenda
 					.into_iter()
-					.map(|schedule| schedule.map(|schedule| ScheduledV2 {
+					.map(|schedule| schedule.map(|schedule| ScheduledV3 {
 						maybe_id: schedule.maybe_id,
 						priority: schedule.priority,
-						call: schedule.call,
+						call: schedule.call.into(),
 						maybe_periodic: schedule.maybe_periodic,
 						origin: system::RawOrigin::Root.into(),
 						_phantom: Default::default(),
 					}))
 					.collect::<Vec<_>>()
 			));
+			Self::count_periodic_tasks();
 
 			true
 		} else {
@@ -445,10 +553,51 @@ impl<T: Config> Module<T> {
 		}
 	}
 
+	/// Migrate storage format from V2 to V3, where calls may be given by their hash.
+	/// Return true if migration is performed.
+	pub fn migrate_v2_to_v3() -> bool {
+		if StorageVersion::get() == Releases::V2 {
+			StorageVersion::put(Releases::V3);
+
+			Agenda::<T>::translate::<
+				Vec<Option<ScheduledV2<
+					<T as Config>::Call, T::BlockNumber, T::PalletsOrigin, T::AccountId
+				>>>, _
+			>(|_, agenda| Some(
+				agenda
+					.into_iter()
+					.map(|schedule| schedule.map(|schedule| ScheduledV3 {
+						maybe_id: schedule.maybe_id,
+						priority: schedule.priority,
+						call: schedule.call.into(),
+						maybe_periodic: schedule.maybe_periodic,
+						origin: schedule.origin,
+						_phantom: Default::default(),
+					}))
+					.collect::<Vec<_>>()
+			));
+			Self::count_periodic_tasks();
+
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Count the periodic tasks of each origin into `PeriodicCount`, which is only tracked from
+	/// `Releases::V3`.
+	fn count_periodic_tasks() {
+		for (_, agenda) in Agenda::<T>::iter() {
+			for s in agenda.into_iter().flatten().filter(|s| s.maybe_periodic.is_some()) {
+				PeriodicCount::<T>::mutate(&s.origin, |count| *count = count.saturating_add(1));
+			}
+		}
+	}
+
 	/// Helper to migrate scheduler when the pallet origin type has changed.
 	pub fn migrate_origin<OldOrigin: Into<T::PalletsOrigin> + codec::Decode>() {
 		Agenda::<T>::translate::<
-			Vec<Option<Scheduled<<T as Config>::Call, T::BlockNumber, OldOrigin, T::AccountId>>>, _
+			Vec<Option<Scheduled<CallOrHashOf<T>, T::BlockNumber, OldOrigin, T::AccountId>>>, _
 		>(|_, agenda| Some(
 			agenda
 				.into_iter()
@@ -464,6 +613,293 @@ impl<T: Config> Module<T> {
 		));
 	}
 
+	/// Service the agenda of the block `when`, dispatching its tasks in priority order while
+	/// they fit in `limit`.
+	///
+	/// Returns `false` if some tasks have been left in the agenda for a later block.
+	fn service_agenda(
+		weight: &mut Weight,
+		executed: &mut u32,
+		now: T::BlockNumber,
+		when: T::BlockNumber,
+		limit: Weight,
+	) -> bool {
+		let mut agenda = Agenda::<T>::get(when);
+		if agenda.is_empty() {
+			return true
+		}
+		// Agenda
+		*weight = weight.saturating_add(T::DbWeight::get().writes(1));
+		let mut ordered = agenda.iter()
+			.enumerate()
+			.filter_map(|(index, s)| s.as_ref().map(|s| (index as u32, s.priority)))
+			.collect::<Vec<_>>();
+		ordered.sort_by_key(|&(_, priority)| priority);
+
+		let mut postponed = 0;
+		let mut out_of_weight = false;
+		for (index, priority) in ordered {
+			let task = match agenda[index as usize].take() {
+				Some(task) => task,
+				None => continue,
+			};
+			if task.call.hash().is_some() {
+				// Preimage + its request status, or the Agenda it is postponed to.
+				*weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 2));
+			}
+			let (call, task) = match Self::resolve_call(now, (when, index), task) {
+				Some(resolved) => resolved,
+				None => continue,
+			};
+
+			// We allow a scheduled call if any is true:
+			// - It's priority is `HARD_DEADLINE`
+			// - It does not push the weight past the limit, and no task before it did.
+			// - It is the first item to be dispatched in this block.
+			let task_weight = Self::task_weight(&call, &task);
+			let fits = !out_of_weight && weight.saturating_add(task_weight) <= limit;
+			if priority <= schedule::HARD_DEADLINE || fits || *executed == 0 {
+				*weight = weight.saturating_add(task_weight);
+				let extra_weight = Self::execute_task(now, (when, index), call, task);
+				*weight = weight.saturating_add(extra_weight);
+				*executed += 1;
+			} else {
+				out_of_weight = true;
+				agenda[index as usize] = Some(task);
+				postponed += 1;
+			}
+		}
+
+		if postponed > 0 {
+			Agenda::<T>::insert(when, agenda);
+		} else {
+			Agenda::<T>::remove(when);
+		}
+		postponed == 0
+	}
+
+	/// The weight of dispatching `call` as the task `s`, used to check it against the limit.
+	fn task_weight(call: &<T as Config>::Call, s: &ScheduledOf<T>) -> Weight {
+		let db_weight = T::DbWeight::get();
+		// Retries
+		let mut weight = call.get_dispatch_info().weight.saturating_add(db_weight.reads(1));
+
+		let origin = <<T as Config>::Origin as From<T::PalletsOrigin>>::from(
+			s.origin.clone()
+		).into();
+		if ensure_signed(origin).is_ok() {
+			// AccountData for inner call origin accountdata.
+			weight = weight.saturating_add(db_weight.reads_writes(1, 1));
+		}
+		if s.maybe_id.is_some() {
+			// Remove/Modify Lookup
+			weight = weight.saturating_add(db_weight.writes(1));
+		}
+		if s.maybe_periodic.is_some() {
+			// Read/Write Agenda for future block
+			weight = weight.saturating_add(db_weight.reads_writes(1, 1));
+		}
+		weight
+	}
+
+	/// Dispatch the call of the task `s` at `address`, then schedule its next run if it is
+	/// periodic and its retry if it failed.
+	///
+	/// Returns the weight used on top of `task_weight`.
+	fn execute_task(
+		now: T::BlockNumber,
+		address: TaskAddress<T::BlockNumber>,
+		call: <T as Config>::Call,
+		mut s: ScheduledOf<T>,
+	) -> Weight {
+		let db_weight = T::DbWeight::get();
+		let mut weight: Weight = 0;
+		let maybe_config = Retries::<T>::get(address);
+		if maybe_config.is_some() {
+			Retries::<T>::remove(address);
+			weight = weight.saturating_add(db_weight.writes(1));
+		}
+
+		let r = call.dispatch(s.origin.clone().into());
+		let failed = r.is_err();
+		let maybe_id = s.maybe_id.clone();
+		Self::deposit_event(RawEvent::Dispatched(
+			address,
+			maybe_id.clone(),
+			r.map(|_| ()).map_err(|e| e.error)
+		));
+		let maybe_retry = maybe_config.filter(|retry| failed && retry.remaining > 0);
+
+		if let Some((period, count)) = s.maybe_periodic {
+			if let Some(retry) = maybe_retry {
+				// The periodic task keeps its schedule, so it is retried as a separate task.
+				let mut retry_task = s.clone();
+				retry_task.maybe_id = None;
+				retry_task.maybe_periodic = None;
+				weight = weight.saturating_add(
+					Self::schedule_retry(now, address, maybe_id.clone(), retry_task, retry)
+				);
+			}
+
+			s.maybe_periodic = if count > 1 { Some((period, count - 1)) } else { None };
+			let still_periodic = s.maybe_periodic.is_some();
+			let origin = s.origin.clone();
+			if !still_periodic {
+				// PeriodicCount
+				Self::decrease_periodic_count(&origin);
+				weight = weight.saturating_add(db_weight.reads_writes(1, 1));
+			}
+			let next = now.saturating_add(period);
+			match Self::place_task(next, s) {
+				Ok(index) => {
+					// If scheduled is named, place it's information in `Lookup`
+					if let Some(ref id) = maybe_id {
+						Lookup::<T>::insert(id, (next, index));
+					}
+					if let Some(retry) = maybe_config {
+						Retries::<T>::insert(
+							(next, index),
+							RetryConfig { remaining: retry.total_retries, ..retry },
+						);
+						weight = weight.saturating_add(db_weight.writes(1));
+					}
+				},
+				Err(_) => {
+					if still_periodic {
+						Self::decrease_periodic_count(&origin);
+					}
+					if let Some(ref id) = maybe_id {
+						Lookup::<T>::remove(id);
+					}
+					Self::deposit_event(RawEvent::PeriodicFailed(address, maybe_id));
+				},
+			}
+		} else if let Some(retry) = maybe_retry {
+			weight = weight.saturating_add(
+				Self::schedule_retry(now, address, maybe_id, s, retry)
+			);
+		} else if let Some(ref id) = maybe_id {
+			Lookup::<T>::remove(id);
+		}
+
+		weight
+	}
+
+	/// Schedule `retry_task` as the retry of the failed task at `address`, returning the weight
+	/// used.
+	fn schedule_retry(
+		now: T::BlockNumber,
+		address: TaskAddress<T::BlockNumber>,
+		maybe_id: Option<Vec<u8>>,
+		retry_task: ScheduledOf<T>,
+		retry: RetryConfig<T::BlockNumber>,
+	) -> Weight {
+		let when = now.saturating_add(retry.period);
+		let retry_id = retry_task.maybe_id.clone();
+		match Self::place_task(when, retry_task) {
+			Ok(index) => {
+				let remaining = retry.remaining.saturating_sub(1);
+				Retries::<T>::insert((when, index), RetryConfig { remaining, ..retry });
+				if let Some(ref id) = retry_id {
+					Lookup::<T>::insert(id, (when, index));
+				}
+			},
+			Err(_) => {
+				if let Some(ref id) = retry_id {
+					Lookup::<T>::remove(id);
+				}
+				Self::deposit_event(RawEvent::RetryFailed(address, maybe_id));
+			},
+		}
+		// Agenda + Retries, Lookup
+		T::DbWeight::get().reads_writes(1, 2)
+	}
+
+	/// Place the task `s` in the agenda of the block `when`, in a free slot if the agenda is
+	/// full of tasks, possibly cancelled ones.
+	///
+	/// Returns the index of the task, or the task itself if there is no room for it.
+	fn place_task(when: T::BlockNumber, s: ScheduledOf<T>) -> Result<u32, ScheduledOf<T>> {
+		let max = T::MaxScheduledPerBlock::get() as usize;
+		let mut agenda = Agenda::<T>::get(when);
+		let index = if agenda.len() < max {
+			agenda.push(Some(s));
+			agenda.len() - 1
+		} else if let Some(index) = agenda.iter().position(|s| s.is_none()) {
+			agenda[index] = Some(s);
+			index
+		} else {
+			return Err(s)
+		};
+		Agenda::<T>::insert(when, agenda);
+		Ok(index as u32)
+	}
+
+	/// Note that a periodic task of `origin` is no longer periodic.
+	fn decrease_periodic_count(origin: &T::PalletsOrigin) {
+		PeriodicCount::<T>::mutate_exists(origin, |count| {
+			*count = count.and_then(|c| c.checked_sub(1)).filter(|c| *c > 0);
+		});
+	}
+
+	/// Look up the call of the task `s` at `address`, dropping the request for its preimage if it
+	/// was given by hash.
+	///
+	/// If the preimage is not available, the task is postponed by `NoPreimagePostponement` blocks
+	/// or dropped, and `None` is returned.
+	fn resolve_call(
+		now: T::BlockNumber,
+		address: TaskAddress<T::BlockNumber>,
+		mut s: ScheduledOf<T>,
+	) -> Option<(<T as Config>::Call, ScheduledOf<T>)> {
+		let (call, maybe_resolved) = s.call.resolved::<T::PreimageProvider>();
+		s.call = call;
+		if let Some(hash) = maybe_resolved {
+			// The call is kept inline from now on, so the preimage is no longer needed.
+			T::PreimageProvider::unrequest(&hash);
+		}
+		if let Some(call) = s.call.as_value() {
+			return Some((call.clone(), s))
+		}
+
+		let hash = s.call.hash()?.clone();
+		let error = if T::PreimageProvider::len(&hash).is_some() {
+			LookupError::BadFormat
+		} else {
+			LookupError::Unknown
+		};
+		let maybe_id = s.maybe_id.clone();
+		let maybe_retry = Retries::<T>::take(address);
+		let postponed = match T::NoPreimagePostponement::get() {
+			Some(delay) if error == LookupError::Unknown => {
+				let until = now.saturating_add(delay.max(One::one()));
+				Self::place_task(until, s).map(|index| (until, index))
+			},
+			_ => Err(s),
+		};
+		match postponed {
+			Ok(new_address) => {
+				if let Some(ref id) = maybe_id {
+					Lookup::<T>::insert(id, new_address);
+				}
+				if let Some(retry) = maybe_retry {
+					Retries::<T>::insert(new_address, retry);
+				}
+			},
+			Err(s) => {
+				if let Some(ref id) = maybe_id {
+					Lookup::<T>::remove(id);
+				}
+				if s.maybe_periodic.is_some() {
+					Self::decrease_periodic_count(&s.origin);
+				}
+				T::PreimageProvider::unrequest(&hash);
+			},
+		}
+		Self::deposit_event(RawEvent::CallLookupFailed(address, maybe_id, error));
+		None
+	}
+
 	fn resolve_time(when: DispatchTime<T::BlockNumber>) -> Result<T::BlockNumber, DispatchError> {
 		let now = frame_system::Module::<T>::block_number();
 
@@ -481,31 +917,50 @@ impl<T: Config> Module<T> {
 		Ok(when)
 	}
 
+	/// Sanitize the periodicity of a task about to be scheduled, and ensure that `origin` may
+	/// have one more periodic task if it is still periodic.
+	fn sanitize_periodic(
+		origin: &T::PalletsOrigin,
+		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+	) -> Result<Option<schedule::Period<T::BlockNumber>>, DispatchError> {
+		let maybe_periodic = maybe_periodic
+			.filter(|p| p.1 > 1 && !p.0.is_zero())
+			// Remove one from the number of repetitions since we will schedule one now.
+			.map(|(p, c)| (p, c - 1));
+		if maybe_periodic.is_some() {
+			ensure!(
+				PeriodicCount::<T>::get(origin) < T::MaxPeriodicPerOrigin::get(),
+				Error::<T>::TooManyPeriodicTasks,
+			);
+		}
+		Ok(maybe_periodic)
+	}
+
 	fn do_schedule(
 		when: DispatchTime<T::BlockNumber>,
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		origin: T::PalletsOrigin,
-		call: <T as Config>::Call
+		call: CallOrHashOf<T>,
 	) -> Result<TaskAddress<T::BlockNumber>, DispatchError> {
 		let when = Self::resolve_time(when)?;
+		let maybe_periodic = Self::sanitize_periodic(&origin, maybe_periodic)?;
+		call.ensure_requested::<T::PreimageProvider>();
 
-		// sanitize maybe_periodic
-		let maybe_periodic = maybe_periodic
-			.filter(|p| p.1 > 1 && !p.0.is_zero())
-			// Remove one from the number of repetitions since we will schedule one now.
-			.map(|(p, c)| (p, c - 1));
-		let s = Some(Scheduled {
-			maybe_id: None, priority, call, maybe_periodic, origin, _phantom: PhantomData::<T::AccountId>::default(),
-		});
-		Agenda::<T>::append(when, s);
-		let index = Agenda::<T>::decode_len(when).unwrap_or(1) as u32 - 1;
-		if index > T::MaxScheduledPerBlock::get() {
-			log::warn!(
-				target: "runtime::scheduler",
-				"Warning: There are more items queued in the Scheduler than \
-				expected from the runtime configuration. An update might be needed.",
-			);
+		let s = Scheduled {
+			maybe_id: None,
+			priority,
+			call,
+			maybe_periodic,
+			origin: origin.clone(),
+			_phantom: PhantomData::<T::AccountId>::default(),
+		};
+		let index = Self::place_task(when, s).map_err(|s| {
+			s.call.ensure_unrequested::<T::PreimageProvider>();
+			Error::<T>::AgendaFull
+		})?;
+		if maybe_periodic.is_some() {
+			PeriodicCount::<T>::mutate(&origin, |count| *count = count.saturating_add(1));
 		}
 		Self::deposit_event(RawEvent::Scheduled(when, index));
 
@@ -531,6 +986,7 @@ impl<T: Config> Module<T> {
 			},
 		)?;
 		if let Some(s) = scheduled {
+			Self::cleanup_cancelled(&s, (when, index));
 			if let Some(id) = s.maybe_id {
 				Lookup::<T>::remove(id);
 			}
@@ -541,6 +997,35 @@ impl<T: Config> Module<T> {
 		}
 	}
 
+	/// Clean up what is kept on behalf of the cancelled task `s`, which was at `address`.
+	fn cleanup_cancelled(s: &ScheduledOf<T>, address: TaskAddress<T::BlockNumber>) {
+		s.call.ensure_unrequested::<T::PreimageProvider>();
+		if s.maybe_periodic.is_some() {
+			Self::decrease_periodic_count(&s.origin);
+		}
+		Retries::<T>::remove(address);
+	}
+
+	/// Move the task at `(when, index)` to `new_time`, returning its new index.
+	fn move_task(
+		(when, index): TaskAddress<T::BlockNumber>,
+		new_time: T::BlockNumber,
+	) -> Result<u32, DispatchError> {
+		let new_index = Agenda::<T>::try_mutate(when, |agenda| -> Result<u32, DispatchError> {
+			let task = agenda.get_mut(index as usize).ok_or(Error::<T>::NotFound)?;
+			let task = task.take().ok_or(Error::<T>::NotFound)?;
+			Self::place_task(new_time, task).map_err(|_| Error::<T>::AgendaFull.into())
+		})?;
+		if let Some(retry) = Retries::<T>::take((when, index)) {
+			Retries::<T>::insert((new_time, new_index), retry);
+		}
+
+		Self::deposit_event(RawEvent::Canceled(when, index));
+		Self::deposit_event(RawEvent::Scheduled(new_time, new_index));
+
+		Ok(new_index)
+	}
+
 	fn do_reschedule(
 		(when, index): TaskAddress<T::BlockNumber>,
 		new_time: DispatchTime<T::BlockNumber>,
@@ -551,16 +1036,7 @@ impl<T: Config> Module<T> {
 			return Err(Error::<T>::RescheduleNoChange.into());
 		}
 
-		Agenda::<T>::try_mutate(when, |agenda| -> DispatchResult {
-			let task = agenda.get_mut(index as usize).ok_or(Error::<T>::NotFound)?;
-			let task = task.take().ok_or(Error::<T>::NotFound)?;
-			Agenda::<T>::append(new_time, Some(task));
-			Ok(())
-		})?;
-
-		let new_index = Agenda::<T>::decode_len(new_time).unwrap_or(1) as u32 - 1;
-		Self::deposit_event(RawEvent::Canceled(when, index));
-		Self::deposit_event(RawEvent::Scheduled(new_time, new_index));
+		let new_index = Self::move_task((when, index), new_time)?;
 
 		Ok((new_time, new_index))
 	}
@@ -571,7 +1047,7 @@ impl<T: Config> Module<T> {
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		origin: T::PalletsOrigin,
-		call: <T as Config>::Call,
+		call: CallOrHashOf<T>,
 	) -> Result<TaskAddress<T::BlockNumber>, DispatchError> {
 		// ensure id it is unique
 		if Lookup::<T>::contains_key(&id) {
@@ -579,24 +1055,23 @@ impl<T: Config> Module<T> {
 		}
 
 		let when = Self::resolve_time(when)?;
-
-		// sanitize maybe_periodic
-		let maybe_periodic = maybe_periodic
-			.filter(|p| p.1 > 1 && !p.0.is_zero())
-			// Remove one from the number of repetitions since we will schedule one now.
-			.map(|(p, c)| (p, c - 1));
+		let maybe_periodic = Self::sanitize_periodic(&origin, maybe_periodic)?;
+		call.ensure_requested::<T::PreimageProvider>();
 
 		let s = Scheduled {
-			maybe_id: Some(id.clone()), priority, call, maybe_periodic, origin, _phantom: Default::default()
+			maybe_id: Some(id.clone()),
+			priority,
+			call,
+			maybe_periodic,
+			origin: origin.clone(),
+			_phantom: Default::default(),
 		};
-		Agenda::<T>::append(when, Some(s));
-		let index = Agenda::<T>::decode_len(when).unwrap_or(1) as u32 - 1;
-		if index > T::MaxScheduledPerBlock::get() {
-			log::warn!(
-				target: "runtime::scheduler",
-				"Warning: There are more items queued in the Scheduler than \
-				expected from the runtime configuration. An update might be needed.",
-			);
+		let index = Self::place_task(when, s).map_err(|s| {
+			s.call.ensure_unrequested::<T::PreimageProvider>();
+			Error::<T>::AgendaFull
+		})?;
+		if maybe_periodic.is_some() {
+			PeriodicCount::<T>::mutate(&origin, |count| *count = count.saturating_add(1));
 		}
 		let address = (when, index);
 		Lookup::<T>::insert(&id, &address);
@@ -616,7 +1091,9 @@ impl<T: Config> Module<T> {
 								return Err(BadOrigin.into());
 							}
 						}
-						*s = None;
+						if let Some(s) = s.take() {
+							Self::cleanup_cancelled(&s, (when, index));
+						}
 					}
 					Ok(())
 				})?;
@@ -641,23 +1118,52 @@ impl<T: Config> Module<T> {
 				return Err(Error::<T>::RescheduleNoChange.into());
 			}
 
-			Agenda::<T>::try_mutate(when, |agenda| -> DispatchResult {
-				let task = agenda.get_mut(index as usize).ok_or(Error::<T>::NotFound)?;
-				let task = task.take().ok_or(Error::<T>::NotFound)?;
-				Agenda::<T>::append(new_time, Some(task));
-
-				Ok(())
-			})?;
-
-			let new_index = Agenda::<T>::decode_len(new_time).unwrap_or(1) as u32 - 1;
-			Self::deposit_event(RawEvent::Canceled(when, index));
-			Self::deposit_event(RawEvent::Scheduled(new_time, new_index));
-
+			let new_index = Self::move_task((when, index), new_time)?;
 			*lookup = Some((new_time, new_index));
 
 			Ok((new_time, new_index))
 		})
 	}
+
+	/// Get the task at `(when, index)`, ensuring that it was scheduled by `origin` if given.
+	fn task_of(
+		origin: Option<T::PalletsOrigin>,
+		(when, index): TaskAddress<T::BlockNumber>,
+	) -> Result<ScheduledOf<T>, DispatchError> {
+		let s = Agenda::<T>::get(when)
+			.into_iter()
+			.nth(index as usize)
+			.flatten()
+			.ok_or(Error::<T>::NotFound)?;
+		if let Some(o) = origin {
+			ensure!(o == s.origin, BadOrigin);
+		}
+		Ok(s)
+	}
+
+	fn do_set_retry(
+		origin: Option<T::PalletsOrigin>,
+		task: TaskAddress<T::BlockNumber>,
+		retries: u8,
+		period: T::BlockNumber,
+	) -> DispatchResult {
+		let s = Self::task_of(origin, task)?;
+		let period = period.max(One::one());
+		let config = RetryConfig { total_retries: retries, remaining: retries, period };
+		Retries::<T>::insert(task, config);
+		Self::deposit_event(RawEvent::RetrySet(task, s.maybe_id, period, retries));
+		Ok(())
+	}
+
+	fn do_cancel_retry(
+		origin: Option<T::PalletsOrigin>,
+		task: TaskAddress<T::BlockNumber>,
+	) -> DispatchResult {
+		let s = Self::task_of(origin, task)?;
+		Retries::<T>::remove(task);
+		Self::deposit_event(RawEvent::RetryCancelled(task, s.maybe_id));
+		Ok(())
+	}
 }
 
 impl<T: Config> schedule::Anon<T::BlockNumber, <T as Config>::Call, T::PalletsOrigin> for Module<T> {
@@ -670,7 +1176,7 @@ impl<T: Config> schedule::Anon<T::BlockNumber, <T as Config>::Call, T::PalletsOr
 		origin: T::PalletsOrigin,
 		call: <T as Config>::Call
 	) -> Result<Self::Address, DispatchError> {
-		Self::do_schedule(when, maybe_periodic, priority, origin, call)
+		Self::do_schedule(when, maybe_periodic, priority, origin, call.into())
 	}
 
 	fn cancel((when, index): Self::Address) -> Result<(), ()> {
@@ -700,7 +1206,8 @@ impl<T: Config> schedule::Named<T::BlockNumber, <T as Config>::Call, T::PalletsO
 		origin: T::PalletsOrigin,
 		call: <T as Config>::Call,
 	) -> Result<Self::Address, ()> {
-		Self::do_schedule_named(id, when, maybe_periodic, priority, origin, call).map_err(|_| ())
+		Self::do_schedule_named(id, when, maybe_periodic, priority, origin, call.into())
+			.map_err(|_| ())
 	}
 
 	fn cancel_named(id: Vec<u8>) -> Result<(), ()> {
@@ -733,7 +1240,7 @@ mod tests {
 	use sp_runtime::{
 		Perbill,
 		testing::Header,
-		traits::{BlakeTwo256, IdentityLookup},
+		traits::{BlakeTwo256, Hash, IdentityLookup},
 	};
 	use frame_system::{EnsureOneOf, EnsureRoot, EnsureSignedBy};
 	use substrate_test_utils::assert_eq_uvec;
@@ -745,10 +1252,15 @@ mod tests {
 
 		thread_local! {
 			static LOG: RefCell<Vec<(OriginCaller, u32)>> = RefCell::new(Vec::new());
+			static FAILURES: RefCell<u32> = RefCell::new(0);
 		}
 		pub fn log() -> Vec<(OriginCaller, u32)> {
 			LOG.with(|log| log.borrow().clone())
 		}
+		/// Make the next `n` calls to `log_or_fail` fail.
+		pub fn set_failures(n: u32) {
+			FAILURES.with(|f| *f.borrow_mut() = n);
+		}
 		pub trait Config: system::Config {
 			type Event: From<Event> + Into<<Self as system::Config>::Event>;
 		}
@@ -780,6 +1292,24 @@ mod tests {
 						log.borrow_mut().push((origin.caller().clone(), i));
 					})
 				}
+
+				#[weight = *weight]
+				fn log_or_fail(origin, i: u32, weight: Weight) -> DispatchResult {
+					let fail = FAILURES.with(|f| {
+						let mut f = f.borrow_mut();
+						let fail = *f > 0;
+						*f = f.saturating_sub(1);
+						fail
+					});
+					if fail {
+						return Err(DispatchError::Other("failed"))
+					}
+					Self::deposit_event(Event::Logged(i, weight));
+					LOG.with(|log| {
+						log.borrow_mut().push((origin.caller().clone(), i));
+					});
+					Ok(())
+				}
 			}
 		}
 	}
@@ -795,6 +1325,8 @@ mod tests {
 		{
 			System: frame_system::{Module, Call, Config, Storage, Event<T>},
 			Logger: logger::{Module, Call, Event},
+			Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+			Preimage: pallet_preimage::{Module, Call, Storage, Event<T>},
 			Scheduler: scheduler::{Module, Call, Storage, Event<T>},
 		}
 	);
@@ -830,7 +1362,7 @@ mod tests {
 		type BlockHashCount = BlockHashCount;
 		type Version = ();
 		type PalletInfo = PalletInfo;
-		type AccountData = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
 		type SystemWeightInfo = ();
@@ -839,9 +1371,35 @@ mod tests {
 	impl logger::Config for Test {
 		type Event = Event;
 	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+	impl pallet_balances::Config for Test {
+		type MaxLocks = ();
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type WeightInfo = ();
+	}
+	parameter_types! {
+		pub const PreimageBaseDeposit: u64 = 1;
+		pub const PreimageByteDeposit: u64 = 1;
+	}
+	impl pallet_preimage::Config for Test {
+		type Event = Event;
+		type Currency = Balances;
+		type ManagerOrigin = EnsureRoot<u64>;
+		type BaseDeposit = PreimageBaseDeposit;
+		type ByteDeposit = PreimageByteDeposit;
+		type WeightInfo = ();
+	}
 	parameter_types! {
 		pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
 		pub const MaxScheduledPerBlock: u32 = 10;
+		pub const MaxPeriodicPerOrigin: u32 = 2;
+		pub const NoPreimagePostponement: Option<u64> = Some(2);
 	}
 	ord_parameter_types! {
 		pub const One: u64 = 1;
@@ -855,6 +1413,9 @@ mod tests {
 		type MaximumWeight = MaximumSchedulerWeight;
 		type ScheduleOrigin = EnsureOneOf<u64, EnsureRoot<u64>, EnsureSignedBy<One, u64>>;
 		type MaxScheduledPerBlock = MaxScheduledPerBlock;
+		type MaxPeriodicPerOrigin = MaxPeriodicPerOrigin;
+		type PreimageProvider = Preimage;
+		type NoPreimagePostponement = NoPreimagePostponement;
 		type WeightInfo = ();
 	}
 
@@ -880,7 +1441,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call.into()));
 			run_to_block(3);
 			assert!(logger::log().is_empty());
 			run_to_block(4);
@@ -897,7 +1458,7 @@ mod tests {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
 			// This will schedule the call 3 blocks after the next block... so block 3 + 3 = 6
-			assert_ok!(Scheduler::do_schedule(DispatchTime::After(3), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::After(3), None, 127, root(), call.into()));
 			run_to_block(5);
 			assert!(logger::log().is_empty());
 			run_to_block(6);
@@ -913,7 +1474,7 @@ mod tests {
 			run_to_block(2);
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_ok!(Scheduler::do_schedule(DispatchTime::After(0), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::After(0), None, 127, root(), call.into()));
 			// Will trigger on the next block.
 			run_to_block(3);
 			assert_eq!(logger::log(), vec![(root(), 42u32)]);
@@ -922,12 +1483,80 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn scheduling_with_preimages_works() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Preimage::note_preimage(Origin::root(), call.encode()));
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+			assert!(Preimage::is_requested(&hash));
+			run_to_block(3);
+			assert!(logger::log().is_empty());
+			run_to_block(4);
+			// The preimage is no longer needed once the call has been looked up.
+			assert!(!Preimage::is_requested(&hash));
+			assert!(!pallet_preimage::PreimageFor::<Test>::contains_key(&hash));
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+		});
+	}
+
+	#[test]
+	fn scheduling_with_missing_preimage_is_postponed() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+			run_to_block(4);
+			assert!(logger::log().is_empty());
+			assert_eq!(
+				System::events().last().unwrap().event,
+				RawEvent::CallLookupFailed(
+					(4, 0), Some(1u32.encode()), LookupError::Unknown
+				).into(),
+			);
+			// Postponed by `NoPreimagePostponement` blocks.
+			assert_eq!(Lookup::<Test>::get(1u32.encode()), Some((6, 0)));
+
+			assert_ok!(Preimage::note_preimage(Origin::root(), call.encode()));
+			run_to_block(6);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			assert_eq!(Lookup::<Test>::get(1u32.encode()), None);
+		});
+	}
+
+	#[test]
+	fn cancelling_hashed_call_drops_preimage_request() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+			let address = Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			).unwrap();
+			assert!(Preimage::is_requested(&hash));
+
+			assert_ok!(Scheduler::do_cancel_named(None, 1u32.encode()));
+			assert!(Preimage::is_requested(&hash));
+			assert_ok!(Scheduler::do_cancel(None, address));
+			assert!(!Preimage::is_requested(&hash));
+		});
+	}
+
 	#[test]
 	fn periodic_scheduling_works() {
 		new_test_ext().execute_with(|| {
 			// at #4, every 3 blocks, 3 times.
 			assert_ok!(Scheduler::do_schedule(
-				DispatchTime::At(4), Some((3, 3)), 127, root(), Call::Logger(logger::Call::log(42, 1000))
+				DispatchTime::At(4), Some((3, 3)), 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
 			));
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -951,7 +1580,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_eq!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call).unwrap(), (4, 0));
+			assert_eq!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call.into()).unwrap(), (4, 0));
 
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -1040,10 +1669,10 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			// at #4.
 			Scheduler::do_schedule_named(
-				1u32.encode(), DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(69, 1000))
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(69, 1000)).into()
 			).unwrap();
 			let i = Scheduler::do_schedule(
-				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000))
+				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
 			).unwrap();
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -1064,7 +1693,7 @@ mod tests {
 				Some((3, 3)),
 				127,
 				root(),
-				Call::Logger(logger::Call::log(42, 1000))
+				Call::Logger(logger::Call::log(42, 1000)).into()
 			).unwrap();
 			// same id results in error.
 			assert!(Scheduler::do_schedule_named(
@@ -1073,11 +1702,11 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(69, 1000))
+				Call::Logger(logger::Call::log(69, 1000)).into()
 			).is_err());
 			// different id is ok.
 			Scheduler::do_schedule_named(
-				2u32.encode(), DispatchTime::At(8), None, 127, root(), Call::Logger(logger::Call::log(69, 1000))
+				2u32.encode(), DispatchTime::At(8), None, 127, root(), Call::Logger(logger::Call::log(69, 1000)).into()
 			).unwrap();
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -1098,13 +1727,13 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				127,
-				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// 69 and 42 do not fit together
 			run_to_block(4);
@@ -1122,14 +1751,14 @@ mod tests {
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// With base weights, 69 and 42 should not fit together, but do because of hard deadlines
 			run_to_block(4);
@@ -1145,14 +1774,14 @@ mod tests {
 				None,
 				1,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			run_to_block(4);
 			assert_eq!(logger::log(), vec![(root(), 69u32), (root(), 42u32)]);
@@ -1166,19 +1795,19 @@ mod tests {
 				DispatchTime::At(4),
 				None,
 				255,
-				root(), Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3))
+				root(), Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				127,
-				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				126,
-				root(), Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)).into()
 			));
 
 			// 2600 does not fit with 69 or 42, but has higher priority, so will go through
@@ -1193,8 +1822,12 @@ mod tests {
 	#[test]
 	fn on_initialize_weight_is_correct() {
 		new_test_ext().execute_with(|| {
+			// IncompleteSince + Agenda write
 			let base_weight: Weight = <Test as frame_system::Config>::DbWeight::get().reads_writes(1, 2);
-			let base_multiplier = 0;
+			// Agenda read, for each serviced agenda
+			let agenda_multiplier = <Test as frame_system::Config>::DbWeight::get().reads(1);
+			// Retries
+			let base_multiplier = <Test as frame_system::Config>::DbWeight::get().reads(1);
 			let named_multiplier = <Test as frame_system::Config>::DbWeight::get().writes(1);
 			let periodic_multiplier = <Test as frame_system::Config>::DbWeight::get().reads_writes(1, 1);
 
@@ -1202,7 +1835,7 @@ mod tests {
 			assert_ok!(
 				Scheduler::do_schedule_named(
 					1u32.encode(), DispatchTime::At(1), None, 255, root(),
-					Call::Logger(logger::Call::log(3, MaximumSchedulerWeight::get() / 3))
+					Call::Logger(logger::Call::log(3, MaximumSchedulerWeight::get() / 3)).into()
 				)
 			);
 			// Anon Periodic
@@ -1211,7 +1844,7 @@ mod tests {
 				Some((1000, 3)),
 				128,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3)).into()
 			));
 			// Anon
 			assert_ok!(Scheduler::do_schedule(
@@ -1219,45 +1852,347 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// Named Periodic
 			assert_ok!(Scheduler::do_schedule_named(
 				2u32.encode(), DispatchTime::At(1), Some((1000, 3)), 126, root(),
-				Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)))
+				Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)).into())
 			);
 
 			// Will include the named periodic only
 			let actual_weight = Scheduler::on_initialize(1);
 			let call_weight = MaximumSchedulerWeight::get() / 2;
 			assert_eq!(
-				actual_weight, call_weight + base_weight + base_multiplier + named_multiplier + periodic_multiplier
+				actual_weight,
+				call_weight + base_weight + agenda_multiplier + base_multiplier + named_multiplier
+					+ periodic_multiplier
 			);
 			assert_eq!(logger::log(), vec![(root(), 2600u32)]);
+			assert_eq!(IncompleteSince::<Test>::get(), Some(1));
 
-			// Will include anon and anon periodic
+			// Will include anon and anon periodic, left in the agenda of block 1
 			let actual_weight = Scheduler::on_initialize(2);
 			let call_weight = MaximumSchedulerWeight::get() / 2 + MaximumSchedulerWeight::get() / 3;
-			assert_eq!(actual_weight, call_weight + base_weight + base_multiplier * 2 + periodic_multiplier);
+			assert_eq!(
+				actual_weight,
+				call_weight + base_weight + agenda_multiplier * 2 + base_multiplier * 2
+					+ periodic_multiplier
+			);
 			assert_eq!(logger::log(), vec![(root(), 2600u32), (root(), 69u32), (root(), 42u32)]);
+			assert_eq!(IncompleteSince::<Test>::get(), Some(1));
 
 			// Will include named only
 			let actual_weight = Scheduler::on_initialize(3);
 			let call_weight = MaximumSchedulerWeight::get() / 3;
-			assert_eq!(actual_weight, call_weight + base_weight + base_multiplier + named_multiplier);
+			assert_eq!(
+				actual_weight,
+				call_weight + base_weight + agenda_multiplier * 3 + base_multiplier + named_multiplier
+			);
 			assert_eq!(logger::log(), vec![(root(), 2600u32), (root(), 69u32), (root(), 42u32), (root(), 3u32)]);
+			assert_eq!(IncompleteSince::<Test>::get(), None);
 
 			// Will contain none
 			let actual_weight = Scheduler::on_initialize(4);
-			assert_eq!(actual_weight, 0);
+			let cursor_weight = <Test as frame_system::Config>::DbWeight::get().reads_writes(1, 1);
+			assert_eq!(actual_weight, cursor_weight + agenda_multiplier);
+		});
+	}
+
+	#[test]
+	fn scheduling_fails_when_agenda_is_full() {
+		new_test_ext().execute_with(|| {
+			let max: u32 = <Test as Config>::MaxScheduledPerBlock::get();
+			for i in 0..max {
+				assert_eq!(Scheduler::do_schedule(
+					DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(i, 1000)).into()
+				).unwrap(), (4, i));
+			}
+			assert_noop!(
+				Scheduler::do_schedule(
+					DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(0, 1000)).into()
+				),
+				Error::<Test>::AgendaFull,
+			);
+			assert_noop!(
+				Scheduler::do_schedule_named(
+					1u32.encode(), DispatchTime::At(4), None, 127, root(),
+					Call::Logger(logger::Call::log(0, 1000)).into()
+				),
+				Error::<Test>::AgendaFull,
+			);
+			// Other blocks are not affected.
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(5), None, 127, root(), Call::Logger(logger::Call::log(0, 1000)).into()
+			));
+			// A cancelled task leaves a hole which is reused.
+			assert_ok!(Scheduler::do_cancel(None, (4, 3)));
+			assert_eq!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
+			).unwrap(), (4, 3));
+		});
+	}
+
+	#[test]
+	fn reschedule_fails_when_agenda_is_full() {
+		new_test_ext().execute_with(|| {
+			let max: u32 = <Test as Config>::MaxScheduledPerBlock::get();
+			for i in 0..max {
+				assert_ok!(Scheduler::do_schedule(
+					DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(i, 1000)).into()
+				));
+			}
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(5), None, 127, root(),
+				Call::Logger(logger::Call::log(42, 1000)).into()
+			));
+			assert_noop!(
+				Scheduler::do_reschedule_named(1u32.encode(), DispatchTime::At(4)),
+				Error::<Test>::AgendaFull,
+			);
+			assert_eq!(Lookup::<Test>::get(1u32.encode()), Some((5, 0)));
+		});
+	}
+
+	#[test]
+	fn incomplete_agendas_are_serviced_first() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(),
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
+			));
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(),
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
+			));
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(5), None, 0, root(),
+				Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)).into()
+			));
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(5), None, 127, root(),
+				Call::Logger(logger::Call::log(3, MaximumSchedulerWeight::get() / 2)).into()
+			));
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			assert_eq!(IncompleteSince::<Test>::get(), Some(4));
+			// The leftover of block 4 goes first; hard deadlines of block 5 are still met.
+			run_to_block(5);
+			assert_eq!(logger::log(), vec![(root(), 42u32), (root(), 69u32), (root(), 2600u32)]);
+			assert_eq!(IncompleteSince::<Test>::get(), Some(5));
+			assert!(!Agenda::<Test>::contains_key(4));
+			run_to_block(6);
+			assert_eq!(
+				logger::log(),
+				vec![(root(), 42u32), (root(), 69u32), (root(), 2600u32), (root(), 3u32)],
+			);
+			assert_eq!(IncompleteSince::<Test>::get(), None);
+			assert!(!Agenda::<Test>::contains_key(5));
+		});
+	}
+
+	#[test]
+	fn retry_works() {
+		new_test_ext().execute_with(|| {
+			logger::set_failures(2);
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log_or_fail(42, 1000)).into()
+			));
+			assert_ok!(Scheduler::set_retry(Origin::root(), (4, 0), 3, 2));
+			assert_eq!(
+				Retries::<Test>::get((4, 0)),
+				Some(RetryConfig { total_retries: 3, remaining: 3, period: 2 }),
+			);
+			run_to_block(4);
+			assert!(logger::log().is_empty());
+			assert!(Retries::<Test>::get((4, 0)).is_none());
+			assert_eq!(
+				Retries::<Test>::get((6, 0)),
+				Some(RetryConfig { total_retries: 3, remaining: 2, period: 2 }),
+			);
+			run_to_block(6);
+			assert!(logger::log().is_empty());
+			assert_eq!(
+				Retries::<Test>::get((8, 0)),
+				Some(RetryConfig { total_retries: 3, remaining: 1, period: 2 }),
+			);
+			run_to_block(8);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			assert!(Retries::<Test>::get((8, 0)).is_none());
+			assert_eq!(Retries::<Test>::iter().count(), 0);
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+		});
+	}
+
+	#[test]
+	fn named_retry_keeps_its_name() {
+		new_test_ext().execute_with(|| {
+			logger::set_failures(1);
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(4), None, 127, root(),
+				Call::Logger(logger::Call::log_or_fail(42, 1000)).into()
+			));
+			assert_ok!(Scheduler::set_retry_named(Origin::root(), 1u32.encode(), 1, 3));
+			run_to_block(4);
+			assert!(logger::log().is_empty());
+			assert_eq!(Lookup::<Test>::get(1u32.encode()), Some((7, 0)));
+			run_to_block(7);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			assert!(Lookup::<Test>::get(1u32.encode()).is_none());
+		});
+	}
+
+	#[test]
+	fn retries_are_bounded() {
+		new_test_ext().execute_with(|| {
+			logger::set_failures(10);
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log_or_fail(42, 1000)).into()
+			));
+			assert_ok!(Scheduler::set_retry(Origin::root(), (4, 0), 2, 1));
+			run_to_block(100);
+			assert!(logger::log().is_empty());
+			assert_eq!(Retries::<Test>::iter().count(), 0);
+			assert_eq!(Agenda::<Test>::iter().count(), 0);
+			// The initial dispatch and two retries.
+			let dispatched = System::events()
+				.into_iter()
+				.filter(|r| matches!(r.event, Event::scheduler(RawEvent::Dispatched(..))))
+				.count();
+			assert_eq!(dispatched, 3);
+		});
+	}
+
+	#[test]
+	fn periodic_tasks_retry_separately() {
+		new_test_ext().execute_with(|| {
+			logger::set_failures(1);
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), Some((10, 2)), 127, root(),
+				Call::Logger(logger::Call::log_or_fail(42, 1000)).into()
+			));
+			assert_ok!(Scheduler::set_retry(Origin::root(), (4, 0), 1, 1));
+			run_to_block(4);
+			assert!(logger::log().is_empty());
+			// The retry is a one-off task, the periodic task keeps its schedule and retry config.
+			assert_eq!(Agenda::<Test>::get(5)[0].as_ref().unwrap().maybe_periodic, None);
+			assert_eq!(
+				Retries::<Test>::get((5, 0)),
+				Some(RetryConfig { total_retries: 1, remaining: 0, period: 1 }),
+			);
+			assert_eq!(
+				Retries::<Test>::get((14, 0)),
+				Some(RetryConfig { total_retries: 1, remaining: 1, period: 1 }),
+			);
+			run_to_block(5);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			run_to_block(14);
+			assert_eq!(logger::log(), vec![(root(), 42u32), (root(), 42u32)]);
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![(root(), 42u32), (root(), 42u32)]);
+			assert_eq!(Retries::<Test>::iter().count(), 0);
+		});
+	}
+
+	#[test]
+	fn set_retry_checks_origin_and_task() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
+			));
+			assert_noop!(Scheduler::set_retry(system::RawOrigin::Signed(2).into(), (4, 0), 1, 1), BadOrigin);
+			assert_noop!(Scheduler::set_retry(system::RawOrigin::Signed(1).into(), (4, 0), 1, 1), BadOrigin);
+			assert_noop!(Scheduler::set_retry(Origin::root(), (4, 1), 1, 1), Error::<Test>::NotFound);
+			assert_noop!(
+				Scheduler::set_retry_named(Origin::root(), 1u32.encode(), 1, 1),
+				Error::<Test>::NotFound,
+			);
+			assert_noop!(Scheduler::cancel_retry(system::RawOrigin::Signed(1).into(), (4, 0)), BadOrigin);
+		});
+	}
+
+	#[test]
+	fn cancel_retry_works() {
+		new_test_ext().execute_with(|| {
+			logger::set_failures(1);
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(4), None, 127, root(),
+				Call::Logger(logger::Call::log_or_fail(42, 1000)).into()
+			));
+			assert_ok!(Scheduler::set_retry_named(Origin::root(), 1u32.encode(), 1, 1));
+			assert_ok!(Scheduler::cancel_retry_named(Origin::root(), 1u32.encode()));
+			assert!(Retries::<Test>::get((4, 0)).is_none());
+			run_to_block(100);
+			assert!(logger::log().is_empty());
+			assert!(Lookup::<Test>::get(1u32.encode()).is_none());
+		});
+	}
+
+	#[test]
+	fn cancel_and_reschedule_carry_retries() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
+			));
+			assert_ok!(Scheduler::set_retry(Origin::root(), (4, 0), 1, 1));
+			assert_eq!(Scheduler::do_reschedule((4, 0), DispatchTime::At(6)).unwrap(), (6, 0));
+			assert!(Retries::<Test>::get((4, 0)).is_none());
+			assert!(Retries::<Test>::get((6, 0)).is_some());
+			assert_ok!(Scheduler::do_cancel(None, (6, 0)));
+			assert_eq!(Retries::<Test>::iter().count(), 0);
+		});
+	}
+
+	#[test]
+	fn periodic_tasks_are_limited_per_origin() {
+		new_test_ext().execute_with(|| {
+			let max: u32 = <Test as Config>::MaxPeriodicPerOrigin::get();
+			for _ in 0..max {
+				assert_ok!(Scheduler::do_schedule(
+					DispatchTime::At(4), Some((3, 2)), 127, root(),
+					Call::Logger(logger::Call::log(42, 1000)).into()
+				));
+			}
+			assert_eq!(PeriodicCount::<Test>::get(root()), max);
+			assert_noop!(
+				Scheduler::do_schedule(
+					DispatchTime::At(4), Some((3, 2)), 127, root(),
+					Call::Logger(logger::Call::log(42, 1000)).into()
+				),
+				Error::<Test>::TooManyPeriodicTasks,
+			);
+			// One-off tasks and other origins are not limited.
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
+			));
+			let signed: OriginCaller = system::RawOrigin::Signed(1).into();
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), Some((3, 2)), 127, signed.clone(),
+				Call::Logger(logger::Call::log(69, 1000)).into()
+			));
+			assert_eq!(PeriodicCount::<Test>::get(&signed), 1);
+
+			// Cancelling frees a slot.
+			assert_ok!(Scheduler::do_cancel(None, (4, 0)));
+			assert_eq!(PeriodicCount::<Test>::get(root()), max - 1);
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(5), Some((3, 2)), 127, root(),
+				Call::Logger(logger::Call::log(42, 1000)).into()
+			));
+
+			// The last repetition is no longer counted.
+			run_to_block(5);
+			assert_eq!(PeriodicCount::<Test>::get(root()), 0);
+			assert!(!PeriodicCount::<Test>::contains_key(&signed));
 		});
 	}
 
 	#[test]
 	fn root_calls_works() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_ok!(Scheduler::schedule_named(Origin::root(), 1u32.encode(), 4, None, 127, call));
 			assert_ok!(Scheduler::schedule(Origin::root(), 4, None, 127, call2));
 			run_to_block(3);
@@ -1277,8 +2212,8 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			run_to_block(3);
 
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 
 			assert_err!(
 				Scheduler::schedule_named(Origin::root(), 1u32.encode(), 2, None, 127, call),
@@ -1300,8 +2235,8 @@ mod tests {
 	#[test]
 	fn should_use_orign() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_ok!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(1).into(), 1u32.encode(), 4, None, 127, call)
 			);
@@ -1321,8 +2256,8 @@ mod tests {
 	#[test]
 	fn should_check_orign() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_noop!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(2).into(), 1u32.encode(), 4, None, 127, call),
 				BadOrigin
@@ -1334,8 +2269,8 @@ mod tests {
 	#[test]
 	fn should_check_orign_for_cancel() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log_without_filter(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log_without_filter(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log_without_filter(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log_without_filter(42, 1000)).into());
 			assert_ok!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(1).into(), 1u32.encode(), 4, None, 127, call)
 			);
@@ -1357,7 +2292,7 @@ mod tests {
 	}
 
 	#[test]
-	fn migration_to_v2_works() {
+	fn migration_to_v3_works() {
 		new_test_ext().execute_with(|| {
 			for i in 0..3u64 {
 				let k = i.twox_64_concat();
@@ -1386,25 +2321,25 @@ mod tests {
 
 			assert_eq!(StorageVersion::get(), Releases::V1);
 
-			assert!(Scheduler::migrate_v1_to_t2());
+			assert!(Scheduler::migrate_v1_to_v3());
 
 			assert_eq_uvec!(Agenda::<Test>::iter().collect::<Vec<_>>(), vec![
 				(
 					0,
 					vec![
-					Some(ScheduledV2 {
+					Some(ScheduledV3 {
 						maybe_id: None,
 						priority: 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						maybe_periodic: None,
 						origin: root(),
 						_phantom: PhantomData::<u64>::default(),
 					}),
 					None,
-					Some(ScheduledV2 {
+					Some(ScheduledV3 {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						origin: root(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1413,19 +2348,19 @@ mod tests {
 				(
 					1,
 					vec![
-						Some(ScheduledV2 {
+						Some(ScheduledV3 {
 							maybe_id: None,
 							priority: 11,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
 						}),
 						None,
-						Some(ScheduledV2 {
+						Some(ScheduledV3 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1435,19 +2370,19 @@ mod tests {
 				(
 					2,
 					vec![
-						Some(ScheduledV2 {
+						Some(ScheduledV3 {
 							maybe_id: None,
 							priority: 12,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
 						}),
 						None,
-						Some(ScheduledV2 {
+						Some(ScheduledV3 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1456,7 +2391,77 @@ mod tests {
 				)
 			]);
 
-			assert_eq!(StorageVersion::get(), Releases::V2);
+			assert_eq!(PeriodicCount::<Test>::get(root()), 3);
+			assert_eq!(StorageVersion::get(), Releases::V3);
+		});
+	}
+
+	#[test]
+	fn migration_v2_to_v3_works() {
+		new_test_ext().execute_with(|| {
+			let signed: OriginCaller = system::RawOrigin::Signed(1).into();
+			for i in 0..3u64 {
+				let k = i.twox_64_concat();
+				let old: Vec<Option<ScheduledV2<Call, u64, OriginCaller, u64>>> = vec![
+					Some(ScheduledV2 {
+						maybe_id: None,
+						priority: i as u8 + 10,
+						call: Call::Logger(logger::Call::log(96, 100)),
+						maybe_periodic: None,
+						origin: root(),
+						_phantom: Default::default(),
+					}),
+					None,
+					Some(ScheduledV2 {
+						maybe_id: Some(b"test".to_vec()),
+						priority: 123,
+						call: Call::Logger(logger::Call::log(69, 1000)),
+						maybe_periodic: Some((456u64, 10)),
+						origin: if i == 0 { signed.clone() } else { root() },
+						_phantom: Default::default(),
+					}),
+				];
+				frame_support::migration::put_storage_value(
+					b"Scheduler",
+					b"Agenda",
+					&k,
+					old,
+				);
+			}
+			StorageVersion::put(Releases::V2);
+
+			assert!(Scheduler::migrate_v2_to_v3());
+
+			let expected = |i: u64| vec![
+				Some(ScheduledV3 {
+					maybe_id: None,
+					priority: i as u8 + 10,
+					call: Call::Logger(logger::Call::log(96, 100)).into(),
+					maybe_periodic: None,
+					origin: root(),
+					_phantom: PhantomData::<u64>::default(),
+				}),
+				None,
+				Some(ScheduledV3 {
+					maybe_id: Some(b"test".to_vec()),
+					priority: 123,
+					call: Call::Logger(logger::Call::log(69, 1000)).into(),
+					maybe_periodic: Some((456u64, 10)),
+					origin: if i == 0 { signed.clone() } else { root() },
+					_phantom: PhantomData::<u64>::default(),
+				}),
+			];
+			assert_eq_uvec!(
+				Agenda::<Test>::iter().collect::<Vec<_>>(),
+				vec![(0, expected(0)), (1, expected(1)), (2, expected(2))]
+			);
+			assert_eq!(PeriodicCount::<Test>::get(root()), 2);
+			assert_eq!(PeriodicCount::<Test>::get(&signed), 1);
+			assert_eq!(StorageVersion::get(), Releases::V3);
+
+			// Only migrates once.
+			assert!(!Scheduler::migrate_v2_to_v3());
+			assert_eq!(PeriodicCount::<Test>::get(root()), 2);
 		});
 	}
 
@@ -1465,11 +2470,11 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			for i in 0..3u64 {
 				let k = i.twox_64_concat();
-				let old: Vec<Option<Scheduled<_, _, u32, u64>>> = vec![
+				let old: Vec<Option<Scheduled<CallOrHashOf<Test>, u64, u32, u64>>> = vec![
 					Some(Scheduled {
 						maybe_id: None,
 						priority: i as u8 + 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						origin: 3u32,
 						maybe_periodic: None,
 						_phantom: Default::default(),
@@ -1479,7 +2484,7 @@ mod tests {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
 						origin: 2u32,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						_phantom: Default::default(),
 					}),
@@ -1511,7 +2516,7 @@ mod tests {
 					Some(ScheduledV2::<_, _, OriginCaller, u64> {
 						maybe_id: None,
 						priority: 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						maybe_periodic: None,
 						origin: system::RawOrigin::Root.into(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1520,7 +2525,7 @@ mod tests {
 					Some(ScheduledV2 {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						origin: system::RawOrigin::None.into(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1532,7 +2537,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: None,
 							priority: 11,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: system::RawOrigin::Root.into(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1541,7 +2546,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: system::RawOrigin::None.into(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1554,7 +2559,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: None,
 							priority: 12,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: system::RawOrigin::Root.into(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1563,7 +2568,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: system::RawOrigin::None.into(),
 							_phantom: PhantomData::<u64>::default(),
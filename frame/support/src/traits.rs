@@ -42,6 +42,9 @@ use impl_trait_for_tuples::impl_for_tuples;
 #[doc(hidden)]
 pub use sp_std::{mem::{swap, take}, cell::RefCell, vec::Vec, boxed::Box};
 
+pub mod tokens;
+pub use tokens::{fungible, fungibles, nonfungible, nonfungibles};
+
 /// A trait for online node inspection in a session.
 ///
 /// Something that can give information about the current validator set.
@@ -1251,7 +1254,7 @@ pub trait VestingSchedule<AccountId> {
 
 	/// Adds a vesting schedule to a given account.
 	///
-	/// If there already exists a vesting schedule for the given account, an `Err` is returned
+	/// If the account already has the maximum number of vesting schedules, an `Err` is returned
 	/// and nothing is updated.
 	///
 	/// Is a no-op if the amount to be vested is zero.
@@ -1264,10 +1267,18 @@ pub trait VestingSchedule<AccountId> {
 		starting_block: Self::Moment,
 	) -> DispatchResult;
 
-	/// Remove a vesting schedule for a given account.
+	/// Checks whether `add_vesting_schedule` would work against `who`.
+	fn can_add_vesting_schedule(
+		who: &AccountId,
+		locked: <Self::Currency as Currency<AccountId>>::Balance,
+		per_block: <Self::Currency as Currency<AccountId>>::Balance,
+		starting_block: Self::Moment,
+	) -> DispatchResult;
+
+	/// Remove the vesting schedule at `schedule_index` of the given account.
 	///
 	/// NOTE: This doesn't alter the free balance of the account.
-	fn remove_vesting_schedule(who: &AccountId);
+	fn remove_vesting_schedule(who: &AccountId, schedule_index: u32) -> DispatchResult;
 }
 
 bitflags! {
@@ -1676,9 +1687,115 @@ pub trait OffchainWorker<BlockNumber> {
 	fn offchain_worker(_n: BlockNumber) {}
 }
 
+/// A provider of preimages: encoded values, usually calls, which are referred to by the hash of
+/// their encoding.
+pub trait QueryPreimage<Hash> {
+	/// Returns the length of the preimage of `hash`, if it is available.
+	fn len(hash: &Hash) -> Option<u32>;
+
+	/// Returns the preimage of `hash`, if it is available.
+	fn fetch(hash: &Hash) -> Option<Vec<u8>>;
+
+	/// Returns whether the preimage of `hash` has been requested.
+	fn is_requested(hash: &Hash) -> bool;
+
+	/// Request that the preimage of `hash` be made available and kept until it is unrequested.
+	///
+	/// Requests are reference counted, so every call must be paired with an `unrequest`.
+	fn request(hash: &Hash);
+
+	/// Drop a previous request for the preimage of `hash`.
+	fn unrequest(hash: &Hash);
+}
+
+impl<Hash> QueryPreimage<Hash> for () {
+	fn len(_: &Hash) -> Option<u32> { None }
+	fn fetch(_: &Hash) -> Option<Vec<u8>> { None }
+	fn is_requested(_: &Hash) -> bool { false }
+	fn request(_: &Hash) {}
+	fn unrequest(_: &Hash) {}
+}
+
+/// A provider of preimages which also allows them to be stored directly, without a deposit.
+pub trait StorePreimage<Hash>: QueryPreimage<Hash> {
+	/// The maximum length of a preimage which can be stored.
+	const MAX_LENGTH: usize;
+
+	/// Store `bytes` as a preimage, returning its hash.
+	fn note(bytes: Vec<u8>) -> Result<Hash, DispatchError>;
+
+	/// Remove a preimage which was stored with `note`. Requested preimages are kept until they
+	/// are no longer requested.
+	fn unnote(hash: &Hash);
+}
+
 pub mod schedule {
 	use super::*;
 
+	/// A value which is either given inline or referred to by the hash of its encoding, in which
+	/// case it has to be looked up from a [`QueryPreimage`] provider before use.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub enum MaybeHashed<T, Hash> {
+		/// The value itself.
+		Value(T),
+		/// The hash of the encoded value.
+		Hash(Hash),
+	}
+
+	impl<T, H> From<T> for MaybeHashed<T, H> {
+		fn from(t: T) -> Self {
+			MaybeHashed::Value(t)
+		}
+	}
+
+	impl<T: Decode, H> MaybeHashed<T, H> {
+		/// The hash of the value, if it is not given inline.
+		pub fn hash(&self) -> Option<&H> {
+			match self {
+				MaybeHashed::Value(_) => None,
+				MaybeHashed::Hash(h) => Some(h),
+			}
+		}
+
+		/// The value, if it is given inline.
+		pub fn as_value(&self) -> Option<&T> {
+			match self {
+				MaybeHashed::Value(v) => Some(v),
+				MaybeHashed::Hash(_) => None,
+			}
+		}
+
+		/// Request the preimage from `P` if this is a hash.
+		pub fn ensure_requested<P: QueryPreimage<H>>(&self) {
+			if let MaybeHashed::Hash(h) = self {
+				P::request(h)
+			}
+		}
+
+		/// Drop the request for the preimage from `P` if this is a hash.
+		pub fn ensure_unrequested<P: QueryPreimage<H>>(&self) {
+			if let MaybeHashed::Hash(h) = self {
+				P::unrequest(h)
+			}
+		}
+
+		/// Attempt to turn this into a `Value` by looking up and decoding the preimage from `P`.
+		///
+		/// On success also returns the hash that was resolved, so that the caller can drop its
+		/// request for it.
+		pub fn resolved<P: QueryPreimage<H>>(self) -> (Self, Option<H>) {
+			match self {
+				MaybeHashed::Value(v) => (MaybeHashed::Value(v), None),
+				MaybeHashed::Hash(h) => {
+					match P::fetch(&h).and_then(|data| T::decode(&mut &data[..]).ok()) {
+						Some(v) => (MaybeHashed::Value(v), Some(h)),
+						None => (MaybeHashed::Hash(h), None),
+					}
+				},
+			}
+		}
+	}
+
 	/// Information relating to the period of a scheduled task. First item is the length of the
 	/// period and the second is the number of times it should be executed in total before the task
 	/// is considered finished and removed.
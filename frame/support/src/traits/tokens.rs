@@ -0,0 +1,31 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits for working with tokens.
+//!
+//! - [`fungible`]: a single fungible token, such as the native balance of a chain.
+//! - [`fungibles`]: a set of fungible tokens, each identified by an asset id.
+//! - [`nonfungible`]: a single class of non-fungible tokens.
+//! - [`nonfungibles`]: a set of classes of non-fungible tokens.
+
+pub mod fungible;
+pub mod fungibles;
+pub mod nonfungible;
+pub mod nonfungibles;
+mod misc;
+
+pub use misc::{WithdrawConsequence, DepositConsequence, Balance, AssetId, BalanceConversion};
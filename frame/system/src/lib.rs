@@ -831,6 +831,32 @@ impl<
 	}
 }
 
+/// Ensure the origin is `Root`, yielding the `T` returned by `Success` on success.
+///
+/// Useful where the origin check also grants a limit, e.g. a maximum amount to spend.
+pub struct EnsureRootWithSuccess<AccountId, T, Success>(
+	sp_std::marker::PhantomData<(AccountId, T, Success)>
+);
+impl<
+	O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>,
+	AccountId,
+	T,
+	Success: Get<T>,
+> EnsureOrigin<O> for EnsureRootWithSuccess<AccountId, T, Success> {
+	type Success = T;
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			RawOrigin::Root => Ok(Success::get()),
+			r => Err(O::from(r)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> O {
+		O::from(RawOrigin::Root)
+	}
+}
+
 pub struct EnsureSigned<AccountId>(sp_std::marker::PhantomData<AccountId>);
 impl<
 	O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>,
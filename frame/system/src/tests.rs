@@ -519,6 +519,20 @@ fn ensure_one_of_works() {
 	assert!(ensure_root_or_signed(RawOrigin::None).is_err())
 }
 
+#[test]
+fn ensure_root_with_success_works() {
+	frame_support::parameter_types! {
+		pub const MaxSpend: u64 = 42;
+	}
+	fn ensure_root_with_success(o: RawOrigin<u64>) -> Result<u64, Origin> {
+		EnsureRootWithSuccess::<u64, u64, MaxSpend>::try_origin(o.into())
+	}
+
+	assert_eq!(ensure_root_with_success(RawOrigin::Root).unwrap(), 42);
+	assert!(ensure_root_with_success(RawOrigin::Signed(0)).is_err());
+	assert!(ensure_root_with_success(RawOrigin::None).is_err());
+}
+
 #[test]
 fn extrinsics_root_is_calculated_correctly() {
 	new_test_ext().execute_with(|| {
@@ -137,6 +137,7 @@ impl pallet_treasury::Config for Test {
 	type BurnDestination = ();  // Just gets burned.
 	type WeightInfo = ();
 	type SpendFunds = ();
+	type SpendOrigin = frame_system::EnsureNever<u64>;
 }
 parameter_types! {
 	pub const TipCountdown: u64 = 1;
@@ -0,0 +1,305 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Asset Transaction Payment Module
+//!
+//! This module extends the transaction payment module so that fees can be paid in assets
+//! instead of the native currency.
+//!
+//! The [`ChargeAssetTxPayment`] signed extension carries an optional `asset_id`:
+//!   - `None`: the fee is charged in the native currency via
+//!     [`pallet_transaction_payment::Config::OnChargeTransaction`], exactly like
+//!     [`pallet_transaction_payment::ChargeTransactionPayment`].
+//!   - `Some(asset_id)`: the fee computed by the transaction payment module is converted into the
+//!     asset and charged via [`Config::OnChargeAssetTransaction`]. Unused weight is refunded in
+//!     the same asset.
+//!
+//! [`FungiblesAdapter`] is the default [`OnChargeAssetTransaction`] implementation. It converts
+//! fees with a [`BalanceConversion`](frame_support::traits::tokens::BalanceConversion), e.g.
+//! `pallet_assets::BalanceToAssetBalance`, and hands the collected fees to a [`HandleCredit`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Encode, Decode};
+use frame_support::{
+	dispatch::DispatchResult,
+	traits::tokens::fungibles::{Inspect, Unbalanced},
+	weights::{DispatchInfo, GetDispatchInfo, PostDispatchInfo},
+};
+use sp_runtime::{
+	FixedPointOperand,
+	traits::{
+		DispatchInfoOf, Dispatchable, PostDispatchInfoOf, Saturating, SignedExtension, Zero,
+	},
+	transaction_validity::{
+		TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+use pallet_transaction_payment::{FeeDetails, OnChargeTransaction};
+
+mod payment;
+#[cfg(test)]
+mod tests;
+
+pub use payment::*;
+pub use pallet::*;
+
+// Type aliases used for interaction with `OnChargeTransaction`.
+pub(crate) type OnChargeTransactionOf<T> =
+	<T as pallet_transaction_payment::Config>::OnChargeTransaction;
+// Balance type alias.
+pub(crate) type BalanceOf<T> = <OnChargeTransactionOf<T> as OnChargeTransaction<T>>::Balance;
+// Liquidity info type alias.
+pub(crate) type LiquidityInfoOf<T> =
+	<OnChargeTransactionOf<T> as OnChargeTransaction<T>>::LiquidityInfo;
+
+// Type alias used for interaction with fungibles (assets).
+pub(crate) type AssetBalanceOf<T> =
+	<<T as Config>::Fungibles as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+pub(crate) type AssetIdOf<T> =
+	<<T as Config>::Fungibles as Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
+
+// Type aliases used for interaction with `OnChargeAssetTransaction`.
+pub(crate) type ChargeAssetBalanceOf<T> =
+	<<T as Config>::OnChargeAssetTransaction as OnChargeAssetTransaction<T>>::Balance;
+pub(crate) type ChargeAssetIdOf<T> =
+	<<T as Config>::OnChargeAssetTransaction as OnChargeAssetTransaction<T>>::AssetId;
+pub(crate) type ChargeAssetLiquidityOf<T> =
+	<<T as Config>::OnChargeAssetTransaction as OnChargeAssetTransaction<T>>::LiquidityInfo;
+
+/// Used to pass the initial payment info from pre- to post-dispatch.
+pub enum InitialPayment<T: Config> {
+	/// No initial fee was payed.
+	Nothing,
+	/// The initial fee was payed in the native currency.
+	Native(LiquidityInfoOf<T>),
+	/// The initial fee was payed in an asset.
+	Asset(ChargeAssetLiquidityOf<T>),
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+		/// The fungibles instance used to pay for transactions in assets.
+		type Fungibles: Unbalanced<Self::AccountId>;
+		/// The actual transaction charging logic that charges the fees.
+		type OnChargeAssetTransaction: OnChargeAssetTransaction<Self>;
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {}
+}
+
+impl<T: Config> Pallet<T> where
+	BalanceOf<T>: FixedPointOperand,
+	T::Call: Dispatchable<Info=DispatchInfo>,
+{
+	/// Query the detailed fee of a given `call` when it is paid in `asset_id`.
+	///
+	/// The total fee is converted into the asset just like when it is charged and split into
+	/// components that add up to it. Returns `None` if the asset cannot be used to pay fees.
+	pub fn query_fee_details_in_asset<Extrinsic: GetDispatchInfo>(
+		unchecked_extrinsic: Extrinsic,
+		len: u32,
+		asset_id: ChargeAssetIdOf<T>,
+	) -> Option<FeeDetails<ChargeAssetBalanceOf<T>>> {
+		let fee_details = pallet_transaction_payment::Module::<T>::query_fee_details(
+			unchecked_extrinsic,
+			len,
+		);
+		// Convert the running total after every component and report the differences. This way
+		// the components add up to the total fee converted as a whole, which is what is charged.
+		let mut total: BalanceOf<T> = Zero::zero();
+		let mut converted_total: ChargeAssetBalanceOf<T> = Zero::zero();
+		let mut convert = |fee: BalanceOf<T>| {
+			total = total.saturating_add(fee);
+			let converted = T::OnChargeAssetTransaction::convert_fee(asset_id, total)?;
+			let part = converted.saturating_sub(converted_total);
+			converted_total = converted;
+			Some(part)
+		};
+		Some(FeeDetails {
+			inclusion_fee: match fee_details.inclusion_fee {
+				Some(inclusion_fee) => Some(pallet_transaction_payment::InclusionFee {
+					base_fee: convert(inclusion_fee.base_fee)?,
+					len_fee: convert(inclusion_fee.len_fee)?,
+					adjusted_weight_fee: convert(inclusion_fee.adjusted_weight_fee)?,
+				}),
+				None => None,
+			},
+			tip: convert(fee_details.tip)?,
+		})
+	}
+}
+
+/// Require the transactor pay for themselves and maybe include a tip to gain additional priority
+/// in the queue. Allows paying via both the native currency and assets.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeAssetTxPayment<T: Config> {
+	#[codec(compact)]
+	tip: BalanceOf<T>,
+	asset_id: Option<ChargeAssetIdOf<T>>,
+}
+
+impl<T: Config> ChargeAssetTxPayment<T> where
+	T::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+	BalanceOf<T>: Send + Sync + FixedPointOperand,
+	ChargeAssetIdOf<T>: Send + Sync,
+{
+	/// Utility constructor. Used only in client/factory code.
+	pub fn from(tip: BalanceOf<T>, asset_id: Option<ChargeAssetIdOf<T>>) -> Self {
+		Self { tip, asset_id }
+	}
+
+	/// Fee withdrawal logic that dispatches to either `OnChargeAssetTransaction` or
+	/// `OnChargeTransaction`.
+	fn withdraw_fee(
+		&self,
+		who: &T::AccountId,
+		call: &T::Call,
+		info: &DispatchInfoOf<T::Call>,
+		len: usize,
+	) -> Result<(BalanceOf<T>, InitialPayment<T>), TransactionValidityError> {
+		let fee = pallet_transaction_payment::Module::<T>::compute_fee(len as u32, info, self.tip);
+		debug_assert!(self.tip <= fee, "tip should be included in the computed fee");
+		if fee.is_zero() {
+			Ok((fee, InitialPayment::Nothing))
+		} else if let Some(asset_id) = self.asset_id {
+			T::OnChargeAssetTransaction::withdraw_fee(who, call, info, asset_id, fee, self.tip)
+				.map(|i| (fee, InitialPayment::Asset(i)))
+		} else {
+			<OnChargeTransactionOf<T> as OnChargeTransaction<T>>::withdraw_fee(
+				who, call, info, fee, self.tip,
+			).map(|i| (fee, InitialPayment::Native(i)))
+		}
+	}
+}
+
+impl<T: Config> sp_std::fmt::Debug for ChargeAssetTxPayment<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeAssetTxPayment<{:?}, {:?}>", self.tip, self.asset_id)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config> SignedExtension for ChargeAssetTxPayment<T> where
+	T::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	ChargeAssetIdOf<T>: Send + Sync,
+{
+	const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+	type AccountId = T::AccountId;
+	type Call = T::Call;
+	type AdditionalSigned = ();
+	type Pre = (
+		// tip
+		BalanceOf<T>,
+		// who paid the fee
+		Self::AccountId,
+		// imbalance resulting from withdrawing the fee
+		InitialPayment<T>,
+	);
+	fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> { Ok(()) }
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		use pallet_transaction_payment::ChargeTransactionPayment;
+		let (fee, _) = self.withdraw_fee(who, call, info, len)?;
+		let priority = ChargeTransactionPayment::<T>::get_priority(len, info, fee);
+		Ok(ValidTransaction {
+			priority,
+			..Default::default()
+		})
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let (_fee, initial_payment) = self.withdraw_fee(who, call, info, len)?;
+		Ok((self.tip, who.clone(), initial_payment))
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		let (tip, who, initial_payment) = pre;
+		match initial_payment {
+			InitialPayment::Native(already_withdrawn) => {
+				pallet_transaction_payment::ChargeTransactionPayment::<T>::post_dispatch(
+					(tip, who, already_withdrawn),
+					info,
+					post_info,
+					len,
+					result,
+				)?;
+			},
+			InitialPayment::Asset(already_withdrawn) => {
+				let actual_fee = pallet_transaction_payment::Module::<T>::compute_actual_fee(
+					len as u32,
+					info,
+					post_info,
+					tip,
+				);
+				T::OnChargeAssetTransaction::correct_and_deposit_fee(
+					&who,
+					info,
+					post_info,
+					actual_fee,
+					tip,
+					already_withdrawn,
+				)?;
+			},
+			InitialPayment::Nothing => {
+				// `actual_fee` should be zero here for any signed extrinsic. It would be non-zero
+				// here in case of unsigned extrinsics as they don't pay fees but
+				// `compute_actual_fee` is not aware of them. In both cases it's fine to just move
+				// ahead without adjusting the fee, though, so we do nothing.
+				debug_assert!(tip.is_zero(), "tip should be zero if initial fee was zero.");
+			},
+		}
+
+		Ok(())
+	}
+}
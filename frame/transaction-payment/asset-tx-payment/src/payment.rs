@@ -0,0 +1,177 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits and default implementation for paying transaction fees in assets.
+
+use crate::{Config, BalanceOf, AssetIdOf, AssetBalanceOf};
+use codec::FullCodec;
+use frame_support::{
+	traits::tokens::{
+		fungibles::{Inspect, Unbalanced}, Balance, BalanceConversion, WithdrawConsequence,
+	},
+	unsigned::TransactionValidityError,
+};
+use sp_runtime::{
+	traits::{DispatchInfoOf, MaybeSerializeDeserialize, One, PostDispatchInfoOf, Saturating, Zero},
+	transaction_validity::InvalidTransaction,
+};
+use sp_std::{fmt::Debug, marker::PhantomData};
+
+/// Handle withdrawing, refunding and depositing of transaction fees in assets.
+pub trait OnChargeAssetTransaction<T: Config> {
+	/// The underlying integer type in which asset fees are charged.
+	type Balance: Balance;
+	/// The type used to identify the assets used for transaction payment.
+	type AssetId: FullCodec + Copy + MaybeSerializeDeserialize + Debug + Default + Eq;
+	/// The type used to store the intermediate values between pre- and post-dispatch.
+	type LiquidityInfo;
+
+	/// Convert a `fee` in the native currency into the amount of `asset_id` that is charged for
+	/// it. Returns `None` if the asset cannot be used to pay fees.
+	fn convert_fee(asset_id: Self::AssetId, fee: BalanceOf<T>) -> Option<Self::Balance>;
+
+	/// Before the transaction is executed the payment of the transaction fees needs to be
+	/// secured.
+	///
+	/// Note: The `fee` is denominated in the native currency and already includes the `tip`.
+	fn withdraw_fee(
+		who: &T::AccountId,
+		call: &T::Call,
+		dispatch_info: &DispatchInfoOf<T::Call>,
+		asset_id: Self::AssetId,
+		fee: BalanceOf<T>,
+		tip: BalanceOf<T>,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError>;
+
+	/// After the transaction was executed the actual fee can be calculated.
+	/// This function should refund any overpaid fees and optionally deposit
+	/// the corrected amount.
+	///
+	/// Note: The `corrected_fee` is denominated in the native currency and already includes the
+	/// `tip`.
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		dispatch_info: &DispatchInfoOf<T::Call>,
+		post_info: &PostDispatchInfoOf<T::Call>,
+		corrected_fee: BalanceOf<T>,
+		tip: BalanceOf<T>,
+		already_withdrawn: Self::LiquidityInfo,
+	) -> Result<(), TransactionValidityError>;
+}
+
+/// Handles the asset fees collected by [`FungiblesAdapter`].
+///
+/// When this is called, `amount` has already been removed from the payer's balance but is still
+/// accounted for in the total issuance of `asset`.
+pub trait HandleCredit<AccountId, F: Unbalanced<AccountId>> {
+	/// Take care of `amount` of `asset` which is no longer held by any account.
+	fn handle_credit(asset: F::AssetId, amount: F::Balance);
+}
+
+/// Burn the collected fees by reducing the total issuance of the asset.
+impl<AccountId, F: Unbalanced<AccountId>> HandleCredit<AccountId, F> for () {
+	fn handle_credit(asset: F::AssetId, amount: F::Balance) {
+		if !amount.is_zero() {
+			F::set_total_issuance(asset, F::total_issuance(asset).saturating_sub(amount));
+		}
+	}
+}
+
+/// Implements the asset transaction payment for a pallet implementing the `fungibles` traits
+/// (eg. pallet_assets), converting fees with `CON` and handing the collected fees to `HC`.
+pub struct FungiblesAdapter<CON, HC>(PhantomData<(CON, HC)>);
+
+/// Convert `fee` into `asset_id` with `CON`, making sure that a non-zero fee is never converted
+/// into a zero asset fee.
+fn to_asset_balance<T, CON>(
+	fee: BalanceOf<T>,
+	asset_id: AssetIdOf<T>,
+) -> Result<AssetBalanceOf<T>, TransactionValidityError> where
+	T: Config,
+	CON: BalanceConversion<BalanceOf<T>, AssetIdOf<T>, AssetBalanceOf<T>>,
+{
+	let min_converted_fee = if fee.is_zero() { Zero::zero() } else { One::one() };
+	CON::to_asset_balance(fee, asset_id)
+		.map(|converted| converted.max(min_converted_fee))
+		.map_err(|_| TransactionValidityError::from(InvalidTransaction::Payment))
+}
+
+/// Default implementation for a fungibles implementation and a credit handler.
+impl<T, CON, HC> OnChargeAssetTransaction<T> for FungiblesAdapter<CON, HC>
+where
+	T: Config,
+	CON: BalanceConversion<BalanceOf<T>, AssetIdOf<T>, AssetBalanceOf<T>>,
+	HC: HandleCredit<T::AccountId, T::Fungibles>,
+	AssetIdOf<T>: FullCodec + Copy + MaybeSerializeDeserialize + Debug + Default + Eq,
+{
+	type Balance = AssetBalanceOf<T>;
+	type AssetId = AssetIdOf<T>;
+	// The asset and the amount that was withdrawn from the payer.
+	type LiquidityInfo = (AssetIdOf<T>, AssetBalanceOf<T>);
+
+	fn convert_fee(asset_id: Self::AssetId, fee: BalanceOf<T>) -> Option<Self::Balance> {
+		to_asset_balance::<T, CON>(fee, asset_id).ok()
+	}
+
+	/// Withdraw the predicted fee from the transaction origin.
+	///
+	/// The payer must keep at least the minimum balance of the asset.
+	fn withdraw_fee(
+		who: &T::AccountId,
+		_call: &T::Call,
+		_info: &DispatchInfoOf<T::Call>,
+		asset_id: Self::AssetId,
+		fee: BalanceOf<T>,
+		_tip: BalanceOf<T>,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+		let converted_fee = to_asset_balance::<T, CON>(fee, asset_id)?;
+		match T::Fungibles::can_withdraw(asset_id, who, converted_fee) {
+			WithdrawConsequence::Success => (),
+			_ => return Err(InvalidTransaction::Payment.into()),
+		}
+		T::Fungibles::decrease_balance(asset_id, who, converted_fee)
+			.map(|withdrawn| (asset_id, withdrawn))
+			.map_err(|_| InvalidTransaction::Payment.into())
+	}
+
+	/// Hand the fee and the tip to the credit handler and refund the rest to the payer in the
+	/// same asset.
+	///
+	/// Note: The `corrected_fee` already includes the `tip`.
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		_dispatch_info: &DispatchInfoOf<T::Call>,
+		_post_info: &PostDispatchInfoOf<T::Call>,
+		corrected_fee: BalanceOf<T>,
+		_tip: BalanceOf<T>,
+		already_withdrawn: Self::LiquidityInfo,
+	) -> Result<(), TransactionValidityError> {
+		let (asset_id, paid) = already_withdrawn;
+		let converted_fee = to_asset_balance::<T, CON>(corrected_fee, asset_id)?;
+		// Never refund more than what was paid.
+		let refund = paid.saturating_sub(converted_fee);
+		// If the refund can't be deposited (e.g. because the account was reaped in the meantime
+		// and the refund is below the minimum balance) it is kept as part of the fee.
+		let refunded = if refund.is_zero() {
+			Zero::zero()
+		} else {
+			T::Fungibles::increase_balance_at_most(asset_id, who, refund)
+		};
+		HC::handle_credit(asset_id, paid.saturating_sub(refunded));
+		Ok(())
+	}
+}
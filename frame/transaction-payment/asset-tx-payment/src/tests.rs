@@ -0,0 +1,594 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the asset transaction payment module.
+
+use super::*;
+use crate as pallet_asset_tx_payment;
+use codec::Encode;
+use frame_support::{
+	assert_ok, parameter_types,
+	traits::Get,
+	weights::{
+		DispatchClass, DispatchInfo, PostDispatchInfo, Pays, Weight, WeightToFeePolynomial,
+		WeightToFeeCoefficients, WeightToFeeCoefficient,
+	},
+};
+use frame_system as system;
+use pallet_balances::Call as BalancesCall;
+use pallet_transaction_payment::{CurrencyAdapter, InclusionFee};
+use sp_core::H256;
+use sp_runtime::{
+	Perbill,
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+};
+use std::cell::RefCell;
+use smallvec::smallvec;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Module, Storage},
+		Assets: pallet_assets::{Module, Call, Storage, Event<T>},
+		AssetTxPayment: pallet_asset_tx_payment::{Module},
+	}
+);
+
+const CALL: &<Runtime as frame_system::Config>::Call =
+	&Call::Balances(BalancesCall::transfer(2, 69));
+
+thread_local! {
+	static EXTRINSIC_BASE_WEIGHT: RefCell<u64> = RefCell::new(0);
+}
+
+pub struct BlockWeights;
+impl Get<frame_system::limits::BlockWeights> for BlockWeights {
+	fn get() -> frame_system::limits::BlockWeights {
+		frame_system::limits::BlockWeights::builder()
+			.base_block(0)
+			.for_class(DispatchClass::all(), |weights| {
+				weights.base_extrinsic = EXTRINSIC_BASE_WEIGHT.with(|v| *v.borrow()).into();
+			})
+			.for_class(DispatchClass::non_mandatory(), |weights| {
+				weights.max_total = 1024.into();
+			})
+			.build_or_panic()
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub static TransactionByteFee: u64 = 1;
+	pub static WeightToFee: u64 = 1;
+}
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = ();
+	type BlockWeights = BlockWeights;
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 10;
+}
+
+impl pallet_balances::Config for Runtime {
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = ();
+	type WeightInfo = ();
+}
+
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = u64;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		smallvec![WeightToFeeCoefficient {
+			degree: 1,
+			coeff_frac: Perbill::zero(),
+			coeff_integer: WEIGHT_TO_FEE.with(|v| *v.borrow()),
+			negative: false,
+		}]
+	}
+}
+
+impl pallet_transaction_payment::Config for Runtime {
+	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = WeightToFee;
+	type FeeMultiplierUpdate = ();
+}
+
+parameter_types! {
+	pub const AssetDeposit: u64 = 2;
+	pub const MetadataDeposit: u64 = 0;
+	pub const StringLimit: u32 = 20;
+}
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDeposit;
+	type MetadataDepositPerByte = MetadataDeposit;
+	type ApprovalDeposit = MetadataDeposit;
+	type StringLimit = StringLimit;
+	type WeightInfo = ();
+}
+
+impl Config for Runtime {
+	type Fungibles = Assets;
+	type OnChargeAssetTransaction = FungiblesAdapter<
+		pallet_assets::BalanceToAssetBalance<Balances, Runtime, ConvertInto>,
+		(),
+	>;
+}
+
+pub struct ExtBuilder {
+	balance_factor: u64,
+	base_weight: u64,
+	byte_fee: u64,
+	weight_to_fee: u64
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balance_factor: 1,
+			base_weight: 0,
+			byte_fee: 1,
+			weight_to_fee: 1,
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn base_weight(mut self, base_weight: u64) -> Self {
+		self.base_weight = base_weight;
+		self
+	}
+	pub fn balance_factor(mut self, factor: u64) -> Self {
+		self.balance_factor = factor;
+		self
+	}
+	fn set_constants(&self) {
+		EXTRINSIC_BASE_WEIGHT.with(|v| *v.borrow_mut() = self.base_weight);
+		TRANSACTION_BYTE_FEE.with(|v| *v.borrow_mut() = self.byte_fee);
+		WEIGHT_TO_FEE.with(|v| *v.borrow_mut() = self.weight_to_fee);
+	}
+	pub fn build(self) -> sp_io::TestExternalities {
+		self.set_constants();
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: if self.balance_factor > 0 {
+				vec![
+					(1, 10 * self.balance_factor),
+					(2, 20 * self.balance_factor),
+					(3, 30 * self.balance_factor),
+					(4, 40 * self.balance_factor),
+					(5, 50 * self.balance_factor),
+					(6, 60 * self.balance_factor)
+				]
+			} else {
+				vec![]
+			},
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+}
+
+/// create a transaction info struct from weight. Handy to avoid building the whole struct.
+pub fn info_from_weight(w: Weight) -> DispatchInfo {
+	// pays_fee: Pays::Yes -- class: DispatchClass::Normal
+	DispatchInfo { weight: w, ..Default::default() }
+}
+
+fn post_info_from_weight(w: Weight) -> PostDispatchInfo {
+	PostDispatchInfo {
+		actual_weight: Some(w),
+		pays_fee: Default::default(),
+	}
+}
+
+fn info_from_pays(p: Pays) -> DispatchInfo {
+	DispatchInfo {
+		pays_fee: p,
+		..Default::default()
+	}
+}
+
+fn default_post_info() -> PostDispatchInfo {
+	PostDispatchInfo {
+		actual_weight: None,
+		pays_fee: Default::default(),
+	}
+}
+
+/// Create the sufficient asset `asset_id` with the given `min_balance` and mint `balance` of it
+/// to `who`.
+fn create_asset(asset_id: u32, min_balance: u64, who: u64, balance: u64) {
+	assert_ok!(Assets::force_create(Origin::root(), asset_id, 42, true, min_balance));
+	assert_ok!(Assets::mint(Origin::signed(42), asset_id, who, balance));
+	assert_eq!(Assets::balance(asset_id, who), balance);
+}
+
+#[test]
+fn transaction_payment_in_native_possible() {
+	ExtBuilder::default()
+		.balance_factor(100)
+		.base_weight(5)
+		.build()
+		.execute_with(||
+	{
+		let len = 10;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, None)
+			.pre_dispatch(&1, CALL, &info_from_weight(5), len)
+			.unwrap();
+		let initial_balance = 10 * 100;
+		assert_eq!(Balances::free_balance(1), initial_balance - 5 - 5 - 10);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(5),
+			&default_post_info(),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Balances::free_balance(1), initial_balance - 5 - 5 - 10);
+
+		let pre = ChargeAssetTxPayment::<Runtime>::from(5 /* tipped */, None)
+			.pre_dispatch(&2, CALL, &info_from_weight(100), len)
+			.unwrap();
+		let initial_balance_for_2 = 20 * 100;
+		assert_eq!(Balances::free_balance(2), initial_balance_for_2 - 5 - 10 - 100 - 5);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(100),
+			&post_info_from_weight(50),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Balances::free_balance(2), initial_balance_for_2 - 5 - 10 - 50 - 5);
+	});
+}
+
+#[test]
+fn transaction_payment_in_asset_possible() {
+	let base_weight = 5;
+	let balance_factor = 100;
+	ExtBuilder::default()
+		.balance_factor(balance_factor)
+		.base_weight(base_weight)
+		.build()
+		.execute_with(||
+	{
+		// create the asset
+		let asset_id = 1;
+		let min_balance = 2;
+		let caller = 1;
+		let balance = 100;
+		create_asset(asset_id, min_balance, caller, balance);
+
+		let weight = 5;
+		let len = 10;
+		// we convert the from weight to fee based on the ratio between asset min balance and
+		// existential deposit
+		let fee = (base_weight + weight + len as u64) * min_balance / ExistentialDeposit::get();
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+			.pre_dispatch(&caller, CALL, &info_from_weight(weight), len)
+			.unwrap();
+		// assert that native balance is not used
+		assert_eq!(Balances::free_balance(caller), 10 * balance_factor);
+		// check that fee was charged in the given asset
+		assert_eq!(Assets::balance(asset_id, caller), balance - fee);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(weight),
+			&default_post_info(),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(asset_id, caller), balance - fee);
+		// the collected fee is burned by the default credit handler
+		assert_eq!(Assets::total_supply(asset_id), balance - fee);
+	});
+}
+
+#[test]
+fn transaction_payment_in_asset_with_tip_and_refund() {
+	let base_weight = 5;
+	ExtBuilder::default()
+		.balance_factor(100)
+		.base_weight(base_weight)
+		.build()
+		.execute_with(||
+	{
+		let asset_id = 1;
+		let min_balance = 2;
+		let caller = 2;
+		let balance = 1000;
+		create_asset(asset_id, min_balance, caller, balance);
+
+		let weight = 100;
+		let tip = 5;
+		let len = 10;
+		// we convert the from weight to fee based on the ratio between asset min balance and
+		// existential deposit
+		let fee_with_tip =
+			(base_weight + weight + len as u64 + tip) * min_balance / ExistentialDeposit::get();
+		let pre = ChargeAssetTxPayment::<Runtime>::from(tip, Some(asset_id))
+			.pre_dispatch(&caller, CALL, &info_from_weight(weight), len)
+			.unwrap();
+		assert_eq!(Assets::balance(asset_id, caller), balance - fee_with_tip);
+
+		let final_weight = 50;
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(weight),
+			&post_info_from_weight(final_weight),
+			len,
+			&Ok(()),
+		));
+		let final_fee =
+			fee_with_tip - (weight - final_weight) * min_balance / ExistentialDeposit::get();
+		assert_eq!(Assets::balance(asset_id, caller), balance - final_fee);
+		assert_eq!(Assets::total_supply(asset_id), balance - final_fee);
+	});
+}
+
+#[test]
+fn payment_from_account_with_only_assets() {
+	let base_weight = 5;
+	ExtBuilder::default()
+		.balance_factor(100)
+		.base_weight(base_weight)
+		.build()
+		.execute_with(||
+	{
+		let asset_id = 1;
+		let min_balance = 2;
+		// the caller has no native balance at all
+		let caller = 333;
+		let balance = 100;
+		create_asset(asset_id, min_balance, caller, balance);
+		assert_eq!(Balances::free_balance(caller), 0);
+
+		let weight = 5;
+		let len = 10;
+		let fee = (base_weight + weight + len as u64) * min_balance / ExistentialDeposit::get();
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+			.pre_dispatch(&caller, CALL, &info_from_weight(weight), len)
+			.unwrap();
+		assert_eq!(Balances::free_balance(caller), 0);
+		assert_eq!(Assets::balance(asset_id, caller), balance - fee);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(weight),
+			&default_post_info(),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(asset_id, caller), balance - fee);
+		assert_eq!(Balances::free_balance(caller), 0);
+	});
+}
+
+#[test]
+fn payment_only_with_existing_sufficient_asset() {
+	let base_weight = 5;
+	ExtBuilder::default()
+		.balance_factor(100)
+		.base_weight(base_weight)
+		.build()
+		.execute_with(||
+	{
+		let asset_id = 1;
+		let caller = 1;
+		let weight = 5;
+		let len = 10;
+		// pre_dispatch fails for non-existent asset
+		assert!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+				.pre_dispatch(&caller, CALL, &info_from_weight(weight), len)
+				.is_err()
+		);
+
+		// create the non-sufficient asset
+		let min_balance = 2;
+		assert_ok!(Assets::force_create(Origin::root(), asset_id, 42, false, min_balance));
+		assert_ok!(Assets::mint(Origin::signed(42), asset_id, caller, 100));
+		// pre_dispatch fails for non-sufficient asset
+		assert!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+				.pre_dispatch(&caller, CALL, &info_from_weight(weight), len)
+				.is_err()
+		);
+		assert_eq!(Assets::balance(asset_id, caller), 100);
+	});
+}
+
+#[test]
+fn payment_must_not_reap_the_account() {
+	let base_weight = 5;
+	ExtBuilder::default()
+		.balance_factor(100)
+		.base_weight(base_weight)
+		.build()
+		.execute_with(||
+	{
+		let asset_id = 1;
+		let min_balance = 2;
+		let caller = 1;
+		let weight = 5;
+		let len = 10;
+		let fee = (base_weight + weight + len as u64) * min_balance / ExistentialDeposit::get();
+		// paying the fee would leave less than the minimum balance
+		let balance = fee + min_balance - 1;
+		create_asset(asset_id, min_balance, caller, balance);
+
+		assert!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+				.pre_dispatch(&caller, CALL, &info_from_weight(weight), len)
+				.is_err()
+		);
+		assert_eq!(Assets::balance(asset_id, caller), balance);
+	});
+}
+
+#[test]
+fn converted_fee_is_never_zero_if_input_fee_is_not() {
+	let base_weight = 1;
+	ExtBuilder::default()
+		.balance_factor(100)
+		.base_weight(base_weight)
+		.build()
+		.execute_with(||
+	{
+		let asset_id = 1;
+		let min_balance = 1;
+		let caller = 1;
+		let balance = 100;
+		create_asset(asset_id, min_balance, caller, balance);
+
+		let weight = 1;
+		let len = 1;
+		// the native fee of 3 converts to an asset fee of 0.3 which is rounded down to zero
+		let fee = (base_weight + weight + len as u64) * min_balance / ExistentialDeposit::get();
+		assert_eq!(fee, 0);
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+			.pre_dispatch(&caller, CALL, &info_from_weight(weight), len)
+			.unwrap();
+		// but the caller still pays the smallest possible amount
+		assert_eq!(Assets::balance(asset_id, caller), balance - 1);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(weight),
+			&default_post_info(),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(asset_id, caller), balance - 1);
+	});
+}
+
+#[test]
+fn post_dispatch_fee_is_zero_if_pre_dispatch_fee_is_zero() {
+	ExtBuilder::default()
+		.balance_factor(100)
+		.base_weight(5)
+		.build()
+		.execute_with(||
+	{
+		let asset_id = 1;
+		let min_balance = 2;
+		let caller = 1;
+		let balance = 100;
+		create_asset(asset_id, min_balance, caller, balance);
+
+		let len = 10;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+			.pre_dispatch(&caller, CALL, &info_from_pays(Pays::No), len)
+			.unwrap();
+		// `Pays::No` implies no pre-dispatch fees
+		assert_eq!(Assets::balance(asset_id, caller), balance);
+		assert!(matches!(pre.2, InitialPayment::Nothing));
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_pays(Pays::No),
+			&post_info_from_weight(1),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(asset_id, caller), balance);
+		assert_eq!(Balances::free_balance(caller), 10 * 100);
+	});
+}
+
+#[test]
+fn query_fee_details_in_asset_works() {
+	let xt = TestXt::new(CALL.clone(), Some((1u64, ())));
+	let len = xt.encode().len() as u32;
+	let base_weight = 5;
+	ExtBuilder::default()
+		.base_weight(base_weight)
+		.build()
+		.execute_with(||
+	{
+		let asset_id = 1;
+		// unknown assets can't be used to pay fees
+		assert_eq!(AssetTxPayment::query_fee_details_in_asset(xt.clone(), len, asset_id), None);
+
+		let min_balance = 2;
+		create_asset(asset_id, min_balance, 1, 100);
+		let weight = CALL.get_dispatch_info().weight.min(BlockWeights::get().max_block);
+		let convert = |fee: u64| fee * min_balance / ExistentialDeposit::get();
+		let fee_details = AssetTxPayment::query_fee_details_in_asset(xt, len, asset_id).unwrap();
+		assert_eq!(
+			fee_details,
+			FeeDetails {
+				inclusion_fee: Some(InclusionFee {
+					base_fee: convert(base_weight),
+					len_fee: convert(base_weight + len as u64) - convert(base_weight),
+					adjusted_weight_fee: convert(base_weight + len as u64 + weight) -
+						convert(base_weight + len as u64),
+				}),
+				tip: 0,
+			},
+		);
+		// The components add up to the fee that is actually charged.
+		assert_eq!(fee_details.final_fee(), convert(base_weight + len as u64 + weight));
+	});
+}
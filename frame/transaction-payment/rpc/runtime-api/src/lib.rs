@@ -25,10 +25,21 @@ use sp_runtime::traits::MaybeDisplay;
 pub use pallet_transaction_payment::{FeeDetails, InclusionFee, RuntimeDispatchInfo};
 
 sp_api::decl_runtime_apis! {
-	pub trait TransactionPaymentApi<Balance> where
+	#[api_version(2)]
+	pub trait TransactionPaymentApi<Balance, AssetId> where
 		Balance: Codec + MaybeDisplay,
+		AssetId: Codec,
 	{
 		fn query_info(uxt: Block::Extrinsic, len: u32) -> RuntimeDispatchInfo<Balance>;
 		fn query_fee_details(uxt: Block::Extrinsic, len: u32) -> FeeDetails<Balance>;
+		/// Query the fee details of `uxt` when it is paid in `asset_id`.
+		///
+		/// The returned amounts are denominated in the given asset. Returns `None` if the asset
+		/// cannot be used to pay transaction fees.
+		fn query_fee_details_in_asset(
+			uxt: Block::Extrinsic,
+			len: u32,
+			asset_id: AssetId,
+		) -> Option<FeeDetails<Balance>>;
 	}
 }
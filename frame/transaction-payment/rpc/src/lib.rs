@@ -24,7 +24,7 @@ use sp_blockchain::HeaderBackend;
 use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
 use jsonrpc_derive::rpc;
 use sp_runtime::{generic::BlockId, traits::{Block as BlockT, MaybeDisplay}};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_core::Bytes;
 use sp_rpc::number::NumberOrHex;
 use pallet_transaction_payment_rpc_runtime_api::{FeeDetails, InclusionFee, RuntimeDispatchInfo};
@@ -32,7 +32,7 @@ pub use pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi as Tra
 pub use self::gen_client::Client as TransactionPaymentClient;
 
 #[rpc]
-pub trait TransactionPaymentApi<BlockHash, ResponseType> {
+pub trait TransactionPaymentApi<BlockHash, AssetId, ResponseType> {
 	#[rpc(name = "payment_queryInfo")]
 	fn query_info(
 		&self,
@@ -45,6 +45,13 @@ pub trait TransactionPaymentApi<BlockHash, ResponseType> {
 		encoded_xt: Bytes,
 		at: Option<BlockHash>
 	) -> Result<FeeDetails<NumberOrHex>>;
+	#[rpc(name = "payment_queryFeeDetailsInAsset")]
+	fn query_fee_details_in_asset(
+		&self,
+		encoded_xt: Bytes,
+		asset_id: AssetId,
+		at: Option<BlockHash>
+	) -> Result<Option<FeeDetails<NumberOrHex>>>;
 }
 
 /// A struct that implements the [`TransactionPaymentApi`].
@@ -66,6 +73,8 @@ pub enum Error {
 	DecodeError,
 	/// The call to runtime failed.
 	RuntimeError,
+	/// The runtime does not support the called method.
+	ApiUnsupported,
 }
 
 impl From<Error> for i64 {
@@ -73,19 +82,48 @@ impl From<Error> for i64 {
 		match e {
 			Error::RuntimeError => 1,
 			Error::DecodeError => 2,
+			Error::ApiUnsupported => 3,
 		}
 	}
 }
 
-impl<C, Block, Balance> TransactionPaymentApi<
+/// Convert the fee details returned by the runtime into their RPC representation.
+fn into_rpc_fee_details<Balance>(
+	fee_details: FeeDetails<Balance>,
+) -> Result<FeeDetails<NumberOrHex>> where
+	Balance: MaybeDisplay + Copy + TryInto<NumberOrHex>,
+{
+	let try_into_rpc_balance = |value: Balance| value.try_into().map_err(|_| RpcError {
+		code: ErrorCode::InvalidParams,
+		message: format!("{} doesn't fit in NumberOrHex representation", value),
+		data: None,
+	});
+
+	Ok(FeeDetails {
+		inclusion_fee: if let Some(inclusion_fee) = fee_details.inclusion_fee {
+			Some(InclusionFee {
+				base_fee: try_into_rpc_balance(inclusion_fee.base_fee)?,
+				len_fee: try_into_rpc_balance(inclusion_fee.len_fee)?,
+				adjusted_weight_fee: try_into_rpc_balance(inclusion_fee.adjusted_weight_fee)?,
+			})
+		} else {
+			None
+		},
+		tip: Default::default(),
+	})
+}
+
+impl<C, Block, Balance, AssetId> TransactionPaymentApi<
 	<Block as BlockT>::Hash,
+	AssetId,
 	RuntimeDispatchInfo<Balance>,
 > for TransactionPayment<C, Block>
 where
 	Block: BlockT,
 	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
-	C::Api: TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: TransactionPaymentRuntimeApi<Block, Balance, AssetId>,
 	Balance: Codec + MaybeDisplay + Copy + TryInto<NumberOrHex>,
+	AssetId: Codec + Send + Sync + 'static,
 {
 	fn query_info(
 		&self,
@@ -136,23 +174,54 @@ where
 			data: Some(format!("{:?}", e).into()),
 		})?;
 
-		let try_into_rpc_balance = |value: Balance| value.try_into().map_err(|_| RpcError {
-			code: ErrorCode::InvalidParams,
-			message: format!("{} doesn't fit in NumberOrHex representation", value),
-			data: None,
-		});
-
-		Ok(FeeDetails {
-			inclusion_fee: if let Some(inclusion_fee) = fee_details.inclusion_fee {
-				Some(InclusionFee {
-					base_fee: try_into_rpc_balance(inclusion_fee.base_fee)?,
-					len_fee: try_into_rpc_balance(inclusion_fee.len_fee)?,
-					adjusted_weight_fee: try_into_rpc_balance(inclusion_fee.adjusted_weight_fee)?,
-				})
-			} else {
-				None
-			},
-			tip: Default::default(),
-		})
+		into_rpc_fee_details(fee_details)
+	}
+
+	fn query_fee_details_in_asset(
+		&self,
+		encoded_xt: Bytes,
+		asset_id: AssetId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<FeeDetails<NumberOrHex>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		// `query_fee_details_in_asset` was added in version 2 of the runtime api.
+		let supported = api
+			.has_api_with::<dyn TransactionPaymentRuntimeApi<Block, Balance, AssetId>, _>(
+				&at,
+				|version| version >= 2,
+			)
+			.map_err(|e| RpcError {
+				code: ErrorCode::ServerError(Error::RuntimeError.into()),
+				message: "Unable to query fee details in asset.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})?;
+		if !supported {
+			return Err(RpcError {
+				code: ErrorCode::ServerError(Error::ApiUnsupported.into()),
+				message: "The runtime does not support querying fee details in an asset.".into(),
+				data: None,
+			});
+		}
+
+		let encoded_len = encoded_xt.len() as u32;
+
+		let uxt: Block::Extrinsic = Decode::decode(&mut &*encoded_xt).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::DecodeError.into()),
+			message: "Unable to query fee details in asset.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})?;
+		let fee_details = api.query_fee_details_in_asset(&at, uxt, encoded_len, asset_id)
+			.map_err(|e| RpcError {
+				code: ErrorCode::ServerError(Error::RuntimeError.into()),
+				message: "Unable to query fee details in asset.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})?;
+
+		fee_details.map(into_rpc_fee_details).transpose()
 	}
 }
@@ -82,6 +82,20 @@ impl<Balance: AtLeast32BitUnsigned + Copy> FeeDetails<Balance> {
 	}
 }
 
+impl<Balance> FeeDetails<Balance> {
+	/// Convert every component of the fee with `f`, e.g. to express it in another balance type.
+	pub fn map<B>(self, f: impl Fn(Balance) -> B) -> FeeDetails<B> {
+		FeeDetails {
+			inclusion_fee: self.inclusion_fee.map(|i| InclusionFee {
+				base_fee: f(i.base_fee),
+				len_fee: f(i.len_fee),
+				adjusted_weight_fee: f(i.adjusted_weight_fee),
+			}),
+			tip: f(self.tip),
+		}
+	}
+}
+
 /// Information related to a dispatchable's class, weight, and fee that can be queried from the runtime.
 #[derive(Eq, PartialEq, Encode, Decode, Default)]
 #[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
@@ -23,7 +23,7 @@ use super::*;
 
 use frame_system::RawOrigin;
 use frame_benchmarking::{benchmarks_instance, account, impl_benchmark_test_suite};
-use frame_support::traits::OnInitialize;
+use frame_support::traits::{OnInitialize, UnfilteredDispatchable};
 
 use crate::Module as Treasury;
 
@@ -94,6 +94,18 @@ benchmarks_instance! {
 		let proposal_id = Treasury::<T, _>::proposal_count() - 1;
 	}: _(RawOrigin::Root, proposal_id)
 
+	spend {
+		let (_, value, beneficiary_lookup) = setup_proposal::<T, _>(SEED);
+		let origin = T::SpendOrigin::successful_origin();
+		let max_amount = T::SpendOrigin::ensure_origin(origin.clone())
+			.map_err(|_| "Spend origin not accepted")?;
+		let value = value.min(max_amount);
+		let call = Call::<T, I>::spend(value, beneficiary_lookup);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		ensure!(<Approvals<I>>::get().len() == 1, "Spend not approved");
+	}
+
 	on_initialize_proposals {
 		let p in 0 .. 100;
 		setup_pot_account::<T, _>();
@@ -50,6 +50,7 @@
 //! - `propose_spend` - Make a spending proposal and stake the required deposit.
 //! - `reject_proposal` - Reject a proposal, slashing the deposit.
 //! - `approve_proposal` - Accept the proposal, returning the deposit.
+//! - `spend` - Approve a spend of up to the limit of the `SpendOrigin`, without a proposal.
 //!
 //! ## GenesisConfig
 //!
@@ -127,6 +128,11 @@ pub trait Config<I=DefaultInstance>: frame_system::Config {
 
 	/// Runtime hooks to external pallet using treasury to compute spend funds.
 	type SpendFunds: SpendFunds<Self, I>;
+
+	/// The origin required for approving spends from the treasury outside of the proposal
+	/// process. The `Success` value is the maximum amount that this origin is allowed to spend at
+	/// a time.
+	type SpendOrigin: EnsureOrigin<Self::Origin, Success = BalanceOf<Self, I>>;
 }
 
 /// A trait to allow the Treasury Pallet to spend it's funds for other purposes.
@@ -217,6 +223,8 @@ decl_event!(
 		Rollover(Balance),
 		/// Some funds have been deposited. \[deposit\]
 		Deposit(Balance),
+		/// A new spend proposal has been approved. \[proposal_index, amount, beneficiary\]
+		SpendApproved(ProposalIndex, Balance, AccountId),
 	}
 );
 
@@ -227,6 +235,9 @@ decl_error! {
 		InsufficientProposersBalance,
 		/// No proposal or bounty at that index.
 		InvalidIndex,
+		/// The spend origin is valid but the amount it is allowed to spend is lower than the
+		/// amount to be spent.
+		InsufficientPermission,
 	}
 }
 
@@ -323,6 +334,45 @@ decl_module! {
 			Approvals::<I>::append(proposal_id);
 		}
 
+		/// Propose and approve a spend of treasury funds.
+		///
+		/// - `origin`: Must be `SpendOrigin` with the `Success` value being at least `amount`.
+		/// - `amount`: The amount to be transferred from the treasury to the `beneficiary`.
+		/// - `beneficiary`: The destination account for the transfer.
+		///
+		/// No deposit is taken. The spend is queued with the approved proposals and paid out at
+		/// the next spend period, if the treasury has enough funds.
+		///
+		/// # <weight>
+		/// - Complexity: O(1).
+		/// - DbReads: `ProposalCount`, `Approvals`
+		/// - DbWrites: `ProposalCount`, `Proposals`, `Approvals`
+		/// # </weight>
+		#[weight = T::WeightInfo::spend()]
+		pub fn spend(
+			origin,
+			#[compact] amount: BalanceOf<T, I>,
+			beneficiary: <T::Lookup as StaticLookup>::Source
+		) {
+			let max_amount = T::SpendOrigin::ensure_origin(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			ensure!(amount <= max_amount, Error::<T, I>::InsufficientPermission);
+
+			let proposal_index = Self::proposal_count();
+			<ProposalCount<I>>::put(proposal_index + 1);
+			let proposal = Proposal {
+				proposer: beneficiary.clone(),
+				value: amount,
+				beneficiary: beneficiary.clone(),
+				bond: Zero::zero(),
+			};
+			<Proposals<T, I>>::insert(proposal_index, proposal);
+			Approvals::<I>::append(proposal_index);
+
+			Self::deposit_event(RawEvent::SpendApproved(proposal_index, amount, beneficiary));
+		}
+
 		/// # <weight>
 		/// - Complexity: `O(A)` where `A` is the number of approvals
 		/// - Db reads and writes: `Approvals`, `pot account data`
@@ -31,7 +31,7 @@ use sp_core::H256;
 use sp_runtime::{
 	ModuleId,
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BadOrigin, BlakeTwo256, IdentityLookup},
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -103,6 +103,25 @@ parameter_types! {
 	pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
 	pub const BountyValueMinimum: u64 = 1;
 }
+pub struct TestSpendOrigin;
+impl frame_support::traits::EnsureOrigin<Origin> for TestSpendOrigin {
+	type Success = u64;
+	fn try_origin(o: Origin) -> Result<Self::Success, Origin> {
+		Result::<frame_system::RawOrigin<_>, Origin>::from(o).and_then(|o| match o {
+			frame_system::RawOrigin::Root => Ok(u64::max_value()),
+			frame_system::RawOrigin::Signed(10) => Ok(5),
+			frame_system::RawOrigin::Signed(11) => Ok(10),
+			frame_system::RawOrigin::Signed(12) => Ok(20),
+			frame_system::RawOrigin::Signed(13) => Ok(50),
+			r => Err(Origin::from(r)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> Origin {
+		Origin::root()
+	}
+}
 impl Config for Test {
 	type ModuleId = TreasuryModuleId;
 	type Currency = pallet_balances::Module<Test>;
@@ -117,6 +136,7 @@ impl Config for Test {
 	type BurnDestination = ();  // Just gets burned.
 	type WeightInfo = ();
 	type SpendFunds = ();
+	type SpendOrigin = TestSpendOrigin;
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -174,6 +194,52 @@ fn spend_proposal_fails_when_proposer_poor() {
 	});
 }
 
+#[test]
+fn spend_origin_permissioning_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Treasury::spend(Origin::signed(1), 1, 1), BadOrigin);
+		assert_noop!(
+			Treasury::spend(Origin::signed(10), 6, 1),
+			Error::<Test, _>::InsufficientPermission,
+		);
+		assert_noop!(
+			Treasury::spend(Origin::signed(11), 11, 1),
+			Error::<Test, _>::InsufficientPermission,
+		);
+		assert_noop!(
+			Treasury::spend(Origin::signed(12), 21, 1),
+			Error::<Test, _>::InsufficientPermission,
+		);
+		assert_noop!(
+			Treasury::spend(Origin::signed(13), 51, 1),
+			Error::<Test, _>::InsufficientPermission,
+		);
+	});
+}
+
+#[test]
+fn spend_origin_works() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		assert_ok!(Treasury::spend(Origin::signed(10), 5, 6));
+		assert_ok!(Treasury::spend(Origin::signed(10), 5, 6));
+		assert_ok!(Treasury::spend(Origin::signed(10), 5, 6));
+		assert_ok!(Treasury::spend(Origin::signed(10), 5, 6));
+		assert_ok!(Treasury::spend(Origin::signed(11), 10, 6));
+		assert_ok!(Treasury::spend(Origin::signed(12), 20, 6));
+		assert_ok!(Treasury::spend(Origin::signed(13), 50, 6));
+		assert_eq!(Treasury::approvals().len(), 7);
+
+		<Treasury as OnInitialize<u64>>::on_initialize(1);
+		assert_eq!(Balances::free_balance(6), 0);
+
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+		assert_eq!(Balances::free_balance(6), 100);
+		assert_eq!(Treasury::pot(), 0);
+		assert!(Treasury::approvals().is_empty());
+	});
+}
+
 #[test]
 fn accepted_spend_proposal_ignored_outside_spend_period() {
 	new_test_ext().execute_with(|| {
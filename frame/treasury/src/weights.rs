@@ -48,6 +48,7 @@ pub trait WeightInfo {
 	fn reject_proposal() -> Weight;
 	fn approve_proposal() -> Weight;
 	fn on_initialize_proposals(p: u32, ) -> Weight;
+	fn spend() -> Weight;
 }
 
 /// Weights for pallet_treasury using the Substrate node and recommended hardware.
@@ -77,6 +78,11 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(p as Weight)))
 	}
+	fn spend() -> Weight {
+		(19_738_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
@@ -105,4 +111,9 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(p as Weight)))
 	}
+	fn spend() -> Weight {
+		(19_738_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
 }
@@ -0,0 +1,125 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementations for `nonfungibles` traits.
+
+use super::*;
+use frame_support::traits::tokens::nonfungibles::{Inspect, InspectEnumerable, Mutate, Transfer};
+use sp_runtime::DispatchResult;
+
+impl<T: Config> Inspect<<T as frame_system::Config>::AccountId> for Pallet<T> {
+	type InstanceId = T::InstanceId;
+	type ClassId = T::ClassId;
+
+	fn owner(
+		class: &Self::ClassId,
+		instance: &Self::InstanceId,
+	) -> Option<<T as frame_system::Config>::AccountId> {
+		Asset::<T>::get(class, instance).map(|a| a.owner)
+	}
+
+	fn class_owner(class: &Self::ClassId) -> Option<<T as frame_system::Config>::AccountId> {
+		Class::<T>::get(class).map(|a| a.owner)
+	}
+
+	/// Returns the attribute value of `instance` of `class` corresponding to `key`.
+	///
+	/// When `key` is empty, we return the instance metadata value.
+	fn attribute(class: &Self::ClassId, instance: &Self::InstanceId, key: &[u8])
+		-> Option<Vec<u8>>
+	{
+		if key.is_empty() {
+			// We make the empty key map to the instance metadata value.
+			InstanceMetadataOf::<T>::get(class, instance).map(|m| m.data)
+		} else {
+			Attribute::<T>::get(class, (Some(*instance), key)).map(|a| a.0)
+		}
+	}
+
+	/// Returns the attribute value of `class` corresponding to `key`.
+	///
+	/// When `key` is empty, we return the class metadata value.
+	fn class_attribute(class: &Self::ClassId, key: &[u8]) -> Option<Vec<u8>> {
+		if key.is_empty() {
+			// We make the empty key map to the class metadata value.
+			ClassMetadataOf::<T>::get(class).map(|m| m.data)
+		} else {
+			Attribute::<T>::get(class, (Option::<T::InstanceId>::None, key)).map(|a| a.0)
+		}
+	}
+
+	/// Returns `true` if the asset `instance` of `class` may be transferred.
+	///
+	/// Default implementation is that all assets are transferable.
+	fn can_transfer(class: &Self::ClassId, instance: &Self::InstanceId) -> bool {
+		match (Class::<T>::get(class), Asset::<T>::get(class, instance)) {
+			(Some(cd), Some(id)) if !cd.is_frozen && !id.is_frozen => true,
+			_ => false,
+		}
+	}
+}
+
+impl<T: Config> InspectEnumerable<T::AccountId> for Pallet<T> {
+	/// Returns an iterator of the asset classes in existence.
+	fn classes() -> Box<dyn Iterator<Item = Self::ClassId>> {
+		Box::new(Class::<T>::iter().map(|(class, _)| class))
+	}
+
+	/// Returns an iterator of the instances of an asset `class` in existence.
+	fn instances(class: &Self::ClassId) -> Box<dyn Iterator<Item = Self::InstanceId>> {
+		Box::new(Asset::<T>::iter_prefix(class).map(|(instance, _)| instance))
+	}
+
+	/// Returns an iterator of the asset instances of all classes owned by `who`.
+	fn owned(who: &T::AccountId) -> Box<dyn Iterator<Item = (Self::ClassId, Self::InstanceId)>> {
+		Box::new(Account::<T>::iter_prefix(who).map(|(key, _)| key))
+	}
+
+	/// Returns an iterator of the asset instances of `class` owned by `who`.
+	fn owned_in_class(class: &Self::ClassId, who: &T::AccountId)
+		-> Box<dyn Iterator<Item = Self::InstanceId>>
+	{
+		let class = *class;
+		Box::new(Account::<T>::iter_prefix(who)
+			.filter(move |((c, _), _)| *c == class)
+			.map(|((_, instance), _)| instance))
+	}
+}
+
+impl<T: Config> Mutate<<T as frame_system::Config>::AccountId> for Pallet<T> {
+	fn mint_into(
+		class: &Self::ClassId,
+		instance: &Self::InstanceId,
+		who: &T::AccountId,
+	) -> DispatchResult {
+		Self::do_mint(*class, *instance, who.clone(), |_| Ok(()))
+	}
+
+	fn burn_from(class: &Self::ClassId, instance: &Self::InstanceId) -> DispatchResult {
+		Self::do_burn(*class, *instance, |_, _| Ok(()))
+	}
+}
+
+impl<T: Config> Transfer<T::AccountId> for Pallet<T> {
+	fn transfer(
+		class: &Self::ClassId,
+		instance: &Self::InstanceId,
+		destination: &T::AccountId,
+	) -> DispatchResult {
+		Self::do_transfer(*class, *instance, destination.clone(), |_, _| Ok(()))
+	}
+}
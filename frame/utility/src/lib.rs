@@ -23,7 +23,7 @@
 //!
 //! ## Overview
 //!
-//! This module contains two basic pieces of functionality:
+//! This module contains three basic pieces of functionality:
 //! - Batch dispatch: A stateless operation, allowing any origin to execute multiple calls in a
 //!   single dispatch. This can be useful to amalgamate proposals, combining `set_code` with
 //!   corresponding `set_storage`s, for efficient multiple payouts with just a single signature
@@ -35,6 +35,8 @@
 //!   it's perfectly fine to have each of them controlled by the same underlying keypair.
 //!   Derivative accounts are, for the purposes of proxy filtering considered exactly the same as
 //!   the oigin and are thus hampered with the origin's filters.
+//! - Dispatch as: A stateless operation, allowing the root origin to execute a call from an
+//!   arbitrary origin, e.g. a signed account, without any dispatch filter.
 //!
 //! Since proxy filters are respected in all dispatches of this module, it should never need to be
 //! filtered by any proxy.
@@ -44,11 +46,17 @@
 //! ### Dispatchable Functions
 //!
 //! #### For batch dispatch
-//! * `batch` - Dispatch multiple calls from the sender's origin.
+//! * `batch` - Dispatch multiple calls from the sender's origin, stopping at the first failure.
+//! * `batch_all` - Dispatch multiple calls from the sender's origin, reverting all of them if
+//!   any fails.
+//! * `force_batch` - Dispatch multiple calls from the sender's origin, continuing past failures.
 //!
 //! #### For pseudonymal dispatch
 //! * `as_derivative` - Dispatch a call from a derivative signed origin.
 //!
+//! #### For dispatch as
+//! * `dispatch_as` - Dispatch a call from the given origin. Root only.
+//!
 //! [`Call`]: ./enum.Call.html
 //! [`Config`]: ./trait.Config.html
 
@@ -64,10 +72,11 @@ use codec::{Encode, Decode};
 use sp_core::TypeId;
 use sp_io::hashing::blake2_256;
 use frame_support::{decl_module, decl_event, decl_storage, Parameter, transactional};
+use frame_support::storage::{with_transaction, TransactionOutcome};
 use frame_support::{
-	traits::{OriginTrait, UnfilteredDispatchable, Get},
+	traits::{OriginTrait, UnfilteredDispatchable, Get, IsType},
 	weights::{Weight, GetDispatchInfo, DispatchClass, extract_actual_weight},
-	dispatch::{PostDispatchInfo, DispatchResultWithPostInfo},
+	dispatch::{PostDispatchInfo, DispatchResult, DispatchResultWithPostInfo},
 };
 use frame_system::{ensure_signed, ensure_root};
 use sp_runtime::{DispatchError, traits::Dispatchable};
@@ -83,6 +92,10 @@ pub trait Config: frame_system::Config {
 		+ GetDispatchInfo + From<frame_system::Call<Self>>
 		+ UnfilteredDispatchable<Origin=Self::Origin>;
 
+	/// The caller origin, overarching type of all pallets origins.
+	type PalletsOrigin: Parameter + Into<<Self as frame_system::Config>::Origin>
+		+ IsType<<<Self as frame_system::Config>::Origin as OriginTrait>::PalletsOrigin>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -99,6 +112,14 @@ decl_event! {
 		BatchInterrupted(u32, DispatchError),
 		/// Batch of dispatches completed fully with no error.
 		BatchCompleted,
+		/// Batch of dispatches completed but has errors.
+		BatchCompletedWithErrors,
+		/// A single item within a batch of dispatches has completed with no error.
+		ItemCompleted,
+		/// A single item within a batch of dispatches has completed with error. \[error\]
+		ItemFailed(DispatchError),
+		/// A call was dispatched. \[result\]
+		DispatchedAs(DispatchResult),
 	}
 }
 
@@ -278,10 +299,134 @@ decl_module! {
 			let base_weight = T::WeightInfo::batch_all(calls_len as u32);
 			Ok(Some(base_weight + weight).into())
 		}
+
+		/// Dispatches a function call with a provided origin.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// The call is dispatched without checking any origin filter. The result of the dispatch
+		/// is deposited in the `DispatchedAs` event.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Limited storage reads.
+		/// - One DB write (event).
+		/// - Weight of derivative `call` execution + T::WeightInfo::dispatch_as().
+		/// # </weight>
+		#[weight = {
+			let dispatch_info = call.get_dispatch_info();
+			(
+				T::WeightInfo::dispatch_as().saturating_add(dispatch_info.weight),
+				dispatch_info.class,
+			)
+		}]
+		fn dispatch_as(
+			origin,
+			as_origin: Box<T::PalletsOrigin>,
+			call: Box<<T as Config>::Call>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let info = call.get_dispatch_info();
+			let result = call.dispatch_bypass_filter((*as_origin).into());
+			// Always take into account the base weight of this call.
+			let weight = T::WeightInfo::dispatch_as()
+				.saturating_add(extract_actual_weight(&result, &info));
+			Self::deposit_event(Event::DispatchedAs(result.map(|_| ()).map_err(|e| e.error)));
+			Ok(Some(weight).into())
+		}
+
+		/// Send a batch of dispatch calls.
+		/// Unlike `batch`, it allows errors and won't interrupt.
+		///
+		/// May be called from any origin.
+		///
+		/// - `calls`: The calls to be dispatched from the same origin.
+		///
+		/// If origin is root then call are dispatch without checking origin filter. (This includes
+		/// bypassing `frame_system::Config::BaseCallFilter`).
+		///
+		/// # <weight>
+		/// - Complexity: O(C) where C is the number of calls to be batched.
+		/// # </weight>
+		///
+		/// Each call is dispatched in its own storage transaction: any changes made by a call which
+		/// fails are reverted, while those of the other calls are kept.
+		///
+		/// This will return `Ok` in all circumstances. An `ItemCompleted` or `ItemFailed` event is
+		/// deposited for each call, followed by `BatchCompleted` if all were successful, or
+		/// `BatchCompletedWithErrors` otherwise.
+		#[weight = {
+			let dispatch_infos = calls.iter().map(|call| call.get_dispatch_info()).collect::<Vec<_>>();
+			let dispatch_weight = dispatch_infos.iter()
+				.map(|di| di.weight)
+				.fold(0, |total: Weight, weight: Weight| total.saturating_add(weight))
+				.saturating_add(T::WeightInfo::force_batch(calls.len() as u32));
+			let dispatch_class = {
+				let all_operational = dispatch_infos.iter()
+					.map(|di| di.class)
+					.all(|class| class == DispatchClass::Operational);
+				if all_operational {
+					DispatchClass::Operational
+				} else {
+					DispatchClass::Normal
+				}
+			};
+			(dispatch_weight, dispatch_class)
+		}]
+		fn force_batch(origin, calls: Vec<<T as Config>::Call>) -> DispatchResultWithPostInfo {
+			let is_root = ensure_root(origin.clone()).is_ok();
+			let calls_len = calls.len();
+			// Track the actual weight of each of the batch calls.
+			let mut weight: Weight = 0;
+			// Track failed dispatch occur.
+			let mut has_error = false;
+			for call in calls.into_iter() {
+				let info = call.get_dispatch_info();
+				let result = Self::dispatch_in_transaction(call, origin.clone(), is_root);
+				// Add the weight of this call.
+				weight = weight.saturating_add(extract_actual_weight(&result, &info));
+				if let Err(e) = result {
+					has_error = true;
+					Self::deposit_event(Event::ItemFailed(e.error));
+				} else {
+					Self::deposit_event(Event::ItemCompleted);
+				}
+			}
+			if has_error {
+				Self::deposit_event(Event::BatchCompletedWithErrors);
+			} else {
+				Self::deposit_event(Event::BatchCompleted);
+			}
+			let base_weight = T::WeightInfo::force_batch(calls_len as u32);
+			Ok(Some(base_weight + weight).into())
+		}
 	}
 }
 
 impl<T: Config> Module<T> {
+	/// Dispatch `call` in its own storage transaction, so that a failing call leaves no storage
+	/// changes behind.
+	///
+	/// If `is_root` is set, no dispatch filters are applied; root can call anything.
+	fn dispatch_in_transaction(
+		call: <T as Config>::Call,
+		origin: T::Origin,
+		is_root: bool,
+	) -> DispatchResultWithPostInfo {
+		with_transaction(|| {
+			let result = if is_root {
+				call.dispatch_bypass_filter(origin)
+			} else {
+				call.dispatch(origin)
+			};
+			if result.is_ok() {
+				TransactionOutcome::Commit(result)
+			} else {
+				TransactionOutcome::Rollback(result)
+			}
+		})
+	}
+
 	/// Derive a derivative account ID from the owner account and the sub-account index.
 	pub fn derivative_account_id(who: T::AccountId, index: u16) -> T::AccountId {
 		let entropy = (b"modlpy/utilisuba", who, index).using_encoded(blake2_256);
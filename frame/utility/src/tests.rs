@@ -62,6 +62,17 @@ pub mod example {
 					Ok(end_weight.into())
 				}
 			}
+
+			#[weight = 0]
+			fn write_then_maybe_fail(origin, key: Vec<u8>, err: bool) -> DispatchResult {
+				let _ = ensure_signed(origin)?;
+				storage::unhashed::put_raw(&key, &key);
+				if err {
+					Err("The cake is a lie.".into())
+				} else {
+					Ok(())
+				}
+			}
 		}
 	}
 }
@@ -149,6 +160,7 @@ impl Filter<Call> for TestBaseCallFilter {
 impl Config for Test {
 	type Event = Event;
 	type Call = Call;
+	type PalletsOrigin = OriginCaller;
 	type WeightInfo = ();
 }
 
@@ -525,3 +537,127 @@ fn batch_all_handles_weight_refund() {
 		);
 	});
 }
+
+#[test]
+fn force_batch_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Balances::free_balance(1), 10);
+		assert_eq!(Balances::free_balance(2), 10);
+		assert_ok!(
+			Utility::force_batch(Origin::signed(1), vec![
+				Call::Balances(BalancesCall::transfer(2, 5)),
+				Call::Balances(BalancesCall::transfer(2, 10)),
+				Call::Balances(BalancesCall::transfer(2, 5)),
+			]),
+		);
+		assert_eq!(Balances::free_balance(1), 0);
+		assert_eq!(Balances::free_balance(2), 20);
+		let utility_events = System::events()
+			.into_iter()
+			.filter_map(|r| match r.event {
+				Event::utility(e) => Some(e),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(utility_events, vec![
+			utility::Event::ItemCompleted,
+			utility::Event::ItemFailed(BalancesError::<Test, _>::InsufficientBalance.into()),
+			utility::Event::ItemCompleted,
+			utility::Event::BatchCompletedWithErrors,
+		]);
+
+		assert_ok!(
+			Utility::force_batch(Origin::signed(2), vec![
+				Call::Balances(BalancesCall::transfer(1, 5)),
+			]),
+		);
+		expect_event(utility::Event::BatchCompleted);
+	});
+}
+
+#[test]
+fn force_batch_with_signed_filters() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(
+			Utility::force_batch(Origin::signed(1), vec![
+				Call::Balances(pallet_balances::Call::transfer_keep_alive(2, 1)),
+			]),
+		);
+		assert_eq!(Balances::free_balance(2), 10);
+		expect_event(utility::Event::BatchCompletedWithErrors);
+	});
+}
+
+#[test]
+fn force_batch_handles_weight_refund() {
+	new_test_ext().execute_with(|| {
+		let start_weight = 100;
+		let end_weight = 75;
+		let diff = start_weight - end_weight;
+
+		// Refund weight of every call, failed or not
+		let good_call = Call::Example(ExampleCall::foobar(false, start_weight, Some(end_weight)));
+		let bad_call = Call::Example(ExampleCall::foobar(true, start_weight, Some(end_weight)));
+		let batch_calls = vec![good_call, bad_call.clone(), bad_call];
+		let batch_len = batch_calls.len() as Weight;
+		let call = Call::Utility(UtilityCall::force_batch(batch_calls));
+		let info = call.get_dispatch_info();
+		let result = call.dispatch(Origin::signed(1));
+		assert_ok!(result);
+		assert_eq!(extract_actual_weight(&result, &info), info.weight - diff * batch_len);
+	});
+}
+
+#[test]
+fn force_batch_reverts_storage_of_failed_calls() {
+	new_test_ext().execute_with(|| {
+		let (k1, k2, k3) = (b"a".to_vec(), b"b".to_vec(), b"c".to_vec());
+		assert_ok!(
+			Utility::force_batch(Origin::signed(1), vec![
+				Call::Example(ExampleCall::write_then_maybe_fail(k1.clone(), false)),
+				Call::Example(ExampleCall::write_then_maybe_fail(k2.clone(), true)),
+				Call::Example(ExampleCall::write_then_maybe_fail(k3.clone(), false)),
+			]),
+		);
+		assert_eq!(storage::unhashed::get_raw(&k1), Some(k1));
+		assert_eq!(storage::unhashed::get_raw(&k2), None);
+		assert_eq!(storage::unhashed::get_raw(&k3), Some(k3));
+		expect_event(utility::Event::BatchCompletedWithErrors);
+	});
+}
+
+#[test]
+fn dispatch_as_works() {
+	new_test_ext().execute_with(|| {
+		let call = Call::Balances(pallet_balances::Call::transfer_keep_alive(2, 5));
+		assert_noop!(
+			Utility::dispatch_as(
+				Origin::signed(1),
+				Box::new(frame_system::RawOrigin::Signed(1).into()),
+				Box::new(call.clone()),
+			),
+			DispatchError::BadOrigin,
+		);
+
+		// Filters are bypassed.
+		assert_ok!(Utility::dispatch_as(
+			Origin::root(),
+			Box::new(frame_system::RawOrigin::Signed(1).into()),
+			Box::new(call.clone()),
+		));
+		assert_eq!(Balances::free_balance(1), 5);
+		assert_eq!(Balances::free_balance(2), 15);
+		expect_event(utility::Event::DispatchedAs(Ok(())));
+
+		// The result of the call is reported in the event.
+		assert_ok!(Utility::dispatch_as(
+			Origin::root(),
+			Box::new(frame_system::RawOrigin::Signed(1).into()),
+			Box::new(call),
+		));
+		assert_eq!(Balances::free_balance(1), 5);
+		expect_event(utility::Event::DispatchedAs(
+			Err(BalancesError::<Test, _>::KeepAlive.into())
+		));
+	});
+}
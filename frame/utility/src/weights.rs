@@ -46,6 +46,8 @@ pub trait WeightInfo {
 	fn batch(c: u32, ) -> Weight;
 	fn as_derivative() -> Weight;
 	fn batch_all(c: u32, ) -> Weight;
+	fn dispatch_as() -> Weight;
+	fn force_batch(c: u32, ) -> Weight;
 	
 }
 
@@ -66,6 +68,15 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add((2_738_000 as Weight).saturating_mul(c as Weight))
 			
 	}
+	fn dispatch_as() -> Weight {
+		(6_103_000 as Weight)
+			
+	}
+	fn force_batch(c: u32, ) -> Weight {
+		(20_315_000 as Weight)
+			.saturating_add((2_741_000 as Weight).saturating_mul(c as Weight))
+			
+	}
 	
 }
 
@@ -85,5 +96,14 @@ impl WeightInfo for () {
 			.saturating_add((2_738_000 as Weight).saturating_mul(c as Weight))
 			
 	}
+	fn dispatch_as() -> Weight {
+		(6_103_000 as Weight)
+			
+	}
+	fn force_batch(c: u32, ) -> Weight {
+		(20_315_000 as Weight)
+			.saturating_add((2_741_000 as Weight).saturating_mul(c as Weight))
+			
+	}
 	
 }
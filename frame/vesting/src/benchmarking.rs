@@ -40,135 +40,140 @@ fn add_locks<T: Config>(who: &T::AccountId, n: u8) {
 	}
 }
 
-fn add_vesting_schedule<T: Config>(who: &T::AccountId) -> Result<(), &'static str> {
-	let locked = 100u32;
-	let per_block = 10u32;
-	let starting_block = 1u32;
+/// Give `who` `n` schedules of `MinVestedTransfer` each, all starting at block 1 and fully
+/// vested from block 2 on. Returns the total amount locked.
+fn add_vesting_schedules<T: Config>(who: &T::AccountId, n: u32) -> Result<BalanceOf<T>, &'static str> {
+	let locked: BalanceOf<T> = T::MinVestedTransfer::get();
+	let per_block = locked;
 
 	System::<T>::set_block_number(0u32.into());
 
-	// Add schedule to avoid `NotVesting` error.
-	Vesting::<T>::add_vesting_schedule(
-		&who,
-		locked.into(),
-		per_block.into(),
-		starting_block.into(),
-	)?;
-	Ok(())
+	let mut total_locked: BalanceOf<T> = Zero::zero();
+	for _ in 0 .. n {
+		Vesting::<T>::add_vesting_schedule(&who, locked, per_block, 1u32.into())?;
+		total_locked = total_locked.saturating_add(locked);
+	}
+	Ok(total_locked)
 }
 
 benchmarks! {
 	vest_locked {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 1 .. T::MaxVestingSchedules::get();
 
 		let caller = whitelisted_caller();
 		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
 		add_locks::<T>(&caller, l as u8);
-		add_vesting_schedule::<T>(&caller)?;
-		// At block zero, everything is vested.
+		let total_locked = add_vesting_schedules::<T>(&caller, s)?;
+		// At block zero, everything is locked.
 		System::<T>::set_block_number(T::BlockNumber::zero());
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&caller),
-			Some(100u32.into()),
-			"Vesting schedule not added",
+			Some(total_locked),
+			"Vesting schedules not added",
 		);
 	}: vest(RawOrigin::Signed(caller.clone()))
 	verify {
-		// Nothing happened since everything is still vested.
+		// Nothing happened since everything is still locked.
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&caller),
-			Some(100u32.into()),
-			"Vesting schedule was removed",
+			Some(total_locked),
+			"Vesting schedules were removed",
 		);
 	}
 
 	vest_unlocked {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 1 .. T::MaxVestingSchedules::get();
 
 		let caller = whitelisted_caller();
 		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
 		add_locks::<T>(&caller, l as u8);
-		add_vesting_schedule::<T>(&caller)?;
-		// At block 20, everything is unvested.
+		add_vesting_schedules::<T>(&caller, s)?;
+		// At block 20, everything is unlocked.
 		System::<T>::set_block_number(20u32.into());
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&caller),
 			Some(BalanceOf::<T>::zero()),
-			"Vesting schedule still active",
+			"Vesting schedules still active",
 		);
 	}: vest(RawOrigin::Signed(caller.clone()))
 	verify {
-		// Vesting schedule is removed!
+		// Vesting schedules are removed!
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&caller),
 			None,
-			"Vesting schedule was not removed",
+			"Vesting schedules were not removed",
 		);
 	}
 
 	vest_other_locked {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 1 .. T::MaxVestingSchedules::get();
 
 		let other: T::AccountId = account("other", 0, SEED);
 		let other_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(other.clone());
 		T::Currency::make_free_balance_be(&other, BalanceOf::<T>::max_value());
 		add_locks::<T>(&other, l as u8);
-		add_vesting_schedule::<T>(&other)?;
-		// At block zero, everything is vested.
+		let total_locked = add_vesting_schedules::<T>(&other, s)?;
+		// At block zero, everything is locked.
 		System::<T>::set_block_number(T::BlockNumber::zero());
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&other),
-			Some(100u32.into()),
-			"Vesting schedule not added",
+			Some(total_locked),
+			"Vesting schedules not added",
 		);
 
 		let caller: T::AccountId = whitelisted_caller();
 	}: vest_other(RawOrigin::Signed(caller.clone()), other_lookup)
 	verify {
-		// Nothing happened since everything is still vested.
+		// Nothing happened since everything is still locked.
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&other),
-			Some(100u32.into()),
-			"Vesting schedule was removed",
+			Some(total_locked),
+			"Vesting schedules were removed",
 		);
 	}
 
 	vest_other_unlocked {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 1 .. T::MaxVestingSchedules::get();
 
 		let other: T::AccountId = account("other", 0, SEED);
 		let other_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(other.clone());
 		T::Currency::make_free_balance_be(&other, BalanceOf::<T>::max_value());
 		add_locks::<T>(&other, l as u8);
-		add_vesting_schedule::<T>(&other)?;
-		// At block 20, everything is unvested.
+		add_vesting_schedules::<T>(&other, s)?;
+		// At block 20, everything is unlocked.
 		System::<T>::set_block_number(20u32.into());
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&other),
 			Some(BalanceOf::<T>::zero()),
-			"Vesting schedule still active",
+			"Vesting schedules still active",
 		);
 
 		let caller: T::AccountId = whitelisted_caller();
 	}: vest_other(RawOrigin::Signed(caller.clone()), other_lookup)
 	verify {
-		// Vesting schedule is removed!
+		// Vesting schedules are removed!
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&other),
 			None,
-			"Vesting schedule was not removed",
+			"Vesting schedules were not removed",
 		);
 	}
 
 	vested_transfer {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 1 .. T::MaxVestingSchedules::get();
 
 		let caller: T::AccountId = whitelisted_caller();
 		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
 		let target: T::AccountId = account("target", 0, SEED);
 		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
-		// Give target existing locks
+		// Give target existing locks and schedules
 		add_locks::<T>(&target, l as u8);
+		let existing_locked = add_vesting_schedules::<T>(&target, s - 1)?;
 
 		let transfer_amount = T::MinVestedTransfer::get();
 
@@ -186,21 +191,23 @@ benchmarks! {
 		);
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&target),
-			Some(T::MinVestedTransfer::get()),
+			Some(existing_locked + transfer_amount),
 			"Lock not created",
 		);
 	}
 
 	force_vested_transfer {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 1 .. T::MaxVestingSchedules::get();
 
 		let source: T::AccountId = account("source", 0, SEED);
 		let source_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(source.clone());
 		T::Currency::make_free_balance_be(&source, BalanceOf::<T>::max_value());
 		let target: T::AccountId = account("target", 0, SEED);
 		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
-		// Give target existing locks
+		// Give target existing locks and schedules
 		add_locks::<T>(&target, l as u8);
+		let existing_locked = add_vesting_schedules::<T>(&target, s - 1)?;
 
 		let transfer_amount = T::MinVestedTransfer::get();
 
@@ -218,10 +225,57 @@ benchmarks! {
 		);
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&target),
-			Some(T::MinVestedTransfer::get()),
+			Some(existing_locked + transfer_amount),
 			"Lock not created",
 		);
 	}
+
+	merge_schedules {
+		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 2 .. T::MaxVestingSchedules::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		add_locks::<T>(&caller, l as u8);
+		let total_locked = add_vesting_schedules::<T>(&caller, s)?;
+		// At block zero, everything is locked.
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&caller),
+			Some(total_locked),
+			"Vesting schedules not added",
+		);
+	}: _(RawOrigin::Signed(caller.clone()), 0, s - 1)
+	verify {
+		// The two schedules were replaced by one, and nothing was unlocked.
+		assert_eq!(
+			Vesting::<T>::vesting(&caller).map(|v| v.len() as u32),
+			Some(s - 1),
+			"Schedules were not merged",
+		);
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&caller),
+			Some(total_locked),
+			"Vesting balance changed",
+		);
+	}
+
+	force_remove_vesting_schedule {
+		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 2 .. T::MaxVestingSchedules::get();
+
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_locks::<T>(&target, l as u8);
+		let total_locked = add_vesting_schedules::<T>(&target, s)?;
+	}: _(RawOrigin::Root, target_lookup, 0)
+	verify {
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&target),
+			Some(total_locked - T::MinVestedTransfer::get()),
+			"Schedule was not removed",
+		);
+	}
 }
 
 impl_benchmark_test_suite!(
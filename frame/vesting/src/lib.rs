@@ -26,6 +26,9 @@
 //! module ensures that there is a lock in place preventing the balance to drop below the *unvested*
 //! amount for any reason other than transaction fee payment.
 //!
+//! An account may have up to `MaxVestingSchedules` vesting schedules at the same time. The lock
+//! placed on the account is the sum of what remains unvested across all of them.
+//!
 //! As the amount vested increases over time, the amount unvested reduces. However, locks remain in
 //! place and explicit action is needed on behalf of the user to ensure that the amount locked is
 //! equivalent to the amount remaining to be vested. This is done through a dispatchable function,
@@ -41,6 +44,10 @@
 //! - `vest` - Update the lock, reducing it in line with the amount "vested" so far.
 //! - `vest_other` - Update the lock of another account, reducing it in line with the amount
 //!   "vested" so far.
+//! - `vested_transfer` - Transfer funds to an account and add a vesting schedule over them.
+//! - `force_vested_transfer` - Like `vested_transfer`, but from an arbitrary source account.
+//! - `merge_schedules` - Combine two of the sender's vesting schedules into one.
+//! - `force_remove_vesting_schedule` - Remove a vesting schedule from an account.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Config`]: ./trait.Config.html
@@ -48,19 +55,21 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod benchmarking;
+pub mod migrations;
 pub mod weights;
 
 use sp_std::prelude::*;
 use sp_std::fmt::Debug;
 use codec::{Encode, Decode};
 use sp_runtime::{DispatchResult, RuntimeDebug, traits::{
-	StaticLookup, Zero, AtLeast32BitUnsigned, MaybeSerializeDeserialize, Convert
+	StaticLookup, Zero, One, Saturating, AtLeast32BitUnsigned, MaybeSerializeDeserialize, Convert
 }};
 use frame_support::{decl_module, decl_event, decl_storage, decl_error, ensure};
 use frame_support::traits::{
 	Currency, LockableCurrency, VestingSchedule, WithdrawReasons, LockIdentifier,
 	ExistenceRequirement, Get,
 };
+use frame_support::weights::Weight;
 use frame_system::{ensure_signed, ensure_root};
 pub use weights::WeightInfo;
 
@@ -80,12 +89,29 @@ pub trait Config: frame_system::Config {
 	/// The minimum amount transferred to call `vested_transfer`.
 	type MinVestedTransfer: Get<BalanceOf<Self>>;
 
+	/// The maximum number of vesting schedules an account may have at a given moment.
+	type MaxVestingSchedules: Get<u32>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
 
 const VESTING_ID: LockIdentifier = *b"vesting ";
 
+// A value placed in storage that represents the current version of the Vesting storage.
+// This value is used by `on_runtime_upgrade` to determine whether we run storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	V0,
+	V1,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V0
+	}
+}
+
 /// Struct to encode the vesting schedule of an individual account.
 #[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
 pub struct VestingInfo<Balance, BlockNumber> {
@@ -117,6 +143,24 @@ impl<
 			Zero::zero()
 		}
 	}
+
+	/// Block number at which the schedule has fully unlocked, expressed as a balance.
+	///
+	/// A `per_block` of zero is treated as one so that every schedule has an end.
+	pub fn ending_block_as_balance<
+		BlockNumberToBalance: Convert<BlockNumber, Balance>
+	>(&self) -> Balance {
+		let starting_block = BlockNumberToBalance::convert(self.starting_block);
+		let per_block = self.per_block.max(One::one());
+		let duration = if per_block >= self.locked {
+			One::one()
+		} else {
+			let duration = self.locked / per_block;
+			// Round up so that the remainder is covered by the last block.
+			if (self.locked % per_block).is_zero() { duration } else { duration.saturating_add(One::one()) }
+		};
+		starting_block.saturating_add(duration)
+	}
 }
 
 decl_storage! {
@@ -124,12 +168,16 @@ decl_storage! {
 		/// Information regarding the vesting of a given account.
 		pub Vesting get(fn vesting):
 			map hasher(blake2_128_concat) T::AccountId
-			=> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>>;
+			=> Option<Vec<VestingInfo<BalanceOf<T>, T::BlockNumber>>>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with the latest version, as determined by the genesis build.
+		StorageVersion build(|_: &GenesisConfig<T>| Releases::V1): Releases;
 	}
 	add_extra_genesis {
 		config(vesting): Vec<(T::AccountId, T::BlockNumber, T::BlockNumber, BalanceOf<T>)>;
 		build(|config: &GenesisConfig<T>| {
-			use sp_runtime::traits::Saturating;
 			// Generate initial vesting configuration
 			// * who - Account which we are generating vesting configuration for
 			// * begin - Block when the account will start to vest
@@ -141,15 +189,23 @@ decl_storage! {
 				// Total genesis `balance` minus `liquid` equals funds locked for vesting
 				let locked = balance.saturating_sub(liquid);
 				let length_as_balance = T::BlockNumberToBalance::convert(length);
-				let per_block = locked / length_as_balance.max(sp_runtime::traits::One::one());
+				let per_block = locked / length_as_balance.max(One::one());
 
-				Vesting::<T>::insert(who, VestingInfo {
+				let mut schedules = Vesting::<T>::get(who).unwrap_or_default();
+				assert!(
+					(schedules.len() as u32) < T::MaxVestingSchedules::get(),
+					"Too many vesting schedules at genesis",
+				);
+				schedules.push(VestingInfo {
 					locked: locked,
 					per_block: per_block,
 					starting_block: begin
 				});
+				let total_locked = schedules.iter()
+					.fold(Zero::zero(), |acc: BalanceOf<T>, s| acc.saturating_add(s.locked));
+				Vesting::<T>::insert(who, schedules);
 				let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
-				T::Currency::set_lock(VESTING_ID, who, locked, reasons);
+				T::Currency::set_lock(VESTING_ID, who, total_locked, reasons);
 			}
 		})
 	}
@@ -171,10 +227,13 @@ decl_error! {
 	pub enum Error for Module<T: Config> {
 		/// The account given is not vesting.
 		NotVesting,
-		/// An existing vesting schedule already exists for this account that cannot be clobbered.
-		ExistingVestingSchedule,
+		/// The account already has `MaxVestingSchedules` vesting schedules. Merge some of them
+		/// before adding another.
+		AtMaxVestingSchedules,
 		/// Amount being transferred is too low to create a vesting schedule.
 		AmountLow,
+		/// An index was out of bounds of the account's vesting schedules.
+		ScheduleIndexOutOfBounds,
 	}
 }
 
@@ -186,8 +245,20 @@ decl_module! {
 		/// The minimum amount to be transferred to create a new vesting schedule.
 		const MinVestedTransfer: BalanceOf<T> = T::MinVestedTransfer::get();
 
+		/// The maximum number of vesting schedules an account may have.
+		const MaxVestingSchedules: u32 = T::MaxVestingSchedules::get();
+
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get() == Releases::V0 {
+				StorageVersion::put(Releases::V1);
+				migrations::v1::migrate::<T>()
+			} else {
+				0
+			}
+		}
+
 		/// Unlock any vested funds of the sender account.
 		///
 		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
@@ -196,14 +267,18 @@ decl_module! {
 		/// Emits either `VestingCompleted` or `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of the sender.
 		/// - DbWeight: 2 Reads, 2 Writes
 		///     - Reads: Vesting Storage, Balances Locks, [Sender Account]
 		///     - Writes: Vesting Storage, Balances Locks, [Sender Account]
 		/// # </weight>
-		#[weight = T::WeightInfo::vest_locked(MaxLocksOf::<T>::get())
-			.max(T::WeightInfo::vest_unlocked(MaxLocksOf::<T>::get()))
-		]
+		#[weight = T::WeightInfo::vest_locked(
+			MaxLocksOf::<T>::get(),
+			T::MaxVestingSchedules::get(),
+		).max(T::WeightInfo::vest_unlocked(
+			MaxLocksOf::<T>::get(),
+			T::MaxVestingSchedules::get(),
+		))]
 		fn vest(origin) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			Self::update_lock(who)
@@ -219,14 +294,18 @@ decl_module! {
 		/// Emits either `VestingCompleted` or `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of `target`.
 		/// - DbWeight: 3 Reads, 3 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account
 		///     - Writes: Vesting Storage, Balances Locks, Target Account
 		/// # </weight>
-		#[weight = T::WeightInfo::vest_other_locked(MaxLocksOf::<T>::get())
-			.max(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get()))
-		]
+		#[weight = T::WeightInfo::vest_other_locked(
+			MaxLocksOf::<T>::get(),
+			T::MaxVestingSchedules::get(),
+		).max(T::WeightInfo::vest_other_unlocked(
+			MaxLocksOf::<T>::get(),
+			T::MaxVestingSchedules::get(),
+		))]
 		fn vest_other(origin, target: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
 			ensure_signed(origin)?;
 			Self::update_lock(T::Lookup::lookup(target)?)
@@ -237,35 +316,31 @@ decl_module! {
 		/// The dispatch origin for this call must be _Signed_.
 		///
 		/// - `target`: The account that should be transferred the vested funds.
-		/// - `amount`: The amount of funds to transfer and will be vested.
 		/// - `schedule`: The vesting schedule attached to the transfer.
 		///
-		/// Emits `VestingCreated`.
+		/// The schedule is added alongside any schedules `target` already has. Fails with
+		/// `AtMaxVestingSchedules` if `target` already has `MaxVestingSchedules` schedules.
+		///
+		/// Emits `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of `target`.
 		/// - DbWeight: 3 Reads, 3 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account, [Sender Account]
 		///     - Writes: Vesting Storage, Balances Locks, Target Account, [Sender Account]
 		/// # </weight>
-		#[weight = T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get())]
+		#[weight = T::WeightInfo::vested_transfer(
+			MaxLocksOf::<T>::get(),
+			T::MaxVestingSchedules::get(),
+		)]
 		pub fn vested_transfer(
 			origin,
 			target: <T::Lookup as StaticLookup>::Source,
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			let transactor = ensure_signed(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
-			let who = T::Lookup::lookup(target)?;
-			ensure!(!Vesting::<T>::contains_key(&who), Error::<T>::ExistingVestingSchedule);
-
-			T::Currency::transfer(&transactor, &who, schedule.locked, ExistenceRequirement::AllowDeath)?;
-
-			Self::add_vesting_schedule(&who, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
-
-			Ok(())
+			let target = T::Lookup::lookup(target)?;
+			Self::do_vested_transfer(transactor, target, schedule)
 		}
 
 		/// Force a vested transfer.
@@ -274,18 +349,20 @@ decl_module! {
 		///
 		/// - `source`: The account whose funds should be transferred.
 		/// - `target`: The account that should be transferred the vested funds.
-		/// - `amount`: The amount of funds to transfer and will be vested.
 		/// - `schedule`: The vesting schedule attached to the transfer.
 		///
-		/// Emits `VestingCreated`.
+		/// Emits `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of `target`.
 		/// - DbWeight: 4 Reads, 4 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account, Source Account
 		///     - Writes: Vesting Storage, Balances Locks, Target Account, Source Account
 		/// # </weight>
-		#[weight = T::WeightInfo::force_vested_transfer(MaxLocksOf::<T>::get())]
+		#[weight = T::WeightInfo::force_vested_transfer(
+			MaxLocksOf::<T>::get(),
+			T::MaxVestingSchedules::get(),
+		)]
 		pub fn force_vested_transfer(
 			origin,
 			source: <T::Lookup as StaticLookup>::Source,
@@ -293,19 +370,86 @@ decl_module! {
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
 			let target = T::Lookup::lookup(target)?;
 			let source = T::Lookup::lookup(source)?;
-			ensure!(!Vesting::<T>::contains_key(&target), Error::<T>::ExistingVestingSchedule);
-
-			T::Currency::transfer(&source, &target, schedule.locked, ExistenceRequirement::AllowDeath)?;
-
-			Self::add_vesting_schedule(&target, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
+			Self::do_vested_transfer(source, target, schedule)
+		}
 
+		/// Merge two vesting schedules of the sender into one.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `schedule1_index`: Index of the first schedule to merge.
+		/// - `schedule2_index`: Index of the second schedule to merge.
+		///
+		/// The merged schedule locks whatever the two schedules still lock, starts at the latest of
+		/// the current block and the two starting blocks, and ends at the later of the two ending
+		/// blocks. A schedule which has already ended is simply dropped. Any vested funds are
+		/// unlocked as with `vest`. Merging a schedule with itself is a no-op.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of vesting schedules of the sender.
+		/// - DbWeight: 2 Reads, 2 Writes
+		///     - Reads: Vesting Storage, Balances Locks, [Sender Account]
+		///     - Writes: Vesting Storage, Balances Locks, [Sender Account]
+		/// # </weight>
+		#[weight = T::WeightInfo::merge_schedules(
+			MaxLocksOf::<T>::get(),
+			T::MaxVestingSchedules::get(),
+		)]
+		pub fn merge_schedules(origin, schedule1_index: u32, schedule2_index: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			if schedule1_index == schedule2_index {
+				return Ok(())
+			}
+			let mut schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+			let (low, high) = (
+				schedule1_index.min(schedule2_index) as usize,
+				schedule1_index.max(schedule2_index) as usize,
+			);
+			ensure!(high < schedules.len(), Error::<T>::ScheduleIndexOutOfBounds);
+
+			// Remove the higher index first so the lower one stays valid.
+			let schedule2 = schedules.remove(high);
+			let schedule1 = schedules.remove(low);
+			let now = <frame_system::Module<T>>::block_number();
+			if let Some(merged) = Self::merge_vesting_info(now, schedule1, schedule2) {
+				schedules.push(merged);
+			}
+			Self::write_vesting(&who, schedules);
 			Ok(())
 		}
+
+		/// Remove a vesting schedule from an account, unlocking whatever it still locks.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// - `target`: The account whose vesting schedule should be removed.
+		/// - `schedule_index`: Index of the schedule to remove.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of vesting schedules of `target`.
+		/// - DbWeight: 3 Reads, 3 Writes
+		///     - Reads: Vesting Storage, Balances Locks, Target Account
+		///     - Writes: Vesting Storage, Balances Locks, Target Account
+		/// # </weight>
+		#[weight = T::WeightInfo::force_remove_vesting_schedule(
+			MaxLocksOf::<T>::get(),
+			T::MaxVestingSchedules::get(),
+		)]
+		pub fn force_remove_vesting_schedule(
+			origin,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule_index: u32,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let who = T::Lookup::lookup(target)?;
+			<Self as VestingSchedule<_>>::remove_vesting_schedule(&who, schedule_index)
+		}
 	}
 }
 
@@ -313,21 +457,90 @@ impl<T: Config> Module<T> {
 	/// (Re)set or remove the module's currency lock on `who`'s account in accordance with their
 	/// current unvested amount.
 	fn update_lock(who: T::AccountId) -> DispatchResult {
-		let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
-		let now = <frame_system::Module<T>>::block_number();
-		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+		let schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+		Self::write_vesting(&who, schedules);
+		Ok(())
+	}
 
-		if locked_now.is_zero() {
-			T::Currency::remove_lock(VESTING_ID, &who);
-			Vesting::<T>::remove(&who);
-			Self::deposit_event(RawEvent::VestingCompleted(who));
+	/// Store `schedules` for `who`, dropping any which have fully vested, and set the lock to
+	/// what remains unvested across them.
+	///
+	/// Removes the storage entry and the lock altogether once nothing is left to vest.
+	fn write_vesting(who: &T::AccountId, schedules: Vec<VestingInfo<BalanceOf<T>, T::BlockNumber>>) {
+		let now = <frame_system::Module<T>>::block_number();
+		let mut total_locked_now: BalanceOf<T> = Zero::zero();
+		let schedules: Vec<_> = schedules.into_iter()
+			.filter(|schedule| {
+				let locked_now = schedule.locked_at::<T::BlockNumberToBalance>(now);
+				total_locked_now = total_locked_now.saturating_add(locked_now);
+				!locked_now.is_zero()
+			})
+			.collect();
+
+		if schedules.is_empty() {
+			T::Currency::remove_lock(VESTING_ID, who);
+			Vesting::<T>::remove(who);
+			Self::deposit_event(RawEvent::VestingCompleted(who.clone()));
 		} else {
 			let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
-			T::Currency::set_lock(VESTING_ID, &who, locked_now, reasons);
-			Self::deposit_event(RawEvent::VestingUpdated(who, locked_now));
+			T::Currency::set_lock(VESTING_ID, who, total_locked_now, reasons);
+			Vesting::<T>::insert(who, schedules);
+			Self::deposit_event(RawEvent::VestingUpdated(who.clone(), total_locked_now));
 		}
+	}
+
+	/// Transfer `schedule.locked` from `source` to `target` and vest it under `schedule`.
+	fn do_vested_transfer(
+		source: T::AccountId,
+		target: T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> DispatchResult {
+		ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+		Self::can_add_vesting_schedule(&target, schedule.locked, schedule.per_block, schedule.starting_block)?;
+
+		T::Currency::transfer(&source, &target, schedule.locked, ExistenceRequirement::AllowDeath)?;
+
+		Self::add_vesting_schedule(&target, schedule.locked, schedule.per_block, schedule.starting_block)
+			.expect("schedule was checked with `can_add_vesting_schedule`; q.e.d.");
+
 		Ok(())
 	}
+
+	/// Combine two schedules into one which releases their remaining funds no earlier than
+	/// either of them would have.
+	///
+	/// Returns `None` if both schedules have already ended.
+	fn merge_vesting_info(
+		now: T::BlockNumber,
+		schedule1: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		schedule2: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>> {
+		let now_as_balance = T::BlockNumberToBalance::convert(now);
+		let schedule1_ending = schedule1.ending_block_as_balance::<T::BlockNumberToBalance>();
+		let schedule2_ending = schedule2.ending_block_as_balance::<T::BlockNumberToBalance>();
+
+		match (schedule1_ending <= now_as_balance, schedule2_ending <= now_as_balance) {
+			(true, true) => return None,
+			(true, false) => return Some(schedule2),
+			(false, true) => return Some(schedule1),
+			(false, false) => {},
+		}
+
+		let locked = schedule1.locked_at::<T::BlockNumberToBalance>(now)
+			.saturating_add(schedule2.locked_at::<T::BlockNumberToBalance>(now));
+		let ending_block = schedule1_ending.max(schedule2_ending);
+		let starting_block = now.max(schedule1.starting_block).max(schedule2.starting_block);
+		let duration = ending_block
+			.saturating_sub(T::BlockNumberToBalance::convert(starting_block))
+			.max(One::one());
+		let per_block = if duration > locked {
+			One::one()
+		} else {
+			locked / duration
+		};
+
+		Some(VestingInfo { locked, per_block, starting_block })
+	}
 }
 
 impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
@@ -338,9 +551,11 @@ impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
 
 	/// Get the amount that is currently being vested and cannot be transferred out of this account.
 	fn vesting_balance(who: &T::AccountId) -> Option<BalanceOf<T>> {
-		if let Some(v) = Self::vesting(who) {
+		if let Some(schedules) = Self::vesting(who) {
 			let now = <frame_system::Module<T>>::block_number();
-			let locked_now = v.locked_at::<T::BlockNumberToBalance>(now);
+			let locked_now = schedules.iter().fold(Zero::zero(), |acc: BalanceOf<T>, schedule| {
+				acc.saturating_add(schedule.locked_at::<T::BlockNumberToBalance>(now))
+			});
 			Some(T::Currency::free_balance(who).min(locked_now))
 		} else {
 			None
@@ -349,8 +564,8 @@ impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
 
 	/// Adds a vesting schedule to a given account.
 	///
-	/// If there already exists a vesting schedule for the given account, an `Err` is returned
-	/// and nothing is updated.
+	/// If the account already has `MaxVestingSchedules` schedules, an `Err` is returned and
+	/// nothing is updated.
 	///
 	/// On success, a linearly reducing amount of funds will be locked. In order to realise any
 	/// reduction of the lock over time as it diminishes, the account owner must use `vest` or
@@ -364,25 +579,39 @@ impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
 		starting_block: T::BlockNumber
 	) -> DispatchResult {
 		if locked.is_zero() { return Ok(()) }
-		if Vesting::<T>::contains_key(who) {
-			Err(Error::<T>::ExistingVestingSchedule)?
-		}
-		let vesting_schedule = VestingInfo {
+		Self::can_add_vesting_schedule(who, locked, per_block, starting_block)?;
+		let mut schedules = Self::vesting(who).unwrap_or_default();
+		schedules.push(VestingInfo {
 			locked,
 			per_block,
 			starting_block
-		};
-		Vesting::<T>::insert(who, vesting_schedule);
-		// it can't fail, but even if somehow it did, we don't really care.
-		let _ = Self::update_lock(who.clone());
+		});
+		Self::write_vesting(who, schedules);
 		Ok(())
 	}
 
-	/// Remove a vesting schedule for a given account.
-	fn remove_vesting_schedule(who: &T::AccountId) {
-		Vesting::<T>::remove(who);
-		// it can't fail, but even if somehow it did, we don't really care.
-		let _ = Self::update_lock(who.clone());
+	/// Checks whether a vesting schedule could be added to the given account.
+	fn can_add_vesting_schedule(
+		who: &T::AccountId,
+		_locked: BalanceOf<T>,
+		_per_block: BalanceOf<T>,
+		_starting_block: T::BlockNumber,
+	) -> DispatchResult {
+		let schedule_count = Vesting::<T>::decode_len(who).unwrap_or_default();
+		ensure!(
+			(schedule_count as u32) < T::MaxVestingSchedules::get(),
+			Error::<T>::AtMaxVestingSchedules,
+		);
+		Ok(())
+	}
+
+	/// Remove the vesting schedule at `schedule_index` of the given account.
+	fn remove_vesting_schedule(who: &T::AccountId, schedule_index: u32) -> DispatchResult {
+		let mut schedules = Self::vesting(who).ok_or(Error::<T>::NotVesting)?;
+		ensure!((schedule_index as usize) < schedules.len(), Error::<T>::ScheduleIndexOutOfBounds);
+		schedules.remove(schedule_index as usize);
+		Self::write_vesting(who, schedules);
+		Ok(())
 	}
 }
 
@@ -457,6 +686,7 @@ mod tests {
 	}
 	parameter_types! {
 		pub const MinVestedTransfer: u64 = 256 * 2;
+		pub const MaxVestingSchedules: u32 = 3;
 		pub static ExistentialDeposit: u64 = 0;
 	}
 	impl Config for Test {
@@ -464,6 +694,7 @@ mod tests {
 		type Currency = Balances;
 		type BlockNumberToBalance = Identity;
 		type MinVestedTransfer = MinVestedTransfer;
+		type MaxVestingSchedules = MaxVestingSchedules;
 		type WeightInfo = ();
 	}
 
@@ -534,9 +765,9 @@ mod tests {
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&1), Some(user1_vesting_schedule)); // Account 1 has a vesting schedule
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule)); // Account 2 has a vesting schedule
-				assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule)); // Account 12 has a vesting schedule
+				assert_eq!(Vesting::vesting(&1), Some(vec![user1_vesting_schedule])); // Account 1 has a vesting schedule
+				assert_eq!(Vesting::vesting(&2), Some(vec![user2_vesting_schedule])); // Account 2 has a vesting schedule
+				assert_eq!(Vesting::vesting(&12), Some(vec![user12_vesting_schedule])); // Account 12 has a vesting schedule
 
 				// Account 1 has only 128 units vested from their illiquid 256 * 5 units at block 1
 				assert_eq!(Vesting::vesting_balance(&1), Some(128 * 9));
@@ -657,7 +888,7 @@ mod tests {
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule));
+				assert_eq!(Vesting::vesting(&12), Some(vec![user12_vesting_schedule]));
 
 				// Account 12 can still send liquid funds
 				assert_ok!(Balances::transfer(Some(12).into(), 3, 256 * 5));
@@ -684,7 +915,7 @@ mod tests {
 				};
 				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule));
 				// Now account 4 should have vesting.
-				assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+				assert_eq!(Vesting::vesting(&4), Some(vec![new_vesting_schedule]));
 				// Ensure the transfer happened correctly.
 				let user3_free_balance_updated = Balances::free_balance(&3);
 				assert_eq!(user3_free_balance_updated, 256 * 25);
@@ -723,17 +954,21 @@ mod tests {
 					per_block: 256, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+				assert_eq!(Vesting::vesting(&2), Some(vec![user2_vesting_schedule]));
 
-				// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+				// Account 2 can take two more schedules before it is at the maximum.
 				let new_vesting_schedule = VestingInfo {
 					locked: 256 * 5,
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
+				for _ in 0..2 {
+					assert_ok!(Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule));
+				}
+				assert_eq!(Vesting::vesting(&2).map(|v| v.len()), Some(3));
 				assert_noop!(
 					Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule),
-					Error::<Test>::ExistingVestingSchedule,
+					Error::<Test>::AtMaxVestingSchedules,
 				);
 
 				// Fails due to too low transfer amount.
@@ -774,7 +1009,7 @@ mod tests {
 				assert_noop!(Vesting::force_vested_transfer(Some(4).into(), 3, 4, new_vesting_schedule), BadOrigin);
 				assert_ok!(Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 4, new_vesting_schedule));
 				// Now account 4 should have vesting.
-				assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+				assert_eq!(Vesting::vesting(&4), Some(vec![new_vesting_schedule]));
 				// Ensure the transfer happened correctly.
 				let user3_free_balance_updated = Balances::free_balance(&3);
 				assert_eq!(user3_free_balance_updated, 256 * 25);
@@ -813,17 +1048,21 @@ mod tests {
 					per_block: 256, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+				assert_eq!(Vesting::vesting(&2), Some(vec![user2_vesting_schedule]));
 
-				// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+				// Account 2 can take two more schedules before it is at the maximum.
 				let new_vesting_schedule = VestingInfo {
 					locked: 256 * 5,
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
+				for _ in 0..2 {
+					assert_ok!(Vesting::force_vested_transfer(RawOrigin::Root.into(), 4, 2, new_vesting_schedule));
+				}
+				assert_eq!(Vesting::vesting(&2).map(|v| v.len()), Some(3));
 				assert_noop!(
 					Vesting::force_vested_transfer(RawOrigin::Root.into(), 4, 2, new_vesting_schedule),
-					Error::<Test>::ExistingVestingSchedule,
+					Error::<Test>::AtMaxVestingSchedules,
 				);
 
 				// Fails due to too low transfer amount.
@@ -842,4 +1081,176 @@ mod tests {
 				assert_eq!(user4_free_balance, 256 * 40);
 			});
 	}
+
+	#[test]
+	fn multiple_schedules_lock_their_sum() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let long_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64, // Vesting over 20 blocks
+					starting_block: 10,
+				};
+				let short_schedule = VestingInfo {
+					locked: 256 * 2,
+					per_block: 256, // Vesting over 2 blocks
+					starting_block: 1,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, long_schedule));
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, short_schedule));
+				assert_eq!(Vesting::vesting(&4), Some(vec![long_schedule, short_schedule]));
+				assert_eq!(Vesting::vesting_balance(&4), Some(256 * 7));
+
+				System::set_block_number(2);
+				assert_eq!(Vesting::vesting_balance(&4), Some(256 * 6));
+
+				// The short schedule has ended and is dropped once the account vests.
+				System::set_block_number(3);
+				assert_ok!(Vesting::vest(Some(4).into()));
+				assert_eq!(Vesting::vesting(&4), Some(vec![long_schedule]));
+				assert_eq!(Vesting::vesting_balance(&4), Some(256 * 5));
+				assert_ok!(Balances::transfer(Some(4).into(), 3, 256 * 42));
+				assert_noop!(
+					Balances::transfer(Some(4).into(), 3, 1),
+					pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+				);
+
+				System::set_block_number(30);
+				assert_ok!(Vesting::vest(Some(4).into()));
+				assert_eq!(Vesting::vesting(&4), None);
+			});
+	}
+
+	#[test]
+	fn merge_schedules_works() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let schedule1 = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64, // Vesting over 20 blocks, ends at 30
+					starting_block: 10,
+				};
+				let schedule2 = VestingInfo {
+					locked: 256 * 4,
+					per_block: 32, // Vesting over 32 blocks, ends at 37
+					starting_block: 5,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule1));
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule2));
+
+				// Merging a schedule with itself does nothing.
+				assert_ok!(Vesting::merge_schedules(Some(4).into(), 1, 1));
+				assert_eq!(Vesting::vesting(&4), Some(vec![schedule1, schedule2]));
+
+				assert_ok!(Vesting::merge_schedules(Some(4).into(), 1, 0));
+				// Starts at the latest start and ends at the latest end.
+				let merged = VestingInfo {
+					locked: 256 * 9,
+					per_block: 256 * 9 / 27,
+					starting_block: 10,
+				};
+				assert_eq!(Vesting::vesting(&4), Some(vec![merged]));
+				assert_eq!(Vesting::vesting_balance(&4), Some(256 * 9));
+				assert_eq!(
+					System::events().last().unwrap().event,
+					Event::pallet_vesting(RawEvent::VestingUpdated(4, 256 * 9)),
+				);
+			});
+	}
+
+	#[test]
+	fn merge_schedules_drops_ended_schedules() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let ended = VestingInfo {
+					locked: 256 * 2,
+					per_block: 256, // Ends at 3
+					starting_block: 1,
+				};
+				let ongoing = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64, // Ends at 30
+					starting_block: 10,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, ended));
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, ongoing));
+
+				System::set_block_number(5);
+				assert_ok!(Vesting::merge_schedules(Some(4).into(), 0, 1));
+				assert_eq!(Vesting::vesting(&4), Some(vec![ongoing]));
+			});
+	}
+
+	#[test]
+	fn merge_schedules_correctly_fails() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				assert_noop!(Vesting::merge_schedules(Some(4).into(), 0, 1), Error::<Test>::NotVesting);
+				assert_noop!(
+					Vesting::merge_schedules(Some(2).into(), 0, 1),
+					Error::<Test>::ScheduleIndexOutOfBounds,
+				);
+			});
+	}
+
+	#[test]
+	fn force_remove_vesting_schedule_works() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				assert_noop!(Vesting::force_remove_vesting_schedule(Some(2).into(), 2, 0), BadOrigin);
+				assert_noop!(
+					Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 2, 1),
+					Error::<Test>::ScheduleIndexOutOfBounds,
+				);
+				assert_noop!(
+					Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 4, 0),
+					Error::<Test>::NotVesting,
+				);
+
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 20));
+				assert_ok!(Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 2, 0));
+				assert_eq!(Vesting::vesting(&2), None);
+				assert_eq!(Vesting::vesting_balance(&2), None);
+				assert_ok!(Balances::transfer(Some(2).into(), 3, 256 * 20));
+			});
+	}
+
+	#[test]
+	fn migration_to_v1_works() {
+		use frame_support::{storage::StoragePrefixedMap, traits::OnRuntimeUpgrade};
+
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				assert_eq!(StorageVersion::get(), Releases::V1);
+
+				let old_schedule = VestingInfo {
+					locked: 256 * 5u64,
+					per_block: 64u64,
+					starting_block: 10u64,
+				};
+				<pallet_vesting::Vesting<Test>>::remove_all();
+				frame_support::storage::unhashed::put(
+					&<pallet_vesting::Vesting<Test>>::hashed_key_for(&2),
+					&old_schedule,
+				);
+				StorageVersion::put(Releases::V0);
+
+				Vesting::on_runtime_upgrade();
+
+				assert_eq!(StorageVersion::get(), Releases::V1);
+				assert_eq!(Vesting::vesting(&2), Some(vec![old_schedule]));
+			});
+	}
 }
@@ -0,0 +1,41 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the vesting pallet.
+
+use super::*;
+
+/// Migration from a single vesting schedule per account to a list of them.
+pub mod v1 {
+	use super::*;
+	use frame_support::storage::IterableStorageMap;
+
+	/// Wrap every stored `VestingInfo` into a single element `Vec`.
+	///
+	/// This is called from `on_runtime_upgrade` while `StorageVersion` is `V0`, so it runs once.
+	pub fn migrate<T: Config>() -> Weight {
+		let mut translated = 0u64;
+		<Vesting<T> as IterableStorageMap<_, _>>::translate::<VestingInfo<BalanceOf<T>, T::BlockNumber>, _>(
+			|_, schedule| {
+				translated += 1;
+				Some(vec![schedule])
+			},
+		);
+		// One read and write per account, plus the `StorageVersion` write.
+		T::DbWeight::get().reads_writes(translated, translated + 1)
+	}
+}
@@ -43,106 +43,152 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_vesting.
 pub trait WeightInfo {
-	fn vest_locked(l: u32, ) -> Weight;
-	fn vest_unlocked(l: u32, ) -> Weight;
-	fn vest_other_locked(l: u32, ) -> Weight;
-	fn vest_other_unlocked(l: u32, ) -> Weight;
-	fn vested_transfer(l: u32, ) -> Weight;
-	fn force_vested_transfer(l: u32, ) -> Weight;
+	fn vest_locked(l: u32, s: u32, ) -> Weight;
+	fn vest_unlocked(l: u32, s: u32, ) -> Weight;
+	fn vest_other_locked(l: u32, s: u32, ) -> Weight;
+	fn vest_other_unlocked(l: u32, s: u32, ) -> Weight;
+	fn vested_transfer(l: u32, s: u32, ) -> Weight;
+	fn force_vested_transfer(l: u32, s: u32, ) -> Weight;
+	fn merge_schedules(l: u32, s: u32, ) -> Weight;
+	fn force_remove_vesting_schedule(l: u32, s: u32, ) -> Weight;
 
 }
 
 /// Weights for pallet_vesting using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	fn vest_locked(l: u32, ) -> Weight {
+	fn vest_locked(l: u32, s: u32, ) -> Weight {
 		(57_472_000 as Weight)
 			.saturating_add((155_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((191_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(2 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 
 	}
-	fn vest_unlocked(l: u32, ) -> Weight {
+	fn vest_unlocked(l: u32, s: u32, ) -> Weight {
 		(61_681_000 as Weight)
 			.saturating_add((138_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((204_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(2 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 
 	}
-	fn vest_other_locked(l: u32, ) -> Weight {
+	fn vest_other_locked(l: u32, s: u32, ) -> Weight {
 		(56_910_000 as Weight)
 			.saturating_add((160_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((187_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 
 	}
-	fn vest_other_unlocked(l: u32, ) -> Weight {
+	fn vest_other_unlocked(l: u32, s: u32, ) -> Weight {
 		(61_319_000 as Weight)
 			.saturating_add((144_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((199_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 
 	}
-	fn vested_transfer(l: u32, ) -> Weight {
+	fn vested_transfer(l: u32, s: u32, ) -> Weight {
 		(124_996_000 as Weight)
 			.saturating_add((209_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((226_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 
 	}
-	fn force_vested_transfer(l: u32, ) -> Weight {
+	fn force_vested_transfer(l: u32, s: u32, ) -> Weight {
 		(123_911_000 as Weight)
 			.saturating_add((213_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((231_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(4 as Weight))
 			.saturating_add(T::DbWeight::get().writes(4 as Weight))
 
 	}
+	fn merge_schedules(l: u32, s: u32, ) -> Weight {
+		(70_254_000 as Weight)
+			.saturating_add((158_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((212_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+
+	}
+	fn force_remove_vesting_schedule(l: u32, s: u32, ) -> Weight {
+		(64_831_000 as Weight)
+			.saturating_add((149_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((176_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+
+	}
 
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	fn vest_locked(l: u32, ) -> Weight {
+	fn vest_locked(l: u32, s: u32, ) -> Weight {
 		(57_472_000 as Weight)
 			.saturating_add((155_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((191_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 
 	}
-	fn vest_unlocked(l: u32, ) -> Weight {
+	fn vest_unlocked(l: u32, s: u32, ) -> Weight {
 		(61_681_000 as Weight)
 			.saturating_add((138_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((204_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 
 	}
-	fn vest_other_locked(l: u32, ) -> Weight {
+	fn vest_other_locked(l: u32, s: u32, ) -> Weight {
 		(56_910_000 as Weight)
 			.saturating_add((160_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((187_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 
 	}
-	fn vest_other_unlocked(l: u32, ) -> Weight {
+	fn vest_other_unlocked(l: u32, s: u32, ) -> Weight {
 		(61_319_000 as Weight)
 			.saturating_add((144_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((199_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 
 	}
-	fn vested_transfer(l: u32, ) -> Weight {
+	fn vested_transfer(l: u32, s: u32, ) -> Weight {
 		(124_996_000 as Weight)
 			.saturating_add((209_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((226_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 
 	}
-	fn force_vested_transfer(l: u32, ) -> Weight {
+	fn force_vested_transfer(l: u32, s: u32, ) -> Weight {
 		(123_911_000 as Weight)
 			.saturating_add((213_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((231_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
 
 	}
+	fn merge_schedules(l: u32, s: u32, ) -> Weight {
+		(70_254_000 as Weight)
+			.saturating_add((158_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((212_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+
+	}
+	fn force_remove_vesting_schedule(l: u32, s: u32, ) -> Weight {
+		(64_831_000 as Weight)
+			.saturating_add((149_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add((176_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+
+	}
 
 }
@@ -0,0 +1,165 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sp_std::{cmp, prelude::*};
+
+use crate::ValidatorSetId;
+
+/// A commitment signed by GRANDPA validators as part of BEEFY protocol.
+///
+/// The commitment contains a payload extracted from the finalized block at height `block_number`.
+/// GRANDPA validators collect signatures on commitments and a stream of such signed commitments
+/// (see [SignedCommitment]) forms the BEEFY protocol.
+#[derive(Clone, Debug, PartialEq, Eq, codec::Encode, codec::Decode)]
+pub struct Commitment<TBlockNumber, TPayload> {
+	/// The payload being signed.
+	///
+	/// This should be some form of cumulative representation of the chain (think MMR root hash).
+	/// The payload should also contain some details that allow the light client to verify next
+	/// validator set. The protocol does not enforce any particular format of this data,
+	/// nor how often it should be present in commitments, however the light client has to be
+	/// provided with full validator set whenever it performs the transition (i.e. importing first
+	/// block with `validator_set_id` incremented).
+	pub payload: TPayload,
+
+	/// Finalized block number this commitment is for.
+	///
+	/// GRANDPA validators agree on a block they create a commitment for and start collecting
+	/// signatures. This process is called a round.
+	/// There might be multiple rounds in progress (depending on the block choice rule), however
+	/// since the payload is supposed to be cumulative, it is not required to import all
+	/// commitments.
+	/// BEEFY light client is expected to import at least one commitment per epoch,
+	/// but is free to import as many as it requires.
+	pub block_number: TBlockNumber,
+
+	/// BEEFY validator set supposed to sign this commitment.
+	///
+	/// Validator set is changing once per epoch. The Light Client must be provided by details
+	/// about the validator set whenever it's importing first commitment with a new
+	/// `validator_set_id`. Validator set data MUST be verifiable, for instance using
+	/// `payload` information.
+	pub validator_set_id: ValidatorSetId,
+}
+
+impl<TBlockNumber, TPayload> cmp::PartialOrd for Commitment<TBlockNumber, TPayload>
+where
+	TBlockNumber: cmp::Ord,
+	TPayload: cmp::Eq,
+{
+	fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<TBlockNumber, TPayload> cmp::Ord for Commitment<TBlockNumber, TPayload>
+where
+	TBlockNumber: cmp::Ord,
+	TPayload: cmp::Eq,
+{
+	fn cmp(&self, other: &Self) -> cmp::Ordering {
+		self.validator_set_id
+			.cmp(&other.validator_set_id)
+			.then_with(|| self.block_number.cmp(&other.block_number))
+	}
+}
+
+/// A commitment with matching GRANDPA validators' signatures.
+#[derive(Clone, Debug, PartialEq, Eq, codec::Encode, codec::Decode)]
+pub struct SignedCommitment<TBlockNumber, TPayload, TSignature> {
+	/// The commitment signatures are collected for.
+	pub commitment: Commitment<TBlockNumber, TPayload>,
+	/// GRANDPA validators' signatures for the commitment.
+	///
+	/// The length of this `Vec` must match number of validators in the current set (see
+	/// [Commitment::validator_set_id]).
+	pub signatures: Vec<Option<TSignature>>,
+}
+
+impl<TBlockNumber, TPayload, TSignature> SignedCommitment<TBlockNumber, TPayload, TSignature> {
+	/// Return the number of collected signatures.
+	pub fn no_of_signatures(&self) -> usize {
+		self.signatures.iter().filter(|x| x.is_some()).count()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::{Decode, Encode};
+	use sp_core::{ecdsa, Pair};
+
+	type TestCommitment = Commitment<u128, String>;
+	type TestSignedCommitment = SignedCommitment<u128, String, ecdsa::Signature>;
+
+	fn commitment(block_number: u128, validator_set_id: ValidatorSetId) -> TestCommitment {
+		Commitment { payload: "Hello World!".into(), block_number, validator_set_id }
+	}
+
+	#[test]
+	fn commitment_encode_decode() {
+		let commitment = commitment(5, 0);
+
+		let encoded = commitment.encode();
+		let decoded = TestCommitment::decode(&mut &*encoded);
+
+		assert_eq!(decoded, Ok(commitment));
+	}
+
+	#[test]
+	fn signed_commitment_encode_decode() {
+		let commitment = commitment(5, 0);
+		let pair = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let signature = pair.sign(&commitment.encode());
+
+		let signed = TestSignedCommitment {
+			commitment,
+			signatures: vec![None, None, Some(signature.clone()), Some(signature)],
+		};
+
+		let encoded = signed.encode();
+		let decoded = TestSignedCommitment::decode(&mut &*encoded);
+
+		assert_eq!(decoded, Ok(signed));
+	}
+
+	#[test]
+	fn signed_commitment_count_signatures() {
+		let pair = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let signature = pair.sign(b"payload");
+
+		let mut signed = TestSignedCommitment {
+			commitment: commitment(5, 0),
+			signatures: vec![None, None, Some(signature.clone()), Some(signature)],
+		};
+		assert_eq!(signed.no_of_signatures(), 2);
+
+		signed.signatures[2] = None;
+		assert_eq!(signed.no_of_signatures(), 1);
+	}
+
+	#[test]
+	fn commitment_ordering() {
+		let a = commitment(5, 0);
+		let b = commitment(10, 0);
+		let c = commitment(5, 1);
+
+		assert!(a < b);
+		assert!(b < c);
+		assert!(a < c);
+	}
+}
@@ -20,8 +20,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use sp_std::vec::Vec;
-use sp_runtime::ConsensusEngineId;
-use codec::Decode;
+use sp_runtime::{ConsensusEngineId, RuntimeDebug};
+use codec::{Encode, Decode};
 
 /// The `ConsensusEngineId` of PoW.
 pub const POW_ENGINE_ID: ConsensusEngineId = [b'p', b'o', b'w', b'_'];
@@ -29,6 +29,22 @@ pub const POW_ENGINE_ID: ConsensusEngineId = [b'p', b'o', b'w', b'_'];
 /// Type of seal.
 pub type Seal = Vec<u8>;
 
+/// Identifier of a PoW algorithm, for chains mined with several of them.
+pub type AlgorithmId = [u8; 4];
+
+/// Identifier of the algorithm of chains mined with a single PoW algorithm.
+pub const DEFAULT_ALGORITHM: AlgorithmId = POW_ENGINE_ID;
+
+/// Pre-runtime digest of chains mined with several PoW algorithms, selecting the algorithm a
+/// block is mined with.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct MultiAlgorithmPreDigest {
+	/// Algorithm the block is mined with.
+	pub algorithm: AlgorithmId,
+	/// Pre-runtime digest handed to the selected algorithm, if any.
+	pub inner: Option<Vec<u8>>,
+}
+
 /// Define methods that total difficulty should implement.
 pub trait TotalDifficulty {
 	fn increment(&mut self, other: Self);
@@ -58,8 +74,16 @@ sp_api::decl_runtime_apis! {
 	/// API for those chains that put their difficulty adjustment algorithm directly
 	/// onto runtime. Note that while putting difficulty adjustment algorithm to
 	/// runtime is safe, putting the PoW algorithm on runtime is not.
+	///
+	/// Version 2 keeps a difficulty per algorithm, for chains mined with several of them.
+	#[api_version(2)]
 	pub trait DifficultyApi<Difficulty: Decode> {
 		/// Return the target difficulty of the next block.
+		#[changed_in(2)]
 		fn difficulty() -> Difficulty;
+		/// Return the target difficulty of the next block mined with `algorithm`.
+		///
+		/// Chains mined with a single algorithm pass [`DEFAULT_ALGORITHM`].
+		fn difficulty(algorithm: AlgorithmId) -> Difficulty;
 	}
 }